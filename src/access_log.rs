@@ -0,0 +1,112 @@
+//! HTTP access log, kept apart from the application's tracing output: one
+//! line per request in common log format or JSON, with status, latency and
+//! a request ID (taken from `x-request-id` or generated, and echoed back).
+
+use crate::config::{config, AccessLogFormat};
+use crate::utils::now_ms;
+use axum::extract::{ConnectInfo, Request};
+use axum::http::header::{HeaderValue, CONTENT_LENGTH};
+use axum::middleware::Next;
+use axum::response::Response;
+use once_cell::sync::Lazy;
+use serde_json::json;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+use tracing::{info, warn};
+
+const REQUEST_ID: &str = "x-request-id";
+
+/// The log file, when `access_log.path` is set and could be opened.
+static FILE: Lazy<Option<Mutex<File>>> = Lazy::new(|| {
+    let path = config().access_log.path.as_deref()?;
+    match OpenOptions::new().create(true).append(true).open(path) {
+        Ok(f) => Some(Mutex::new(f)),
+        Err(e) => {
+            warn!(
+                "access log {}: {} (logging to the `access` target)",
+                path, e
+            );
+            None
+        }
+    }
+});
+
+/// Generated IDs are the process start (hex seconds) plus a counter, so
+/// they stay unique across restarts.
+static BOOT: Lazy<u64> = Lazy::new(|| now_ms() / 1000);
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Middleware; a no-op unless `access_log.enabled`.
+pub async fn log(req: Request, next: Next) -> Response {
+    let cfg = &config().access_log;
+    if !cfg.enabled {
+        return next.run(req).await;
+    }
+    let started = Instant::now();
+    let id = req
+        .headers()
+        .get(REQUEST_ID)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{:x}-{}", *BOOT, NEXT_ID.fetch_add(1, Ordering::Relaxed)));
+    let client = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|c| c.0.ip().to_string())
+        .unwrap_or_else(|| "-".into());
+    let method = req.method().to_string();
+    let uri = req.uri().to_string();
+    let version = format!("{:?}", req.version());
+
+    let mut res = next.run(req).await;
+
+    let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+    let status = res.status().as_u16();
+    let bytes = res
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        res.headers_mut().insert(REQUEST_ID, value);
+    }
+
+    let line = match cfg.format {
+        AccessLogFormat::Common => format!(
+            "{} - - [{}] \"{} {} {}\" {} {} {:.1}ms {}",
+            client,
+            chrono::Utc::now().format("%d/%b/%Y:%H:%M:%S %z"),
+            method,
+            uri,
+            version,
+            status,
+            bytes.as_deref().unwrap_or("-"),
+            latency_ms,
+            id
+        ),
+        AccessLogFormat::Json => json!({
+            "ts": chrono::Utc::now().to_rfc3339(),
+            "request_id": id,
+            "client": client,
+            "method": method,
+            "uri": uri,
+            "version": version,
+            "status": status,
+            "bytes": bytes.and_then(|b| b.parse::<u64>().ok()),
+            "latency_ms": (latency_ms * 1000.0).round() / 1000.0,
+        })
+        .to_string(),
+    };
+    match FILE.as_ref() {
+        Some(file) => {
+            let mut file = file.lock().unwrap_or_else(|e| e.into_inner());
+            let _ = writeln!(file, "{}", line);
+        }
+        None => info!(target: "access", "{}", line),
+    }
+    res
+}
@@ -0,0 +1,211 @@
+//! Access control for the admin routes: a CIDR allowlist checked against
+//! the client address of each request (the peer, or the address a trusted
+//! reverse proxy forwards).
+
+use crate::config::config;
+use axum::extract::{ConnectInfo, Request};
+use axum::http::{HeaderMap, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use once_cell::sync::Lazy;
+use std::net::{IpAddr, SocketAddr};
+use tracing::warn;
+
+/// Parsed `admin.allow`; entries that don't parse are logged and skipped.
+static ALLOW: Lazy<Vec<Cidr>> = Lazy::new(|| parse_list("admin.allow", &config().admin.allow));
+/// Parsed `admin.trusted_proxies`.
+static PROXIES: Lazy<Vec<Cidr>> =
+    Lazy::new(|| parse_list("admin.trusted_proxies", &config().admin.trusted_proxies));
+
+fn parse_list(key: &str, entries: &[String]) -> Vec<Cidr> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let cidr = Cidr::parse(entry);
+            if cidr.is_none() {
+                warn!("{}: ignoring invalid entry {:?}", key, entry);
+            }
+            cidr
+        })
+        .collect()
+}
+
+/// An address range like `10.0.0.0/8` or `::1/128`; a bare address is a
+/// single host.
+#[derive(Debug, Clone, Copy)]
+pub struct Cidr {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    pub fn parse(text: &str) -> Option<Self> {
+        let (addr, prefix) = match text.trim().split_once('/') {
+            Some((addr, prefix)) => (addr.parse::<IpAddr>().ok()?, Some(prefix.parse().ok()?)),
+            None => (text.trim().parse::<IpAddr>().ok()?, None),
+        };
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(max);
+        (prefix <= max).then_some(Self { addr, prefix })
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        // IPv4 clients may show up as IPv4-mapped IPv6 on dual-stack sockets.
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+            v4 => v4,
+        };
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Middleware rejecting clients outside `admin.allow` with 403 (an empty
+/// list refuses everyone).
+pub async fn allowlist(req: Request, next: Next) -> Response {
+    let client = client_ip(&req);
+    match client {
+        Some(ip) if ALLOW.iter().any(|c| c.contains(ip)) => next.run(req).await,
+        _ => {
+            warn!("admin request from {:?} refused by allowlist", client);
+            StatusCode::FORBIDDEN.into_response()
+        }
+    }
+}
+
+/// Header every non-GET admin request must carry. Browsers only send a
+/// custom header cross-origin after a CORS preflight, which the admin routes
+/// never answer, so a web page can't trigger admin actions.
+pub const ADMIN_HEADER: &str = "x-admin-request";
+
+/// Middleware rejecting admin writes without [`ADMIN_HEADER`] with 403.
+pub async fn require_header(req: Request, next: Next) -> Response {
+    if req.method() == Method::GET || req.method() == Method::HEAD {
+        return next.run(req).await;
+    }
+    if req.headers().contains_key(ADMIN_HEADER) {
+        return next.run(req).await;
+    }
+    warn!(
+        "admin {} {} refused: no {} header",
+        req.method(),
+        req.uri().path(),
+        ADMIN_HEADER
+    );
+    (
+        StatusCode::FORBIDDEN,
+        format!("admin writes need an {} header", ADMIN_HEADER),
+    )
+        .into_response()
+}
+
+fn client_ip(req: &Request) -> Option<IpAddr> {
+    let peer = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|c| c.0.ip())?;
+    resolve_client(peer, req.headers(), &PROXIES)
+}
+
+/// The peer address, or, when the peer is one of `proxies`, the right-most
+/// `X-Forwarded-For` entry that isn't one (entries further left are client
+/// supplied). None when a trusted proxy forwards no usable address.
+fn resolve_client(peer: IpAddr, headers: &HeaderMap, proxies: &[Cidr]) -> Option<IpAddr> {
+    let trusted = |ip: IpAddr| proxies.iter().any(|c| c.contains(ip));
+    if !trusted(peer) {
+        return Some(peer);
+    }
+    let forwarded: Vec<&str> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .collect();
+    for entry in forwarded.into_iter().rev() {
+        let ip = entry.trim().parse::<IpAddr>().ok()?;
+        if !trusted(ip) {
+            return Some(ip);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(text: &str) -> IpAddr {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn cidr_parsing_and_matching() {
+        let net = Cidr::parse("10.0.0.0/8").unwrap();
+        assert!(net.contains(ip("10.1.2.3")));
+        assert!(!net.contains(ip("11.0.0.1")));
+        // IPv4-mapped IPv6 peers match IPv4 ranges.
+        assert!(net.contains(ip("::ffff:10.0.0.1")));
+
+        let host = Cidr::parse(" 192.168.1.5 ").unwrap();
+        assert!(host.contains(ip("192.168.1.5")));
+        assert!(!host.contains(ip("192.168.1.6")));
+
+        assert!(Cidr::parse("::1/128").unwrap().contains(ip("::1")));
+        assert!(!Cidr::parse("fd00::/8").unwrap().contains(ip("10.0.0.1")));
+
+        assert!(Cidr::parse("10.0.0.0/33").is_none());
+        assert!(Cidr::parse("10.0.0/8").is_none());
+        assert!(Cidr::parse("10.0.0.0/x").is_none());
+    }
+
+    fn forwarded(entries: &[&str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for entry in entries {
+            headers.append("x-forwarded-for", entry.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn forwarded_for_is_only_trusted_from_proxies() {
+        let proxies = [Cidr::parse("10.0.0.0/8").unwrap()];
+        let headers = forwarded(&["1.2.3.4"]);
+        // A direct client can't claim another address.
+        assert_eq!(
+            resolve_client(ip("5.6.7.8"), &headers, &proxies),
+            Some(ip("5.6.7.8"))
+        );
+        assert_eq!(
+            resolve_client(ip("10.0.0.2"), &headers, &proxies),
+            Some(ip("1.2.3.4"))
+        );
+    }
+
+    #[test]
+    fn forwarded_for_takes_the_rightmost_untrusted_entry() {
+        let proxies = [Cidr::parse("10.0.0.0/8").unwrap()];
+        let peer = ip("10.0.0.2");
+        // The left-most entry is client supplied and ignored.
+        let headers = forwarded(&["127.0.0.1, 1.2.3.4", "10.0.0.9"]);
+        assert_eq!(
+            resolve_client(peer, &headers, &proxies),
+            Some(ip("1.2.3.4"))
+        );
+        // Only proxies forwarded, or nothing usable: no client address.
+        assert_eq!(
+            resolve_client(peer, &forwarded(&["10.0.0.9"]), &proxies),
+            None
+        );
+        assert_eq!(resolve_client(peer, &HeaderMap::new(), &proxies), None);
+        assert_eq!(resolve_client(peer, &forwarded(&["junk"]), &proxies), None);
+    }
+}
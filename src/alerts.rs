@@ -0,0 +1,158 @@
+use crate::config::{config, AlertConfig, AlertKind};
+use crate::models::TriangularResult;
+use crate::stats::canonical;
+use crate::tasks;
+use crate::template::render;
+use crate::utils::now_ms;
+use once_cell::sync::Lazy;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tracing::{info, warn};
+
+const DEFAULT_TELEGRAM_TEMPLATE: &str =
+    "{{ exchange }}: {{ triangle }} at {{ profit_after | round(3) }}% after fees";
+
+/// Sends configured alerts for scan results, at most once per triangle and
+/// alert every `cooldown_ms`.
+static ALERTS: Lazy<Alerts> = Lazy::new(Alerts::default);
+
+pub fn alerts() -> &'static Alerts {
+    &ALERTS
+}
+
+#[derive(Debug, Default)]
+pub struct Alerts {
+    /// (alert index, "exchange:A>B>C") -> last sent at.
+    sent: Mutex<HashMap<(usize, String), u64>>,
+    /// Shared by every send, so connections to a target are reused.
+    client: reqwest::Client,
+    delivered: AtomicU64,
+    failed: AtomicU64,
+}
+
+impl Alerts {
+    /// Fire every alert whose threshold a result clears. Sending happens in
+    /// the background; failures are logged.
+    pub fn notify(&self, exchange: &str, results: &[TriangularResult]) {
+        let cfg = &config().alerts;
+        if cfg.is_empty() {
+            return;
+        }
+        let now = now_ms();
+        let mut sent = self.sent.lock().unwrap_or_else(|e| e.into_inner());
+        expire(&mut sent, cfg, now);
+        for (idx, alert) in cfg.iter().enumerate() {
+            for r in results
+                .iter()
+                .filter(|r| r.profit_after >= alert.min_profit)
+            {
+                if alert.skip_flagged && !r.flags.is_empty() {
+                    continue;
+                }
+                let key = (idx, format!("{}:{}", exchange, canonical(r)));
+                if sent
+                    .get(&key)
+                    .is_some_and(|at| now.saturating_sub(*at) < alert.cooldown_ms)
+                {
+                    continue;
+                }
+                sent.insert(key, now);
+                let mut ctx = serde_json::to_value(r).unwrap_or(Value::Null);
+                if let Value::Object(map) = &mut ctx {
+                    map.insert("exchange".into(), json!(exchange));
+                }
+                tasks::spawn("alert", send(self.client.clone(), alert.clone(), ctx));
+            }
+        }
+    }
+
+    /// Alerts delivered and failed since startup.
+    pub fn totals(&self) -> (u64, u64) {
+        (
+            self.delivered.load(Ordering::Relaxed),
+            self.failed.load(Ordering::Relaxed),
+        )
+    }
+}
+
+/// Forget sends whose cooldown has passed, so the map only holds triangles
+/// still being held back.
+fn expire(sent: &mut HashMap<(usize, String), u64>, alerts: &[AlertConfig], now: u64) {
+    sent.retain(|(idx, _), at| {
+        alerts
+            .get(*idx)
+            .is_some_and(|alert| now.saturating_sub(*at) < alert.cooldown_ms)
+    });
+}
+
+/// `template` rendered against `ctx`; on failure (e.g. an unknown field)
+/// the caller falls back to the default body.
+fn rendered(template: &str, ctx: &Value) -> Option<String> {
+    render(template, ctx)
+        .map_err(|e| warn!("alert template failed, sending the default body: {}", e))
+        .ok()
+}
+
+async fn send(client: reqwest::Client, alert: AlertConfig, ctx: Value) {
+    let request = match alert.kind {
+        AlertKind::Webhook => {
+            let body = alert
+                .template
+                .as_deref()
+                .and_then(|t| rendered(t, &ctx))
+                .unwrap_or_else(|| ctx.to_string());
+            client
+                .post(&alert.url)
+                .header("content-type", &alert.content_type)
+                .body(body)
+        }
+        AlertKind::Telegram => {
+            let text = alert
+                .template
+                .as_deref()
+                .and_then(|t| rendered(t, &ctx))
+                .or_else(|| rendered(DEFAULT_TELEGRAM_TEMPLATE, &ctx))
+                .unwrap_or_default();
+            let url = format!(
+                "https://api.telegram.org/bot{}/sendMessage",
+                alert.bot_token
+            );
+            client
+                .post(url)
+                .json(&json!({ "chat_id": alert.chat_id, "text": text }))
+        }
+    };
+    match request.send().await.and_then(|r| r.error_for_status()) {
+        Ok(_) => {
+            alerts().delivered.fetch_add(1, Ordering::Relaxed);
+            info!("alert sent ({:?}): {}", alert.kind, ctx["triangle"]);
+        }
+        Err(e) => {
+            alerts().failed.fetch_add(1, Ordering::Relaxed);
+            warn!("alert ({:?}) failed: {}", alert.kind, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sends_past_their_cooldown_are_forgotten() {
+        let alert = AlertConfig {
+            cooldown_ms: 1_000,
+            ..AlertConfig::default()
+        };
+        let mut sent = HashMap::from([
+            ((0, "a".to_string()), 500),
+            ((0, "b".to_string()), 1_500),
+            // An alert no longer configured.
+            ((1, "a".to_string()), 1_500),
+        ]);
+        expire(&mut sent, &[alert], 2_000);
+        assert_eq!(sent.into_keys().collect::<Vec<_>>(), [(0, "b".to_string())]);
+    }
+}
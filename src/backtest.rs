@@ -0,0 +1,420 @@
+//! Replay of recorded scans (`/scans` history): every opportunity that
+//! clears the threshold under the given fees and slippage is taken once per
+//! cooldown with a fixed notional, producing trades, an equity curve and
+//! per-triangle stats. Runs are kept in memory for `/backtests/{id}`.
+
+use crate::history::{history, ScanRecord};
+use crate::stats::canonical;
+use crate::utils::now_ms;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt::Write as _;
+use std::sync::RwLock;
+use utoipa::ToSchema;
+
+/// Finished runs kept for retrieval.
+const MAX_RUNS: usize = 50;
+
+static BACKTESTS: Lazy<Backtests> = Lazy::new(Backtests::default);
+
+pub fn backtests() -> &'static Backtests {
+    &BACKTESTS
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct BacktestParams {
+    /// Unix ms; defaults to all retained scans.
+    pub since_ms: u64,
+    pub until_ms: Option<u64>,
+    /// Fee per leg (%); defaults to the fee each result was scanned with.
+    pub fee_per_leg_pct: Option<f64>,
+    /// Adverse price move per leg (%).
+    pub slippage_pct: f64,
+    /// Minimum profit (%) after fees and slippage to take a trade.
+    pub min_profit: f64,
+    /// Quote amount committed per trade.
+    pub notional: f64,
+    /// Don't trade the same triangle again within this window.
+    pub cooldown_ms: u64,
+    pub skip_flagged: bool,
+}
+
+impl Default for BacktestParams {
+    fn default() -> Self {
+        Self {
+            since_ms: 0,
+            until_ms: None,
+            fee_per_leg_pct: None,
+            slippage_pct: 0.0,
+            min_profit: 0.0,
+            notional: 1000.0,
+            cooldown_ms: 60_000,
+            skip_flagged: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Trade {
+    pub at_ms: u64,
+    pub scan_id: u64,
+    pub triangle: String,
+    pub profit_pct: f64,
+    pub pnl: f64,
+    /// Cumulative P&L after this trade.
+    pub equity: f64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TriangleSummary {
+    pub triangle: String,
+    pub trades: usize,
+    pub pnl: f64,
+    pub avg_profit_pct: f64,
+    pub best_profit_pct: f64,
+    pub worst_profit_pct: f64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Backtest {
+    pub id: u64,
+    pub created_ms: u64,
+    pub params: BacktestParams,
+    pub scans: usize,
+    pub opportunities: usize,
+    pub total_pnl: f64,
+    pub win_rate: f64,
+    /// Largest drop of the equity curve from a previous peak.
+    pub max_drawdown: f64,
+    /// Most profitable first.
+    pub triangles: Vec<TriangleSummary>,
+    pub trades: Vec<Trade>,
+}
+
+/// Profit (%) of a triangle with gross edge `profit_before` (%) after
+/// `fee` and `slippage` (%) on each of the three legs.
+pub fn net_profit_pct(profit_before: f64, fee: f64, slippage: f64) -> f64 {
+    let per_leg = (1.0 - fee / 100.0) * (1.0 - slippage / 100.0);
+    ((1.0 + profit_before / 100.0) * per_leg.powi(3) - 1.0) * 100.0
+}
+
+/// Replay `scans` (oldest first) under `params`; `id` is left 0.
+pub fn run(scans: &[ScanRecord], params: &BacktestParams) -> Backtest {
+    let until = params.until_ms.unwrap_or(u64::MAX);
+    let scans: Vec<&ScanRecord> = scans
+        .iter()
+        .filter(|s| s.at_ms >= params.since_ms && s.at_ms <= until)
+        .collect();
+    let mut last_trade: HashMap<String, u64> = HashMap::new();
+    let mut trades = Vec::new();
+    let mut opportunities = 0;
+    let mut equity = 0.0;
+
+    for scan in &scans {
+        for r in &scan.results {
+            if params.skip_flagged && !r.flags.is_empty() {
+                continue;
+            }
+            opportunities += 1;
+            let fee = params.fee_per_leg_pct.unwrap_or(r.fees / 3.0);
+            let profit_pct = net_profit_pct(r.profit_before, fee, params.slippage_pct);
+            if profit_pct < params.min_profit {
+                continue;
+            }
+            let key = canonical(r);
+            if last_trade
+                .get(&key)
+                .is_some_and(|at| scan.at_ms < at + params.cooldown_ms)
+            {
+                continue;
+            }
+            last_trade.insert(key, scan.at_ms);
+            let pnl = params.notional * profit_pct / 100.0;
+            equity += pnl;
+            trades.push(Trade {
+                at_ms: scan.at_ms,
+                scan_id: scan.id,
+                triangle: r.triangle.clone(),
+                profit_pct,
+                pnl,
+                equity,
+            });
+        }
+    }
+
+    let mut peak: f64 = 0.0;
+    let mut max_drawdown: f64 = 0.0;
+    for t in &trades {
+        peak = peak.max(t.equity);
+        max_drawdown = max_drawdown.max(peak - t.equity);
+    }
+    let wins = trades.iter().filter(|t| t.pnl > 0.0).count();
+
+    Backtest {
+        id: 0,
+        created_ms: now_ms(),
+        params: params.clone(),
+        scans: scans.len(),
+        opportunities,
+        total_pnl: equity,
+        win_rate: if trades.is_empty() {
+            0.0
+        } else {
+            wins as f64 / trades.len() as f64
+        },
+        max_drawdown,
+        triangles: summarize(&trades),
+        trades,
+    }
+}
+
+fn summarize(trades: &[Trade]) -> Vec<TriangleSummary> {
+    let mut by_triangle: BTreeMap<&str, Vec<&Trade>> = BTreeMap::new();
+    for t in trades {
+        by_triangle.entry(&t.triangle).or_default().push(t);
+    }
+    let mut out: Vec<TriangleSummary> = by_triangle
+        .into_iter()
+        .map(|(triangle, ts)| {
+            let profits = ts.iter().map(|t| t.profit_pct);
+            TriangleSummary {
+                triangle: triangle.to_string(),
+                trades: ts.len(),
+                pnl: ts.iter().map(|t| t.pnl).sum(),
+                avg_profit_pct: profits.clone().sum::<f64>() / ts.len() as f64,
+                best_profit_pct: profits.clone().fold(f64::MIN, f64::max),
+                worst_profit_pct: profits.fold(f64::MAX, f64::min),
+            }
+        })
+        .collect();
+    out.sort_by(|a, b| b.pnl.total_cmp(&a.pnl));
+    out
+}
+
+/// Grid cells a single sweep may run.
+const MAX_SWEEP_CELLS: usize = 10_000;
+
+/// Values to try per axis; an empty axis keeps the base value.
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct SweepRequest {
+    /// Settings shared by every cell (notional, window, cooldown, ...).
+    pub base: BacktestParams,
+    /// Fee per leg (%).
+    pub fees: Vec<f64>,
+    /// `min_profit` (%).
+    pub thresholds: Vec<f64>,
+    /// Slippage per leg (%).
+    pub slippages: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SweepCell {
+    pub fee_per_leg_pct: Option<f64>,
+    pub min_profit: f64,
+    pub slippage_pct: f64,
+    pub total_pnl: f64,
+    pub trades: usize,
+    pub opportunities: usize,
+    pub max_drawdown: f64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Sweep {
+    pub scans: usize,
+    /// Every combination, fee-major then threshold then slippage.
+    pub cells: Vec<SweepCell>,
+    /// The cell with the highest P&L.
+    pub best: Option<SweepCell>,
+}
+
+/// Backtest every combination of the request's axes over the retained
+/// scan history.
+pub fn sweep(req: &SweepRequest) -> Result<Sweep, String> {
+    let fees: Vec<Option<f64>> = match req.fees.as_slice() {
+        [] => vec![req.base.fee_per_leg_pct],
+        v => v.iter().copied().map(Some).collect(),
+    };
+    let thresholds = axis(&req.thresholds, req.base.min_profit);
+    let slippages = axis(&req.slippages, req.base.slippage_pct);
+    let total = fees.len() * thresholds.len() * slippages.len();
+    if total > MAX_SWEEP_CELLS {
+        return Err(format!(
+            "{} combinations, at most {} allowed",
+            total, MAX_SWEEP_CELLS
+        ));
+    }
+
+    let scans = history().since(0, usize::MAX);
+    let mut cells = Vec::with_capacity(total);
+    let mut scanned = 0;
+    for &fee in &fees {
+        for &min_profit in &thresholds {
+            for &slippage_pct in &slippages {
+                let params = BacktestParams {
+                    fee_per_leg_pct: fee,
+                    min_profit,
+                    slippage_pct,
+                    ..req.base.clone()
+                };
+                let b = run(&scans, &params);
+                scanned = b.scans;
+                cells.push(SweepCell {
+                    fee_per_leg_pct: fee,
+                    min_profit,
+                    slippage_pct,
+                    total_pnl: b.total_pnl,
+                    trades: b.trades.len(),
+                    opportunities: b.opportunities,
+                    max_drawdown: b.max_drawdown,
+                });
+            }
+        }
+    }
+    let best = cells
+        .iter()
+        .max_by(|a, b| a.total_pnl.total_cmp(&b.total_pnl))
+        .cloned();
+    Ok(Sweep {
+        scans: scanned,
+        cells,
+        best,
+    })
+}
+
+fn axis(values: &[f64], base: f64) -> Vec<f64> {
+    if values.is_empty() {
+        vec![base]
+    } else {
+        values.to_vec()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Backtests {
+    inner: RwLock<(u64, VecDeque<Backtest>)>,
+}
+
+impl Backtests {
+    /// Run over the retained scan history and keep the result.
+    pub fn start(&self, params: BacktestParams) -> Backtest {
+        let scans = history().since(0, usize::MAX);
+        let mut result = run(&scans, &params);
+        let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        guard.0 += 1;
+        result.id = guard.0;
+        guard.1.push_back(result.clone());
+        while guard.1.len() > MAX_RUNS {
+            guard.1.pop_front();
+        }
+        result
+    }
+
+    pub fn get(&self, id: u64) -> Option<Backtest> {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        guard.1.iter().find(|b| b.id == id).cloned()
+    }
+}
+
+/// One row per trade.
+pub fn csv_report(b: &Backtest) -> String {
+    let mut out = String::from("at_ms,scan_id,triangle,profit_pct,pnl,equity\n");
+    for t in &b.trades {
+        let _ = writeln!(
+            out,
+            "{},{},\"{}\",{:.6},{:.6},{:.6}",
+            t.at_ms, t.scan_id, t.triangle, t.profit_pct, t.pnl, t.equity
+        );
+    }
+    out
+}
+
+/// Standalone page: headline numbers, equity curve (inline SVG) and the
+/// per-triangle table.
+pub fn html_report(b: &Backtest) -> String {
+    let p = &b.params;
+    let fee = p
+        .fee_per_leg_pct
+        .map_or("as scanned".to_string(), |f| format!("{}%", f));
+    let mut rows = String::new();
+    for t in &b.triangles {
+        let _ = writeln!(
+            rows,
+            "<tr><td>{}</td><td>{}</td><td>{:.2}</td><td>{:.4}</td><td>{:.4}</td><td>{:.4}</td></tr>",
+            escape(&t.triangle),
+            t.trades,
+            t.pnl,
+            t.avg_profit_pct,
+            t.best_profit_pct,
+            t.worst_profit_pct
+        );
+    }
+    format!(
+        r#"<!doctype html>
+<html>
+<head><meta charset="utf-8"><title>Backtest {id}</title>
+<style>body{{font-family:sans-serif;margin:2em}}table{{border-collapse:collapse}}td,th{{border:1px solid #ccc;padding:4px 8px;text-align:right}}td:first-child{{text-align:left}}</style>
+</head>
+<body>
+<h1>Backtest {id}</h1>
+<p>Fee per leg {fee}, slippage {slip}% per leg, min profit {min}%, notional {notional}, cooldown {cooldown} ms.</p>
+<p>{scans} scans, {opps} opportunities, {trades} trades. Total P&amp;L <b>{pnl:.2}</b>, win rate {win:.1}%, max drawdown {dd:.2}.</p>
+<h2>Equity curve</h2>
+{curve}
+<h2>Triangles</h2>
+<table>
+<tr><th>Triangle</th><th>Trades</th><th>P&amp;L</th><th>Avg %</th><th>Best %</th><th>Worst %</th></tr>
+{rows}</table>
+</body>
+</html>
+"#,
+        id = b.id,
+        fee = fee,
+        slip = p.slippage_pct,
+        min = p.min_profit,
+        notional = p.notional,
+        cooldown = p.cooldown_ms,
+        scans = b.scans,
+        opps = b.opportunities,
+        trades = b.trades.len(),
+        pnl = b.total_pnl,
+        win = b.win_rate * 100.0,
+        dd = b.max_drawdown,
+        curve = equity_svg(&b.trades),
+        rows = rows,
+    )
+}
+
+fn equity_svg(trades: &[Trade]) -> String {
+    const W: f64 = 800.0;
+    const H: f64 = 240.0;
+    if trades.is_empty() {
+        return "<p>No trades.</p>".to_string();
+    }
+    let (lo, hi) = trades.iter().fold((0.0f64, 0.0f64), |(lo, hi), t| {
+        (lo.min(t.equity), hi.max(t.equity))
+    });
+    let span = (hi - lo).max(f64::EPSILON);
+    let step = W / trades.len() as f64;
+    let mut points = format!("0,{:.1}", H - (0.0 - lo) / span * H);
+    for (i, t) in trades.iter().enumerate() {
+        let x = (i + 1) as f64 * step;
+        let y = H - (t.equity - lo) / span * H;
+        let _ = write!(points, " {:.1},{:.1}", x, y);
+    }
+    let zero = H - (0.0 - lo) / span * H;
+    format!(
+        r##"<svg width="{W}" height="{H}" viewBox="0 0 {W} {H}" style="border:1px solid #ccc">
+<line x1="0" y1="{zero:.1}" x2="{W}" y2="{zero:.1}" stroke="#bbb" stroke-dasharray="4"/>
+<polyline fill="none" stroke="#2a7" stroke-width="2" points="{points}"/>
+</svg>"##
+    )
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,149 @@
+//! In-process scan benchmark: a seeded synthetic pair universe scanned
+//! repeatedly, reporting latency percentiles and allocations per scan.
+
+use crate::alloc::thread_totals;
+use crate::logic::find_triangular_opportunities;
+use crate::models::{PairPrice, ScanParams};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct BenchRequest {
+    /// Non-quote assets in the universe.
+    pub assets: usize,
+    /// Quote currencies every asset is listed against.
+    pub quotes: usize,
+    /// Share of asset/asset cross pairs that are listed, 0..1.
+    pub cross_density: f64,
+    pub iterations: usize,
+    /// Same seed, same universe; compare versions with a fixed one.
+    pub seed: u64,
+    pub neighbor_limit: usize,
+}
+
+impl Default for BenchRequest {
+    fn default() -> Self {
+        Self {
+            assets: 200,
+            quotes: 4,
+            cross_density: 0.05,
+            iterations: 20,
+            seed: 42,
+            neighbor_limit: ScanParams::default().neighbor_limit,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BenchReport {
+    pub pairs: usize,
+    pub iterations: usize,
+    /// Results of one scan (identical every iteration).
+    pub results: usize,
+    pub latency_ms: Percentiles,
+    pub allocations_per_scan: f64,
+    pub allocated_bytes_per_scan: f64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Percentiles {
+    pub min: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+    pub mean: f64,
+}
+
+/// Largest universe a request may generate, in pairs.
+const MAX_PAIRS: usize = 200_000;
+
+/// Run the benchmark on the calling thread (call from a blocking task).
+pub fn run(req: &BenchRequest) -> Result<BenchReport, String> {
+    let pairs = universe(req);
+    if pairs.len() > MAX_PAIRS {
+        return Err(format!(
+            "{} pairs, at most {} allowed",
+            pairs.len(),
+            MAX_PAIRS
+        ));
+    }
+    let params = ScanParams {
+        neighbor_limit: req.neighbor_limit,
+        ..ScanParams::default()
+    };
+    let iterations = req.iterations.max(1);
+    let mut latencies = Vec::with_capacity(iterations);
+    let (mut allocs, mut bytes) = (0, 0);
+    let mut results = 0;
+
+    for _ in 0..iterations {
+        let input = pairs.clone();
+        let (count_before, bytes_before) = thread_totals();
+        let started = Instant::now();
+        results = find_triangular_opportunities("bench", input, &params).len();
+        latencies.push(started.elapsed().as_secs_f64() * 1000.0);
+        let (count_after, bytes_after) = thread_totals();
+        allocs += count_after - count_before;
+        bytes += bytes_after - bytes_before;
+    }
+
+    latencies.sort_by(f64::total_cmp);
+    let pick = |q: f64| latencies[((iterations - 1) as f64 * q).round() as usize];
+    Ok(BenchReport {
+        pairs: pairs.len(),
+        iterations,
+        results,
+        latency_ms: Percentiles {
+            min: latencies[0],
+            p50: pick(0.5),
+            p90: pick(0.9),
+            p99: pick(0.99),
+            max: latencies[iterations - 1],
+            mean: latencies.iter().sum::<f64>() / iterations as f64,
+        },
+        allocations_per_scan: allocs as f64 / iterations as f64,
+        allocated_bytes_per_scan: bytes as f64 / iterations as f64,
+    })
+}
+
+/// Consistent prices from random fair values, each pair nudged by up to
+/// ±0.2% so some cycles clear the fees.
+fn universe(req: &BenchRequest) -> Vec<PairPrice> {
+    let mut rng = StdRng::seed_from_u64(req.seed);
+    let quotes: Vec<(String, f64)> = (0..req.quotes)
+        .map(|i| (format!("Q{}", i), rng.gen_range(0.5..2.0)))
+        .collect();
+    let assets: Vec<(String, f64)> = (0..req.assets)
+        .map(|i| (format!("A{}", i), rng.gen_range(0.01..1000.0)))
+        .collect();
+
+    let pair = |base: &(String, f64), quote: &(String, f64), rng: &mut StdRng| PairPrice {
+        base: base.0.clone(),
+        quote: quote.0.clone(),
+        price: base.1 / quote.1 * (1.0 + rng.gen_range(-0.002..0.002)),
+        is_spot: true,
+        volume: rng.gen_range(1_000.0..1_000_000.0),
+        event_time: 0,
+        bid: None,
+        ask: None,
+    };
+    let mut out = Vec::new();
+    for a in &assets {
+        for q in &quotes {
+            out.push(pair(a, q, &mut rng));
+        }
+    }
+    for (i, a) in assets.iter().enumerate() {
+        for b in &assets[i + 1..] {
+            if rng.gen_bool(req.cross_density.clamp(0.0, 1.0)) {
+                out.push(pair(a, b, &mut rng));
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,130 @@
+use crate::config::config;
+use crate::models::PairPrice;
+use crate::store::pair_key;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::RwLock;
+use tracing::{info, warn};
+use utoipa::{IntoParams, ToSchema};
+
+/// Symbols and assets excluded from ingestion and scans, managed through
+/// `/admin/blacklist` and saved to `blacklist.path` on every change.
+static BLACKLIST: Lazy<Blacklist> = Lazy::new(Blacklist::load);
+
+pub fn blacklist() -> &'static Blacklist {
+    &BLACKLIST
+}
+
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema, IntoParams,
+)]
+#[into_params(parameter_in = Query)]
+pub struct BlacklistEntry {
+    /// "BASE/QUOTE" for one pair, or a bare asset to drop every pair
+    /// containing it.
+    pub symbol: String,
+    /// Only on this exchange; absent means everywhere.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exchange: Option<String>,
+}
+
+impl BlacklistEntry {
+    fn normalized(&self) -> Self {
+        Self {
+            symbol: self.symbol.trim().to_uppercase(),
+            exchange: self.exchange.as_deref().map(|e| e.trim().to_lowercase()),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Blacklist {
+    entries: RwLock<BTreeSet<BlacklistEntry>>,
+}
+
+impl Blacklist {
+    fn load() -> Self {
+        let mut entries = BTreeSet::new();
+        if let Some(path) = config().blacklist.path.as_deref() {
+            if let Ok(text) = std::fs::read_to_string(path) {
+                match serde_json::from_str::<Vec<BlacklistEntry>>(&text) {
+                    Ok(list) => {
+                        entries.extend(list.iter().map(BlacklistEntry::normalized));
+                        info!("loaded {} blacklist entries from {}", entries.len(), path);
+                    }
+                    Err(e) => warn!("ignoring unreadable blacklist {}: {}", path, e),
+                }
+            }
+        }
+        Self {
+            entries: RwLock::new(entries),
+        }
+    }
+
+    pub fn list(&self) -> Vec<BlacklistEntry> {
+        let guard = self.entries.read().unwrap_or_else(|e| e.into_inner());
+        guard.iter().cloned().collect()
+    }
+
+    /// Add an entry; false if it was already listed.
+    pub fn add(&self, entry: &BlacklistEntry) -> bool {
+        let mut guard = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        let added = guard.insert(entry.normalized());
+        if added {
+            save(&guard);
+        }
+        added
+    }
+
+    /// Remove an entry; false if it wasn't listed.
+    pub fn remove(&self, entry: &BlacklistEntry) -> bool {
+        let mut guard = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        let removed = guard.remove(&entry.normalized());
+        if removed {
+            save(&guard);
+        }
+        removed
+    }
+
+    /// Whether `p` on `exchange` is excluded, by pair or by either asset,
+    /// globally or for that exchange.
+    pub fn blocks(&self, exchange: &str, p: &PairPrice) -> bool {
+        let guard = self.entries.read().unwrap_or_else(|e| e.into_inner());
+        if guard.is_empty() {
+            return false;
+        }
+        let base = p.base.to_uppercase();
+        let quote = p.quote.to_uppercase();
+        let exchange = exchange.to_lowercase();
+        [pair_key(&base, &quote), base, quote]
+            .into_iter()
+            .any(|symbol| {
+                [None, Some(exchange.clone())].into_iter().any(|exchange| {
+                    guard.contains(&BlacklistEntry {
+                        symbol: symbol.clone(),
+                        exchange,
+                    })
+                })
+            })
+    }
+
+    /// Drop blacklisted pairs from a scan's input.
+    pub fn retain(&self, exchange: &str, pairs: &mut Vec<PairPrice>) {
+        pairs.retain(|p| !self.blocks(exchange, p));
+    }
+}
+
+fn save(entries: &BTreeSet<BlacklistEntry>) {
+    let Some(path) = config().blacklist.path.as_deref() else {
+        return;
+    };
+    let tmp = format!("{}.tmp", path);
+    let written = serde_json::to_string_pretty(entries)
+        .map_err(|e| e.to_string())
+        .and_then(|text| std::fs::write(&tmp, text).map_err(|e| e.to_string()))
+        .and_then(|_| std::fs::rename(&tmp, path).map_err(|e| e.to_string()));
+    if let Err(e) = written {
+        warn!("cannot persist blacklist to {}: {}", path, e);
+    }
+}
@@ -0,0 +1,255 @@
+use crate::config::{config, BusConfig};
+use crate::exchanges::Delta;
+use crate::health::health;
+use crate::models::PairPrice;
+use crate::protocol::PriceUpdate;
+use crate::store::{pair_key, SharedPrices};
+use crate::tasks;
+use crate::utils::now_ms;
+use crate::ws_manager::{Backoff, SessionEnd};
+use futures::StreamExt;
+use once_cell::sync::OnceCell;
+use redis::AsyncCommands;
+use std::collections::{HashMap, HashSet};
+use tokio::sync::mpsc;
+use tokio::time::Interval;
+use tracing::{error, info, warn};
+
+/// Flushes waiting for the Redis connection; older ones are dropped when
+/// the collector can't keep up or Redis is down.
+const PUBLISH_BACKLOG: usize = 10_000;
+
+/// Set on collector nodes; every store flush is queued here for Redis.
+static PUBLISHER: OnceCell<mpsc::Sender<String>> = OnceCell::new();
+
+/// Collector role: publish every store flush to `bus.channel`, plus a full
+/// snapshot of the store every `bus.snapshot_interval_ms`.
+pub fn start_publisher(prices: SharedPrices) {
+    let (tx, rx) = mpsc::channel(PUBLISH_BACKLOG);
+    if PUBLISHER.set(tx).is_ok() {
+        tasks::spawn(
+            "bus:publisher",
+            run_publisher(config().bus.clone(), rx, prices),
+        );
+    }
+}
+
+/// Queue one flush for publishing; a no-op outside the collector role.
+pub fn publish(exchange: &str, delta: &Delta) {
+    let Some(tx) = PUBLISHER.get() else {
+        return;
+    };
+    if delta.changed.is_empty() && delta.removed.is_empty() {
+        return;
+    }
+    let payload = PriceUpdate::new(exchange, delta, now_ms()).encode();
+    if tx.try_send(payload).is_err() {
+        warn!("bus: publish backlog full, dropping {} flush", exchange);
+    }
+}
+
+/// API role: apply the flushes collectors publish to the local store.
+pub fn start_subscriber(prices: SharedPrices) {
+    tasks::spawn(
+        "bus:subscriber",
+        run_subscriber(config().bus.clone(), prices),
+    );
+}
+
+async fn run_publisher(cfg: BusConfig, mut rx: mpsc::Receiver<String>, prices: SharedPrices) {
+    let mut backoff = Backoff::default();
+    let mut snapshots = tokio::time::interval(cfg.snapshot_interval());
+    loop {
+        let Some(client) = client(&cfg) else {
+            return;
+        };
+        let mut conn = match client.get_multiplexed_tokio_connection().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!("bus: cannot connect to redis: {}", e);
+                backoff.wait().await;
+                continue;
+            }
+        };
+        info!("bus: publishing price updates to {}", cfg.channel);
+        // Subscribers that joined while we were away get everything at once.
+        snapshots.reset_immediately();
+        'session: loop {
+            let Some(payloads) = next_payloads(&mut rx, &mut snapshots, &prices).await else {
+                return;
+            };
+            for payload in payloads {
+                match conn.publish::<_, _, i64>(&cfg.channel, payload).await {
+                    Ok(_) => {}
+                    Err(e) if e.is_io_error() || e.is_connection_dropped() => {
+                        error!("bus: publish failed: {}", e);
+                        break 'session;
+                    }
+                    Err(e) => warn!("bus: publish rejected: {}", e),
+                }
+            }
+        }
+        backoff.after(SessionEnd::Closed).await;
+    }
+}
+
+/// The next payloads to publish; `None` once the queue is closed. Queued
+/// flushes are already in the store, so they always go out before a
+/// snapshot: sent after it, they would roll subscribers back.
+async fn next_payloads(
+    rx: &mut mpsc::Receiver<String>,
+    snapshots: &mut Interval,
+    prices: &SharedPrices,
+) -> Option<Vec<String>> {
+    tokio::select! {
+        biased;
+        payload = rx.recv() => payload.map(|payload| vec![payload]),
+        _ = snapshots.tick() => {
+            let mut payloads: Vec<String> = std::iter::from_fn(|| rx.try_recv().ok()).collect();
+            payloads.extend(full_snapshots(prices));
+            Some(payloads)
+        }
+    }
+}
+
+/// One full update per exchange in the store.
+fn full_snapshots(prices: &SharedPrices) -> Vec<String> {
+    let at_ms = now_ms();
+    prices
+        .exchanges()
+        .iter()
+        .map(|exchange| PriceUpdate::full(exchange, prices.snapshot(exchange), at_ms).encode())
+        .collect()
+}
+
+async fn run_subscriber(cfg: BusConfig, prices: SharedPrices) {
+    let mut backoff = Backoff::default();
+    loop {
+        let Some(client) = client(&cfg) else {
+            return;
+        };
+        let mut pubsub = match client.get_async_pubsub().await {
+            Ok(pubsub) => pubsub,
+            Err(e) => {
+                error!("bus: cannot connect to redis: {}", e);
+                backoff.wait().await;
+                continue;
+            }
+        };
+        if let Err(e) = pubsub.subscribe(&cfg.channel).await {
+            error!("bus: subscribe failed: {}", e);
+            backoff.wait().await;
+            continue;
+        }
+        info!("bus: consuming price updates from {}", cfg.channel);
+        // Exchanges seen on this connection count as connected until it drops.
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut messages = pubsub.on_message();
+        while let Some(msg) = messages.next().await {
+            match PriceUpdate::decode(msg.get_payload_bytes()) {
+                Ok(update) => {
+                    let full = update.full;
+                    let (exchange, delta) = update.into_delta();
+                    if seen.insert(exchange.clone()) {
+                        health().set_connected(&exchange, true);
+                    }
+                    health().record_message(&exchange);
+                    let delta = if full {
+                        resync(&prices.snapshot(&exchange), delta.changed)
+                    } else {
+                        delta
+                    };
+                    prices.apply_delta(&exchange, delta);
+                }
+                Err(e) => warn!("bus: dropping unreadable update: {}", e),
+            }
+        }
+        error!("bus: subscription to {} dropped", cfg.channel);
+        drop(messages);
+        for exchange in &seen {
+            health().set_connected(exchange, false);
+        }
+        backoff.after(SessionEnd::Closed).await;
+    }
+}
+
+/// Client for `bus.redis_url`; `None` (logged) when the URL is unusable,
+/// which retrying won't fix.
+fn client(cfg: &BusConfig) -> Option<redis::Client> {
+    match redis::Client::open(cfg.redis_url.as_str()) {
+        Ok(client) => Some(client),
+        Err(e) => {
+            error!("bus: invalid bus.redis_url: {}", e);
+            None
+        }
+    }
+}
+
+/// The delta turning `local` into a collector's full snapshot: symbols
+/// missing or different here, and those the collector no longer has.
+/// Unchanged symbols are left alone so their freshness stays accurate.
+fn resync(local: &[PairPrice], snapshot: Vec<PairPrice>) -> Delta {
+    let mut local: HashMap<String, &PairPrice> = local
+        .iter()
+        .map(|p| (pair_key(&p.base, &p.quote), p))
+        .collect();
+    let mut changed = Vec::new();
+    for p in snapshot {
+        let same = local.remove(&pair_key(&p.base, &p.quote)).is_some_and(|l| {
+            l.event_time == p.event_time && l.price == p.price && l.bid == p.bid && l.ask == p.ask
+        });
+        if !same {
+            changed.push(p);
+        }
+    }
+    Delta {
+        changed,
+        removed: local.into_keys().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(base: &str, price: f64) -> PairPrice {
+        PairPrice {
+            base: base.into(),
+            quote: "USDT".into(),
+            price,
+            is_spot: true,
+            volume: 0.0,
+            event_time: 1,
+            bid: None,
+            ask: None,
+        }
+    }
+
+    #[test]
+    fn resync_fills_gaps_and_drops_stale_symbols() {
+        let local = vec![pair("BTC", 100.0), pair("ETH", 10.0), pair("OLD", 1.0)];
+        let snapshot = vec![pair("BTC", 100.0), pair("ETH", 11.0), pair("SOL", 5.0)];
+        let delta = resync(&local, snapshot);
+        let mut changed: Vec<_> = delta.changed.iter().map(|p| p.base.as_str()).collect();
+        changed.sort();
+        assert_eq!(changed, ["ETH", "SOL"]);
+        assert_eq!(delta.removed, ["OLD/USDT"]);
+    }
+
+    #[tokio::test]
+    async fn queued_flushes_go_out_before_a_snapshot() {
+        let (tx, mut rx) = mpsc::channel(4);
+        tx.send("flush".to_string()).await.unwrap();
+        // The first tick is due at once, racing the queued flush.
+        let mut snapshots = tokio::time::interval(std::time::Duration::from_secs(60));
+        let prices = SharedPrices::default();
+        assert_eq!(
+            next_payloads(&mut rx, &mut snapshots, &prices).await,
+            Some(vec!["flush".to_string()])
+        );
+        assert_eq!(
+            next_payloads(&mut rx, &mut snapshots, &prices).await,
+            Some(vec![])
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use crate::config::config;
+use crate::models::PairPrice;
+use crate::store::pair_key;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::RwLock;
+use utoipa::ToSchema;
+
+/// OHLCV bars per symbol built from store flushes, for `/candles` and the
+/// scan-time models that need recent price action.
+static CANDLES: Lazy<CandleBook> = Lazy::new(CandleBook::default);
+
+pub fn candles() -> &'static CandleBook {
+    &CANDLES
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum Interval {
+    #[serde(rename = "1s")]
+    Second,
+    #[serde(rename = "1m")]
+    Minute,
+}
+
+impl Interval {
+    pub fn ms(self) -> u64 {
+        match self {
+            Interval::Second => 1_000,
+            Interval::Minute => 60_000,
+        }
+    }
+
+    /// Bars kept per symbol.
+    fn capacity(self) -> usize {
+        let cfg = &config().candles;
+        match self {
+            Interval::Second => cfg.second_bars,
+            Interval::Minute => cfg.minute_bars,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, ToSchema)]
+pub struct Candle {
+    pub start_ms: u64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    /// Growth of the venue's rolling 24h volume during the bar, an
+    /// approximation of traded volume (0 for venues reporting none).
+    pub volume: f64,
+    /// Price updates that went into the bar.
+    pub ticks: u32,
+}
+
+#[derive(Debug, Default)]
+struct Series {
+    seconds: VecDeque<Candle>,
+    minutes: VecDeque<Candle>,
+    /// Last reported 24h volume, to turn it into per-bar increments.
+    last_volume: Option<f64>,
+}
+
+impl Series {
+    fn bars(&self, interval: Interval) -> &VecDeque<Candle> {
+        match interval {
+            Interval::Second => &self.seconds,
+            Interval::Minute => &self.minutes,
+        }
+    }
+
+    fn bars_mut(&mut self, interval: Interval) -> &mut VecDeque<Candle> {
+        match interval {
+            Interval::Second => &mut self.seconds,
+            Interval::Minute => &mut self.minutes,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CandleBook {
+    /// (exchange, "BASE/QUOTE") -> bars.
+    inner: RwLock<HashMap<(String, String), Series>>,
+}
+
+impl CandleBook {
+    /// Fold one flush worth of changed prices into the bars. Ticks use the
+    /// exchange timestamp when there is one, else `now`; ticks older than
+    /// the current bar are ignored.
+    pub fn record(&self, exchange: &str, changed: &[PairPrice], now: u64) {
+        if changed.is_empty() {
+            return;
+        }
+        let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        for p in changed {
+            let key = (exchange.to_string(), pair_key(&p.base, &p.quote));
+            let series = guard.entry(key).or_default();
+            let traded = series
+                .last_volume
+                .map_or(0.0, |prev| (p.volume - prev).max(0.0));
+            series.last_volume = Some(p.volume);
+            let ts = if p.event_time > 0 { p.event_time } else { now };
+            for interval in [Interval::Second, Interval::Minute] {
+                let start = ts - ts % interval.ms();
+                let bars = series.bars_mut(interval);
+                match bars.back_mut() {
+                    Some(bar) if bar.start_ms == start => {
+                        bar.high = bar.high.max(p.price);
+                        bar.low = bar.low.min(p.price);
+                        bar.close = p.price;
+                        bar.volume += traded;
+                        bar.ticks += 1;
+                    }
+                    Some(bar) if bar.start_ms > start => {}
+                    _ => {
+                        bars.push_back(Candle {
+                            start_ms: start,
+                            open: p.price,
+                            high: p.price,
+                            low: p.price,
+                            close: p.price,
+                            volume: traded,
+                            ticks: 1,
+                        });
+                        while bars.len() > interval.capacity() {
+                            bars.pop_front();
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Standard deviation (%) of the 1s log returns over the latest
+    /// `window` one-second bars; None with fewer than three bars.
+    pub fn realized_volatility(&self, exchange: &str, symbol: &str, window: usize) -> Option<f64> {
+        let bars = self.latest(exchange, symbol, Interval::Second, window);
+        if bars.len() < 3 {
+            return None;
+        }
+        let returns: Vec<f64> = bars
+            .windows(2)
+            .map(|w| (w[1].close / w[0].close).ln())
+            .collect();
+        let n = returns.len() as f64;
+        let mean = returns.iter().sum::<f64>() / n;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        Some(variance.sqrt() * 100.0)
+    }
+
+    /// The latest `limit` bars of one symbol, oldest first. Intervals
+    /// without ticks have no bar.
+    pub fn latest(
+        &self,
+        exchange: &str,
+        symbol: &str,
+        interval: Interval,
+        limit: usize,
+    ) -> Vec<Candle> {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        guard
+            .get(&(exchange.to_string(), symbol.to_string()))
+            .map(|series| {
+                let bars = series.bars(interval);
+                bars.iter()
+                    .skip(bars.len().saturating_sub(limit))
+                    .copied()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
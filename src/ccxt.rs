@@ -0,0 +1,180 @@
+//! Markets and tickers in ccxt's unified structures, so clients written
+//! against ccxt (`exchange.markets`, `fetchTickers()`) can read this
+//! scanner's data without a translation layer.
+
+use crate::models::PairPrice;
+use crate::rest::Instrument;
+use crate::store::{pair_key, SharedPrices};
+use crate::symbols::symbols;
+use chrono::{TimeZone, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+/// ccxt `Market`, restricted to spot and to the fields we can fill.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Market {
+    pub id: String,
+    /// Unified symbol, "BASE/QUOTE".
+    pub symbol: String,
+    pub base: String,
+    pub quote: String,
+    pub base_id: String,
+    pub quote_id: String,
+    pub active: bool,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub spot: bool,
+    pub margin: bool,
+    pub swap: bool,
+    pub future: bool,
+    pub option: bool,
+    pub contract: bool,
+    pub precision: MarketPrecision,
+    pub limits: MarketLimits,
+}
+
+/// ccxt `precision`, as tick sizes (ccxt's `TICK_SIZE` mode).
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct MarketPrecision {
+    pub price: Option<f64>,
+    pub amount: Option<f64>,
+}
+
+/// ccxt `limits`; only the minimum order cost is known.
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct MarketLimits {
+    pub cost: MinMax,
+}
+
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct MinMax {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl Market {
+    fn spot(id: String, base: &str, quote: &str, active: bool) -> Self {
+        Self {
+            id,
+            symbol: pair_key(base, quote),
+            base: base.to_string(),
+            quote: quote.to_string(),
+            base_id: base.to_string(),
+            quote_id: quote.to_string(),
+            active,
+            kind: "spot",
+            spot: true,
+            margin: false,
+            swap: false,
+            future: false,
+            option: false,
+            contract: false,
+            precision: MarketPrecision::default(),
+            limits: MarketLimits::default(),
+        }
+    }
+}
+
+impl From<Instrument> for Market {
+    fn from(i: Instrument) -> Self {
+        let mut m = Market::spot(i.symbol, &i.base, &i.quote, i.trading);
+        m.precision = MarketPrecision {
+            price: i.tick_size,
+            amount: i.step_size,
+        };
+        m.limits.cost.min = i.min_notional;
+        m
+    }
+}
+
+/// ccxt `Ticker`; fields we have no data for are null, as in ccxt.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Ticker {
+    pub symbol: String,
+    pub timestamp: Option<u64>,
+    pub datetime: Option<String>,
+    pub high: Option<f64>,
+    pub low: Option<f64>,
+    pub bid: Option<f64>,
+    pub bid_volume: Option<f64>,
+    pub ask: Option<f64>,
+    pub ask_volume: Option<f64>,
+    pub vwap: Option<f64>,
+    pub open: Option<f64>,
+    pub close: f64,
+    pub last: f64,
+    pub previous_close: Option<f64>,
+    pub change: Option<f64>,
+    pub percentage: Option<f64>,
+    pub average: Option<f64>,
+    pub base_volume: f64,
+    pub quote_volume: Option<f64>,
+}
+
+impl From<PairPrice> for Ticker {
+    fn from(p: PairPrice) -> Self {
+        let timestamp = (p.event_time > 0).then_some(p.event_time);
+        Self {
+            symbol: pair_key(&p.base, &p.quote),
+            timestamp,
+            datetime: timestamp.and_then(iso8601),
+            high: None,
+            low: None,
+            bid: p.bid,
+            bid_volume: None,
+            ask: p.ask,
+            ask_volume: None,
+            vwap: None,
+            open: None,
+            close: p.price,
+            last: p.price,
+            previous_close: None,
+            change: None,
+            percentage: None,
+            average: None,
+            base_volume: p.volume,
+            quote_volume: None,
+        }
+    }
+}
+
+fn iso8601(ms: u64) -> Option<String> {
+    Utc.timestamp_millis_opt(ms as i64)
+        .single()
+        .map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+}
+
+/// `exchange.markets`: unified symbol -> market. Taken from the venue's
+/// instrument metadata (see [`crate::symbols`]), or from the symbols in the store for venues without a
+/// REST client (mock, declarative).
+pub async fn markets(exchange: &str, prices: &SharedPrices) -> BTreeMap<String, Market> {
+    match symbols().load(exchange).await {
+        Ok((_, list)) => list
+            .into_iter()
+            .map(Market::from)
+            .map(|m| (m.symbol.clone(), m))
+            .collect(),
+        Err(_) => prices
+            .snapshot(exchange)
+            .into_iter()
+            .map(|p| {
+                let id = format!("{}{}", p.base, p.quote);
+                let m = Market::spot(id, &p.base, &p.quote, true);
+                (m.symbol.clone(), m)
+            })
+            .collect(),
+    }
+}
+
+/// `fetchTickers()`: unified symbol -> latest ticker held in the store.
+pub fn tickers(exchange: &str, prices: &SharedPrices) -> BTreeMap<String, Ticker> {
+    prices
+        .snapshot(exchange)
+        .into_iter()
+        .map(Ticker::from)
+        .map(|t| (t.symbol.clone(), t))
+        .collect()
+}
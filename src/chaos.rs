@@ -0,0 +1,46 @@
+use crate::config::config;
+use rand::Rng;
+use tokio::time::Duration;
+
+/// A fault to apply to one received frame.
+pub enum Fault {
+    Disconnect,
+    Delay(Duration),
+    /// Parse this mangled payload instead of the real one.
+    Corrupt(String),
+}
+
+/// Roll the configured fault probabilities for one frame. Always `None`
+/// unless chaos mode is enabled for `exchange`.
+pub fn roll(exchange: &str, frame: &str) -> Option<Fault> {
+    let cfg = &config().chaos;
+    if !cfg.applies_to(exchange) {
+        return None;
+    }
+    let mut rng = rand::thread_rng();
+    if rng.gen_bool(cfg.disconnect_prob.clamp(0.0, 1.0)) {
+        return Some(Fault::Disconnect);
+    }
+    if rng.gen_bool(cfg.delay_prob.clamp(0.0, 1.0)) {
+        let ms = rng.gen_range(0..=cfg.delay_ms);
+        return Some(Fault::Delay(Duration::from_millis(ms)));
+    }
+    if rng.gen_bool(cfg.corrupt_prob.clamp(0.0, 1.0)) {
+        return Some(Fault::Corrupt(corrupt(frame, &mut rng)));
+    }
+    None
+}
+
+/// Truncate at a random char boundary, or flip the frame into a JSON shape
+/// no connector expects.
+fn corrupt(frame: &str, rng: &mut impl Rng) -> String {
+    if rng.gen_bool(0.5) {
+        let mut cut = rng.gen_range(0..=frame.len());
+        while !frame.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        frame[..cut].to_string()
+    } else {
+        r#"{"chaos":true,"data":[{"s":null,"c":"NaN"}]}"#.to_string()
+    }
+}
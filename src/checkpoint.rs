@@ -0,0 +1,77 @@
+//! Crash-safe snapshots of the price store and triangle statistics, so a
+//! restart serves (clearly marked) prices right away instead of empty scans
+//! while the feeds warm up.
+
+use crate::config::config;
+use crate::stats::stats;
+use crate::store::{SharedPrices, StoreCheckpoint};
+use crate::tasks;
+use crate::utils::now_ms;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use tokio::time::Duration;
+use tracing::{info, warn};
+
+#[derive(Serialize, Deserialize)]
+struct Checkpoint {
+    store: StoreCheckpoint,
+    /// Triangle appearances behind `context.seen_1h`.
+    #[serde(default)]
+    triangles: HashMap<String, VecDeque<(u64, f64)>>,
+}
+
+/// Reload the last checkpoint into `prices`, then keep writing new ones
+/// every `checkpoint.every_ms`. Call before the connectors start.
+pub fn start(prices: SharedPrices) {
+    let cfg = config().checkpoint.clone();
+    let Some(path) = cfg.path else {
+        return;
+    };
+    restore(&path, cfg.max_age_ms, &prices);
+
+    let every = Duration::from_millis(cfg.every_ms.max(1_000));
+    tasks::spawn("checkpoint", async move {
+        let mut timer = tokio::time::interval(every);
+        timer.tick().await;
+        loop {
+            timer.tick().await;
+            let (path, prices) = (path.clone(), prices.clone());
+            if let Ok(Err(e)) = tokio::task::spawn_blocking(move || save(&path, &prices)).await {
+                warn!("writing checkpoint: {}", e);
+            }
+        }
+    });
+}
+
+fn restore(path: &str, max_age_ms: u64, prices: &SharedPrices) {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(_) => return,
+    };
+    let cp: Checkpoint = match serde_json::from_str(&text) {
+        Ok(cp) => cp,
+        Err(e) => {
+            warn!("ignoring bad checkpoint {}: {}", path, e);
+            return;
+        }
+    };
+    let age_s = now_ms().saturating_sub(cp.store.saved_ms) / 1000;
+    stats().import(cp.triangles);
+    let loaded = prices.restore(cp.store, max_age_ms);
+    info!(
+        "restored {} symbols from {} (saved {}s ago); flagged until live data arrives",
+        loaded, path, age_s
+    );
+}
+
+/// Write via a temp file so a crash mid-write keeps the previous checkpoint.
+pub fn save(path: &str, prices: &SharedPrices) -> std::io::Result<()> {
+    let cp = Checkpoint {
+        store: prices.checkpoint(),
+        triangles: stats().export(),
+    };
+    let json = serde_json::to_vec(&cp).map_err(std::io::Error::other)?;
+    let tmp = format!("{}.tmp", path);
+    std::fs::write(&tmp, json)?;
+    std::fs::rename(&tmp, path)
+}
@@ -0,0 +1,1236 @@
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::info;
+
+/// Global configuration, loaded once by [`init`] from `SCANNER_CONFIG`
+/// (default `config.json`). A missing file is not an error: every field has
+/// a sensible default.
+static CONFIG: OnceCell<Config> = OnceCell::new();
+
+pub fn config() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Per-connector settings keyed by exchange name (e.g. "binance").
+    pub connectors: HashMap<String, ConnectorConfig>,
+    /// REST ticker polling for venues whose stream is down or stale.
+    pub fallback: FallbackConfig,
+    /// Instrument metadata refresh (listings and trading status).
+    pub symbols: SymbolsConfig,
+    /// Leveraged tokens (BTCUP, ETH3L, ...) left out at ingestion.
+    pub leveraged_tokens: LeveragedTokensConfig,
+    /// Venues described entirely in config and run by the declarative connector.
+    pub declarative: Vec<DeclarativeSpec>,
+    /// Uniswap v3 pools polled over JSON-RPC (run when `connectors.uniswap`
+    /// is enabled).
+    pub uniswap: DexPoolsConfig,
+    /// PancakeSwap pairs on BSC (run when `connectors.pancakeswap` is enabled).
+    pub pancakeswap: DexPoolsConfig,
+    /// Raydium/Orca pools streamed from a Solana RPC WebSocket (run when
+    /// `connectors.solana-dex` is enabled).
+    pub solana_dex: SolanaDexConfig,
+    /// Curve stable pools read over JSON-RPC (run when `connectors.curve` is
+    /// enabled).
+    pub curve: DexPoolsConfig,
+    /// Osmosis pools polled from an LCD endpoint (run when
+    /// `connectors.osmosis` is enabled).
+    pub osmosis: OsmosisConfig,
+    /// Tokens priced from Jupiter swap quotes (run when `connectors.jupiter`
+    /// is enabled).
+    pub jupiter: JupiterConfig,
+    /// Built-in synthetic exchange for offline demos and CI.
+    pub mock: MockConfig,
+    /// High-volume generated venue for soak tests.
+    pub synthetic: SyntheticConfig,
+    /// Fault injection for exercising reconnect/health logic; off by default.
+    pub chaos: ChaosConfig,
+    pub load_shed: LoadShedConfig,
+    pub scan: ScanConfig,
+    pub history: HistoryConfig,
+    pub blacklist: BlacklistConfig,
+    pub ticks: TickConfig,
+    pub candles: CandleConfig,
+    /// Notifications for scan results; bodies may be templated.
+    pub alerts: Vec<AlertConfig>,
+    /// Append opportunities or job summaries to a Google Sheet.
+    pub sheets: Option<SheetsConfig>,
+    /// Scans run on a timer; see `/admin/jobs`.
+    pub jobs: Vec<JobConfig>,
+    /// What this process runs; split roles exchange prices over `bus`.
+    pub role: Role,
+    pub bus: BusConfig,
+    /// Read-only API keys whose balances make up `/portfolio`.
+    pub accounts: Vec<AccountConfig>,
+    /// Tokio thread pools; read once at startup.
+    pub runtime: RuntimeConfig,
+    /// Counters kept across restarts for `/stats/lifetime`.
+    pub lifetime: LifetimeConfig,
+    /// Periodic snapshot of the price store reloaded on startup.
+    pub checkpoint: CheckpointConfig,
+    /// Local L2 books kept from snapshot + diff streams.
+    pub orderbook: OrderBookConfig,
+    /// Where diagnostic dumps (`POST /admin/dump`, SIGUSR1) are written.
+    pub dump: DumpConfig,
+    pub access_log: AccessLogConfig,
+    /// Where `/admin/*` is served and who may call it.
+    pub admin: AdminConfig,
+    pub shutdown: ShutdownConfig,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ShutdownConfig {
+    /// JSONL file each run's shutdown summary is appended to (unset only
+    /// logs it).
+    pub summary_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct AdminConfig {
+    /// Separate listener for the admin routes, e.g. "127.0.0.1:8081"; unset
+    /// serves them on the main port.
+    pub bind: Option<String>,
+    /// CIDRs (or single addresses) allowed to call the admin routes;
+    /// loopback only by default, and an empty list refuses every client.
+    pub allow: Vec<String>,
+    /// Reverse proxies whose `X-Forwarded-For` names the real client. A
+    /// request from one of them is checked against the client it forwards,
+    /// not against the proxy's own address.
+    pub trusted_proxies: Vec<String>,
+}
+
+impl Default for AdminConfig {
+    fn default() -> Self {
+        Self {
+            bind: None,
+            allow: vec!["127.0.0.1".into(), "::1".into()],
+            trusted_proxies: Vec::new(),
+        }
+    }
+}
+
+/// One line per HTTP request, separate from the application log.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AccessLogConfig {
+    pub enabled: bool,
+    pub format: AccessLogFormat,
+    /// File lines are appended to; unset logs them as tracing events with
+    /// target `access` instead.
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccessLogFormat {
+    /// Apache common log format plus latency and request ID.
+    #[default]
+    Common,
+    Json,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DumpConfig {
+    pub dir: String,
+}
+
+impl Default for DumpConfig {
+    fn default() -> Self {
+        Self { dir: ".".into() }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CheckpointConfig {
+    /// JSON file the store is written to (null disables checkpoints).
+    pub path: Option<String>,
+    pub every_ms: u64,
+    /// Symbols older than this at startup are not reloaded.
+    pub max_age_ms: u64,
+}
+
+impl Default for CheckpointConfig {
+    fn default() -> Self {
+        Self {
+            path: Some("checkpoint.json".into()),
+            every_ms: 30_000,
+            max_age_ms: 600_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LifetimeConfig {
+    /// JSON file the counters are saved to (null keeps them in memory only).
+    pub path: Option<String>,
+    pub save_every_ms: u64,
+}
+
+impl Default for LifetimeConfig {
+    fn default() -> Self {
+        Self {
+            path: Some("lifetime.json".into()),
+            save_every_ms: 60_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RuntimeConfig {
+    /// Async worker threads for the HTTP server and scans (0 = one per core).
+    pub worker_threads: usize,
+    /// Upper bound on the blocking pool (backtests, benchmarks, file I/O).
+    pub max_blocking_threads: usize,
+    /// Run the exchange connectors on their own runtime so ingestion bursts
+    /// can't delay HTTP requests, and vice versa.
+    pub dedicated_connectors: bool,
+    /// Worker threads of the connector runtime.
+    pub connector_threads: usize,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            worker_threads: 0,
+            max_blocking_threads: 512,
+            dedicated_connectors: false,
+            connector_threads: 1,
+        }
+    }
+}
+
+/// Exchange account queried for balances. Keys only need read permission.
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AccountConfig {
+    pub name: String,
+    pub exchange: String,
+    pub api_key: String,
+    pub api_secret: String,
+    /// KuCoin only.
+    pub passphrase: String,
+}
+
+impl std::fmt::Debug for AccountConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AccountConfig")
+            .field("name", &self.name)
+            .field("exchange", &self.exchange)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertKind {
+    #[default]
+    Webhook,
+    Telegram,
+}
+
+/// Where to send an alert for results at or above `min_profit`. The body
+/// is rendered from the Jinja `template` (see `template::render`) with the
+/// result's fields plus `exchange`; webhooks default to the result as JSON.
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct AlertConfig {
+    pub kind: AlertKind,
+    /// Webhook target.
+    pub url: String,
+    pub content_type: String,
+    pub bot_token: String,
+    pub chat_id: String,
+    pub template: Option<String>,
+    pub min_profit: f64,
+    /// Don't re-alert the same triangle within this window.
+    pub cooldown_ms: u64,
+    /// Leave out results with anomaly flags.
+    pub skip_flagged: bool,
+}
+
+impl std::fmt::Debug for AlertConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Webhook URLs and bot tokens are credentials.
+        f.debug_struct("AlertConfig")
+            .field("kind", &self.kind)
+            .field("chat_id", &self.chat_id)
+            .field("template", &self.template)
+            .field("min_profit", &self.min_profit)
+            .field("cooldown_ms", &self.cooldown_ms)
+            .field("skip_flagged", &self.skip_flagged)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        Self {
+            kind: AlertKind::Webhook,
+            url: String::new(),
+            content_type: "application/json".into(),
+            bot_token: String::new(),
+            chat_id: String::new(),
+            template: None,
+            min_profit: 0.3,
+            cooldown_ms: 60_000,
+            skip_flagged: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SheetRows {
+    /// One row per passing result, at most one per triangle per cooldown.
+    #[default]
+    Opportunities,
+    /// One row per scheduled scan.
+    Summaries,
+}
+
+/// Google Sheets export through a service account (share the sheet with
+/// the account's email).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SheetsConfig {
+    /// Service-account JSON key file.
+    pub credentials_path: String,
+    pub spreadsheet_id: String,
+    /// A1 range the rows are appended after, e.g. "Opportunities!A1".
+    pub range: String,
+    pub rows: SheetRows,
+    /// Export results at or above this; unset exports the results that pass
+    /// the scan's own `min_profit` (top-N near-misses left out).
+    pub min_profit: Option<f64>,
+    /// Don't export the same triangle on the same exchange again within
+    /// this window.
+    pub cooldown_ms: u64,
+}
+
+impl Default for SheetsConfig {
+    fn default() -> Self {
+        Self {
+            credentials_path: "service-account.json".into(),
+            spreadsheet_id: String::new(),
+            range: "Sheet1!A1".into(),
+            rows: SheetRows::Opportunities,
+            min_profit: None,
+            cooldown_ms: 60_000,
+        }
+    }
+}
+
+/// A scan of the live store run every `every_ms` and recorded in history
+/// like any other scan.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct JobConfig {
+    pub name: String,
+    pub every_ms: u64,
+    /// Empty scans every exchange with live prices (or the preset's).
+    pub exchanges: Vec<String>,
+    pub preset: Option<String>,
+    pub min_profit: Option<f64>,
+}
+
+/// Deployment role of this process.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Connectors and API in one process.
+    #[default]
+    All,
+    /// Runs the connectors and publishes every store flush to the bus.
+    Collector,
+    /// Runs no connectors; fills its store from the bus.
+    Api,
+}
+
+/// Redis pub/sub channel collectors publish price updates on.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct BusConfig {
+    /// `redis://[user:password@]host:port[/db]`, or `rediss://` for TLS.
+    pub redis_url: String,
+    pub channel: String,
+    /// How often collectors publish their whole store, so API nodes that
+    /// started late or lost the connection catch up on quiet symbols.
+    pub snapshot_interval_ms: u64,
+}
+
+impl Default for BusConfig {
+    fn default() -> Self {
+        Self {
+            redis_url: "redis://127.0.0.1:6379".into(),
+            channel: "scanner:prices".into(),
+            snapshot_interval_ms: 10_000,
+        }
+    }
+}
+
+impl BusConfig {
+    pub fn snapshot_interval(&self) -> Duration {
+        Duration::from_millis(self.snapshot_interval_ms.max(1000))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    /// JSONL file past scans are appended to (null keeps them in memory only).
+    pub path: Option<String>,
+    /// Scans kept in memory and served by `/scans`.
+    pub max_entries: usize,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            path: Some("scans.jsonl".into()),
+            max_entries: 1_000,
+        }
+    }
+}
+
+/// Per-symbol price samples kept for `/prices/.../changes`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct TickConfig {
+    /// Samples older than this are dropped.
+    pub retention_ms: u64,
+    /// Cap per symbol, whatever their age.
+    pub max_samples: usize,
+}
+
+impl Default for TickConfig {
+    fn default() -> Self {
+        Self {
+            retention_ms: 10 * 60 * 1000,
+            max_samples: 600,
+        }
+    }
+}
+
+/// OHLCV bars kept per symbol, served by `/candles`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CandleConfig {
+    pub second_bars: usize,
+    pub minute_bars: usize,
+}
+
+impl Default for CandleConfig {
+    fn default() -> Self {
+        Self {
+            second_bars: 300,
+            minute_bars: 120,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct BlacklistConfig {
+    /// JSON file the `/admin/blacklist` entries are saved to (null keeps
+    /// them in memory only).
+    pub path: Option<String>,
+}
+
+impl Default for BlacklistConfig {
+    fn default() -> Self {
+        Self {
+            path: Some("blacklist.json".into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ScanConfig {
+    /// How long an identical live scan is served from cache (0 disables).
+    pub cache_ttl_ms: u64,
+    /// Taker fee per leg, in percent, for exchanges not listed in `fees`.
+    pub default_fee_pct: f64,
+    /// Per-exchange taker fee per leg, in percent.
+    pub fees: HashMap<String, f64>,
+    /// Named parameter sets selectable with `preset` in scan requests.
+    pub presets: HashMap<String, ScanPreset>,
+    /// Quote currencies allowed into the graph for every scan (empty = any),
+    /// e.g. to leave out fiat and exotic quote markets.
+    pub quotes: Vec<String>,
+    /// Let derivatives markets (`is_spot: false`) into every scan's graph.
+    pub derivatives: bool,
+    /// Default for `book_prices`: price legs at the bid/ask instead of the
+    /// last price where pairs carry them.
+    pub book_prices: bool,
+    /// Directory `source: "file"` scans may name a `.json` pairs file in;
+    /// unset, file scans take inline `pairs` only.
+    pub pairs_dir: Option<String>,
+    pub anomaly: AnomalyConfig,
+    pub confidence: ConfidenceConfig,
+    pub volatility: VolatilityConfig,
+    pub noise: NoiseConfig,
+    pub simulation: SimulationConfig,
+}
+
+/// Rules marking results as suspicious; flagged results rank below clean ones.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct AnomalyConfig {
+    /// Profit after fees (%) above which a triangle is implausible.
+    pub max_profit_pct: f64,
+    /// A leg volume below this (but not every leg 0, i.e. unreported)
+    /// counts as near-zero.
+    pub min_leg_volume: f64,
+    /// Symbols first seen within this window count as recently listed.
+    pub new_listing_ms: u64,
+    /// Symbols appearing within this long of an exchange's first data were
+    /// already listed when we connected.
+    pub listing_grace_ms: u64,
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        Self {
+            max_profit_pct: 3.0,
+            min_leg_volume: 10.0,
+            new_listing_ms: 6 * 60 * 60 * 1000,
+            listing_grace_ms: 5 * 60 * 1000,
+        }
+    }
+}
+
+/// How the confidence score weighs each leg's data. Each factor is in 0..1
+/// and a leg scores their product; unknown inputs score `unknown_factor`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ConfidenceConfig {
+    /// Data this old (ms) halves the age factor.
+    pub age_half_life_ms: u64,
+    /// Average update interval (ms) that halves the frequency factor.
+    pub interval_ref_ms: u64,
+    /// Bid/ask spread (% of mid) that halves the spread factor.
+    pub spread_ref_pct: f64,
+    pub unknown_factor: f64,
+}
+
+impl Default for ConfidenceConfig {
+    fn default() -> Self {
+        Self {
+            age_half_life_ms: 2_000,
+            interval_ref_ms: 1_000,
+            spread_ref_pct: 0.05,
+            unknown_factor: 0.5,
+        }
+    }
+}
+
+/// Realized volatility of each leg from its 1s candles. Legs that move a
+/// lot likely lose the edge before an order lands.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct VolatilityConfig {
+    /// One-second bars the volatility is measured over.
+    pub window_bars: usize,
+    /// Volatility (% stdev of 1s returns) that halves `volatility_score`.
+    pub ref_pct: f64,
+    /// Legs above this are flagged `volatile_leg`, ranking the result
+    /// below clean ones.
+    pub max_pct: f64,
+}
+
+impl Default for VolatilityConfig {
+    fn default() -> Self {
+        Self {
+            window_bars: 60,
+            ref_pct: 0.05,
+            max_pct: 0.5,
+        }
+    }
+}
+
+/// Monte Carlo execution model behind `profit_distribution`: legs fill one
+/// after another, each after a normally distributed latency during which
+/// its price drifts with the leg's realized volatility, and each fill pays
+/// adverse slippage drawn from a normal distribution.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SimulationConfig {
+    /// Runs per result when the scan doesn't ask for `simulate` (0 = off).
+    pub runs: usize,
+    /// Cap on `simulate` per request.
+    pub max_runs: usize,
+    pub latency_mean_ms: f64,
+    pub latency_stdev_ms: f64,
+    pub slippage_mean_pct: f64,
+    pub slippage_stdev_pct: f64,
+    /// Volatility (% stdev of 1s returns) for legs without recent candles.
+    pub default_volatility_pct: f64,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            runs: 0,
+            max_runs: 10_000,
+            latency_mean_ms: 150.0,
+            latency_stdev_ms: 50.0,
+            slippage_mean_pct: 0.01,
+            slippage_stdev_pct: 0.01,
+            default_volatility_pct: 0.02,
+        }
+    }
+}
+
+/// When a triangle counts as noise: it shows up while exactly one leg has
+/// been quiet for `stale_leg_ms` and the other two updated within
+/// `fresh_leg_ms`. `strikes` such scans within `window_ms` suppress it.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct NoiseConfig {
+    pub stale_leg_ms: u64,
+    pub fresh_leg_ms: u64,
+    pub strikes: usize,
+    pub window_ms: u64,
+    /// Drop suppressed triangles instead of flagging them `noisy_triangle`.
+    pub drop: bool,
+}
+
+impl Default for NoiseConfig {
+    fn default() -> Self {
+        Self {
+            stale_leg_ms: 30_000,
+            fresh_leg_ms: 5_000,
+            strikes: 5,
+            window_ms: 60 * 60 * 1000,
+            drop: false,
+        }
+    }
+}
+
+/// Parameters a preset fills in; anything the request sets explicitly wins,
+/// and fields left out here fall back to the usual defaults.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ScanPreset {
+    /// Used when the request names no exchanges.
+    pub exchanges: Vec<String>,
+    pub min_profit: Option<f64>,
+    pub fee_per_leg_pct: Option<f64>,
+    pub neighbor_limit: Option<usize>,
+    pub max_age_ms: Option<u64>,
+    pub min_leg_volume: Option<f64>,
+    pub assets: Vec<String>,
+}
+
+fn builtin_presets() -> HashMap<String, ScanPreset> {
+    let conservative = ScanPreset {
+        min_profit: Some(0.3),
+        max_age_ms: Some(2_000),
+        min_leg_volume: Some(100_000.0),
+        ..ScanPreset::default()
+    };
+    let aggressive = ScanPreset {
+        min_profit: Some(0.0),
+        neighbor_limit: Some(500),
+        ..ScanPreset::default()
+    };
+    let majors = ScanPreset {
+        assets: ["BTC", "ETH", "BNB", "SOL", "XRP", "USDT", "USDC"]
+            .map(String::from)
+            .to_vec(),
+        max_age_ms: Some(5_000),
+        ..ScanPreset::default()
+    };
+    HashMap::from([
+        ("conservative".to_string(), conservative),
+        ("aggressive".to_string(), aggressive),
+        ("majors-only".to_string(), majors),
+    ])
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            cache_ttl_ms: 2_000,
+            default_fee_pct: 0.10,
+            fees: HashMap::new(),
+            presets: builtin_presets(),
+            quotes: Vec::new(),
+            derivatives: false,
+            book_prices: false,
+            pairs_dir: None,
+            anomaly: AnomalyConfig::default(),
+            confidence: ConfidenceConfig::default(),
+            volatility: VolatilityConfig::default(),
+            noise: NoiseConfig::default(),
+            simulation: SimulationConfig::default(),
+        }
+    }
+}
+
+impl ScanConfig {
+    pub fn fee_for(&self, exchange: &str) -> f64 {
+        self.fees
+            .get(exchange)
+            .copied()
+            .unwrap_or(self.default_fee_pct)
+    }
+
+    pub fn cache_ttl(&self) -> Duration {
+        Duration::from_millis(self.cache_ttl_ms)
+    }
+}
+
+/// Thresholds past which a connector sheds load (longer flush interval,
+/// fewer symbols) rather than letting store write latency balloon.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LoadShedConfig {
+    pub enabled: bool,
+    /// Time one flush may spend writing into the price store.
+    pub max_write_ms: u64,
+    /// Changed symbols waiting in a connector buffer at flush time.
+    pub max_backlog: usize,
+}
+
+impl Default for LoadShedConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_write_ms: 50,
+            max_backlog: 5_000,
+        }
+    }
+}
+
+/// Per-frame fault probabilities applied to live WS sessions when enabled.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ChaosConfig {
+    pub enabled: bool,
+    /// Exchanges to inject faults into (empty = all).
+    pub exchanges: Vec<String>,
+    /// Chance per frame of dropping the connection.
+    pub disconnect_prob: f64,
+    /// Chance per frame of stalling the session for up to `delay_ms`.
+    pub delay_prob: f64,
+    pub delay_ms: u64,
+    /// Chance per frame of truncating/garbling the payload before parsing.
+    pub corrupt_prob: f64,
+}
+
+impl Default for ChaosConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            exchanges: Vec::new(),
+            disconnect_prob: 0.001,
+            delay_prob: 0.01,
+            delay_ms: 3_000,
+            corrupt_prob: 0.01,
+        }
+    }
+}
+
+impl ChaosConfig {
+    pub fn applies_to(&self, exchange: &str) -> bool {
+        self.enabled && (self.exchanges.is_empty() || self.exchanges.iter().any(|e| e == exchange))
+    }
+}
+
+/// Local mock exchange: a WS server emitting random-walk tickers for
+/// `assets` against `quote` and against the first asset, with a cross pair
+/// periodically skewed by `arb_pct` to create a detectable cycle.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct MockConfig {
+    pub enabled: bool,
+    pub bind: String,
+    pub assets: Vec<String>,
+    pub quote: String,
+    pub interval_ms: u64,
+    /// Max per-tick random-walk move, in percent.
+    pub volatility_pct: f64,
+    /// Inject an arbitrage cycle every N ticks (0 = never).
+    pub arb_every: u64,
+    pub arb_pct: f64,
+}
+
+impl Default for MockConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: "127.0.0.1:9555".into(),
+            assets: ["BTC", "ETH", "BNB", "SOL", "XRP"]
+                .map(String::from)
+                .to_vec(),
+            quote: "USDT".into(),
+            interval_ms: 500,
+            volatility_pct: 0.05,
+            arb_every: 10,
+            arb_pct: 0.8,
+        }
+    }
+}
+
+/// Synthetic load generator: `pairs` random-walk pairs (generated assets
+/// `S0..` against every quote) streamed at `updates_per_sec`, with
+/// arbitrage cycles injected at `arb_per_sec`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SyntheticConfig {
+    pub enabled: bool,
+    pub bind: String,
+    pub pairs: usize,
+    pub quotes: Vec<String>,
+    pub updates_per_sec: u64,
+    /// Updates are batched into one frame per `frame_ms`.
+    pub frame_ms: u64,
+    /// Max per-frame random-walk move, in percent.
+    pub volatility_pct: f64,
+    pub arb_per_sec: f64,
+    pub arb_pct: f64,
+    /// How long an injected cycle stays open.
+    pub arb_hold_ms: u64,
+}
+
+impl Default for SyntheticConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: "127.0.0.1:9556".into(),
+            pairs: 3000,
+            quotes: ["USDT", "BTC", "ETH"].map(String::from).to_vec(),
+            updates_per_sec: 20_000,
+            frame_ms: 20,
+            volatility_pct: 0.01,
+            arb_per_sec: 0.5,
+            arb_pct: 0.8,
+            arb_hold_ms: 3000,
+        }
+    }
+}
+
+/// A simple WS venue described without code. Field paths are JSON Pointers
+/// (`/data/0/c`) resolved against each ticker item.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeclarativeSpec {
+    /// Exchange key used in the price store and in `connectors` settings.
+    pub name: String,
+    pub url: String,
+    /// Frames sent after connecting; strings go out verbatim, anything else
+    /// is serialized as JSON.
+    #[serde(default)]
+    pub subscribe: Vec<Value>,
+    #[serde(default)]
+    pub heartbeat: Option<HeartbeatSpec>,
+    /// Pointer to the ticker item (or array of items) in a frame; default is
+    /// the frame itself.
+    #[serde(default)]
+    pub items: Option<String>,
+    /// Pointer to a combined symbol, split on `separator` (or by known quote
+    /// suffixes when no separator is given).
+    #[serde(default)]
+    pub symbol: Option<String>,
+    #[serde(default)]
+    pub separator: Option<String>,
+    /// Pointers to separate base/quote fields; take precedence over `symbol`.
+    #[serde(default)]
+    pub base: Option<String>,
+    #[serde(default)]
+    pub quote: Option<String>,
+    pub price: String,
+    #[serde(default)]
+    pub volume: Option<String>,
+    /// Event time in milliseconds.
+    #[serde(default)]
+    pub event_time: Option<String>,
+    /// Pointer whose presence (non-null) marks a frame as an error response.
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// An on-chain DEX connector: the chain's RPC endpoint and the pools to read.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DexPoolsConfig {
+    pub rpc_url: String,
+    pub poll_interval_ms: u64,
+    pub pools: Vec<PoolSpec>,
+}
+
+impl Default for DexPoolsConfig {
+    fn default() -> Self {
+        Self {
+            rpc_url: String::new(),
+            poll_interval_ms: 3000,
+            pools: Vec::new(),
+        }
+    }
+}
+
+impl DexPoolsConfig {
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval_ms.max(500))
+    }
+}
+
+/// One pool. Token names are what the pair is stored under, so wrapped
+/// assets can be listed as their CEX symbol (WETH as "ETH"); decimals are
+/// each token's on-chain decimals (ERC-20 `decimals()`, SPL mint decimals).
+#[derive(Debug, Clone, Deserialize)]
+pub struct PoolSpec {
+    pub address: String,
+    pub token0: String,
+    pub token1: String,
+    pub decimals0: u8,
+    pub decimals1: u8,
+    /// Curve only: indexes of token0 and token1 among the pool's coins
+    /// (default `[0, 1]`).
+    #[serde(default)]
+    pub coins: Option<(u8, u8)>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SolanaDexConfig {
+    /// Solana RPC WebSocket, e.g. "wss://api.mainnet-beta.solana.com".
+    pub ws_url: String,
+    pub pools: Vec<SolanaPoolSpec>,
+}
+
+/// A concentrated-liquidity pool account; `token0`/`token1` follow the
+/// pool's own mint order.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SolanaPoolSpec {
+    pub kind: SolanaPoolKind,
+    #[serde(flatten)]
+    pub pool: PoolSpec,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SolanaPoolKind {
+    RaydiumClmm,
+    OrcaWhirlpool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct OsmosisConfig {
+    /// Cosmos REST (LCD) endpoint, e.g. "https://lcd.osmosis.zone".
+    pub lcd_url: String,
+    pub poll_interval_ms: u64,
+    pub pools: Vec<OsmosisPoolSpec>,
+}
+
+impl Default for OsmosisConfig {
+    fn default() -> Self {
+        Self {
+            lcd_url: String::new(),
+            poll_interval_ms: 5000,
+            pools: Vec::new(),
+        }
+    }
+}
+
+impl OsmosisConfig {
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval_ms.max(1000))
+    }
+}
+
+/// One Osmosis pool and the two of its assets to price, by chain denom
+/// ("uosmo", "ibc/...") and by the name the pair is stored under.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OsmosisPoolSpec {
+    pub pool_id: u64,
+    pub denom0: String,
+    pub denom1: String,
+    pub token0: String,
+    pub token1: String,
+    pub decimals0: u8,
+    pub decimals1: u8,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct OrderBookConfig {
+    /// Venues to keep books for (binance, binanceus, coinbase); empty
+    /// disables them.
+    pub exchanges: Vec<String>,
+    /// Pairs per venue, the most traded by quote volume.
+    pub top_n: usize,
+    /// Levels requested in each REST snapshot (Binance; Coinbase sends full
+    /// snapshots on the stream).
+    pub snapshot_limit: usize,
+}
+
+impl Default for OrderBookConfig {
+    fn default() -> Self {
+        Self {
+            exchanges: Vec::new(),
+            top_n: 20,
+            snapshot_limit: 1000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct JupiterConfig {
+    /// Swap API base; `/quote` is appended.
+    pub api_url: String,
+    pub poll_interval_ms: u64,
+    /// What every token is bought with and priced in (USDC by default).
+    pub quote: JupiterToken,
+    /// Size of each quoted swap in `quote` tokens; larger sizes include
+    /// more price impact.
+    pub notional: f64,
+    pub tokens: Vec<JupiterToken>,
+}
+
+impl Default for JupiterConfig {
+    fn default() -> Self {
+        Self {
+            api_url: "https://lite-api.jup.ag/swap/v1".into(),
+            poll_interval_ms: 10_000,
+            quote: JupiterToken {
+                symbol: "USDC".into(),
+                mint: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".into(),
+                decimals: 6,
+            },
+            notional: 1000.0,
+            tokens: Vec::new(),
+        }
+    }
+}
+
+impl JupiterConfig {
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval_ms.max(1000))
+    }
+}
+
+/// An SPL token: the symbol it is stored under, its mint and decimals.
+#[derive(Debug, Clone, Deserialize)]
+pub struct JupiterToken {
+    pub symbol: String,
+    pub mint: String,
+    pub decimals: u8,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HeartbeatSpec {
+    pub interval_ms: u64,
+    /// Text to send; `{ts}` / `{ts_s}` expand to the current unix ms / seconds.
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ConnectorConfig {
+    pub enabled: bool,
+    /// How often buffered ticker updates are written into the shared price store.
+    pub flush_interval_ms: u64,
+    /// Pairs whose reported 24h volume is below this never enter the price
+    /// store (0 disables the filter). Rejected at startup for connectors
+    /// whose feeds report no volume (KuCoin's all-tickers feed, the DEXes).
+    pub min_volume: f64,
+    /// Cap on tracked symbols; beyond it the least recently updated symbol is
+    /// evicted (0 = unlimited).
+    pub max_symbols: usize,
+    /// Price frames ingested per second; beyond it prices are held back,
+    /// latest per symbol, and ingested at the next flush (0 = unlimited).
+    /// Book frames count too; replies, notices and errors are exempt.
+    pub max_messages_per_sec: u32,
+    /// Also stream the venue's best bid/ask where the connector supports it
+    /// (Binance `!bookTicker`, Bybit `orderbook.1`), so pairs carry a live
+    /// bid/ask.
+    pub top_of_book: bool,
+    /// Use the venue's testnet/sandbox for both the stream and REST calls
+    /// (signed ones included). Supported by binance, bybit and kucoin;
+    /// prices are still stored under the venue's own name.
+    pub testnet: bool,
+}
+
+impl Default for ConnectorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            flush_interval_ms: 1000,
+            min_volume: 0.0,
+            max_symbols: 0,
+            max_messages_per_sec: 0,
+            top_of_book: false,
+            testnet: false,
+        }
+    }
+}
+
+impl ConnectorConfig {
+    pub fn flush_interval(&self) -> Duration {
+        Duration::from_millis(self.flush_interval_ms.max(50))
+    }
+
+    /// `testnet` when the connector is switched to its testnet, else `live`.
+    pub fn endpoint<'a>(&self, live: &'a str, testnet: &'a str) -> &'a str {
+        if self.testnet {
+            testnet
+        } else {
+            live
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct FallbackConfig {
+    pub enabled: bool,
+    /// A connected stream silent for this long counts as stale.
+    pub stale_after_ms: u64,
+    pub poll_interval_ms: u64,
+}
+
+impl Default for FallbackConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            stale_after_ms: 30_000,
+            poll_interval_ms: 5000,
+        }
+    }
+}
+
+impl FallbackConfig {
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval_ms.max(1000))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SymbolsConfig {
+    /// How often loaded instrument lists are re-fetched, so halted and
+    /// delisted pairs are noticed between reconnects; 0 disables.
+    pub refresh_ms: u64,
+}
+
+impl Default for SymbolsConfig {
+    fn default() -> Self {
+        Self {
+            refresh_ms: 300_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LeveragedTokensConfig {
+    pub enabled: bool,
+    /// Base-asset suffixes marking a leveraged token, per exchange. A
+    /// configured map replaces the defaults (Binance UP/DOWN/BULL/BEAR,
+    /// KuCoin and Gate.io 3L/3S, Gate.io 5L/5S) as a whole.
+    pub suffixes: HashMap<String, Vec<String>>,
+}
+
+impl Default for LeveragedTokensConfig {
+    fn default() -> Self {
+        let list = |s: &[&str]| s.iter().map(|x| x.to_string()).collect();
+        Self {
+            enabled: true,
+            suffixes: HashMap::from([
+                ("binance".to_string(), list(&["UP", "DOWN", "BULL", "BEAR"])),
+                ("kucoin".to_string(), list(&["3L", "3S", "UP", "DOWN"])),
+                ("gateio".to_string(), list(&["3L", "3S", "5L", "5S"])),
+            ]),
+        }
+    }
+}
+
+impl Config {
+    /// Checks serde can't express: every alert template must parse, and no
+    /// volume filter is set on a connector that reports no volume.
+    fn validate(&self) -> Result<(), String> {
+        for (name, c) in &self.connectors {
+            if c.min_volume > 0.0 && crate::ws_manager::NO_VOLUME_VENUES.contains(&name.as_str()) {
+                return Err(format!(
+                    "connectors.{}.min_volume: {} reports no volume, so every pair would be dropped",
+                    name, name
+                ));
+            }
+        }
+        for (idx, alert) in self.alerts.iter().enumerate() {
+            if let Some(template) = &alert.template {
+                crate::template::check(template)
+                    .map_err(|e| format!("alerts[{}].template: {}", idx, e))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Settings for one connector, falling back to defaults when not configured.
+    pub fn connector(&self, exchange: &str) -> ConnectorConfig {
+        self.connectors.get(exchange).cloned().unwrap_or_default()
+    }
+
+    /// Connectors beyond the original four only run when listed in
+    /// `connectors` with `enabled` left on.
+    pub fn opted_in(&self, exchange: &str) -> bool {
+        self.connectors.get(exchange).is_some_and(|c| c.enabled)
+    }
+}
+
+/// Load the config file; called once at startup, before anything reads
+/// [`config`]. A file that can't be read or doesn't validate is an error:
+/// starting on defaults would silently drop testnet, admin and account
+/// settings.
+pub fn init() -> Result<(), String> {
+    let path = std::env::var("SCANNER_CONFIG").unwrap_or_else(|_| "config.json".into());
+    let cfg = match std::fs::read_to_string(&path) {
+        Ok(txt) => {
+            let cfg = serde_json::from_str::<Config>(&txt)
+                .map_err(|e| format!("invalid config {}: {}", path, e))?;
+            cfg.validate()
+                .map_err(|e| format!("invalid config {}: {}", path, e))?;
+            info!("loaded config from {}", path);
+            cfg
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Config::default(),
+        Err(e) => return Err(format!("cannot read config {}: {}", path, e)),
+    };
+    CONFIG
+        .set(cfg)
+        .map_err(|_| "config read before it was loaded".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn min_volume_is_rejected_where_no_volume_is_reported() {
+        let mut cfg = Config::default();
+        let filtered = ConnectorConfig {
+            min_volume: 1000.0,
+            ..ConnectorConfig::default()
+        };
+        cfg.connectors.insert("binance".into(), filtered.clone());
+        assert!(cfg.validate().is_ok());
+        cfg.connectors.insert("kucoin".into(), filtered);
+        let err = cfg.validate().unwrap_err();
+        assert!(err.starts_with("connectors.kucoin.min_volume"), "{}", err);
+    }
+}
@@ -0,0 +1,115 @@
+use crate::store::SharedPrices;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::RwLock;
+use utoipa::ToSchema;
+
+/// Dropped symbols listed per reason in `/coverage`; the count is exact.
+const MAX_LISTED_DROPS: usize = 50;
+
+/// What each venue offers versus what actually reaches the price store,
+/// written by the connectors and read by `/coverage`.
+static COVERAGE: Lazy<Coverage> = Lazy::new(Coverage::default);
+
+pub fn coverage() -> &'static Coverage {
+    &COVERAGE
+}
+
+#[derive(Debug, Default)]
+struct Venue {
+    /// Instrument count from the venue's listing, for connectors that fetch one.
+    listed: Option<usize>,
+    /// Symbols the venue streamed, parsed or not.
+    seen: HashSet<String>,
+    /// Symbol -> why it was last left out.
+    dropped: HashMap<String, &'static str>,
+}
+
+#[derive(Debug, Default)]
+pub struct Coverage {
+    inner: RwLock<HashMap<String, Venue>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExchangeCoverage {
+    /// Instruments the venue lists, or the distinct symbols it streamed
+    /// when the connector has no instrument list.
+    pub listed: usize,
+    /// "instruments" or "stream".
+    pub listed_from: &'static str,
+    pub tracked: usize,
+    pub missing: usize,
+    /// Reason -> symbols left out for it and not tracked now.
+    pub dropped: BTreeMap<&'static str, DroppedSymbols>,
+}
+
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct DroppedSymbols {
+    pub count: usize,
+    pub symbols: Vec<String>,
+}
+
+impl Coverage {
+    pub fn set_listed(&self, exchange: &str, count: usize) {
+        let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        guard.entry(exchange.to_string()).or_default().listed = Some(count);
+    }
+
+    /// Symbols the venue streamed in one frame.
+    pub fn record_seen<'a>(&self, exchange: &str, symbols: impl IntoIterator<Item = &'a str>) {
+        let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        let venue = guard.entry(exchange.to_string()).or_default();
+        for symbol in symbols {
+            if !venue.seen.contains(symbol) {
+                venue.seen.insert(symbol.to_string());
+            }
+        }
+    }
+
+    /// `symbol` was left out before reaching the store, e.g. "unparsed" or
+    /// "min_volume". Unparsed symbols also count as seen.
+    pub fn record_dropped(&self, exchange: &str, symbol: &str, reason: &'static str) {
+        let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        let venue = guard.entry(exchange.to_string()).or_default();
+        if !venue.seen.contains(symbol) {
+            venue.seen.insert(symbol.to_string());
+        }
+        venue.dropped.insert(symbol.to_string(), reason);
+    }
+
+    pub fn report(&self, prices: &SharedPrices) -> BTreeMap<String, ExchangeCoverage> {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        guard
+            .iter()
+            .map(|(exchange, venue)| {
+                let tracked = prices.symbols(exchange);
+                let (listed, listed_from) = match venue.listed {
+                    Some(n) => (n, "instruments"),
+                    None => (venue.seen.len(), "stream"),
+                };
+                let mut dropped: BTreeMap<&'static str, DroppedSymbols> = BTreeMap::new();
+                for (symbol, reason) in &venue.dropped {
+                    if tracked.contains(symbol) {
+                        continue;
+                    }
+                    let entry = dropped.entry(reason).or_default();
+                    entry.count += 1;
+                    entry.symbols.push(symbol.clone());
+                }
+                for entry in dropped.values_mut() {
+                    entry.symbols.sort();
+                    entry.symbols.truncate(MAX_LISTED_DROPS);
+                }
+                let coverage = ExchangeCoverage {
+                    listed,
+                    listed_from,
+                    tracked: tracked.len(),
+                    missing: listed.saturating_sub(tracked.len()),
+                    dropped,
+                };
+                (exchange.clone(), coverage)
+            })
+            .collect()
+    }
+}
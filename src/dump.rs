@@ -0,0 +1,87 @@
+//! Diagnostic snapshot for bug reports, written on `POST /admin/dump` or
+//! SIGUSR1: connector health, store sizes, metrics, jobs, tasks, recent
+//! errors and the config in effect (credentials left out).
+
+use crate::config::config;
+use crate::health::{health, ErrorEvent, HealthReport};
+use crate::metrics::{metrics, MetricsReport};
+use crate::scheduler::{scheduler, JobState};
+use crate::store::SharedPrices;
+use crate::tasks::{self, tasks, TasksReport};
+use crate::utils::now_ms;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::{error, info};
+
+#[derive(Debug, Serialize)]
+pub struct Dump {
+    pub at_ms: u64,
+    pub version: &'static str,
+    pub health: HealthReport,
+    /// Symbols held per exchange.
+    pub store: BTreeMap<String, usize>,
+    pub metrics: MetricsReport,
+    pub jobs: BTreeMap<String, JobState>,
+    pub tasks: TasksReport,
+    pub recent_errors: Vec<ErrorEvent>,
+    /// Debug rendering of the loaded config.
+    pub config: String,
+}
+
+pub fn collect(prices: &SharedPrices) -> Dump {
+    Dump {
+        at_ms: now_ms(),
+        version: env!("CARGO_PKG_VERSION"),
+        health: health().report(),
+        store: prices
+            .exchanges()
+            .into_iter()
+            .map(|name| {
+                let size = prices.symbols(&name).len();
+                (name, size)
+            })
+            .collect(),
+        metrics: metrics().report(),
+        jobs: scheduler().report(),
+        tasks: tasks().report(),
+        recent_errors: health().recent_errors(),
+        config: format!("{:#?}", config()),
+    }
+}
+
+/// Write a dump to `dump.dir` and return its path.
+pub fn write(prices: &SharedPrices) -> std::io::Result<PathBuf> {
+    let dump = collect(prices);
+    let path = Path::new(&config().dump.dir).join(format!("dump-{}.json", dump.at_ms));
+    let json = serde_json::to_vec_pretty(&dump).map_err(std::io::Error::other)?;
+    std::fs::write(&path, json)?;
+    info!("wrote diagnostic dump to {}", path.display());
+    Ok(path)
+}
+
+/// Write a dump whenever the process receives SIGUSR1.
+#[cfg(unix)]
+pub fn start_signal_handler(prices: SharedPrices) {
+    use tokio::signal::unix::{signal, SignalKind};
+    let mut usr1 = match signal(SignalKind::user_defined1()) {
+        Ok(s) => s,
+        Err(e) => {
+            error!("cannot listen for SIGUSR1: {}", e);
+            return;
+        }
+    };
+    tasks::spawn("dump:sigusr1", async move {
+        while usr1.recv().await.is_some() {
+            let prices = prices.clone();
+            match tokio::task::spawn_blocking(move || write(&prices)).await {
+                Ok(Err(e)) => error!("writing diagnostic dump: {}", e),
+                Err(e) => error!("writing diagnostic dump: {}", e),
+                Ok(Ok(_)) => {}
+            }
+        }
+    });
+}
+
+#[cfg(not(unix))]
+pub fn start_signal_handler(_prices: SharedPrices) {}
@@ -0,0 +1,97 @@
+//! Minimal Ethereum JSON-RPC client for reading pool state with `eth_call`,
+//! plus the ABI word decoding the DEX connectors need.
+
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// JSON-RPC endpoint of one EVM chain.
+pub struct RpcClient {
+    url: String,
+    http: reqwest::Client,
+}
+
+impl RpcClient {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// `eth_call` of every `(contract, calldata)` at the latest block in one
+    /// batch request. A call the node rejects (or reverts) yields `None` so
+    /// one bad pool does not hide the others.
+    pub async fn call_batch(&self, calls: &[(&str, &str)]) -> Result<Vec<Option<Vec<u8>>>, String> {
+        if calls.is_empty() {
+            return Ok(Vec::new());
+        }
+        let body: Vec<Value> = calls
+            .iter()
+            .enumerate()
+            .map(|(id, (to, data))| {
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "method": "eth_call",
+                    "params": [{ "to": to, "data": data }, "latest"],
+                })
+            })
+            .collect();
+        let replies: Vec<Value> = self
+            .http
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("eth_call: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("eth_call: {}", e))?;
+        // Batch replies may come back in any order; match them up by id.
+        let mut results = vec![None; calls.len()];
+        for reply in replies {
+            let Some(id) = reply.get("id").and_then(Value::as_u64) else {
+                continue;
+            };
+            let data = reply
+                .get("result")
+                .and_then(Value::as_str)
+                .and_then(decode_hex);
+            if let Some(slot) = results.get_mut(id as usize) {
+                *slot = data;
+            }
+        }
+        Ok(results)
+    }
+}
+
+/// The `index`-th 32-byte word of ABI-encoded return data.
+pub fn word(data: &[u8], index: usize) -> Option<&[u8]> {
+    data.get(index * 32..(index + 1) * 32)
+}
+
+/// An unsigned integer as a 32-byte ABI word in hex (no `0x`).
+pub fn encode_uint(value: u128) -> String {
+    format!("{:064x}", value)
+}
+
+/// Unsigned ABI integer as `f64`; large values lose precision but prices
+/// only need the leading digits.
+pub fn uint_f64(word: &[u8]) -> f64 {
+    word.iter().fold(0.0, |acc, b| acc * 256.0 + *b as f64)
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
@@ -0,0 +1,213 @@
+use super::{parse_f64, Parsed, VenueError};
+use crate::coverage::coverage;
+use crate::models::PairPrice;
+use crate::rest;
+use crate::store::SharedPrices;
+use crate::symbols::symbols;
+use crate::ws_manager::{run_connector, ExchangeConnector, Heartbeat, Subscriptions};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tokio::time::Duration;
+use tracing::{info, warn};
+
+const EXCHANGE: &str = "ascendex";
+const WS_URL: &str = "wss://ascendex.com/0/api/pro/v1/stream";
+/// Symbols per `sub` request (comma-separated in `ch`).
+const SUBSCRIBE_CHUNK: usize = 50;
+
+/// Long-running AscendEX spot worker: `trades` set the last price, `bbo` the
+/// best bid/ask, for every normal cash product.
+pub async fn run_ascendex_ws(prices: SharedPrices) {
+    let ascendex = AscendEx {
+        trades: Subscriptions::new(SUBSCRIBE_CHUNK, |s| subscribe_message("trades", s)),
+        bbo: Subscriptions::new(SUBSCRIBE_CHUNK, |s| subscribe_message("bbo", s)),
+        latest: HashMap::new(),
+    };
+    run_connector(ascendex, prices).await;
+}
+
+struct AscendEx {
+    trades: Subscriptions,
+    bbo: Subscriptions,
+    /// Latest price per symbol, so book updates can carry the last price and
+    /// trades keep the last book.
+    latest: HashMap<String, PairPrice>,
+}
+
+impl ExchangeConnector for AscendEx {
+    fn name(&self) -> &'static str {
+        EXCHANGE
+    }
+
+    async fn connect(&mut self) -> Result<String, String> {
+        match rest::ascendex::instruments().await {
+            Ok(list) => {
+                let normal: Vec<String> = list
+                    .into_iter()
+                    .filter(|i| i.trading)
+                    .map(|i| i.symbol)
+                    .collect();
+                coverage().set_listed(EXCHANGE, normal.len());
+                self.trades.replace(normal.clone());
+                self.bbo.replace(normal);
+            }
+            Err(e) if self.trades.len() == 0 => {
+                return Err(format!("products fetch failed: {}", e));
+            }
+            Err(e) => warn!(
+                "ascendex products fetch failed: {} (restoring {} known subscriptions)",
+                e,
+                self.trades.len()
+            ),
+        }
+        info!("ascendex: subscribing to {} symbols", self.trades.len());
+        Ok(WS_URL.to_string())
+    }
+
+    fn subscribe(&self) -> Vec<String> {
+        let mut subscribe = self.trades.messages();
+        subscribe.extend(self.bbo.messages());
+        subscribe
+    }
+
+    fn keepalive(&self) -> Heartbeat {
+        Heartbeat::Text {
+            interval: Duration::from_secs(15),
+            message: ping_message,
+        }
+    }
+
+    fn parse_message(&mut self, txt: &str) -> Parsed {
+        parse_message(txt, &mut self.latest)
+    }
+}
+
+/// Connections idle for 30s are closed; `{"op":"ping"}` is answered with
+/// `{"m":"pong"}`.
+fn ping_message() -> String {
+    json!({ "op": "ping" }).to_string()
+}
+
+fn subscribe_message(channel: &str, symbols: &[String]) -> String {
+    json!({
+        "op": "sub",
+        "id": format!("{}-{}", channel, symbols.first().map(String::as_str).unwrap_or("")),
+        "ch": format!("{}:{}", channel, symbols.join(",")),
+    })
+    .to_string()
+}
+
+fn parse_message(txt: &str, latest: &mut HashMap<String, PairPrice>) -> Parsed {
+    let v: Value = match serde_json::from_str(txt) {
+        Ok(v) => v,
+        Err(_) => return Parsed::Prices(Vec::new()),
+    };
+    let kind = v.get("m").and_then(|m| m.as_str()).unwrap_or("");
+    let code = v.get("code").and_then(|c| c.as_i64()).unwrap_or(0);
+    if kind == "error" || code != 0 {
+        let reason = v
+            .get("reason")
+            .or_else(|| v.get("info"))
+            .and_then(|r| r.as_str())
+            .unwrap_or("error");
+        return Parsed::Error(VenueError::new(format!("{} {}", code, reason)));
+    }
+    match kind {
+        // Server pings expect a pong or the connection is dropped.
+        "ping" => Parsed::Reply(json!({ "op": "pong" }).to_string()),
+        "trades" => parse_trades(&v, latest).into(),
+        "bbo" => parse_bbo(&v, latest).into(),
+        _ => Parsed::Prices(Vec::new()),
+    }
+}
+
+/// `{"m":"trades","symbol":"BTC/USDT","data":[{"p":"..","q":"..","ts":..}]}`.
+fn parse_trades(v: &Value, latest: &mut HashMap<String, PairPrice>) -> Vec<PairPrice> {
+    let Some(symbol) = v.get("symbol").and_then(|s| s.as_str()) else {
+        return Vec::new();
+    };
+    let Some(last) = v
+        .get("data")
+        .and_then(|d| d.as_array())
+        .and_then(|d| d.last())
+    else {
+        return Vec::new();
+    };
+    let Some(price) = parse_f64(last.get("p")) else {
+        return Vec::new();
+    };
+    let event_time = last.get("ts").and_then(|t| t.as_u64()).unwrap_or(0);
+    let entry = match latest.get_mut(symbol) {
+        Some(entry) => entry,
+        None => {
+            let Some((base, quote)) = symbols().split(EXCHANGE, symbol) else {
+                coverage().record_dropped(EXCHANGE, symbol, "unparsed");
+                return Vec::new();
+            };
+            latest.entry(symbol.to_string()).or_insert(PairPrice {
+                base,
+                quote,
+                price,
+                is_spot: true,
+                volume: 0.0,
+                event_time,
+                bid: None,
+                ask: None,
+            })
+        }
+    };
+    entry.price = price;
+    entry.event_time = event_time;
+    vec![entry.clone()]
+}
+
+/// `{"m":"bbo","symbol":"BTC/USDT","data":{"ts":..,"bid":["p","q"],"ask":["p","q"]}}`;
+/// dropped until a trade has supplied a last price.
+fn parse_bbo(v: &Value, latest: &mut HashMap<String, PairPrice>) -> Vec<PairPrice> {
+    let symbol = v.get("symbol").and_then(|s| s.as_str()).unwrap_or("");
+    let (Some(data), Some(entry)) = (v.get("data"), latest.get_mut(symbol)) else {
+        return Vec::new();
+    };
+    entry.bid = parse_f64(data.get("bid").and_then(|b| b.get(0))).or(entry.bid);
+    entry.ask = parse_f64(data.get("ask").and_then(|a| a.get(0))).or(entry.ask);
+    entry.event_time = data
+        .get("ts")
+        .and_then(|t| t.as_u64())
+        .unwrap_or(entry.event_time);
+    vec![entry.clone()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_pings_get_an_op_pong() {
+        let mut latest = HashMap::new();
+        let ping = r#"{"m":"ping","hp":3}"#;
+        assert!(
+            matches!(parse_message(ping, &mut latest), Parsed::Reply(r) if r == r#"{"op":"pong"}"#)
+        );
+    }
+
+    #[test]
+    fn bbo_updates_follow_the_last_trade() {
+        let mut latest = HashMap::new();
+        let bbo = r#"{"m":"bbo","symbol":"BTC/USDT","data":{"ts":1700000000050,"bid":["64999.9","0.1"],"ask":["65000.3","0.2"]}}"#;
+        assert!(parse_message(bbo, &mut latest).prices().is_empty());
+
+        let trades = r#"{"m":"trades","symbol":"BTC/USDT","data":[{"p":"65000.0","q":"0.01","ts":1700000000000},{"p":"65000.1","q":"0.02","ts":1700000000001}]}"#;
+        let prices = parse_message(trades, &mut latest).prices();
+        assert_eq!(
+            (prices[0].base.as_str(), prices[0].quote.as_str()),
+            ("BTC", "USDT")
+        );
+        assert_eq!(prices[0].price, 65000.1);
+
+        let prices = parse_message(bbo, &mut latest).prices();
+        let p = &prices[0];
+        assert_eq!(p.price, 65000.1);
+        assert_eq!((p.bid, p.ask), (Some(64999.9), Some(65000.3)));
+        assert_eq!(p.event_time, 1_700_000_000_050);
+    }
+}
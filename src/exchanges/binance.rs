@@ -0,0 +1,390 @@
+use super::{notice_text, parse_f64, refresh_symbols, Parsed, TickerBuffer, VenueError};
+use crate::config::config;
+use crate::coverage::coverage;
+use crate::models::PairPrice;
+use crate::store::{pair_key, SharedPrices};
+use crate::symbols::symbols;
+use crate::utils::now_ms;
+use crate::ws_manager::{ingest, run_connector, ExchangeConnector, Heartbeat, Subscriptions};
+use futures_util::StreamExt;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tokio::time::{Duration, Instant};
+use tokio_tungstenite::connect_async;
+use tracing::{error, info, warn};
+
+const EXCHANGE: &str = "binance";
+const WS_URL: &str = "wss://stream.binance.com:9443/ws/!ticker@arr";
+const TESTNET_WS_URL: &str = "wss://stream.testnet.binance.vision/ws/!ticker@arr";
+/// Bare endpoint for the live worker, which subscribes with `SUBSCRIBE` frames.
+const WS_BASE_URL: &str = "wss://stream.binance.com:9443/ws";
+const TESTNET_WS_BASE_URL: &str = "wss://stream.testnet.binance.vision/ws";
+/// Binance.US: same stream protocol, separate venue (own symbols and fees).
+const US_EXCHANGE: &str = "binanceus";
+const US_WS_BASE_URL: &str = "wss://stream.binance.us:9443/ws";
+/// Streams per `SUBSCRIBE` request.
+const SUBSCRIBE_CHUNK: usize = 200;
+
+/// Collect a snapshot of Binance (WS-only) tickers over `seconds` seconds.
+/// Returns the latest price of each symbol, filtered like the live stream
+/// (volume floor, blacklist, halted and leveraged tokens).
+pub async fn collect_binance_snapshot(seconds: u64) -> Vec<PairPrice> {
+    let url = config()
+        .connector(EXCHANGE)
+        .endpoint(WS_URL, TESTNET_WS_URL);
+    info!("Connecting to Binance WS at {}", url);
+    if !symbols().is_loaded(EXCHANGE) {
+        refresh_symbols(EXCHANGE).await;
+    }
+
+    let mut buffer = TickerBuffer::new(EXCHANGE);
+
+    match connect_async(url).await {
+        Ok((mut ws_stream, _)) => {
+            let deadline = Instant::now() + Duration::from_secs(seconds);
+
+            while let Some(msg) = ws_stream.next().await {
+                if Instant::now() >= deadline {
+                    break;
+                }
+
+                match msg {
+                    Ok(m) if m.is_text() => {
+                        if let Ok(txt) = m.into_text() {
+                            let ps = match parse_ticker_message(EXCHANGE, &txt) {
+                                Parsed::Prices(ps) => ps,
+                                Parsed::Error(err) => {
+                                    warn!("binance error response: {}", err.message);
+                                    continue;
+                                }
+                                Parsed::Book(_) | Parsed::Reply(_) => continue,
+                                Parsed::Notice(text) => {
+                                    warn!("binance system notice: {}", text);
+                                    continue;
+                                }
+                            };
+                            ingest(EXCHANGE, ps, &mut buffer);
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        error!("binance ws read error: {:?}", e);
+                        break;
+                    }
+                }
+
+                // prevent tight CPU loop
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }
+        Err(e) => {
+            error!("binance connect error: {:?}", e);
+        }
+    }
+
+    let pairs = buffer.drain().changed;
+    info!(
+        "scan complete for binance: collected {} unique pairs",
+        pairs.len()
+    );
+    pairs
+}
+
+/// Long-running worker: keeps the Binance all-tickers stream open (reconnecting
+/// with backoff) and flushes changed symbols into `prices` every flush interval.
+pub async fn run_binance_ws(prices: SharedPrices) {
+    let url = config()
+        .connector(EXCHANGE)
+        .endpoint(WS_BASE_URL, TESTNET_WS_BASE_URL);
+    run_ticker_stream(EXCHANGE, url, prices).await
+}
+
+/// The Binance worker against `stream.binance.us`, stored under "binanceus".
+pub async fn run_binance_us_ws(prices: SharedPrices) {
+    run_ticker_stream(US_EXCHANGE, US_WS_BASE_URL, prices).await
+}
+
+async fn run_ticker_stream(exchange: &'static str, ws_base_url: &str, prices: SharedPrices) {
+    let top_of_book = config().connector(exchange).top_of_book;
+    let mut topics = vec!["!ticker@arr".to_string()];
+    if top_of_book {
+        topics.push("!bookTicker".to_string());
+    }
+    let binance = Binance {
+        exchange,
+        url: ws_base_url.to_string(),
+        subs: Subscriptions::new(SUBSCRIBE_CHUNK, subscribe_message).with_topics(topics),
+        book: top_of_book.then(BookTickers::default),
+        refreshed: false,
+    };
+    run_connector(binance, prices).await
+}
+
+struct Binance {
+    exchange: &'static str,
+    url: String,
+    subs: Subscriptions,
+    book: Option<BookTickers>,
+    /// Symbols are fetched before the first session only; later refreshes
+    /// come from `symbols.refresh_ms`.
+    refreshed: bool,
+}
+
+impl ExchangeConnector for Binance {
+    fn name(&self) -> &'static str {
+        self.exchange
+    }
+
+    async fn connect(&mut self) -> Result<String, String> {
+        if !self.refreshed {
+            refresh_symbols(self.exchange).await;
+            self.refreshed = true;
+        }
+        Ok(self.url.clone())
+    }
+
+    fn subscribe(&self) -> Vec<String> {
+        self.subs.messages()
+    }
+
+    fn keepalive(&self) -> Heartbeat {
+        Heartbeat::ServerPing
+    }
+
+    fn parse_message(&mut self, txt: &str) -> Parsed {
+        match self.book.as_mut() {
+            Some(book) => book.parse(self.exchange, txt),
+            None => parse_ticker_message(self.exchange, txt),
+        }
+    }
+}
+
+fn subscribe_message(streams: &[String]) -> String {
+    json!({ "method": "SUBSCRIBE", "params": streams, "id": now_ms() }).to_string()
+}
+
+/// Best bid/ask from `!bookTicker` merged into the 24h tickers, so pairs
+/// carry the live top of book instead of the ticker's once-a-second copy.
+#[derive(Default)]
+struct BookTickers {
+    /// Pair key -> (bid, ask) from the latest book update.
+    quotes: HashMap<String, (f64, f64)>,
+    /// Pair key -> latest ticker, re-emitted with each book update.
+    last: HashMap<String, PairPrice>,
+}
+
+impl BookTickers {
+    fn parse(&mut self, exchange: &str, txt: &str) -> Parsed {
+        // Ticker frames are arrays. An object carrying a symbol and both
+        // sides (`s`, `b`, `a`) is a book update; any other object (notice,
+        // error, subscribe ack) goes through the ticker parser.
+        if !txt.trim_start().starts_with('[') {
+            if let Ok(v) = serde_json::from_str::<Value>(txt) {
+                if ["s", "b", "a"].iter().all(|k| v.get(k).is_some()) {
+                    return Parsed::Book(self.book_update(exchange, &v).into_iter().collect());
+                }
+            }
+        }
+        let mut ps = match parse_ticker_message(exchange, txt) {
+            Parsed::Prices(ps) => ps,
+            other => return other,
+        };
+        for p in &mut ps {
+            let key = pair_key(&p.base, &p.quote);
+            if let Some(&(bid, ask)) = self.quotes.get(&key) {
+                p.bid = Some(bid);
+                p.ask = Some(ask);
+            }
+            self.last.insert(key, p.clone());
+        }
+        ps.into()
+    }
+
+    /// `{"u":400900217,"s":"BNBUSDT","b":"25.35","B":"31.21","a":"25.36","A":"40.66"}`.
+    /// Emits the pair once its ticker has been seen (the book frame has no
+    /// last price or volume).
+    fn book_update(&mut self, exchange: &str, v: &Value) -> Option<PairPrice> {
+        let sym = v.get("s")?.as_str()?;
+        let (bid, ask) = (parse_f64(v.get("b"))?, parse_f64(v.get("a"))?);
+        let Some((base, quote)) = symbols().split(exchange, sym) else {
+            coverage().record_dropped(exchange, sym, "unparsed");
+            return None;
+        };
+        let key = pair_key(&base, &quote);
+        self.quotes.insert(key.clone(), (bid, ask));
+        let p = self.last.get_mut(&key)?;
+        p.bid = Some(bid);
+        p.ask = Some(ask);
+        Some(p.clone())
+    }
+}
+
+/// Parse one `!ticker@arr` frame into normalized pair prices.
+fn parse_ticker_message(exchange: &str, txt: &str) -> Parsed {
+    #[cfg(feature = "binance-fast-parse")]
+    if txt.starts_with('[') {
+        if let Ok(raw) = serde_json::from_str::<Vec<RawTicker>>(txt) {
+            return raw
+                .iter()
+                .filter_map(|t| t.to_pair(exchange))
+                .collect::<Vec<_>>()
+                .into();
+        }
+    }
+
+    let v = match serde_json::from_str::<Value>(txt) {
+        Ok(v) => v,
+        Err(_) => {
+            warn!("Failed to parse Binance WS message: {}", txt);
+            return Parsed::Prices(Vec::new());
+        }
+    };
+    let arr = match v {
+        Value::Array(arr) => arr,
+        other => {
+            if is_notice(&other) {
+                return Parsed::Notice(notice_text(&other));
+            }
+            return match error_response(&other) {
+                Some(err) => Parsed::Error(err),
+                None => Parsed::Prices(Vec::new()),
+            };
+        }
+    };
+
+    let mut out = Vec::with_capacity(arr.len());
+    for it in arr {
+        let sym = it.get("s").and_then(|v| v.as_str());
+        let price_opt = parse_f64(it.get("c"));
+        let vol_opt = parse_f64(it.get("v"))
+            .or_else(|| parse_f64(it.get("q")))
+            .or_else(|| parse_f64(it.get("Q")));
+        let event_time = it.get("E").and_then(|v| v.as_u64()).unwrap_or(0);
+        let bid = parse_f64(it.get("b"));
+        let ask = parse_f64(it.get("a"));
+
+        if let (Some(sym), Some(price)) = (sym, price_opt) {
+            let Some((base, quote)) = symbols().split(exchange, sym) else {
+                coverage().record_dropped(exchange, sym, "unparsed");
+                continue;
+            };
+            out.push(PairPrice {
+                base,
+                quote,
+                price,
+                is_spot: true,
+                volume: vol_opt.unwrap_or(0.0),
+                event_time,
+                bid,
+                ask,
+            });
+        }
+    }
+    out.into()
+}
+
+/// Borrowed view of one `24hrTicker` entry, decoded without building a
+/// `Value` tree; unknown fields are skipped by serde.
+#[cfg(feature = "binance-fast-parse")]
+#[derive(serde::Deserialize)]
+struct RawTicker<'a> {
+    #[serde(rename = "E", default)]
+    event_time: u64,
+    #[serde(rename = "s")]
+    symbol: &'a str,
+    #[serde(rename = "c")]
+    close: &'a str,
+    #[serde(rename = "v")]
+    volume: &'a str,
+    #[serde(rename = "b", default)]
+    bid: Option<&'a str>,
+    #[serde(rename = "a", default)]
+    ask: Option<&'a str>,
+}
+
+#[cfg(feature = "binance-fast-parse")]
+impl RawTicker<'_> {
+    fn to_pair(&self, exchange: &str) -> Option<PairPrice> {
+        let Some((base, quote)) = symbols().split(exchange, self.symbol) else {
+            coverage().record_dropped(exchange, self.symbol, "unparsed");
+            return None;
+        };
+        Some(PairPrice {
+            base,
+            quote,
+            price: self.close.parse().ok()?,
+            is_spot: true,
+            volume: self.volume.parse().unwrap_or(0.0),
+            event_time: self.event_time,
+            bid: self.bid.and_then(|b| b.parse().ok()),
+            ask: self.ask.and_then(|a| a.parse().ok()),
+        })
+    }
+}
+
+/// Binance announces planned disconnects with a `serverShutdown` event.
+fn is_notice(v: &Value) -> bool {
+    v.get("e").and_then(|e| e.as_str()) == Some("serverShutdown")
+}
+
+/// Binance errors come either bare (`{"code":-1003,"msg":"..."}`) or wrapped
+/// in an `error` object when answering a request id.
+fn error_response(v: &Value) -> Option<VenueError> {
+    let err = v.get("error").unwrap_or(v);
+    let code = err.get("code")?;
+    let msg = err.get("msg").and_then(|m| m.as_str()).unwrap_or("error");
+    let mut err = VenueError::new(format!("code {}: {}", code, msg));
+    // -1003: too many requests, 429/418: HTTP-level rate limit / ban
+    if matches!(code.as_i64(), Some(-1003) | Some(429) | Some(418)) {
+        err.rate_limited = true;
+    }
+    Some(err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(txt: &str) -> Parsed {
+        parse_ticker_message("binance", txt)
+    }
+
+    #[test]
+    fn request_weight_errors_are_rate_limits() {
+        let weight = r#"{"code":-1003,"msg":"Too much request weight used"}"#;
+        assert!(matches!(parse(weight), Parsed::Error(e) if e.rate_limited));
+        let invalid = r#"{"error":{"code":2,"msg":"Invalid request"},"id":1}"#;
+        assert!(matches!(parse(invalid), Parsed::Error(e) if !e.rate_limited));
+        // Subscription acks are not errors.
+        assert!(parse(r#"{"result":null,"id":1}"#).prices().is_empty());
+    }
+
+    #[test]
+    fn server_shutdown_is_a_notice() {
+        let shutdown = r#"{"e":"serverShutdown","E":1700000000000}"#;
+        assert!(matches!(parse(shutdown), Parsed::Notice(_)));
+    }
+
+    #[test]
+    fn book_tickers_override_the_ticker_top_of_book() {
+        let tickers = r#"[{"e":"24hrTicker","E":1700000000000,"s":"BTCUSDT","c":"65000.1","b":"65000.0","a":"65000.2","v":"10000.5"}]"#;
+        let mut book = BookTickers::default();
+        let update = r#"{"u":400900217,"s":"BTCUSDT","b":"65000.05","B":"31.21","a":"65000.15","A":"40.66"}"#;
+        // No ticker yet: the update is kept for it.
+        assert!(book.parse("binance", update).prices().is_empty());
+        let prices = book.parse("binance", tickers).prices();
+        assert_eq!(prices[0].price, 65000.1);
+        assert_eq!(
+            (prices[0].bid, prices[0].ask),
+            (Some(65000.05), Some(65000.15))
+        );
+
+        let update = r#"{"u":400900218,"s":"BTCUSDT","b":"65000.1","B":"1","a":"65000.3","A":"2"}"#;
+        let prices = book.parse("binance", update).prices();
+        assert_eq!(prices[0].price, 65000.1);
+        assert_eq!(
+            (prices[0].bid, prices[0].ask),
+            (Some(65000.1), Some(65000.3))
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use super::{parse_f64, Parsed, VenueError};
+use crate::coverage::coverage;
+use crate::models::PairPrice;
+use crate::rest;
+use crate::store::SharedPrices;
+use crate::symbols::symbols;
+use crate::utils::now_ms;
+use crate::ws_manager::{run_connector, ExchangeConnector, Heartbeat, Subscriptions};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use tokio::time::Duration;
+use tracing::{info, warn};
+
+const EXCHANGE: &str = "bingx";
+const WS_URL: &str = "wss://open-api-ws.bingx.com/market";
+/// BingX takes one `dataType` per subscribe request.
+const SUBSCRIBE_CHUNK: usize = 1;
+/// Topics per connection, taken from the most traded symbols.
+const MAX_TOPICS: usize = 200;
+
+/// Long-running BingX spot worker on the per-symbol `@ticker` streams of the
+/// most traded online symbols. Frames arrive gzipped; the session driver
+/// inflates them.
+pub async fn run_bingx_ws(prices: SharedPrices) {
+    let subs = Subscriptions::new(SUBSCRIBE_CHUNK, subscribe_message);
+    run_connector(BingX { subs }, prices).await;
+}
+
+struct BingX {
+    subs: Subscriptions,
+}
+
+impl ExchangeConnector for BingX {
+    fn name(&self) -> &'static str {
+        EXCHANGE
+    }
+
+    async fn connect(&mut self) -> Result<String, String> {
+        let online = match rest::bingx::instruments().await {
+            Ok(list) => {
+                let online: HashSet<String> = list
+                    .into_iter()
+                    .filter(|i| i.trading)
+                    .map(|i| i.symbol)
+                    .collect();
+                coverage().set_listed(EXCHANGE, online.len());
+                online
+            }
+            Err(e) if self.subs.len() == 0 => {
+                return Err(format!("symbols fetch failed: {}", e));
+            }
+            Err(e) => {
+                warn!(
+                    "bingx symbols fetch failed: {} (restoring {} known subscriptions)",
+                    e,
+                    self.subs.len()
+                );
+                HashSet::new()
+            }
+        };
+        if !online.is_empty() {
+            match rest::bingx::most_traded().await {
+                Ok(ranked) => self.subs.replace(
+                    ranked
+                        .into_iter()
+                        .filter(|s| online.contains(s))
+                        .take(MAX_TOPICS)
+                        .map(|s| format!("{}@ticker", s)),
+                ),
+                Err(e) if self.subs.len() == 0 => {
+                    return Err(format!("tickers fetch failed: {}", e));
+                }
+                Err(e) => warn!("bingx tickers fetch failed: {}", e),
+            }
+        }
+        info!("bingx: subscribing to {} symbols", self.subs.len());
+        Ok(WS_URL.to_string())
+    }
+
+    fn subscribe(&self) -> Vec<String> {
+        self.subs.messages()
+    }
+
+    fn keepalive(&self) -> Heartbeat {
+        Heartbeat::Text {
+            interval: Duration::from_secs(5),
+            message: pong_message,
+        }
+    }
+
+    fn parse_message(&mut self, txt: &str) -> Parsed {
+        parse_message(txt)
+    }
+}
+
+/// The server sends "Ping" every 5s and drops clients that don't answer
+/// "Pong"; answering on the same cadence keeps the session alive.
+fn pong_message() -> String {
+    "Pong".to_string()
+}
+
+fn subscribe_message(topics: &[String]) -> String {
+    let topic = topics.first().map(String::as_str).unwrap_or_default();
+    json!({ "id": now_ms().to_string(), "reqType": "sub", "dataType": topic }).to_string()
+}
+
+fn parse_message(txt: &str) -> Parsed {
+    let v: Value = match serde_json::from_str(txt) {
+        Ok(v) => v,
+        // "Ping" and other bare-text frames.
+        Err(_) => return Parsed::Prices(Vec::new()),
+    };
+    let code = v.get("code").and_then(|c| c.as_i64()).unwrap_or(0);
+    if code != 0 {
+        let msg = v.get("msg").and_then(|m| m.as_str()).unwrap_or("error");
+        return Parsed::Error(VenueError::new(format!("{} {}", code, msg)));
+    }
+    let topic = v.get("dataType").and_then(|d| d.as_str()).unwrap_or("");
+    if !topic.ends_with("@ticker") {
+        return Parsed::Prices(Vec::new());
+    }
+    v.get("data")
+        .and_then(parse_ticker)
+        .into_iter()
+        .collect::<Vec<_>>()
+        .into()
+}
+
+/// `{"s":"BTC-USDT","c":"..","v":"..","B":"..","A":"..","E":..,"P":"1.2%"}`.
+fn parse_ticker(t: &Value) -> Option<PairPrice> {
+    let sym = t.get("s").and_then(|s| s.as_str())?;
+    let Some((base, quote)) = symbols().split(EXCHANGE, sym) else {
+        coverage().record_dropped(EXCHANGE, sym, "unparsed");
+        return None;
+    };
+    Some(PairPrice {
+        base,
+        quote,
+        price: parse_f64(t.get("c"))?,
+        is_spot: true,
+        volume: parse_f64(t.get("v")).unwrap_or(0.0),
+        event_time: t.get("E").and_then(|e| e.as_u64()).unwrap_or(0),
+        bid: parse_f64(t.get("B")),
+        ask: parse_f64(t.get("A")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tickers_parse_past_percent_fields() {
+        let frame = r#"{"code":0,"dataType":"BTC-USDT@ticker","data":{"e":"24hTicker","E":1700000000000,"s":"BTC-USDT","p":"1000.1","P":"1.56%","o":"64000","h":"65500","l":"63800","c":"65000.1","v":"10000.5","q":"650000000","B":"65000","A":"65000.2"}}"#;
+        let prices = parse_message(frame).prices();
+        assert_eq!(prices.len(), 1);
+        let p = &prices[0];
+        assert_eq!((p.base.as_str(), p.quote.as_str()), ("BTC", "USDT"));
+        assert_eq!((p.price, p.volume), (65000.1, 10000.5));
+        assert_eq!((p.bid, p.ask), (Some(65000.0), Some(65000.2)));
+        assert_eq!(p.event_time, 1_700_000_000_000);
+        // Keepalive text frames carry nothing.
+        assert!(parse_message("Ping").prices().is_empty());
+    }
+}
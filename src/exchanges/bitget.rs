@@ -0,0 +1,146 @@
+use super::{Parsed, VenueError};
+use crate::coverage::coverage;
+use crate::models::PairPrice;
+use crate::rest::bitget::Ticker;
+use crate::store::SharedPrices;
+use crate::symbols::symbols;
+use crate::ws_manager::{run_connector, ExchangeConnector, Heartbeat, Subscriptions};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::time::Duration;
+use tracing::{info, warn};
+
+const EXCHANGE: &str = "bitget";
+const WS_URL: &str = "wss://ws.bitget.com/v2/ws/public";
+/// Bitget caps a connection at 1000 channels and a request at 4096 bytes.
+const SUBSCRIBE_CHUNK: usize = 50;
+
+/// Long-running Bitget spot worker on the v2 `ticker` channel, one `instId`
+/// per online symbol.
+pub async fn run_bitget_ws(prices: SharedPrices) {
+    let subs = Subscriptions::new(SUBSCRIBE_CHUNK, subscribe_message);
+    run_connector(Bitget { subs }, prices).await;
+}
+
+struct Bitget {
+    subs: Subscriptions,
+}
+
+impl ExchangeConnector for Bitget {
+    fn name(&self) -> &'static str {
+        EXCHANGE
+    }
+
+    async fn connect(&mut self) -> Result<String, String> {
+        match symbols().refresh(EXCHANGE).await {
+            Ok(list) => {
+                self.subs
+                    .replace(list.into_iter().filter(|i| i.trading).map(|i| i.symbol));
+            }
+            Err(e) if self.subs.len() == 0 => {
+                return Err(format!("symbols fetch failed: {}", e));
+            }
+            Err(e) => warn!(
+                "bitget symbols fetch failed: {} (restoring {} known subscriptions)",
+                e,
+                self.subs.len()
+            ),
+        }
+        info!("bitget: subscribing to {} spot symbols", self.subs.len());
+        Ok(WS_URL.to_string())
+    }
+
+    fn subscribe(&self) -> Vec<String> {
+        self.subs.messages()
+    }
+
+    fn keepalive(&self) -> Heartbeat {
+        Heartbeat::Text {
+            interval: Duration::from_secs(30),
+            message: ping_message,
+        }
+    }
+
+    fn parse_message(&mut self, txt: &str) -> Parsed {
+        parse_message(txt)
+    }
+}
+
+/// Bitget drops connections without a "ping" for two minutes.
+fn ping_message() -> String {
+    "ping".to_string()
+}
+
+fn subscribe_message(symbols: &[String]) -> String {
+    let args: Vec<Value> = symbols
+        .iter()
+        .map(|s| json!({ "instType": "SPOT", "channel": "ticker", "instId": s }))
+        .collect();
+    json!({ "op": "subscribe", "args": args }).to_string()
+}
+
+#[derive(Deserialize)]
+struct Push {
+    #[serde(default)]
+    arg: Option<Value>,
+    #[serde(default)]
+    data: Vec<Ticker>,
+}
+
+fn parse_message(txt: &str) -> Parsed {
+    if txt == "pong" {
+        return Parsed::Prices(Vec::new());
+    }
+    let v: Value = match serde_json::from_str(txt) {
+        Ok(v) => v,
+        Err(_) => return Parsed::Prices(Vec::new()),
+    };
+    if v.get("event").and_then(|e| e.as_str()) == Some("error") {
+        let code = v.get("code").map(|c| c.to_string()).unwrap_or_default();
+        let msg = v.get("msg").and_then(|m| m.as_str()).unwrap_or("error");
+        return Parsed::Error(VenueError::new(format!("{} {}", code, msg)));
+    }
+    let Ok(push) = serde_json::from_value::<Push>(v) else {
+        return Parsed::Prices(Vec::new());
+    };
+    let channel = push.arg.as_ref().and_then(|a| a.get("channel")?.as_str());
+    if channel != Some("ticker") {
+        return Parsed::Prices(Vec::new());
+    }
+    push.data
+        .into_iter()
+        .filter_map(|t| {
+            let Some((base, quote)) = symbols().pair(EXCHANGE, &t.symbol) else {
+                coverage().record_dropped(EXCHANGE, &t.symbol, "unparsed");
+                return None;
+            };
+            Some(t.into_price(&base, &quote))
+        })
+        .collect::<Vec<PairPrice>>()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::list_symbols;
+
+    fn parse(txt: &str) -> Parsed {
+        list_symbols(EXCHANGE, &[("BTCUSDT", "BTC", "USDT")]);
+        parse_message(txt)
+    }
+
+    #[test]
+    fn symbols_split_from_the_instrument_list_only() {
+        let frame = r#"{"action":"snapshot","arg":{"instType":"SPOT","channel":"ticker","instId":"BTCUSDT"},"data":[{"instId":"BTCUSDT","lastPr":"65000.1","bidPr":"65000","askPr":"65000.2","baseVolume":"10000.5","ts":"1700000000000"}],"ts":1700000000001}"#;
+        let prices = parse(frame).prices();
+        assert_eq!(prices.len(), 1);
+        assert_eq!(
+            (prices[0].base.as_str(), prices[0].quote.as_str()),
+            ("BTC", "USDT")
+        );
+        // Not listed: dropped rather than split by guessing at the suffix.
+        let unlisted = frame.replace("BTCUSDT", "ETHUSDT");
+        assert!(parse(&unlisted).prices().is_empty());
+    }
+}
@@ -0,0 +1,165 @@
+use super::{Parsed, VenueError};
+use crate::coverage::coverage;
+use crate::models::PairPrice;
+use crate::rest;
+use crate::rest::bithumb::{Orderbook, Ticker};
+use crate::store::SharedPrices;
+use crate::utils::now_ms;
+use crate::ws_manager::{run_connector, ExchangeConnector, Heartbeat, Subscriptions};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tokio::time::Duration;
+use tracing::{info, warn};
+
+const EXCHANGE: &str = "bithumb";
+const WS_URL: &str = "wss://ws-api.bithumb.com/websocket/v1";
+/// A new request replaces the connection's subscription, so every market
+/// has to fit in one frame.
+const SUBSCRIBE_CHUNK: usize = 2000;
+
+/// Long-running Bithumb worker for the KRW and BTC markets: `ticker` sets the
+/// last price and volume, `orderbook` the best bid/ask.
+pub async fn run_bithumb_ws(prices: SharedPrices) {
+    let bithumb = Bithumb {
+        subs: Subscriptions::new(SUBSCRIBE_CHUNK, subscribe_message),
+        latest: HashMap::new(),
+    };
+    run_connector(bithumb, prices).await;
+}
+
+struct Bithumb {
+    subs: Subscriptions,
+    /// Latest price per market, so book updates can carry the last price and
+    /// tickers keep the last book.
+    latest: HashMap<String, PairPrice>,
+}
+
+impl ExchangeConnector for Bithumb {
+    fn name(&self) -> &'static str {
+        EXCHANGE
+    }
+
+    async fn connect(&mut self) -> Result<String, String> {
+        match rest::bithumb::instruments().await {
+            Ok(list) => {
+                coverage().set_listed(EXCHANGE, list.len());
+                self.subs.replace(list.into_iter().map(|i| i.symbol));
+            }
+            Err(e) if self.subs.len() == 0 => {
+                return Err(format!("markets fetch failed: {}", e));
+            }
+            Err(e) => warn!(
+                "bithumb markets fetch failed: {} (restoring {} known subscriptions)",
+                e,
+                self.subs.len()
+            ),
+        }
+        info!("bithumb: subscribing to {} markets", self.subs.len());
+        Ok(WS_URL.to_string())
+    }
+
+    fn subscribe(&self) -> Vec<String> {
+        self.subs.messages()
+    }
+
+    fn keepalive(&self) -> Heartbeat {
+        Heartbeat::Text {
+            interval: Duration::from_secs(60),
+            message: ping_message,
+        }
+    }
+
+    fn parse_message(&mut self, txt: &str) -> Parsed {
+        parse_message(txt, &mut self.latest)
+    }
+}
+
+/// Idle connections are closed after 120s; "PING" is answered with
+/// `{"status":"UP"}`.
+fn ping_message() -> String {
+    "PING".to_string()
+}
+
+fn subscribe_message(markets: &[String]) -> String {
+    json!([
+        { "ticket": format!("scanner-{}", now_ms()) },
+        { "type": "ticker", "codes": markets },
+        { "type": "orderbook", "codes": markets },
+    ])
+    .to_string()
+}
+
+fn parse_message(txt: &str, latest: &mut HashMap<String, PairPrice>) -> Parsed {
+    let v: Value = match serde_json::from_str(txt) {
+        Ok(v) => v,
+        Err(_) => return Parsed::Prices(Vec::new()),
+    };
+    if let Some(err) = v.get("error") {
+        let name = err.get("name").and_then(|n| n.as_str()).unwrap_or("error");
+        let msg = err.get("message").and_then(|m| m.as_str()).unwrap_or("");
+        return Parsed::Error(VenueError::new(format!("{}: {}", name, msg)));
+    }
+    // Type names are upper case in some responses (`TICKER`, `ORDERBOOK`).
+    let kind = v
+        .get("type")
+        .and_then(|t| t.as_str())
+        .map(|t| t.to_ascii_lowercase());
+    match kind.as_deref() {
+        Some("ticker") => parse_ticker(v, latest).into(),
+        Some("orderbook") => parse_orderbook(v, latest).into(),
+        _ => Parsed::Prices(Vec::new()),
+    }
+}
+
+fn parse_ticker(v: Value, latest: &mut HashMap<String, PairPrice>) -> Vec<PairPrice> {
+    let Ok(t) = serde_json::from_value::<Ticker>(v) else {
+        return Vec::new();
+    };
+    let market = t.market.clone();
+    let Some(p) = t.into_price() else {
+        coverage().record_dropped(EXCHANGE, &market, "unparsed");
+        return Vec::new();
+    };
+    let entry = latest.entry(market).or_insert_with(|| p.clone());
+    entry.price = p.price;
+    entry.volume = p.volume;
+    entry.event_time = p.event_time;
+    vec![entry.clone()]
+}
+
+/// Dropped until a ticker has supplied a last price.
+fn parse_orderbook(v: Value, latest: &mut HashMap<String, PairPrice>) -> Vec<PairPrice> {
+    let Ok(book) = serde_json::from_value::<Orderbook>(v) else {
+        return Vec::new();
+    };
+    let (Some(top), Some(entry)) = (book.orderbook_units.first(), latest.get_mut(&book.market))
+    else {
+        return Vec::new();
+    };
+    entry.bid = Some(top.bid_price).filter(|p| *p > 0.0);
+    entry.ask = Some(top.ask_price).filter(|p| *p > 0.0);
+    entry.event_time = book.timestamp.unwrap_or(entry.event_time);
+    vec![entry.clone()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upper_case_orderbook_frames_merge_into_the_ticker() {
+        let mut latest = HashMap::new();
+        let ticker = r#"{"type":"TICKER","code":"KRW-BTC","trade_price":90500000,"acc_trade_volume_24h":1520.37,"timestamp":1700000000000,"stream_type":"REALTIME"}"#;
+        let prices = parse_message(ticker, &mut latest).prices();
+        let p = &prices[0];
+        assert_eq!((p.base.as_str(), p.quote.as_str()), ("BTC", "KRW"));
+        assert_eq!((p.price, p.volume), (90_500_000.0, 1520.37));
+
+        let book = r#"{"type":"ORDERBOOK","code":"KRW-BTC","timestamp":1700000000100,"orderbook_units":[{"ask_price":90510000,"bid_price":90490000,"ask_size":0.5,"bid_size":0.7}],"stream_type":"REALTIME"}"#;
+        let prices = parse_message(book, &mut latest).prices();
+        let p = &prices[0];
+        assert_eq!(p.price, 90_500_000.0);
+        assert_eq!((p.bid, p.ask), (Some(90_490_000.0), Some(90_510_000.0)));
+        assert_eq!(p.event_time, 1_700_000_000_100);
+    }
+}
@@ -0,0 +1,159 @@
+use super::{parse_f64, Parsed, VenueError};
+use crate::coverage::coverage;
+use crate::models::PairPrice;
+use crate::store::SharedPrices;
+use crate::symbols::symbols;
+use crate::ws_manager::{run_connector, ExchangeConnector, Heartbeat, Subscriptions};
+use serde_json::{json, Value};
+use tokio::time::Duration;
+use tracing::{info, warn};
+
+const EXCHANGE: &str = "bitstamp";
+const WS_URL: &str = "wss://ws.bitstamp.net";
+const LIVE_TRADES: &str = "live_trades_";
+/// Bitstamp takes one channel per `bts:subscribe` event.
+const SUBSCRIBE_CHUNK: usize = 1;
+
+/// Long-running Bitstamp worker on the per-pair `live_trades` channels, the
+/// pairs taken from the REST trading-pairs list. The public socket has no
+/// ticker channel, so prices are last trades without volume or book.
+pub async fn run_bitstamp_ws(prices: SharedPrices) {
+    let subs = Subscriptions::new(SUBSCRIBE_CHUNK, subscribe_message);
+    run_connector(Bitstamp { subs }, prices).await;
+}
+
+struct Bitstamp {
+    subs: Subscriptions,
+}
+
+impl ExchangeConnector for Bitstamp {
+    fn name(&self) -> &'static str {
+        EXCHANGE
+    }
+
+    async fn connect(&mut self) -> Result<String, String> {
+        match symbols().refresh(EXCHANGE).await {
+            Ok(list) => {
+                self.subs.replace(
+                    list.into_iter()
+                        .filter(|i| i.trading)
+                        .map(|i| format!("{}{}", LIVE_TRADES, i.symbol)),
+                );
+            }
+            Err(e) if self.subs.len() == 0 => {
+                return Err(format!("trading pairs fetch failed: {}", e));
+            }
+            Err(e) => warn!(
+                "bitstamp trading pairs fetch failed: {} (restoring {} known subscriptions)",
+                e,
+                self.subs.len()
+            ),
+        }
+        info!("bitstamp: subscribing to {} pairs", self.subs.len());
+        Ok(WS_URL.to_string())
+    }
+
+    fn subscribe(&self) -> Vec<String> {
+        self.subs.messages()
+    }
+
+    fn keepalive(&self) -> Heartbeat {
+        Heartbeat::Text {
+            interval: Duration::from_secs(30),
+            message: ping_message,
+        }
+    }
+
+    fn parse_message(&mut self, txt: &str) -> Parsed {
+        parse_message(txt)
+    }
+}
+
+fn ping_message() -> String {
+    json!({ "event": "bts:heartbeat" }).to_string()
+}
+
+fn subscribe_message(channels: &[String]) -> String {
+    let channel = channels.first().map(String::as_str).unwrap_or_default();
+    json!({ "event": "bts:subscribe", "data": { "channel": channel } }).to_string()
+}
+
+fn parse_message(txt: &str) -> Parsed {
+    let v: Value = match serde_json::from_str(txt) {
+        Ok(v) => v,
+        Err(_) => return Parsed::Prices(Vec::new()),
+    };
+    match v.get("event").and_then(|e| e.as_str()) {
+        Some("trade") => parse_trade(&v).into(),
+        Some("bts:error") => {
+            let msg = v
+                .get("data")
+                .and_then(|d| d.get("message"))
+                .and_then(|m| m.as_str())
+                .unwrap_or("error");
+            Parsed::Error(VenueError::new(msg))
+        }
+        // Sent ahead of maintenance; the session is closed shortly after.
+        Some("bts:request_reconnect") => Parsed::Notice("reconnect requested".to_string()),
+        _ => Parsed::Prices(Vec::new()),
+    }
+}
+
+/// `{"event":"trade","channel":"live_trades_btcusd","data":{"price":..,"microtimestamp":".."}}`.
+fn parse_trade(v: &Value) -> Vec<PairPrice> {
+    let Some(sym) = v
+        .get("channel")
+        .and_then(|c| c.as_str())
+        .and_then(|c| c.strip_prefix(LIVE_TRADES))
+    else {
+        return Vec::new();
+    };
+    let Some((base, quote)) = symbols().pair(EXCHANGE, sym) else {
+        coverage().record_dropped(EXCHANGE, sym, "unparsed");
+        return Vec::new();
+    };
+    let Some(data) = v.get("data") else {
+        return Vec::new();
+    };
+    let Some(price) = parse_f64(data.get("price")) else {
+        return Vec::new();
+    };
+    let event_time = parse_f64(data.get("microtimestamp"))
+        .map(|us| (us / 1000.0) as u64)
+        .unwrap_or(0);
+    vec![PairPrice {
+        base,
+        quote,
+        price,
+        is_spot: true,
+        volume: 0.0,
+        event_time,
+        bid: None,
+        ask: None,
+    }]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::list_symbols;
+
+    fn parse(txt: &str) -> Parsed {
+        list_symbols(EXCHANGE, &[("btcusd", "BTC", "USD")]);
+        parse_message(txt)
+    }
+
+    #[test]
+    fn trades_are_keyed_by_their_channel() {
+        let frame = r#"{"data":{"id":318523391,"timestamp":"1700000000","amount":0.0021,"price":37012.5,"type":0,"microtimestamp":"1700000000123456"},"channel":"live_trades_btcusd","event":"trade"}"#;
+        let prices = parse(frame).prices();
+        assert_eq!(prices.len(), 1);
+        let p = &prices[0];
+        assert_eq!((p.base.as_str(), p.quote.as_str()), ("BTC", "USD"));
+        assert_eq!(p.price, 37012.5);
+        assert_eq!(p.event_time, 1_700_000_000_123);
+
+        let reconnect = r#"{"event":"bts:request_reconnect","channel":"","data":""}"#;
+        assert!(matches!(parse(reconnect), Parsed::Notice(_)));
+    }
+}
@@ -0,0 +1,231 @@
+use super::{notice_text, parse_f64, Parsed, VenueError};
+use crate::config::config;
+use crate::coverage::coverage;
+use crate::models::PairPrice;
+use crate::store::SharedPrices;
+use crate::symbols::symbols;
+use crate::utils::now_ms;
+use crate::ws_manager::{run_connector, ExchangeConnector, Heartbeat, Subscriptions};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tokio::time::Duration;
+use tracing::{info, warn};
+
+const EXCHANGE: &str = "bybit";
+const WS_URL: &str = "wss://stream.bybit.com/v5/public/spot";
+const TESTNET_WS_URL: &str = "wss://stream-testnet.bybit.com/v5/public/spot";
+/// Bybit spot accepts at most 10 topics per subscribe request.
+const SUBSCRIBE_CHUNK: usize = 10;
+
+/// Long-running Bybit spot worker. Bybit has no all-tickers topic, so the
+/// instrument list is fetched over REST and `tickers.{symbol}` subscribed in chunks.
+/// With `top_of_book`, `orderbook.1.{symbol}` is subscribed too and supplies
+/// the bid/ask the spot tickers lack.
+pub async fn run_bybit_ws(prices: SharedPrices) {
+    let bybit = Bybit {
+        top_of_book: config().connector(EXCHANGE).top_of_book,
+        subs: Subscriptions::new(SUBSCRIBE_CHUNK, subscribe_message),
+        latest: Latest::default(),
+    };
+    run_connector(bybit, prices).await;
+}
+
+struct Bybit {
+    top_of_book: bool,
+    subs: Subscriptions,
+    latest: Latest,
+}
+
+impl ExchangeConnector for Bybit {
+    fn name(&self) -> &'static str {
+        EXCHANGE
+    }
+
+    async fn connect(&mut self) -> Result<String, String> {
+        match symbols().refresh(EXCHANGE).await {
+            Ok(_) => {
+                let top_of_book = self.top_of_book;
+                self.subs
+                    .replace(symbols().tradable(EXCHANGE).into_iter().flat_map(|s| {
+                        let book = top_of_book.then(|| format!("orderbook.1.{}", s));
+                        std::iter::once(format!("tickers.{}", s)).chain(book)
+                    }));
+            }
+            Err(e) if !symbols().is_loaded(EXCHANGE) => {
+                return Err(format!("instruments fetch failed: {}", e));
+            }
+            Err(e) => warn!(
+                "bybit instruments fetch failed: {} (restoring {} known subscriptions)",
+                e,
+                self.subs.len()
+            ),
+        }
+        info!(
+            "bybit: subscribing to {} topics for {} spot symbols",
+            self.subs.len(),
+            symbols().count(EXCHANGE)
+        );
+        Ok(config()
+            .connector(EXCHANGE)
+            .endpoint(WS_URL, TESTNET_WS_URL)
+            .to_string())
+    }
+
+    fn subscribe(&self) -> Vec<String> {
+        self.subs.messages()
+    }
+
+    fn keepalive(&self) -> Heartbeat {
+        Heartbeat::Text {
+            interval: Duration::from_secs(20),
+            message: ping_message,
+        }
+    }
+
+    fn parse_message(&mut self, txt: &str) -> Parsed {
+        parse_message(txt, &mut self.latest)
+    }
+}
+
+/// Bybit's documented keepalive: `{"req_id": ..., "op": "ping"}` every 20s.
+fn ping_message() -> String {
+    json!({ "req_id": now_ms().to_string(), "op": "ping" }).to_string()
+}
+
+fn subscribe_message(topics: &[String]) -> String {
+    json!({ "op": "subscribe", "args": topics }).to_string()
+}
+
+/// Per symbol, the last ticker and top of book, merged because each
+/// topic only carries half of a `PairPrice`.
+#[derive(Default)]
+struct Latest {
+    tickers: HashMap<String, PairPrice>,
+    books: HashMap<String, (Option<f64>, Option<f64>)>,
+}
+
+fn parse_message(txt: &str, latest: &mut Latest) -> Parsed {
+    let v: Value = match serde_json::from_str(txt) {
+        Ok(v) => v,
+        Err(_) => return Parsed::Prices(Vec::new()),
+    };
+    if is_notice(&v) {
+        return Parsed::Notice(notice_text(&v));
+    }
+    if let Some(err) = error_response(&v) {
+        return Parsed::Error(err);
+    }
+    let topic = v.get("topic").and_then(|t| t.as_str()).unwrap_or("");
+    if topic.starts_with("orderbook.1.") {
+        return Parsed::Book(parse_book(&v, latest).into_iter().collect());
+    }
+    let Some((sym, mut p)) = parse_ticker(&v) else {
+        return Parsed::Prices(Vec::new());
+    };
+    if let Some(&(bid, ask)) = latest.books.get(&sym) {
+        p.bid = bid;
+        p.ask = ask;
+    }
+    latest.tickers.insert(sym, p.clone());
+    vec![p].into()
+}
+
+/// `orderbook.1.{symbol}`: every push is a one-level snapshot,
+/// `{"data":{"s":"BTCUSDT","b":[["65000.1","0.5"]],"a":[["65000.2","1.2"]]}}`.
+/// Emits the pair once its ticker has been seen.
+fn parse_book(v: &Value, latest: &mut Latest) -> Option<PairPrice> {
+    let data = v.get("data")?;
+    let sym = data.get("s").and_then(|s| s.as_str())?;
+    let best = |side: &str| parse_f64(data.get(side)?.get(0)?.get(0));
+    let (bid, ask) = (best("b"), best("a"));
+    latest.books.insert(sym.to_string(), (bid, ask));
+    let p = latest.tickers.get_mut(sym)?;
+    p.bid = bid;
+    p.ask = ask;
+    Some(p.clone())
+}
+
+/// Bybit pushes maintenance/system messages as `notice` ops or `system.*` topics.
+fn is_notice(v: &Value) -> bool {
+    let op = v.get("op").and_then(|o| o.as_str());
+    let kind = v.get("type").and_then(|t| t.as_str());
+    let topic = v.get("topic").and_then(|t| t.as_str()).unwrap_or("");
+    op == Some("notice") || kind == Some("notice") || topic.starts_with("system")
+}
+
+/// Bybit acknowledges every op with `success`; a `false` means the request was
+/// rejected, e.g. `{"success":false,"ret_msg":"...","op":"subscribe"}`.
+fn error_response(v: &Value) -> Option<VenueError> {
+    if v.get("success").and_then(|s| s.as_bool()) != Some(false) {
+        return None;
+    }
+    let op = v.get("op").and_then(|o| o.as_str()).unwrap_or("request");
+    let msg = v
+        .get("ret_msg")
+        .and_then(|m| m.as_str())
+        .unwrap_or("rejected");
+    Some(VenueError::new(format!("{} failed: {}", op, msg)))
+}
+
+/// A `tickers.{symbol}` push and the symbol it is for.
+fn parse_ticker(v: &Value) -> Option<(String, PairPrice)> {
+    let topic = v.get("topic").and_then(|t| t.as_str())?;
+    if !topic.starts_with("tickers.") {
+        return None;
+    }
+
+    let data = v.get("data")?;
+    let sym = data.get("symbol").and_then(|s| s.as_str())?;
+    let Some((base, quote)) = symbols().pair(EXCHANGE, sym) else {
+        coverage().record_dropped(EXCHANGE, sym, "unparsed");
+        return None;
+    };
+    let price = parse_f64(data.get("lastPrice"))?;
+
+    let p = PairPrice {
+        base,
+        quote,
+        price,
+        is_spot: true,
+        volume: parse_f64(data.get("volume24h")).unwrap_or(0.0),
+        event_time: v.get("ts").and_then(|t| t.as_u64()).unwrap_or(0),
+        bid: parse_f64(data.get("bid1Price")),
+        ask: parse_f64(data.get("ask1Price")),
+    };
+    Some((sym.to_string(), p))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::list_symbols;
+
+    fn parse(txt: &str, latest: &mut Latest) -> Parsed {
+        list_symbols(EXCHANGE, &[("BTCUSDT", "BTC", "USDT")]);
+        parse_message(txt, latest)
+    }
+
+    #[test]
+    fn top_of_book_applies_to_the_ticker() {
+        let mut latest = Latest::default();
+        let book = r#"{"topic":"orderbook.1.BTCUSDT","ts":1700000000100,"type":"snapshot","data":{"s":"BTCUSDT","b":[["65000.1","0.5"]],"a":[["65000.3","1.2"]],"u":1,"seq":2}}"#;
+        // Held until the ticker arrives.
+        assert!(parse(book, &mut latest).prices().is_empty());
+
+        let ticker = r#"{"topic":"tickers.BTCUSDT","ts":1700000000000,"type":"snapshot","cs":1,"data":{"symbol":"BTCUSDT","lastPrice":"65000.2","highPrice24h":"65500","lowPrice24h":"63800","prevPrice24h":"64000","volume24h":"10000.5","turnover24h":"650000000","price24hPcnt":"0.0156","usdIndexPrice":"65001"}}"#;
+        let prices = parse(ticker, &mut latest).prices();
+        assert_eq!(prices[0].price, 65000.2);
+        assert_eq!(
+            (prices[0].bid, prices[0].ask),
+            (Some(65000.1), Some(65000.3))
+        );
+
+        let book = r#"{"topic":"orderbook.1.BTCUSDT","ts":1700000000200,"type":"snapshot","data":{"s":"BTCUSDT","b":[["65000.0","0.7"]],"a":[["65000.2","0.3"]],"u":2,"seq":3}}"#;
+        let prices = parse(book, &mut latest).prices();
+        assert_eq!(prices[0].price, 65000.2);
+        assert_eq!(
+            (prices[0].bid, prices[0].ask),
+            (Some(65000.0), Some(65000.2))
+        );
+    }
+}
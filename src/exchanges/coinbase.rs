@@ -0,0 +1,140 @@
+use super::{parse_f64, Parsed, VenueError};
+use crate::coverage::coverage;
+use crate::models::PairPrice;
+use crate::rest;
+use crate::store::SharedPrices;
+use crate::symbols::symbols;
+use crate::ws_manager::{run_connector, ExchangeConnector, Heartbeat, Subscriptions};
+use serde_json::{json, Value};
+use tracing::{info, warn};
+
+const EXCHANGE: &str = "coinbase";
+const WS_URL: &str = "wss://advanced-trade-ws.coinbase.com";
+const SUBSCRIBE_CHUNK: usize = 100;
+
+/// Long-running Coinbase Advanced Trade worker on the public `ticker`
+/// channel, subscribed per online spot product.
+pub async fn run_coinbase_ws(prices: SharedPrices) {
+    let subs = Subscriptions::new(SUBSCRIBE_CHUNK, subscribe_message);
+    run_connector(Coinbase { subs }, prices).await;
+}
+
+struct Coinbase {
+    subs: Subscriptions,
+}
+
+impl ExchangeConnector for Coinbase {
+    fn name(&self) -> &'static str {
+        EXCHANGE
+    }
+
+    async fn connect(&mut self) -> Result<String, String> {
+        match rest::coinbase::instruments().await {
+            Ok(list) => {
+                let online: Vec<String> = list
+                    .into_iter()
+                    .filter(|i| i.trading)
+                    .map(|i| i.symbol)
+                    .collect();
+                coverage().set_listed(EXCHANGE, online.len());
+                self.subs.replace(online);
+            }
+            Err(e) if self.subs.len() == 0 => {
+                return Err(format!("products fetch failed: {}", e));
+            }
+            Err(e) => warn!(
+                "coinbase products fetch failed: {} (restoring {} known subscriptions)",
+                e,
+                self.subs.len()
+            ),
+        }
+        info!("coinbase: subscribing to {} products", self.subs.len());
+        Ok(WS_URL.to_string())
+    }
+
+    fn subscribe(&self) -> Vec<String> {
+        // The heartbeats channel keeps the connection open while quiet
+        // products produce no ticker updates.
+        let mut subscribe = self.subs.messages();
+        subscribe.push(json!({ "type": "subscribe", "channel": "heartbeats" }).to_string());
+        subscribe
+    }
+
+    fn keepalive(&self) -> Heartbeat {
+        Heartbeat::ServerPing
+    }
+
+    fn parse_message(&mut self, txt: &str) -> Parsed {
+        parse_message(txt)
+    }
+}
+
+fn subscribe_message(product_ids: &[String]) -> String {
+    json!({ "type": "subscribe", "channel": "ticker", "product_ids": product_ids }).to_string()
+}
+
+fn parse_message(txt: &str) -> Parsed {
+    let v: Value = match serde_json::from_str(txt) {
+        Ok(v) => v,
+        Err(_) => return Parsed::Prices(Vec::new()),
+    };
+    if v.get("type").and_then(|t| t.as_str()) == Some("error") {
+        let msg = v.get("message").and_then(|m| m.as_str()).unwrap_or("error");
+        return Parsed::Error(VenueError::new(msg));
+    }
+    parse_tickers(&v).into()
+}
+
+/// `{"channel":"ticker","timestamp":"...","events":[{"tickers":[...]}]}`.
+fn parse_tickers(v: &Value) -> Vec<PairPrice> {
+    if v.get("channel").and_then(|c| c.as_str()) != Some("ticker") {
+        return Vec::new();
+    }
+    let event_time = v
+        .get("timestamp")
+        .and_then(|t| t.as_str())
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.timestamp_millis().max(0) as u64)
+        .unwrap_or(0);
+    let events = v.get("events").and_then(|e| e.as_array());
+    events
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.get("tickers")?.as_array())
+        .flatten()
+        .filter_map(|t| {
+            let product = t.get("product_id").and_then(|p| p.as_str())?;
+            let Some((base, quote)) = symbols().split(EXCHANGE, product) else {
+                coverage().record_dropped(EXCHANGE, product, "unparsed");
+                return None;
+            };
+            Some(PairPrice {
+                base,
+                quote,
+                price: parse_f64(t.get("price"))?,
+                is_spot: true,
+                volume: parse_f64(t.get("volume_24_h")).unwrap_or(0.0),
+                event_time,
+                bid: parse_f64(t.get("best_bid")),
+                ask: parse_f64(t.get("best_ask")),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dashed_products_take_the_frame_timestamp() {
+        let frame = r#"{"channel":"ticker","client_id":"","timestamp":"2023-02-09T20:30:37.167359596Z","sequence_num":0,"events":[{"type":"snapshot","tickers":[{"type":"ticker","product_id":"BTC-USD","price":"21932.98","volume_24_h":"16038.28770938","best_bid":"21931.98","best_ask":"21933.98"}]}]}"#;
+        let prices = parse_message(frame).prices();
+        assert_eq!(prices.len(), 1);
+        let p = &prices[0];
+        assert_eq!((p.base.as_str(), p.quote.as_str()), ("BTC", "USD"));
+        assert_eq!((p.bid, p.ask), (Some(21931.98), Some(21933.98)));
+        // RFC 3339 with nanoseconds, truncated to milliseconds.
+        assert_eq!(p.event_time, 1_675_974_637_167);
+    }
+}
@@ -0,0 +1,140 @@
+use super::{parse_f64, Parsed, VenueError};
+use crate::coverage::coverage;
+use crate::models::PairPrice;
+use crate::store::SharedPrices;
+use crate::symbols::symbols;
+use crate::utils::now_ms;
+use crate::ws_manager::{run_connector, ExchangeConnector, Heartbeat};
+use serde_json::{json, Value};
+use tokio::time::Duration;
+use tracing::{info, warn};
+
+const EXCHANGE: &str = "coinex";
+const WS_URL: &str = "wss://socket.coinex.com/v2/spot";
+
+/// Long-running CoinEx spot worker on `state.subscribe` with an empty market
+/// list, which streams 24h state for every market. Frames arrive gzipped;
+/// the session driver inflates them.
+pub async fn run_coinex_ws(prices: SharedPrices) {
+    run_connector(Coinex, prices).await;
+}
+
+struct Coinex;
+
+impl ExchangeConnector for Coinex {
+    fn name(&self) -> &'static str {
+        EXCHANGE
+    }
+
+    async fn connect(&mut self) -> Result<String, String> {
+        match symbols().refresh(EXCHANGE).await {
+            Ok(_) => {}
+            Err(e) if !symbols().is_loaded(EXCHANGE) => {
+                return Err(format!("markets fetch failed: {}", e));
+            }
+            Err(e) => warn!(
+                "coinex markets fetch failed: {} (keeping {} known markets)",
+                e,
+                symbols().count(EXCHANGE)
+            ),
+        }
+        info!("coinex: {} markets", symbols().count(EXCHANGE));
+        Ok(WS_URL.to_string())
+    }
+
+    fn subscribe(&self) -> Vec<String> {
+        vec![subscribe_message()]
+    }
+
+    fn keepalive(&self) -> Heartbeat {
+        Heartbeat::Text {
+            interval: Duration::from_secs(20),
+            message: ping_message,
+        }
+    }
+
+    fn parse_message(&mut self, txt: &str) -> Parsed {
+        parse_message(txt)
+    }
+}
+
+fn ping_message() -> String {
+    json!({ "method": "server.ping", "params": {}, "id": now_ms() }).to_string()
+}
+
+/// An empty `market_list` subscribes every market.
+fn subscribe_message() -> String {
+    json!({ "method": "state.subscribe", "params": { "market_list": [] }, "id": 1 }).to_string()
+}
+
+fn parse_message(txt: &str) -> Parsed {
+    let v: Value = match serde_json::from_str(txt) {
+        Ok(v) => v,
+        Err(_) => return Parsed::Prices(Vec::new()),
+    };
+    let code = v.get("code").and_then(|c| c.as_i64()).unwrap_or(0);
+    if code != 0 {
+        let msg = v.get("message").and_then(|m| m.as_str()).unwrap_or("error");
+        return Parsed::Error(VenueError::new(format!("{} {}", code, msg)));
+    }
+    if v.get("method").and_then(|m| m.as_str()) != Some("state.update") {
+        return Parsed::Prices(Vec::new());
+    }
+    parse_states(&v).into()
+}
+
+/// `{"method":"state.update","data":{"state_list":[{"market":"BTCUSDT","last":"..","volume":".."}]}}`;
+/// volume is in the base asset.
+fn parse_states(v: &Value) -> Vec<PairPrice> {
+    let Some(list) = v
+        .get("data")
+        .and_then(|d| d.get("state_list"))
+        .and_then(|l| l.as_array())
+    else {
+        return Vec::new();
+    };
+    list.iter()
+        .filter_map(|s| {
+            let market = s.get("market").and_then(|m| m.as_str())?;
+            let Some((base, quote)) = symbols().pair(EXCHANGE, market) else {
+                coverage().record_dropped(EXCHANGE, market, "unparsed");
+                return None;
+            };
+            Some(PairPrice {
+                base,
+                quote,
+                price: parse_f64(s.get("last")).filter(|p| *p > 0.0)?,
+                is_spot: true,
+                volume: parse_f64(s.get("volume")).unwrap_or(0.0),
+                event_time: 0,
+                bid: None,
+                ask: None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::list_symbols;
+
+    fn parse(txt: &str) -> Parsed {
+        list_symbols(
+            EXCHANGE,
+            &[("BTCUSDT", "BTC", "USDT"), ("ETHBTC", "ETH", "BTC")],
+        );
+        parse_message(txt)
+    }
+
+    #[test]
+    fn one_state_update_covers_every_market() {
+        let frame = r#"{"method":"state.update","data":{"state_list":[{"market":"BTCUSDT","last":"65000.1","volume":"1520.37"},{"market":"ETHBTC","last":"0.05","volume":"300"},{"market":"NEWUSDT","last":"0","volume":"0"}]},"id":null}"#;
+        let prices: Vec<String> = parse(frame)
+            .prices()
+            .iter()
+            .map(|p| format!("{}/{} {} {}", p.base, p.quote, p.price, p.volume))
+            .collect();
+        assert_eq!(prices, ["BTC/USDT 65000.1 1520.37", "ETH/BTC 0.05 300"]);
+    }
+}
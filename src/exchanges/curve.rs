@@ -0,0 +1,31 @@
+use super::evm_pools::{run_pool_poller, PoolReader};
+use crate::config::{DexPoolsConfig, PoolSpec};
+use crate::evm::{encode_uint, uint_f64, word};
+use crate::store::SharedPrices;
+
+/// Long-running Curve worker for stable pools: quotes `get_dy` for one
+/// whole token0 into token1 on every configured pool each poll interval, so
+/// the price includes the pool fee.
+pub async fn run_curve(cfg: DexPoolsConfig, prices: SharedPrices) {
+    let reader = PoolReader {
+        exchange: "curve",
+        calldata: get_dy_call,
+        price: get_dy_price,
+    };
+    run_pool_poller(reader, cfg, prices).await;
+}
+
+/// `get_dy(int128 i, int128 j, uint256 dx)` with `dx` one whole token0.
+fn get_dy_call(pool: &PoolSpec) -> String {
+    let (i, j) = pool.coins.unwrap_or((0, 1));
+    format!(
+        "0x5e0d443f{}{}{}",
+        encode_uint(i as u128),
+        encode_uint(j as u128),
+        encode_uint(10u128.pow(pool.decimals0 as u32))
+    )
+}
+
+fn get_dy_price(data: &[u8], pool: &PoolSpec) -> Option<f64> {
+    Some(uint_f64(word(data, 0)?) / 10f64.powi(pool.decimals1 as i32))
+}
@@ -0,0 +1,185 @@
+use super::{parse_f64, Parsed, VenueError};
+use crate::config::DeclarativeSpec;
+use crate::models::PairPrice;
+use crate::store::SharedPrices;
+use crate::symbols::symbols;
+use crate::ws_manager::{run_connector, ExchangeConnector, Heartbeat};
+use serde_json::Value;
+use tokio::time::Duration;
+
+/// Generic worker for a venue described by a [`DeclarativeSpec`] in config,
+/// so simple exchanges can be added without writing a connector.
+pub async fn run_declarative_ws(spec: DeclarativeSpec, prices: SharedPrices) {
+    run_connector(Declarative { spec }, prices).await;
+}
+
+struct Declarative {
+    spec: DeclarativeSpec,
+}
+
+impl ExchangeConnector for Declarative {
+    fn name(&self) -> &str {
+        &self.spec.name
+    }
+
+    async fn connect(&mut self) -> Result<String, String> {
+        Ok(self.spec.url.clone())
+    }
+
+    fn subscribe(&self) -> Vec<String> {
+        self.spec
+            .subscribe
+            .iter()
+            .map(|v| match v {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+            .collect()
+    }
+
+    fn keepalive(&self) -> Heartbeat {
+        match &self.spec.heartbeat {
+            Some(hb) => Heartbeat::Template {
+                interval: Duration::from_millis(hb.interval_ms.max(1000)),
+                template: hb.message.clone(),
+            },
+            None => Heartbeat::ServerPing,
+        }
+    }
+
+    fn parse_message(&mut self, txt: &str) -> Parsed {
+        parse_message(txt, &self.spec)
+    }
+}
+
+fn parse_message(txt: &str, spec: &DeclarativeSpec) -> Parsed {
+    let v: Value = match serde_json::from_str(txt) {
+        Ok(v) => v,
+        Err(_) => return Parsed::Prices(Vec::new()),
+    };
+    if let Some(err) = spec
+        .error
+        .as_deref()
+        .and_then(|ptr| v.pointer(ptr))
+        .filter(|e| !e.is_null())
+    {
+        return Parsed::Error(VenueError::new(err.to_string()));
+    }
+
+    let items = match spec.items.as_deref() {
+        Some(ptr) => v.pointer(ptr),
+        None => Some(&v),
+    };
+    match items {
+        Some(Value::Array(arr)) => arr.iter().filter_map(|it| parse_item(it, spec)).collect(),
+        Some(item) => parse_item(item, spec).into_iter().collect(),
+        None => Vec::new(),
+    }
+    .into()
+}
+
+fn parse_item(it: &Value, spec: &DeclarativeSpec) -> Option<PairPrice> {
+    let text = |ptr: &str| {
+        it.pointer(ptr)
+            .and_then(|v| v.as_str())
+            .map(str::to_uppercase)
+    };
+
+    let (base, quote) = match (spec.base.as_deref(), spec.quote.as_deref()) {
+        (Some(b), Some(q)) => (text(b)?, text(q)?),
+        _ => {
+            let sym = text(spec.symbol.as_deref()?)?;
+            match spec.separator.as_deref() {
+                Some(sep) if !sep.is_empty() => {
+                    let (b, q) = sym.split_once(&sep.to_uppercase())?;
+                    (b.to_string(), q.to_string())
+                }
+                _ => symbols().split(&spec.name, &sym)?,
+            }
+        }
+    };
+    let price = parse_f64(it.pointer(&spec.price))?;
+    let volume = spec
+        .volume
+        .as_deref()
+        .and_then(|ptr| parse_f64(it.pointer(ptr)))
+        .unwrap_or(0.0);
+    let event_time = spec
+        .event_time
+        .as_deref()
+        .and_then(|ptr| parse_f64(it.pointer(ptr)))
+        .map(|t| t as u64)
+        .unwrap_or(0);
+
+    Some(PairPrice {
+        base,
+        quote,
+        price,
+        is_spot: true,
+        volume,
+        event_time,
+        bid: None,
+        ask: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec(fields: Value) -> DeclarativeSpec {
+        let mut v = json!({ "name": "example", "url": "wss://example.com/ws", "price": "/c" });
+        v.as_object_mut()
+            .unwrap()
+            .extend(fields.as_object().unwrap().clone());
+        serde_json::from_value(v).unwrap()
+    }
+
+    #[test]
+    fn reads_items_through_the_spec_pointers() {
+        let spec = spec(json!({
+            "items": "/data",
+            "symbol": "/s",
+            "separator": "-",
+            "volume": "/v",
+            "event_time": "/t",
+        }));
+        let frame = r#"{"data":[{"s":"btc-usdt","c":"65000.1","v":"10.5","t":1700000000000},{"s":"eth-usdt","c":3500.5},{"s":"junk","c":"1"}]}"#;
+        let prices = parse_message(frame, &spec).prices();
+        let got: Vec<_> = prices
+            .iter()
+            .map(|p| {
+                (
+                    p.base.as_str(),
+                    p.quote.as_str(),
+                    p.price,
+                    p.volume,
+                    p.event_time,
+                )
+            })
+            .collect();
+        assert_eq!(
+            got,
+            [
+                ("BTC", "USDT", 65000.1, 10.5, 1_700_000_000_000),
+                ("ETH", "USDT", 3500.5, 0.0, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn base_and_quote_fields_win_over_the_symbol() {
+        let spec = spec(json!({ "symbol": "/s", "base": "/b", "quote": "/q", "error": "/error" }));
+        let frame = r#"{"s":"XXXYYY","b":"sol","q":"usdc","c":"150"}"#;
+        let prices = parse_message(frame, &spec).prices();
+        assert_eq!(
+            (prices[0].base.as_str(), prices[0].quote.as_str()),
+            ("SOL", "USDC")
+        );
+        let error = r#"{"error":{"code":10001,"msg":"bad topic"}}"#;
+        assert!(
+            matches!(parse_message(error, &spec), Parsed::Error(e) if e.message.contains("bad topic"))
+        );
+    }
+}
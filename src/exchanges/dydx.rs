@@ -0,0 +1,150 @@
+use super::{parse_f64, Parsed, VenueError};
+use crate::coverage::coverage;
+use crate::models::PairPrice;
+use crate::rest::dydx::perp_price;
+use crate::store::SharedPrices;
+use crate::ws_manager::{run_connector, ExchangeConnector, Heartbeat};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+const EXCHANGE: &str = "dydx";
+const WS_URL: &str = "wss://indexer.dydx.trade/v4/ws";
+
+/// Oracle price and 24h USD volume of one market, merged from the
+/// `oraclePrices` and `trading` sections of `v4_markets` updates.
+#[derive(Default)]
+struct MarketState {
+    oracle_price: Option<f64>,
+    volume_usd: Option<f64>,
+}
+
+/// Long-running dYdX v4 worker on the indexer's `v4_markets` channel.
+/// Markets are perpetuals, stored with `is_spot: false`, so scans only use
+/// them with `scan.derivatives` on.
+pub async fn run_dydx_ws(prices: SharedPrices) {
+    run_connector(Dydx::default(), prices).await;
+}
+
+#[derive(Default)]
+struct Dydx {
+    markets: HashMap<String, MarketState>,
+}
+
+impl ExchangeConnector for Dydx {
+    fn name(&self) -> &'static str {
+        EXCHANGE
+    }
+
+    async fn connect(&mut self) -> Result<String, String> {
+        super::refresh_symbols(EXCHANGE).await;
+        Ok(WS_URL.to_string())
+    }
+
+    fn subscribe(&self) -> Vec<String> {
+        vec![subscribe_message()]
+    }
+
+    fn keepalive(&self) -> Heartbeat {
+        Heartbeat::ServerPing
+    }
+
+    fn parse_message(&mut self, txt: &str) -> Parsed {
+        parse_message(txt, &mut self.markets)
+    }
+}
+
+fn subscribe_message() -> String {
+    json!({ "type": "subscribe", "channel": "v4_markets" }).to_string()
+}
+
+fn parse_message(txt: &str, markets: &mut HashMap<String, MarketState>) -> Parsed {
+    let v: Value = match serde_json::from_str(txt) {
+        Ok(v) => v,
+        Err(_) => return Parsed::Prices(Vec::new()),
+    };
+    match v.get("type").and_then(|t| t.as_str()) {
+        Some("error") => {
+            let msg = v.get("message").and_then(|m| m.as_str()).unwrap_or("error");
+            Parsed::Error(VenueError::new(msg))
+        }
+        Some("subscribed") | Some("channel_data") => parse_markets(&v, markets).into(),
+        _ => Parsed::Prices(Vec::new()),
+    }
+}
+
+/// The snapshot carries `contents.markets`, updates `contents.trading` and
+/// `contents.oraclePrices`, each keyed by ticker ("BTC-USD").
+fn parse_markets(v: &Value, markets: &mut HashMap<String, MarketState>) -> Vec<PairPrice> {
+    let Some(contents) = v.get("contents") else {
+        return Vec::new();
+    };
+    let event_time = crate::utils::now_ms();
+    let mut touched: Vec<String> = Vec::new();
+    for section in ["markets", "trading", "oraclePrices"] {
+        let Some(entries) = contents.get(section).and_then(Value::as_object) else {
+            continue;
+        };
+        merge(entries, markets, &mut touched);
+    }
+    touched
+        .into_iter()
+        .filter_map(|ticker| {
+            let state = markets.get(&ticker)?;
+            let price = state.oracle_price.filter(|p| *p > 0.0)?;
+            let p = perp_price(&ticker, price, state.volume_usd, event_time);
+            if p.is_none() {
+                coverage().record_dropped(EXCHANGE, &ticker, "unparsed");
+            }
+            p
+        })
+        .collect()
+}
+
+fn merge(
+    entries: &Map<String, Value>,
+    markets: &mut HashMap<String, MarketState>,
+    touched: &mut Vec<String>,
+) {
+    for (ticker, fields) in entries {
+        if fields
+            .get("status")
+            .and_then(|s| s.as_str())
+            .is_some_and(|s| s != "ACTIVE")
+        {
+            markets.remove(ticker);
+            continue;
+        }
+        let state = markets.entry(ticker.clone()).or_default();
+        if let Some(price) = parse_f64(fields.get("oraclePrice")) {
+            state.oracle_price = Some(price);
+        }
+        if let Some(volume) = parse_f64(fields.get("volume24H")) {
+            state.volume_usd = Some(volume);
+        }
+        if !touched.contains(ticker) {
+            touched.push(ticker.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oracle_updates_price_perpetuals_with_the_snapshot_volume() {
+        let mut markets = HashMap::new();
+        let snapshot = r#"{"type":"subscribed","channel":"v4_markets","contents":{"markets":{"BTC-USD":{"ticker":"BTC-USD","status":"ACTIVE","oraclePrice":"65000","volume24H":"130000000"},"OLD-USD":{"ticker":"OLD-USD","status":"FINAL_SETTLEMENT","oraclePrice":"1"}}}}"#;
+        let prices = parse_message(snapshot, &mut markets).prices();
+        assert_eq!(prices.len(), 1);
+        let p = &prices[0];
+        assert_eq!((p.base.as_str(), p.quote.as_str()), ("BTC", "USD"));
+        assert!(!p.is_spot);
+        assert_eq!((p.price, p.volume), (65000.0, 2000.0));
+
+        let update = r#"{"type":"channel_data","channel":"v4_markets","contents":{"oraclePrices":{"BTC-USD":{"oraclePrice":"65500","effectiveAtHeight":"123","marketId":0}}}}"#;
+        let prices = parse_message(update, &mut markets).prices();
+        assert_eq!(prices[0].price, 65500.0);
+        assert!((prices[0].volume - 130_000_000.0 / 65500.0).abs() < 1e-9);
+    }
+}
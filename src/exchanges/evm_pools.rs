@@ -0,0 +1,93 @@
+use super::TickerBuffer;
+use crate::config::{DexPoolsConfig, PoolSpec};
+use crate::coverage::coverage;
+use crate::evm::RpcClient;
+use crate::health::health;
+use crate::models::PairPrice;
+use crate::store::SharedPrices;
+use crate::utils::now_ms;
+use crate::ws_manager::publish;
+use tracing::{error, info, warn};
+
+/// How one DEX reads a pool: the view function to call and how its return
+/// data becomes the price of token0 in token1.
+pub(super) struct PoolReader {
+    pub exchange: &'static str,
+    /// Hex calldata (selector and arguments) for one pool.
+    pub calldata: fn(&PoolSpec) -> String,
+    pub price: fn(&[u8], &PoolSpec) -> Option<f64>,
+}
+
+/// Poll every configured pool each interval with one batched `eth_call`
+/// and store the token0/token1 prices. Pools report no volume.
+pub(super) async fn run_pool_poller(reader: PoolReader, cfg: DexPoolsConfig, prices: SharedPrices) {
+    let exchange = reader.exchange;
+    if cfg.rpc_url.is_empty() || cfg.pools.is_empty() {
+        error!("{}: rpc_url and pools must be configured", exchange);
+        return;
+    }
+    let rpc = RpcClient::new(&cfg.rpc_url);
+    let mut buffer = TickerBuffer::new(exchange);
+    let mut poll = tokio::time::interval(cfg.poll_interval());
+    coverage().set_listed(exchange, cfg.pools.len());
+    info!("{}: polling {} pools", exchange, cfg.pools.len());
+
+    loop {
+        poll.tick().await;
+        match read_pools(&reader, &rpc, &cfg.pools).await {
+            Ok(ps) => {
+                health().set_connected(exchange, true);
+                publish(exchange, ps, &mut buffer, &prices);
+            }
+            Err(e) => {
+                warn!("{}: pool poll failed: {}", exchange, e);
+                health().record_error(exchange, &e, false);
+                health().set_connected(exchange, false);
+            }
+        }
+    }
+}
+
+async fn read_pools(
+    reader: &PoolReader,
+    rpc: &RpcClient,
+    pools: &[PoolSpec],
+) -> Result<Vec<PairPrice>, String> {
+    let calldata: Vec<String> = pools.iter().map(reader.calldata).collect();
+    let calls: Vec<(&str, &str)> = pools
+        .iter()
+        .zip(&calldata)
+        .map(|(p, data)| (p.address.as_str(), data.as_str()))
+        .collect();
+    let results = rpc.call_batch(&calls).await?;
+    let event_time = now_ms();
+    Ok(pools
+        .iter()
+        .zip(results)
+        .filter_map(|(pool, data)| {
+            let price = data
+                .as_deref()
+                .and_then(|d| (reader.price)(d, pool))
+                .filter(|p| p.is_finite() && *p > 0.0);
+            if price.is_none() {
+                coverage().record_dropped(reader.exchange, &pool.address, "unparsed");
+            }
+            Some(PairPrice {
+                base: pool.token0.to_uppercase(),
+                quote: pool.token1.to_uppercase(),
+                price: price?,
+                is_spot: true,
+                volume: 0.0,
+                event_time,
+                bid: None,
+                ask: None,
+            })
+        })
+        .collect())
+}
+
+/// `10^(decimals0 - decimals1)`: turns a raw-unit token1/token0 ratio into
+/// a price in whole tokens.
+pub(super) fn decimals_scale(pool: &PoolSpec) -> f64 {
+    10f64.powi(pool.decimals0 as i32 - pool.decimals1 as i32)
+}
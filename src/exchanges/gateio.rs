@@ -0,0 +1,161 @@
+use super::{notice_text, parse_f64, Parsed, VenueError};
+use crate::coverage::coverage;
+use crate::models::PairPrice;
+use crate::store::SharedPrices;
+use crate::symbols::symbols;
+use crate::utils::now_ms;
+use crate::ws_manager::{run_connector, ExchangeConnector, Heartbeat, Subscriptions};
+use serde_json::{json, Value};
+use tokio::time::Duration;
+use tracing::{info, warn};
+
+const EXCHANGE: &str = "gateio";
+const WS_URL: &str = "wss://api.gateio.ws/ws/v4/";
+/// Currency pairs per `spot.tickers` subscribe payload.
+const SUBSCRIBE_CHUNK: usize = 100;
+
+/// Long-running Gate.io worker on the `spot.tickers` channel. An empty
+/// payload doesn't reliably deliver every pair, so the tradable pairs are
+/// fetched over REST and subscribed by name in batches, refreshed and
+/// re-sent on every reconnect.
+pub async fn run_gateio_ws(prices: SharedPrices) {
+    let subs = Subscriptions::new(SUBSCRIBE_CHUNK, subscribe_message);
+    run_connector(GateIo { subs }, prices).await;
+}
+
+struct GateIo {
+    subs: Subscriptions,
+}
+
+impl ExchangeConnector for GateIo {
+    fn name(&self) -> &'static str {
+        EXCHANGE
+    }
+
+    async fn connect(&mut self) -> Result<String, String> {
+        match fetch_pairs().await {
+            Ok(pairs) => {
+                coverage().set_listed(EXCHANGE, pairs.len());
+                self.subs.replace(pairs);
+            }
+            Err(e) if self.subs.len() == 0 => {
+                return Err(format!("currency pairs fetch failed: {}", e));
+            }
+            Err(e) => warn!(
+                "gateio currency pairs fetch failed: {} (restoring {} known pairs)",
+                e,
+                self.subs.len()
+            ),
+        }
+        info!(
+            "gateio: subscribing to {} pairs in {} batches",
+            self.subs.len(),
+            self.subs.len().div_ceil(SUBSCRIBE_CHUNK)
+        );
+        Ok(WS_URL.to_string())
+    }
+
+    fn subscribe(&self) -> Vec<String> {
+        self.subs.messages()
+    }
+
+    fn keepalive(&self) -> Heartbeat {
+        Heartbeat::Text {
+            interval: Duration::from_secs(10),
+            message: ping_message,
+        }
+    }
+
+    fn parse_message(&mut self, txt: &str) -> Parsed {
+        parse_message(txt)
+    }
+}
+
+/// Tradable currency pairs, e.g. "BTC_USDT".
+async fn fetch_pairs() -> Result<Vec<String>, String> {
+    Ok(symbols()
+        .refresh(EXCHANGE)
+        .await?
+        .into_iter()
+        .filter(|i| i.trading)
+        .map(|i| i.symbol)
+        .collect())
+}
+
+fn subscribe_message(pairs: &[String]) -> String {
+    json!({
+        "time": now_ms() / 1000,
+        "channel": "spot.tickers",
+        "event": "subscribe",
+        "payload": pairs,
+    })
+    .to_string()
+}
+
+/// Gate.io's keepalive is a message on the `spot.ping` channel.
+fn ping_message() -> String {
+    json!({ "time": now_ms() / 1000, "channel": "spot.ping" }).to_string()
+}
+
+fn parse_message(txt: &str) -> Parsed {
+    let v: Value = match serde_json::from_str(txt) {
+        Ok(v) => v,
+        Err(_) => return Parsed::Prices(Vec::new()),
+    };
+    if is_notice(&v) {
+        return Parsed::Notice(notice_text(&v));
+    }
+    if let Some(err) = error_response(&v) {
+        return Parsed::Error(err);
+    }
+    parse_ticker(&v).into_iter().collect::<Vec<_>>().into()
+}
+
+/// Gate.io announces maintenance on `*.notice`/`system` channels.
+fn is_notice(v: &Value) -> bool {
+    let channel = v.get("channel").and_then(|c| c.as_str()).unwrap_or("");
+    channel.ends_with(".notice") || channel == "system"
+}
+
+/// Gate.io puts failures in a non-null `error` object on the channel event,
+/// e.g. `{"channel":"spot.tickers","event":"subscribe","error":{"code":2,"message":"..."}}`.
+fn error_response(v: &Value) -> Option<VenueError> {
+    let err = v.get("error").filter(|e| !e.is_null())?;
+    let channel = v.get("channel").and_then(|c| c.as_str()).unwrap_or("?");
+    let code = err.get("code").map(|c| c.to_string()).unwrap_or_default();
+    let msg = err
+        .get("message")
+        .and_then(|m| m.as_str())
+        .unwrap_or("error");
+    Some(VenueError::new(format!(
+        "{} code {}: {}",
+        channel, code, msg
+    )))
+}
+
+fn parse_ticker(v: &Value) -> Option<PairPrice> {
+    let is_ticker_update = v.get("channel").and_then(|c| c.as_str()) == Some("spot.tickers")
+        && v.get("event").and_then(|e| e.as_str()) == Some("update");
+    if !is_ticker_update {
+        return None;
+    }
+
+    let r = v.get("result")?;
+    let pair = r.get("currency_pair").and_then(|s| s.as_str())?;
+    let Some((base, quote)) = symbols().split(EXCHANGE, pair) else {
+        coverage().record_dropped(EXCHANGE, pair, "unparsed");
+        return None;
+    };
+    let price = parse_f64(r.get("last"))?;
+
+    Some(PairPrice {
+        base,
+        quote,
+        price,
+        is_spot: true,
+        volume: parse_f64(r.get("base_volume")).unwrap_or(0.0),
+        event_time: v.get("time_ms").and_then(|t| t.as_u64()).unwrap_or(0),
+        bid: parse_f64(r.get("highest_bid")),
+        ask: parse_f64(r.get("lowest_ask")),
+    })
+}
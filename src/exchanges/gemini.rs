@@ -0,0 +1,174 @@
+use super::{parse_f64, Parsed, VenueError};
+use crate::coverage::coverage;
+use crate::models::PairPrice;
+use crate::store::SharedPrices;
+use crate::symbols::symbols;
+use crate::ws_manager::{run_connector, ExchangeConnector, Heartbeat};
+use serde_json::Value;
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+const EXCHANGE: &str = "gemini";
+const WS_URL: &str = "wss://api.gemini.com/v1/multimarketdata";
+
+/// Long-running Gemini worker on the multi-market data feed. Symbols are
+/// chosen in the URL; trades set the last price and top-of-book changes the
+/// bid/ask.
+pub async fn run_gemini_ws(prices: SharedPrices) {
+    let gemini = Gemini {
+        latest: HashMap::new(),
+    };
+    run_connector(gemini, prices).await;
+}
+
+struct Gemini {
+    /// Last trade price and top of book per symbol; a frame usually moves
+    /// only one of them.
+    latest: HashMap<String, PairPrice>,
+}
+
+impl ExchangeConnector for Gemini {
+    fn name(&self) -> &'static str {
+        EXCHANGE
+    }
+
+    async fn connect(&mut self) -> Result<String, String> {
+        match symbols().refresh(EXCHANGE).await {
+            Ok(_) => {}
+            Err(e) if !symbols().is_loaded(EXCHANGE) => {
+                return Err(format!("symbols fetch failed: {}", e));
+            }
+            Err(e) => warn!(
+                "gemini symbols fetch failed: {} (keeping {} known symbols)",
+                e,
+                symbols().count(EXCHANGE)
+            ),
+        }
+        let list = symbols().tradable(EXCHANGE);
+        info!("gemini: streaming {} symbols", list.len());
+        Ok(format!(
+            "{}?symbols={}&heartbeat=true&top_of_book=true&bids=true&offers=true&trades=true",
+            WS_URL,
+            list.join(",")
+        ))
+    }
+
+    fn subscribe(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn keepalive(&self) -> Heartbeat {
+        Heartbeat::ServerPing
+    }
+
+    fn parse_message(&mut self, txt: &str) -> Parsed {
+        parse_message(txt, &mut self.latest)
+    }
+}
+
+fn parse_message(txt: &str, latest: &mut HashMap<String, PairPrice>) -> Parsed {
+    let v: Value = match serde_json::from_str(txt) {
+        Ok(v) => v,
+        Err(_) => return Parsed::Prices(Vec::new()),
+    };
+    match v.get("type").and_then(|t| t.as_str()) {
+        Some("update") => parse_update(&v, latest).into(),
+        Some("error") | Some("Error") => {
+            let reason = v
+                .get("reason")
+                .or_else(|| v.get("message"))
+                .and_then(|r| r.as_str())
+                .unwrap_or("error");
+            Parsed::Error(VenueError::new(reason))
+        }
+        _ => Parsed::Prices(Vec::new()),
+    }
+}
+
+/// `{"type":"update","timestampms":..,"events":[{"type":"trade","symbol":"BTCUSD","price":".."},
+/// {"type":"change","side":"bid","price":"..","remaining":"..","symbol":"BTCUSD"}]}`.
+/// Book changes are dropped until a trade has supplied a last price.
+fn parse_update(v: &Value, latest: &mut HashMap<String, PairPrice>) -> Vec<PairPrice> {
+    let event_time = v.get("timestampms").and_then(|t| t.as_u64()).unwrap_or(0);
+    let Some(events) = v.get("events").and_then(|e| e.as_array()) else {
+        return Vec::new();
+    };
+    let mut touched: Vec<String> = Vec::new();
+    for e in events {
+        let Some(sym) = e.get("symbol").and_then(|s| s.as_str()) else {
+            continue;
+        };
+        let Some(price) = parse_f64(e.get("price")) else {
+            continue;
+        };
+        match e.get("type").and_then(|t| t.as_str()) {
+            Some("trade") => {
+                let Some((base, quote)) = symbols().pair(EXCHANGE, sym) else {
+                    coverage().record_dropped(EXCHANGE, sym, "unparsed");
+                    continue;
+                };
+                let entry = latest.entry(sym.to_string()).or_insert_with(|| PairPrice {
+                    base,
+                    quote,
+                    price,
+                    is_spot: true,
+                    volume: 0.0,
+                    event_time,
+                    bid: None,
+                    ask: None,
+                });
+                entry.price = price;
+                entry.event_time = event_time;
+            }
+            Some("change") => {
+                let Some(entry) = latest.get_mut(sym) else {
+                    continue;
+                };
+                if parse_f64(e.get("remaining")).unwrap_or(0.0) <= 0.0 {
+                    continue;
+                }
+                match e.get("side").and_then(|s| s.as_str()) {
+                    Some("bid") => entry.bid = Some(price),
+                    Some("ask") => entry.ask = Some(price),
+                    _ => continue,
+                }
+                entry.event_time = event_time;
+            }
+            _ => continue,
+        }
+        if !touched.iter().any(|t| t == sym) {
+            touched.push(sym.to_string());
+        }
+    }
+    touched
+        .iter()
+        .filter_map(|sym| latest.get(sym).cloned())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::list_symbols;
+
+    fn parse(txt: &str, latest: &mut HashMap<String, PairPrice>) -> Vec<PairPrice> {
+        list_symbols(EXCHANGE, &[("btcusd", "BTC", "USD")]);
+        parse_message(txt, latest).prices()
+    }
+
+    #[test]
+    fn book_changes_follow_the_first_trade() {
+        let mut latest = HashMap::new();
+        let book = r#"{"type":"update","eventId":5375461993,"timestampms":1547760288001,"socket_sequence":1,"events":[{"type":"change","side":"bid","price":"3626.73","remaining":"1.6","delta":"0.8","reason":"place","symbol":"btcusd"}]}"#;
+        assert!(parse(book, &mut latest).is_empty());
+
+        let trade = r#"{"type":"update","eventId":5375547515,"timestampms":1547760288002,"socket_sequence":2,"events":[{"type":"trade","tid":5375547515,"price":"3632.54","amount":"0.1362819142","makerSide":"ask","symbol":"btcusd"},{"type":"change","side":"ask","price":"3632.60","remaining":"2","delta":"-0.1","reason":"trade","symbol":"btcusd"}]}"#;
+        let prices = parse(trade, &mut latest);
+        assert_eq!(prices.len(), 1);
+        let p = &prices[0];
+        assert_eq!((p.base.as_str(), p.quote.as_str()), ("BTC", "USD"));
+        assert_eq!(p.price, 3632.54);
+        assert_eq!((p.bid, p.ask), (None, Some(3632.60)));
+        assert_eq!(p.event_time, 1_547_760_288_002);
+    }
+}
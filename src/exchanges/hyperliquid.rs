@@ -0,0 +1,151 @@
+use super::{parse_f64, Parsed, VenueError};
+use crate::coverage::coverage;
+use crate::models::PairPrice;
+use crate::store::SharedPrices;
+use crate::symbols::symbols;
+use crate::ws_manager::{run_connector, ExchangeConnector, Heartbeat};
+use serde_json::{json, Value};
+use tokio::time::Duration;
+use tracing::{info, warn};
+
+const EXCHANGE: &str = "hyperliquid";
+const WS_URL: &str = "wss://api.hyperliquid.xyz/ws";
+
+/// Long-running Hyperliquid spot worker on `allMids`. Spot markets are keyed
+/// by pair index ("@107") or legacy name ("PURR/USDC") and mapped to tokens
+/// with `spotMeta`; perp mids on the same feed are skipped.
+pub async fn run_hyperliquid_ws(prices: SharedPrices) {
+    run_connector(Hyperliquid, prices).await;
+}
+
+struct Hyperliquid;
+
+impl ExchangeConnector for Hyperliquid {
+    fn name(&self) -> &'static str {
+        EXCHANGE
+    }
+
+    async fn connect(&mut self) -> Result<String, String> {
+        match symbols().refresh(EXCHANGE).await {
+            Ok(_) => {}
+            Err(e) if !symbols().is_loaded(EXCHANGE) => {
+                return Err(format!("spotMeta fetch failed: {}", e));
+            }
+            Err(e) => warn!(
+                "hyperliquid spotMeta fetch failed: {} (keeping {} known markets)",
+                e,
+                symbols().count(EXCHANGE)
+            ),
+        }
+        info!("hyperliquid: {} spot markets", symbols().count(EXCHANGE));
+        Ok(WS_URL.to_string())
+    }
+
+    fn subscribe(&self) -> Vec<String> {
+        vec![subscribe_message()]
+    }
+
+    fn keepalive(&self) -> Heartbeat {
+        Heartbeat::Text {
+            interval: Duration::from_secs(30),
+            message: ping_message,
+        }
+    }
+
+    fn parse_message(&mut self, txt: &str) -> Parsed {
+        parse_message(txt)
+    }
+}
+
+/// Connections idle for 60s are closed.
+fn ping_message() -> String {
+    json!({ "method": "ping" }).to_string()
+}
+
+fn subscribe_message() -> String {
+    json!({ "method": "subscribe", "subscription": { "type": "allMids" } }).to_string()
+}
+
+fn parse_message(txt: &str) -> Parsed {
+    let v: Value = match serde_json::from_str(txt) {
+        Ok(v) => v,
+        Err(_) => return Parsed::Prices(Vec::new()),
+    };
+    match v.get("channel").and_then(|c| c.as_str()) {
+        Some("allMids") => parse_mids(&v).into(),
+        Some("error") => {
+            let msg = v.get("data").and_then(|d| d.as_str()).unwrap_or("error");
+            Parsed::Error(VenueError::new(msg))
+        }
+        _ => Parsed::Prices(Vec::new()),
+    }
+}
+
+/// `{"channel":"allMids","data":{"mids":{"BTC":"..","@107":"..","PURR/USDC":".."}}}`;
+/// the mid is used as the price, and there is no volume or book.
+fn parse_mids(v: &Value) -> Vec<PairPrice> {
+    let Some(mids) = v
+        .get("data")
+        .and_then(|d| d.get("mids"))
+        .and_then(|m| m.as_object())
+    else {
+        return Vec::new();
+    };
+    mids.iter()
+        .filter_map(|(key, mid)| {
+            let (base, quote) = match symbols().pair(EXCHANGE, key) {
+                Some(pair) => pair,
+                // "@N" is always spot; a new listing since the last
+                // spotMeta fetch.
+                None if key.starts_with('@') => {
+                    coverage().record_dropped(EXCHANGE, key, "unparsed");
+                    return None;
+                }
+                None => return None,
+            };
+            Some(PairPrice {
+                base,
+                quote,
+                price: parse_f64(Some(mid)).filter(|p| *p > 0.0)?,
+                is_spot: true,
+                volume: 0.0,
+                event_time: 0,
+                bid: None,
+                ask: None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::list_symbols;
+
+    fn parse(txt: &str) -> Parsed {
+        list_symbols(
+            EXCHANGE,
+            &[("PURR/USDC", "PURR", "USDC"), ("@107", "HYPE", "USDC")],
+        );
+        parse_message(txt)
+    }
+
+    #[test]
+    fn only_listed_spot_mids_are_priced() {
+        // "BTC" is a perp mid; "@999" was listed after the last spotMeta fetch.
+        let frame = r#"{"channel":"allMids","data":{"mids":{"BTC":"65000.5","PURR/USDC":"0.1834","@107":"23.415","@999":"1.0"}}}"#;
+        let mut prices: Vec<(String, f64)> = parse(frame)
+            .prices()
+            .into_iter()
+            .map(|p| (format!("{}/{}", p.base, p.quote), p.price))
+            .collect();
+        prices.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            prices,
+            [
+                ("HYPE/USDC".to_string(), 23.415),
+                ("PURR/USDC".to_string(), 0.1834)
+            ]
+        );
+    }
+}
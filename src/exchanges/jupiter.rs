@@ -0,0 +1,106 @@
+use super::TickerBuffer;
+use crate::config::{config, JupiterConfig, JupiterToken};
+use crate::coverage::coverage;
+use crate::health::health;
+use crate::models::PairPrice;
+use crate::store::SharedPrices;
+use crate::utils::now_ms;
+use crate::ws_manager::publish;
+use serde::Deserialize;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+const EXCHANGE: &str = "jupiter";
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Quote {
+    /// Output amount of the best route, in the output mint's base units.
+    out_amount: String,
+}
+
+/// Long-running Jupiter worker: each poll interval quotes a swap of
+/// `notional` quote tokens into every configured token and stores the
+/// effective price of the best aggregated route. Routes report no volume.
+pub async fn run_jupiter(prices: SharedPrices) {
+    let cfg = &config().jupiter;
+    if cfg.tokens.is_empty() {
+        error!("jupiter: no tokens configured");
+        return;
+    }
+    let http = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .unwrap_or_default();
+    let mut buffer = TickerBuffer::new(EXCHANGE);
+    let mut poll = tokio::time::interval(cfg.poll_interval());
+    coverage().set_listed(EXCHANGE, cfg.tokens.len());
+    info!(
+        "jupiter: quoting {} tokens against {}",
+        cfg.tokens.len(),
+        cfg.quote.symbol
+    );
+
+    loop {
+        poll.tick().await;
+        let mut ps = Vec::new();
+        // Sequential on purpose: the public quote API is tightly rate limited.
+        for token in &cfg.tokens {
+            match route_price(&http, cfg, token).await {
+                Ok(price) => ps.push(PairPrice {
+                    base: token.symbol.to_uppercase(),
+                    quote: cfg.quote.symbol.to_uppercase(),
+                    price,
+                    is_spot: true,
+                    volume: 0.0,
+                    event_time: now_ms(),
+                    bid: None,
+                    ask: None,
+                }),
+                Err(e) => {
+                    warn!("jupiter: {} quote failed: {}", token.symbol, e);
+                    health().record_error(EXCHANGE, &e, e.contains("429"));
+                }
+            }
+        }
+        health().set_connected(EXCHANGE, !ps.is_empty());
+        if !ps.is_empty() {
+            publish(EXCHANGE, ps, &mut buffer, &prices);
+        }
+    }
+}
+
+/// `GET {api_url}/quote` for `notional` quote tokens into `token`: the
+/// price paid per token, route fees and price impact included.
+async fn route_price(
+    http: &reqwest::Client,
+    cfg: &JupiterConfig,
+    token: &JupiterToken,
+) -> Result<f64, String> {
+    let amount = (cfg.notional * 10f64.powi(cfg.quote.decimals as i32)).round() as u64;
+    let quote: Quote = http
+        .get(format!("{}/quote", cfg.api_url.trim_end_matches('/')))
+        .query(&[
+            ("inputMint", cfg.quote.mint.as_str()),
+            ("outputMint", token.mint.as_str()),
+            ("amount", &amount.to_string()),
+            ("slippageBps", "50"),
+        ])
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+    let out: f64 = quote
+        .out_amount
+        .parse()
+        .map_err(|_| format!("bad outAmount {}", quote.out_amount))?;
+    let received = out / 10f64.powi(token.decimals as i32);
+    if received > 0.0 {
+        Ok(cfg.notional / received)
+    } else {
+        Err("no route".to_string())
+    }
+}
@@ -0,0 +1,169 @@
+use super::{parse_f64, Parsed, VenueError};
+use crate::coverage::coverage;
+use crate::models::PairPrice;
+use crate::rest;
+use crate::rest::kraken::normalize_asset;
+use crate::store::SharedPrices;
+use crate::symbols::symbols;
+use crate::ws_manager::{run_connector, ExchangeConnector, Heartbeat, Subscriptions};
+use serde_json::{json, Value};
+use tokio::time::Duration;
+use tracing::{info, warn};
+
+const EXCHANGE: &str = "kraken";
+const WS_URL: &str = "wss://ws.kraken.com/v2";
+const SUBSCRIBE_CHUNK: usize = 200;
+
+/// Long-running Kraken spot worker on the v2 `ticker` channel, subscribed
+/// per pair from the REST asset-pair list.
+pub async fn run_kraken_ws(prices: SharedPrices) {
+    let subs = Subscriptions::new(SUBSCRIBE_CHUNK, subscribe_message);
+    run_connector(Kraken { subs }, prices).await;
+}
+
+struct Kraken {
+    subs: Subscriptions,
+}
+
+impl ExchangeConnector for Kraken {
+    fn name(&self) -> &'static str {
+        EXCHANGE
+    }
+
+    async fn connect(&mut self) -> Result<String, String> {
+        match rest::kraken::instruments().await {
+            Ok(list) => {
+                let online: Vec<String> = list
+                    .into_iter()
+                    .filter(|i| i.trading)
+                    .map(|i| i.symbol)
+                    .collect();
+                coverage().set_listed(EXCHANGE, online.len());
+                self.subs.replace(online);
+            }
+            Err(e) if self.subs.len() == 0 => {
+                return Err(format!("asset pairs fetch failed: {}", e));
+            }
+            Err(e) => warn!(
+                "kraken asset pairs fetch failed: {} (restoring {} known subscriptions)",
+                e,
+                self.subs.len()
+            ),
+        }
+        info!("kraken: subscribing to {} pairs", self.subs.len());
+        Ok(WS_URL.to_string())
+    }
+
+    fn subscribe(&self) -> Vec<String> {
+        self.subs.messages()
+    }
+
+    fn keepalive(&self) -> Heartbeat {
+        Heartbeat::Text {
+            interval: Duration::from_secs(30),
+            message: ping_message,
+        }
+    }
+
+    fn parse_message(&mut self, txt: &str) -> Parsed {
+        parse_message(txt)
+    }
+}
+
+/// Kraken also pushes a `heartbeat` channel every second; the ping keeps
+/// quiet subscriptions alive through proxies.
+fn ping_message() -> String {
+    json!({ "method": "ping" }).to_string()
+}
+
+fn subscribe_message(symbols: &[String]) -> String {
+    json!({
+        "method": "subscribe",
+        "params": { "channel": "ticker", "symbol": symbols },
+    })
+    .to_string()
+}
+
+fn parse_message(txt: &str) -> Parsed {
+    let v: Value = match serde_json::from_str(txt) {
+        Ok(v) => v,
+        Err(_) => return Parsed::Prices(Vec::new()),
+    };
+    if let Some(status) = system_status(&v) {
+        return Parsed::Notice(status);
+    }
+    if let Some(err) = error_response(&v) {
+        return Parsed::Error(err);
+    }
+    parse_ticker(&v).into()
+}
+
+/// The `status` channel reports `online`, `maintenance`, `cancel_only` or
+/// `post_only`; anything but online is worth a notice.
+fn system_status(v: &Value) -> Option<String> {
+    if v.get("channel").and_then(|c| c.as_str()) != Some("status") {
+        return None;
+    }
+    let system = v.get("data")?.get(0)?.get("system")?.as_str()?;
+    (system != "online").then(|| format!("system status: {}", system))
+}
+
+/// Method responses carry `"success": false` and an `error` string on failure.
+fn error_response(v: &Value) -> Option<VenueError> {
+    if v.get("success").and_then(|s| s.as_bool()) != Some(false) {
+        return None;
+    }
+    let method = v
+        .get("method")
+        .and_then(|m| m.as_str())
+        .unwrap_or("request");
+    let msg = v
+        .get("error")
+        .and_then(|e| e.as_str())
+        .unwrap_or("rejected");
+    Some(VenueError::new(format!("{} failed: {}", method, msg)))
+}
+
+fn parse_ticker(v: &Value) -> Vec<PairPrice> {
+    if v.get("channel").and_then(|c| c.as_str()) != Some("ticker") {
+        return Vec::new();
+    }
+    let Some(data) = v.get("data").and_then(|d| d.as_array()) else {
+        return Vec::new();
+    };
+    data.iter()
+        .filter_map(|t| {
+            let sym = t.get("symbol").and_then(|s| s.as_str())?;
+            let Some((base, quote)) = symbols().split(EXCHANGE, sym) else {
+                coverage().record_dropped(EXCHANGE, sym, "unparsed");
+                return None;
+            };
+            Some(PairPrice {
+                base: normalize_asset(&base),
+                quote: normalize_asset(&quote),
+                price: parse_f64(t.get("last"))?,
+                is_spot: true,
+                volume: parse_f64(t.get("volume")).unwrap_or(0.0),
+                event_time: 0,
+                bid: parse_f64(t.get("bid")),
+                ask: parse_f64(t.get("ask")),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xbt_and_xdg_become_btc_and_doge() {
+        let frame = r#"{"channel":"ticker","type":"update","data":[{"symbol":"XBT/EUR","bid":60000.0,"ask":60000.5,"last":60000.2,"volume":12.5},{"symbol":"XDG/USD","last":0.15,"volume":900000.0},{"symbol":"ETH/XBT","last":0.05}]}"#;
+        let pairs: Vec<String> = parse_message(frame)
+            .prices()
+            .iter()
+            .map(|p| format!("{}/{}", p.base, p.quote))
+            .collect();
+        assert_eq!(pairs, ["BTC/EUR", "DOGE/USD", "ETH/BTC"]);
+    }
+}
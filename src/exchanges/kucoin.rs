@@ -0,0 +1,157 @@
+use super::{notice_text, parse_f64, Parsed, VenueError};
+use crate::coverage::coverage;
+use crate::models::PairPrice;
+use crate::rest;
+use crate::store::SharedPrices;
+use crate::symbols::symbols;
+use crate::utils::now_ms;
+use crate::ws_manager::{run_connector, ExchangeConnector, Heartbeat, Subscriptions};
+use serde_json::{json, Value};
+use tokio::time::Duration;
+
+const EXCHANGE: &str = "kucoin";
+/// KuCoin accepts up to 100 comma-separated topics per subscribe.
+const SUBSCRIBE_CHUNK: usize = 100;
+
+/// Long-running KuCoin worker on the `/market/ticker:all` topic. Bullet
+/// tokens expire, so every (re)connect fetches a fresh one and re-sends the
+/// subscription; a session whose pings go unanswered is dropped.
+pub async fn run_kucoin_ws(prices: SharedPrices) {
+    let kucoin = KuCoin {
+        subs: Subscriptions::new(SUBSCRIBE_CHUNK, subscribe_message)
+            .with_topics(["/market/ticker:all".to_string()]),
+        ping: Duration::from_millis(18_000),
+        ping_timeout: Duration::from_millis(10_000),
+    };
+    run_connector(kucoin, prices).await;
+}
+
+struct KuCoin {
+    subs: Subscriptions,
+    /// Ping interval and timeout the current bullet's server expects.
+    ping: Duration,
+    ping_timeout: Duration,
+}
+
+impl ExchangeConnector for KuCoin {
+    fn name(&self) -> &'static str {
+        EXCHANGE
+    }
+
+    /// Request a public bullet token; the response carries the WS endpoint
+    /// and the ping interval the server expects.
+    async fn connect(&mut self) -> Result<String, String> {
+        super::refresh_symbols(EXCHANGE).await;
+        let bullet = rest::kucoin::bullet_public()
+            .await
+            .map_err(|e| format!("bullet request failed: {}", e))?;
+        let server = bullet
+            .instance_servers
+            .first()
+            .ok_or("missing instanceServers")?;
+        self.ping = Duration::from_millis(server.ping_interval.unwrap_or(18_000));
+        self.ping_timeout = Duration::from_millis(server.ping_timeout.unwrap_or(10_000));
+        Ok(format!(
+            "{}?token={}&connectId={}",
+            server.endpoint,
+            bullet.token,
+            now_ms()
+        ))
+    }
+
+    fn subscribe(&self) -> Vec<String> {
+        self.subs.messages()
+    }
+
+    fn keepalive(&self) -> Heartbeat {
+        Heartbeat::Answered {
+            interval: self.ping,
+            timeout: self.ping_timeout,
+            message: ping_message,
+        }
+    }
+
+    fn parse_message(&mut self, txt: &str) -> Parsed {
+        parse_message(txt)
+    }
+}
+
+/// KuCoin's keepalive: `{"id": ..., "type": "ping"}` at the server-provided interval.
+fn ping_message() -> String {
+    json!({ "id": now_ms().to_string(), "type": "ping" }).to_string()
+}
+
+/// KuCoin batches topics sharing a prefix as `/prefix:A,B,C`.
+fn subscribe_message(topics: &[String]) -> String {
+    let prefix = topics
+        .first()
+        .and_then(|t| t.split_once(':'))
+        .map(|(p, _)| p)
+        .unwrap_or_default();
+    let args: Vec<&str> = topics
+        .iter()
+        .map(|t| t.split_once(':').map(|(_, a)| a).unwrap_or(t))
+        .collect();
+    json!({
+        "id": now_ms().to_string(),
+        "type": "subscribe",
+        "topic": format!("{}:{}", prefix, args.join(",")),
+        "response": true,
+    })
+    .to_string()
+}
+
+fn parse_message(txt: &str) -> Parsed {
+    let v: Value = match serde_json::from_str(txt) {
+        Ok(v) => v,
+        Err(_) => return Parsed::Prices(Vec::new()),
+    };
+    if is_notice(&v) {
+        return Parsed::Notice(notice_text(&v));
+    }
+    if let Some(err) = error_response(&v) {
+        return Parsed::Error(err);
+    }
+    parse_ticker(&v).into_iter().collect::<Vec<_>>().into()
+}
+
+/// KuCoin pushes maintenance announcements as `{"type":"notice", ...}`.
+fn is_notice(v: &Value) -> bool {
+    v.get("type").and_then(|t| t.as_str()) == Some("notice")
+}
+
+/// KuCoin reports rejected requests as `{"type":"error","code":...,"data":"..."}`.
+fn error_response(v: &Value) -> Option<VenueError> {
+    if v.get("type").and_then(|t| t.as_str()) != Some("error") {
+        return None;
+    }
+    let code = v.get("code").map(|c| c.to_string()).unwrap_or_default();
+    let msg = v.get("data").and_then(|d| d.as_str()).unwrap_or("error");
+    Some(VenueError::new(format!("code {}: {}", code, msg)))
+}
+
+fn parse_ticker(v: &Value) -> Option<PairPrice> {
+    if v.get("type").and_then(|t| t.as_str()) != Some("message") {
+        return None;
+    }
+
+    let sym = v.get("subject").and_then(|s| s.as_str())?;
+    let Some((base, quote)) = symbols().split(EXCHANGE, sym) else {
+        coverage().record_dropped(EXCHANGE, sym, "unparsed");
+        return None;
+    };
+    let data = v.get("data")?;
+    let price = parse_f64(data.get("price"))?;
+
+    Some(PairPrice {
+        base,
+        quote,
+        price,
+        is_spot: true,
+        // ticker:all carries no 24h volume
+        volume: 0.0,
+        event_time: data.get("time").and_then(|t| t.as_u64()).unwrap_or(0),
+        bid: parse_f64(data.get("bestBid")),
+        ask: parse_f64(data.get("bestAsk")),
+    })
+}
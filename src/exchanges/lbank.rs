@@ -0,0 +1,144 @@
+use super::{parse_f64, Parsed, VenueError};
+use crate::coverage::coverage;
+use crate::models::PairPrice;
+use crate::rest;
+use crate::rest::lbank::split_pair;
+use crate::store::SharedPrices;
+use crate::utils::now_ms;
+use crate::ws_manager::{run_connector, ExchangeConnector, Heartbeat, Subscriptions};
+use serde_json::{json, Value};
+use tokio::time::Duration;
+use tracing::{info, warn};
+
+const EXCHANGE: &str = "lbank";
+const WS_URL: &str = "wss://www.lbkex.net/ws/V2/";
+/// LBank takes one pair per subscribe request.
+const SUBSCRIBE_CHUNK: usize = 1;
+
+/// Long-running LBank spot worker on the per-pair `tick` subscription.
+pub async fn run_lbank_ws(prices: SharedPrices) {
+    let subs = Subscriptions::new(SUBSCRIBE_CHUNK, subscribe_message);
+    run_connector(Lbank { subs }, prices).await;
+}
+
+struct Lbank {
+    subs: Subscriptions,
+}
+
+impl ExchangeConnector for Lbank {
+    fn name(&self) -> &'static str {
+        EXCHANGE
+    }
+
+    async fn connect(&mut self) -> Result<String, String> {
+        match rest::lbank::instruments().await {
+            Ok(list) => {
+                coverage().set_listed(EXCHANGE, list.len());
+                self.subs.replace(list.into_iter().map(|i| i.symbol));
+            }
+            Err(e) if self.subs.len() == 0 => {
+                return Err(format!("pairs fetch failed: {}", e));
+            }
+            Err(e) => warn!(
+                "lbank pairs fetch failed: {} (restoring {} known subscriptions)",
+                e,
+                self.subs.len()
+            ),
+        }
+        info!("lbank: subscribing to {} pairs", self.subs.len());
+        Ok(WS_URL.to_string())
+    }
+
+    fn subscribe(&self) -> Vec<String> {
+        self.subs.messages()
+    }
+
+    fn keepalive(&self) -> Heartbeat {
+        Heartbeat::Text {
+            interval: Duration::from_secs(30),
+            message: ping_message,
+        }
+    }
+
+    fn parse_message(&mut self, txt: &str) -> Parsed {
+        parse_message(txt)
+    }
+}
+
+/// Our own ping; the server answers `{"action":"pong","pong":<id>}`.
+fn ping_message() -> String {
+    json!({ "action": "ping", "ping": now_ms().to_string() }).to_string()
+}
+
+fn subscribe_message(pairs: &[String]) -> String {
+    let pair = pairs.first().map(String::as_str).unwrap_or_default();
+    json!({ "action": "subscribe", "subscribe": "tick", "pair": pair }).to_string()
+}
+
+fn parse_message(txt: &str) -> Parsed {
+    let v: Value = match serde_json::from_str(txt) {
+        Ok(v) => v,
+        Err(_) => return Parsed::Prices(Vec::new()),
+    };
+    // The server pings too and closes the connection unless the pong echoes
+    // its id.
+    if v.get("action").and_then(|a| a.as_str()) == Some("ping") {
+        let id = v.get("ping").cloned().unwrap_or(Value::Null);
+        return Parsed::Reply(json!({ "action": "pong", "pong": id }).to_string());
+    }
+    if v.get("status").and_then(|s| s.as_str()) == Some("error") {
+        let msg = v.get("message").and_then(|m| m.as_str()).unwrap_or("error");
+        return Parsed::Error(VenueError::new(msg));
+    }
+    if v.get("type").and_then(|t| t.as_str()) != Some("tick") {
+        return Parsed::Prices(Vec::new());
+    }
+    parse_tick(&v).into_iter().collect::<Vec<_>>().into()
+}
+
+/// `{"type":"tick","pair":"eth_btc","tick":{"latest":..,"vol":..}}`; `TS`
+/// is a zone-less local timestamp, so no event time is taken.
+fn parse_tick(v: &Value) -> Option<PairPrice> {
+    let pair = v.get("pair")?.as_str()?;
+    let tick = v.get("tick")?;
+    let Some((base, quote)) = split_pair(pair) else {
+        coverage().record_dropped(EXCHANGE, pair, "unparsed");
+        return None;
+    };
+    Some(PairPrice {
+        base,
+        quote,
+        price: parse_f64(tick.get("latest")).filter(|p| *p > 0.0)?,
+        is_spot: true,
+        volume: parse_f64(tick.get("vol")).unwrap_or(0.0),
+        event_time: 0,
+        bid: None,
+        ask: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_pings_are_answered_with_their_id() {
+        let ping = r#"{"action":"ping","ping":"0ca8f854-7ba7-4341-9d86-d3327e52804e"}"#;
+        let Parsed::Reply(pong) = parse_message(ping) else {
+            panic!("expected a reply");
+        };
+        let pong: Value = serde_json::from_str(&pong).unwrap();
+        assert_eq!(
+            pong,
+            json!({ "action": "pong", "pong": "0ca8f854-7ba7-4341-9d86-d3327e52804e" })
+        );
+
+        let tick = r#"{"tick":{"to_cny":76643.5,"high":0.02719761,"vol":1290.7,"low":0.02603001,"change":2.54,"usd":299.73,"to_usd":11083.66,"dir":"sell","turnover":34.15,"latest":0.0272,"cny":2060.27},"type":"tick","pair":"eth_btc","SERVER":"V2","TS":"2019-07-01T17:28:12.233"}"#;
+        let prices = parse_message(tick).prices();
+        assert_eq!(
+            (prices[0].base.as_str(), prices[0].quote.as_str()),
+            ("ETH", "BTC")
+        );
+        assert_eq!((prices[0].price, prices[0].volume), (0.0272, 1290.7));
+    }
+}
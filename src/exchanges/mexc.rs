@@ -0,0 +1,216 @@
+use super::{parse_f64, Parsed, VenueError};
+use crate::coverage::coverage;
+use crate::models::PairPrice;
+use crate::rest;
+use crate::store::SharedPrices;
+use crate::symbols::symbols;
+use crate::ws_manager::{run_connector, ExchangeConnector, Heartbeat, Subscriptions};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tokio::time::Duration;
+use tracing::{info, warn};
+
+const EXCHANGE: &str = "mexc";
+const WS_URL: &str = "wss://wbs.mexc.com/ws";
+/// Every symbol's mini ticker in one stream.
+const MINI_TICKERS: &str = "spot@public.miniTickers.v3.api@UTC+8";
+const BOOK_TICKER: &str = "spot@public.bookTicker.v3.api@";
+/// MEXC allows 30 streams per connection; one goes to the mini tickers.
+const BOOK_TICKER_STREAMS: usize = 29;
+const SUBSCRIBE_CHUNK: usize = 30;
+
+/// Long-running MEXC spot worker: last prices for every symbol from the
+/// `miniTickers` stream, plus best bid/ask from `bookTicker` streams for the
+/// most traded symbols.
+pub async fn run_mexc_ws(prices: SharedPrices) {
+    let mexc = Mexc {
+        subs: Subscriptions::new(SUBSCRIBE_CHUNK, subscribe_message),
+        latest: HashMap::new(),
+    };
+    run_connector(mexc, prices).await;
+}
+
+struct Mexc {
+    subs: Subscriptions,
+    /// Latest price per symbol, so book updates can carry the last price and
+    /// mini tickers keep the last book.
+    latest: HashMap<String, PairPrice>,
+}
+
+impl ExchangeConnector for Mexc {
+    fn name(&self) -> &'static str {
+        EXCHANGE
+    }
+
+    async fn connect(&mut self) -> Result<String, String> {
+        match symbols().refresh(EXCHANGE).await {
+            Ok(_) => {}
+            Err(e) if !symbols().is_loaded(EXCHANGE) => {
+                return Err(format!("exchangeInfo fetch failed: {}", e));
+            }
+            Err(e) => warn!(
+                "mexc exchangeInfo fetch failed: {} (keeping {} known symbols)",
+                e,
+                symbols().count(EXCHANGE)
+            ),
+        }
+        match rest::mexc::most_traded().await {
+            Ok(ranked) => {
+                let books = ranked
+                    .into_iter()
+                    .filter(|s| symbols().pair(EXCHANGE, s).is_some())
+                    .take(BOOK_TICKER_STREAMS)
+                    .map(|s| format!("{}{}", BOOK_TICKER, s));
+                self.subs
+                    .replace(std::iter::once(MINI_TICKERS.to_string()).chain(books));
+            }
+            Err(e) if self.subs.len() == 0 => {
+                warn!("mexc ticker ranking unavailable: {}", e);
+                self.subs.replace([MINI_TICKERS.to_string()]);
+            }
+            Err(e) => warn!("mexc ticker ranking unavailable: {}", e),
+        }
+        info!(
+            "mexc: {} symbols, {} book ticker streams",
+            symbols().count(EXCHANGE),
+            self.subs.len() - 1
+        );
+        Ok(WS_URL.to_string())
+    }
+
+    fn subscribe(&self) -> Vec<String> {
+        self.subs.messages()
+    }
+
+    fn keepalive(&self) -> Heartbeat {
+        Heartbeat::Text {
+            interval: Duration::from_secs(20),
+            message: ping_message,
+        }
+    }
+
+    fn parse_message(&mut self, txt: &str) -> Parsed {
+        parse_message(txt, &mut self.latest)
+    }
+}
+
+/// MEXC closes connections without traffic for 60s.
+fn ping_message() -> String {
+    json!({ "method": "PING" }).to_string()
+}
+
+fn subscribe_message(streams: &[String]) -> String {
+    json!({ "method": "SUBSCRIPTION", "params": streams }).to_string()
+}
+
+fn parse_message(txt: &str, latest: &mut HashMap<String, PairPrice>) -> Parsed {
+    let v: Value = match serde_json::from_str(txt) {
+        Ok(v) => v,
+        Err(_) => return Parsed::Prices(Vec::new()),
+    };
+    if let Some(err) = error_response(&v) {
+        return Parsed::Error(err);
+    }
+    let Some(channel) = v.get("c").and_then(|c| c.as_str()) else {
+        return Parsed::Prices(Vec::new());
+    };
+    let event_time = v.get("t").and_then(|t| t.as_u64()).unwrap_or(0);
+    if channel == MINI_TICKERS {
+        parse_mini_tickers(&v, event_time, latest).into()
+    } else if let Some(sym) = channel.strip_prefix(BOOK_TICKER) {
+        parse_book_ticker(&v, sym, event_time, latest).into()
+    } else {
+        Parsed::Prices(Vec::new())
+    }
+}
+
+/// Acks are `{"id":0,"code":0,"msg":"<stream>"}` even when the subscription
+/// was refused; the message then starts with "Not Subscribed".
+fn error_response(v: &Value) -> Option<VenueError> {
+    let msg = v.get("msg").and_then(|m| m.as_str())?;
+    let code = v.get("code").and_then(|c| c.as_i64()).unwrap_or(0);
+    (code != 0 || msg.starts_with("Not Subscribed")).then(|| VenueError::new(msg))
+}
+
+/// `{"c":"spot@public.miniTickers...","d":[{"s":"BTCUSDT","p":"...","q":"..."}]}`.
+fn parse_mini_tickers(
+    v: &Value,
+    event_time: u64,
+    latest: &mut HashMap<String, PairPrice>,
+) -> Vec<PairPrice> {
+    let Some(data) = v.get("d").and_then(|d| d.as_array()) else {
+        return Vec::new();
+    };
+    data.iter()
+        .filter_map(|t| {
+            let sym = t.get("s").and_then(|s| s.as_str())?;
+            let Some((base, quote)) = symbols().pair(EXCHANGE, sym) else {
+                coverage().record_dropped(EXCHANGE, sym, "unparsed");
+                return None;
+            };
+            let price = parse_f64(t.get("p"))?;
+            let entry = latest.entry(sym.to_string()).or_insert_with(|| PairPrice {
+                base,
+                quote,
+                price,
+                is_spot: true,
+                volume: 0.0,
+                event_time,
+                bid: None,
+                ask: None,
+            });
+            entry.price = price;
+            entry.volume = parse_f64(t.get("q")).unwrap_or(entry.volume);
+            entry.event_time = event_time;
+            Some(entry.clone())
+        })
+        .collect()
+}
+
+/// `{"c":"spot@public.bookTicker.v3.api@BTCUSDT","d":{"b":"..","a":".."}}`;
+/// dropped until the mini tickers have supplied a last price.
+fn parse_book_ticker(
+    v: &Value,
+    sym: &str,
+    event_time: u64,
+    latest: &mut HashMap<String, PairPrice>,
+) -> Vec<PairPrice> {
+    let (Some(d), Some(entry)) = (v.get("d"), latest.get_mut(sym)) else {
+        return Vec::new();
+    };
+    entry.bid = parse_f64(d.get("b")).or(entry.bid);
+    entry.ask = parse_f64(d.get("a")).or(entry.ask);
+    entry.event_time = event_time;
+    vec![entry.clone()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::list_symbols;
+
+    fn parse(txt: &str, latest: &mut HashMap<String, PairPrice>) -> Vec<PairPrice> {
+        list_symbols(EXCHANGE, &[("BTCUSDT", "BTC", "USDT")]);
+        parse_message(txt, latest).prices()
+    }
+
+    #[test]
+    fn book_tickers_fill_in_the_mini_ticker() {
+        let mut latest = HashMap::new();
+        let book = r#"{"c":"spot@public.bookTicker.v3.api@BTCUSDT","d":{"A":"0.5","B":"1.2","a":"65000.2","b":"65000"},"s":"BTCUSDT","t":1700000000100}"#;
+        // No last price yet for the book to attach to.
+        assert!(parse(book, &mut latest).is_empty());
+
+        let mini = r#"{"c":"spot@public.miniTickers.v3.api@UTC+8","d":[{"s":"BTCUSDT","p":"65000.1","r":"0.0156","h":"65500","l":"63800","v":"650000000","q":"10000.5"}],"t":1700000000000}"#;
+        let prices = parse(mini, &mut latest);
+        assert_eq!((prices[0].price, prices[0].volume), (65000.1, 10000.5));
+        assert_eq!(prices[0].bid, None);
+
+        let prices = parse(book, &mut latest);
+        let p = &prices[0];
+        assert_eq!((p.base.as_str(), p.quote.as_str()), ("BTC", "USDT"));
+        assert_eq!(p.price, 65000.1);
+        assert_eq!((p.bid, p.ask), (Some(65000.0), Some(65000.2)));
+        assert_eq!(p.event_time, 1_700_000_000_100);
+    }
+}
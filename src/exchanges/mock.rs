@@ -0,0 +1,234 @@
+use super::{parse_f64, Parsed};
+use crate::config::MockConfig;
+use crate::models::PairPrice;
+use crate::store::SharedPrices;
+use crate::tasks;
+use crate::utils::now_ms;
+use crate::ws_manager::{run_connector, ExchangeConnector, Heartbeat};
+use futures_util::SinkExt;
+use rand::Rng;
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tokio::sync::broadcast;
+use tokio::time::Duration;
+use tokio_tungstenite::{accept_async, tungstenite::Message};
+use tracing::{error, info, warn};
+
+pub const EXCHANGE: &str = "mock";
+
+/// Start the local mock exchange (WS server + generator) and a worker that
+/// ingests it like any other venue, so the whole pipeline runs offline.
+pub async fn run_mock(cfg: MockConfig, prices: SharedPrices) {
+    let listener = match TcpListener::bind(&cfg.bind).await {
+        Ok(l) => l,
+        Err(e) => {
+            error!("mock exchange: cannot bind {}: {}", cfg.bind, e);
+            return;
+        }
+    };
+    info!(
+        "mock exchange serving synthetic tickers on ws://{}",
+        cfg.bind
+    );
+
+    let (tx, _) = broadcast::channel::<String>(64);
+    tasks::spawn("mock:generate", generate(cfg.clone(), tx.clone()));
+    tasks::spawn("mock:serve", serve(listener, tx));
+    run_local_ws(EXCHANGE, format!("ws://{}", cfg.bind), prices).await;
+}
+
+/// Fan generator frames out to every connected WS client.
+pub(super) async fn serve(listener: TcpListener, tx: broadcast::Sender<String>) {
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(s) => s,
+            Err(e) => {
+                warn!("mock exchange accept error: {}", e);
+                continue;
+            }
+        };
+        let mut rx = tx.subscribe();
+        tasks::spawn("local-ws:client", async move {
+            let mut ws = match accept_async(stream).await {
+                Ok(ws) => ws,
+                Err(e) => {
+                    warn!("mock exchange handshake with {} failed: {}", peer, e);
+                    return;
+                }
+            };
+            while let Ok(frame) = rx.recv().await {
+                if ws.send(Message::Text(frame)).await.is_err() {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+/// Random-walk "fair" prices for every asset, published as ASSET/QUOTE and
+/// ASSET/ANCHOR pairs that are consistent with each other, except when a
+/// cross pair is deliberately skewed to create an arbitrage cycle.
+async fn generate(cfg: MockConfig, tx: broadcast::Sender<String>) {
+    let mut fair: Vec<f64> = cfg
+        .assets
+        .iter()
+        .enumerate()
+        .map(|(i, a)| reference_price(a, i))
+        .collect();
+    let mut tick: u64 = 0;
+    let mut timer = tokio::time::interval(Duration::from_millis(cfg.interval_ms.max(10)));
+
+    loop {
+        timer.tick().await;
+        tick += 1;
+        let mut rng = rand::thread_rng();
+        for p in fair.iter_mut() {
+            *p *= 1.0 + rng.gen_range(-1.0..=1.0) * cfg.volatility_pct / 100.0;
+        }
+
+        let ts = now_ms();
+        let mut data = Vec::new();
+        for (asset, p) in cfg.assets.iter().zip(&fair) {
+            data.push(ticker(asset, &cfg.quote, *p, ts));
+        }
+        // Cross pairs against the first asset (the anchor, e.g. BTC).
+        let skewed =
+            (cfg.arb_every > 0 && tick.is_multiple_of(cfg.arb_every) && cfg.assets.len() > 1)
+                .then(|| rng.gen_range(1..cfg.assets.len()));
+        if let Some((anchor, anchor_price)) = cfg.assets.first().zip(fair.first()) {
+            for (i, (asset, p)) in cfg.assets.iter().zip(&fair).enumerate().skip(1) {
+                let mut cross = p / anchor_price;
+                if skewed == Some(i) {
+                    cross *= 1.0 + cfg.arb_pct / 100.0;
+                }
+                data.push(ticker(asset, anchor, cross, ts));
+            }
+        }
+
+        let frame = json!({ "type": "tickers", "data": data }).to_string();
+        // No receivers just means no client is connected yet.
+        let _ = tx.send(frame);
+    }
+}
+
+/// Half the quoted bid/ask spread, as a fraction of the price.
+const HALF_SPREAD: f64 = 0.0001;
+
+pub(super) fn ticker(base: &str, quote: &str, price: f64, ts: u64) -> Value {
+    json!({
+        "base": base,
+        "quote": quote,
+        "price": price,
+        "bid": price * (1.0 - HALF_SPREAD),
+        "ask": price * (1.0 + HALF_SPREAD),
+        "volume": 1_000_000.0,
+        "ts": ts,
+    })
+}
+
+/// Rough starting prices for well-known assets so output looks plausible.
+fn reference_price(asset: &str, idx: usize) -> f64 {
+    match asset {
+        "BTC" => 60_000.0,
+        "ETH" => 3_000.0,
+        "BNB" => 550.0,
+        "SOL" => 150.0,
+        "XRP" => 0.6,
+        "ADA" => 0.45,
+        "DOGE" => 0.12,
+        _ => 1.0 + idx as f64,
+    }
+}
+
+/// Ingest a local generator's stream like any other venue.
+pub(super) async fn run_local_ws(exchange: &'static str, url: String, prices: SharedPrices) {
+    run_connector(LocalFeed { exchange, url }, prices).await;
+}
+
+struct LocalFeed {
+    exchange: &'static str,
+    url: String,
+}
+
+impl ExchangeConnector for LocalFeed {
+    fn name(&self) -> &'static str {
+        self.exchange
+    }
+
+    async fn connect(&mut self) -> Result<String, String> {
+        Ok(self.url.clone())
+    }
+
+    fn subscribe(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn keepalive(&self) -> Heartbeat {
+        Heartbeat::ServerPing
+    }
+
+    fn parse_message(&mut self, txt: &str) -> Parsed {
+        parse_message(txt)
+    }
+}
+
+fn parse_message(txt: &str) -> Parsed {
+    let v: Value = match serde_json::from_str(txt) {
+        Ok(v) => v,
+        Err(_) => return Parsed::Prices(Vec::new()),
+    };
+    let items = v.get("data").and_then(|d| d.as_array());
+    items
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|it| {
+                    Some(PairPrice {
+                        base: it.get("base")?.as_str()?.to_string(),
+                        quote: it.get("quote")?.as_str()?.to_string(),
+                        price: parse_f64(it.get("price"))?,
+                        is_spot: true,
+                        volume: parse_f64(it.get("volume")).unwrap_or(0.0),
+                        event_time: it.get("ts").and_then(|t| t.as_u64()).unwrap_or(0),
+                        bid: parse_f64(it.get("bid")),
+                        ask: parse_f64(it.get("ask")),
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_tickers_parse_back() {
+        let data = vec![
+            ticker("BTC", "USDT", 60_000.0, 1_700_000_000_000),
+            ticker("ETH", "BTC", 0.05, 1_700_000_000_000),
+        ];
+        let frame = json!({ "type": "tickers", "data": data }).to_string();
+        let prices = parse_message(&frame).prices();
+        let got: Vec<_> = prices
+            .iter()
+            .map(|p| (p.base.as_str(), p.quote.as_str(), p.price, p.event_time))
+            .collect();
+        assert_eq!(
+            got,
+            [
+                ("BTC", "USDT", 60_000.0, 1_700_000_000_000),
+                ("ETH", "BTC", 0.05, 1_700_000_000_000),
+            ]
+        );
+    }
+
+    #[test]
+    fn generated_tickers_quote_a_spread() {
+        let frame = json!({ "data": [ticker("BTC", "USDT", 60_000.0, 1)] }).to_string();
+        let prices = parse_message(&frame).prices();
+        let spread = prices[0].spread_pct().unwrap();
+        assert!((spread - 2.0 * HALF_SPREAD * 100.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,559 @@
+use crate::config::config;
+use crate::coverage::coverage;
+use crate::models::PairPrice;
+use crate::rest;
+use crate::store::{pair_key, SharedPrices};
+use crate::symbols::symbols;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path};
+use tracing::warn;
+
+pub mod ascendex;
+pub mod binance;
+pub mod bingx;
+pub mod bitget;
+pub mod bithumb;
+pub mod bitstamp;
+pub mod bybit;
+pub mod coinbase;
+pub mod coinex;
+pub mod curve;
+pub mod declarative;
+pub mod dydx;
+mod evm_pools;
+pub mod gateio;
+pub mod gemini;
+pub mod hyperliquid;
+pub mod jupiter;
+pub mod kraken;
+pub mod kucoin;
+pub mod lbank;
+pub mod mexc;
+pub mod mock;
+pub mod okx;
+pub mod osmosis;
+pub mod pancakeswap;
+pub mod phemex;
+pub mod poloniex;
+pub mod rest_fallback;
+pub mod solana_dex;
+pub mod synthetic;
+pub mod uniswap;
+pub mod whitebit;
+
+/// Wrapper so routes.rs can call collect_exchange_snapshot(exchange, seconds).
+/// Binance is collected fresh over `seconds`; other venues are served from
+/// their live worker's latest prices, or polled over REST when the worker
+/// has none yet.
+pub async fn collect_exchange_snapshot(
+    exchange: &str,
+    seconds: u64,
+    prices: &SharedPrices,
+) -> Vec<PairPrice> {
+    match exchange.to_lowercase().as_str() {
+        "binance" => binance::collect_binance_snapshot(seconds).await,
+        other => {
+            let pairs = prices.snapshot(other);
+            if !pairs.is_empty() {
+                return pairs;
+            }
+            warn!(
+                "collect_exchange_snapshot: no live prices for '{}', polling REST tickers",
+                other
+            );
+            rest::tickers(other).await.unwrap_or_else(|e| {
+                warn!("collect_exchange_snapshot: {}", e);
+                Vec::new()
+            })
+        }
+    }
+}
+
+/// Refresh the venue's instrument metadata (and `/coverage` listed count);
+/// on failure the cached list stays in use.
+pub(crate) async fn refresh_symbols(exchange: &str) {
+    if let Err(e) = symbols().refresh(exchange).await {
+        warn!("{}: instrument list unavailable: {}", exchange, e);
+    }
+}
+
+/// Fixed pair set for reproducible scans: a JSON array of `PairPrice`
+/// (the format `/scan` inputs are shared in), named relative to `dir`.
+/// Errors are deliberately vague since `name` comes from API callers; the
+/// details are logged.
+pub async fn load_pairs_file(dir: &str, name: &str) -> Result<Vec<PairPrice>, String> {
+    let invalid = |detail: String| {
+        warn!("pairs file {:?}: {}", name, detail);
+        "invalid pairs file".to_string()
+    };
+    if !is_pairs_file_name(name) {
+        return Err(invalid("not a relative .json path".to_string()));
+    }
+    let dir = tokio::fs::canonicalize(dir)
+        .await
+        .map_err(|e| invalid(format!("pairs_dir {}: {}", dir, e)))?;
+    let path = tokio::fs::canonicalize(dir.join(name))
+        .await
+        .map_err(|e| invalid(e.to_string()))?;
+    // A symlink may still point outside the directory.
+    if !path.starts_with(&dir) {
+        return Err(invalid(format!("resolves outside {}", dir.display())));
+    }
+    let text = tokio::fs::read_to_string(&path)
+        .await
+        .map_err(|e| invalid(e.to_string()))?;
+    serde_json::from_str(&text).map_err(|e| invalid(e.to_string()))
+}
+
+/// A relative `.json` path without `..`, `.` or a root.
+fn is_pairs_file_name(name: &str) -> bool {
+    let rel = Path::new(name);
+    rel.components().all(|c| matches!(c, Component::Normal(_)))
+        && rel.extension().and_then(|e| e.to_str()) == Some("json")
+}
+
+/// What a connector made of one text frame.
+pub enum Parsed {
+    Prices(Vec<PairPrice>),
+    /// Top-of-book updates: bid/ask only, no last price or volume.
+    Book(Vec<PairPrice>),
+    /// The venue rejected a request (subscription error, rate limit, ...).
+    Error(VenueError),
+    /// System/maintenance notice pushed on the stream.
+    Notice(String),
+    /// A frame the venue expects sent back on the same connection (e.g. the
+    /// answer to a server ping carrying an id).
+    Reply(String),
+}
+
+impl From<Vec<PairPrice>> for Parsed {
+    fn from(prices: Vec<PairPrice>) -> Self {
+        Parsed::Prices(prices)
+    }
+}
+
+#[cfg(test)]
+impl Parsed {
+    /// The updates of a price frame; panics on anything else.
+    pub(crate) fn prices(self) -> Vec<PairPrice> {
+        match self {
+            Parsed::Prices(p) | Parsed::Book(p) => p,
+            Parsed::Error(e) => panic!("venue error: {}", e.message),
+            Parsed::Notice(n) => panic!("notice: {}", n),
+            Parsed::Reply(r) => panic!("reply: {}", r),
+        }
+    }
+}
+
+/// Stand in for a venue's instrument list, for connectors that split
+/// symbols strictly from it.
+#[cfg(test)]
+pub(crate) fn list_symbols(exchange: &str, listing: &[(&str, &str, &str)]) {
+    let list: Vec<rest::Instrument> = listing
+        .iter()
+        .map(|(symbol, base, quote)| rest::Instrument {
+            symbol: symbol.to_string(),
+            base: base.to_string(),
+            quote: quote.to_string(),
+            trading: true,
+            ..Default::default()
+        })
+        .collect();
+    symbols().replace(exchange, &list);
+}
+
+#[derive(Debug, Clone)]
+pub struct VenueError {
+    pub message: String,
+    pub rate_limited: bool,
+}
+
+impl VenueError {
+    pub fn new(message: impl Into<String>) -> Self {
+        let message = message.into();
+        let lower = message.to_lowercase();
+        let rate_limited = ["rate limit", "too many", "exceed", "limit exceeded", "429"]
+            .iter()
+            .any(|k| lower.contains(k));
+        Self {
+            message,
+            rate_limited,
+        }
+    }
+}
+
+/// Pull a human-readable text out of a notice frame, trying the usual fields.
+pub(crate) fn notice_text(v: &Value) -> String {
+    ["msg", "message", "data", "ret_msg", "reason"]
+        .iter()
+        .find_map(|k| match v.get(*k) {
+            Some(Value::String(s)) => Some(s.clone()),
+            Some(other) if !other.is_null() => Some(other.to_string()),
+            _ => None,
+        })
+        .unwrap_or_else(|| v.to_string())
+}
+
+/// Changes accumulated by a [`TickerBuffer`] since the previous flush.
+#[derive(Debug, Default)]
+pub struct Delta {
+    pub changed: Vec<PairPrice>,
+    /// Pair keys that no longer pass the ingestion filters or were evicted.
+    pub removed: Vec<String>,
+}
+
+/// Connector-side buffer between the WS stream and the shared price store.
+/// Keeps the latest update per symbol and remembers which ones changed since
+/// the last flush, so flushes only write deltas. Ingestion filters and the
+/// symbol cap from the connector's config are applied here, before anything
+/// reaches the store.
+#[derive(Debug, Default)]
+pub struct TickerBuffer {
+    /// key -> (latest update, change sequence number for LRU eviction)
+    latest: HashMap<String, (PairPrice, u64)>,
+    dirty: HashSet<String>,
+    removed: HashSet<String>,
+    seq: u64,
+    min_volume: f64,
+    max_symbols: usize,
+    /// Volume floor raised while the connector is shedding load.
+    shed_volume: f64,
+    /// Symbols kept out while shedding when volume can't rank them (venues
+    /// reporting none), with the change sequence they had when shed.
+    shed_keys: HashMap<String, u64>,
+    exchange: String,
+}
+
+impl TickerBuffer {
+    pub fn new(exchange: &str) -> Self {
+        let cfg = config().connector(exchange);
+        Self {
+            min_volume: cfg.min_volume,
+            max_symbols: cfg.max_symbols,
+            exchange: exchange.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Record an update; stale (out-of-order) and unchanged updates are ignored.
+    pub fn record(&mut self, p: PairPrice) {
+        let key = pair_key(&p.base, &p.quote);
+        if let Some((prev, _)) = self.latest.get(&key) {
+            if is_out_of_order(Some(prev), p.event_time) {
+                return;
+            }
+            if prev.price == p.price
+                && prev.volume == p.volume
+                && prev.bid == p.bid
+                && prev.ask == p.ask
+            {
+                return;
+            }
+        }
+        if self.shed_keys.contains_key(&key) {
+            self.reject(&key, "load_shed");
+            return;
+        }
+        if p.volume < self.min_volume.max(self.shed_volume) {
+            let reason = if p.volume < self.min_volume {
+                "min_volume"
+            } else {
+                "load_shed"
+            };
+            self.reject(&key, reason);
+            return;
+        }
+        self.seq += 1;
+        self.removed.remove(&key);
+        let is_new = self.latest.insert(key.clone(), (p, self.seq)).is_none();
+        self.dirty.insert(key);
+        if is_new && self.max_symbols > 0 && self.latest.len() > self.max_symbols {
+            self.evict_least_recent();
+        }
+    }
+
+    /// Leave `key` out of the store, removing it if tracked, and record why.
+    pub fn reject(&mut self, key: &str, reason: &'static str) {
+        coverage().record_dropped(&self.exchange, key, reason);
+        self.remove(key);
+    }
+
+    fn remove(&mut self, key: &str) {
+        if self.latest.remove(key).is_some() {
+            self.dirty.remove(key);
+            self.removed.insert(key.to_string());
+        }
+    }
+
+    /// Drop the symbol whose price changed least recently.
+    fn evict_least_recent(&mut self) {
+        let oldest = self
+            .latest
+            .iter()
+            .min_by_key(|(_, (_, seq))| *seq)
+            .map(|(k, _)| k.clone());
+        if let Some(key) = oldest {
+            self.reject(&key, "max_symbols");
+        }
+    }
+
+    /// Changed symbols waiting for the next flush.
+    pub fn pending(&self) -> usize {
+        self.dirty.len()
+    }
+
+    /// Stop tracking the lowest-volume `fraction` of symbols until called
+    /// again with a lower fraction (0 lifts the restriction). When volumes
+    /// can't tell symbols apart (most of them report none), the least
+    /// recently changed `fraction` is dropped instead.
+    pub fn shed_lowest(&mut self, fraction: f64) {
+        let previous = std::mem::take(&mut self.shed_keys);
+        self.shed_volume = 0.0;
+        if fraction > 0.0 {
+            let mut volumes: Vec<f64> = self.latest.values().map(|(p, _)| p.volume).collect();
+            volumes.sort_by(f64::total_cmp);
+            let idx = (volumes.len() as f64 * fraction) as usize;
+            self.shed_volume = volumes
+                .get(idx.min(volumes.len().saturating_sub(1)))
+                .copied()
+                .unwrap_or(0.0);
+            if self.shed_volume <= 0.0 {
+                // Previously shed symbols are ranked too, so a lower
+                // fraction lets the most recent of them back in.
+                let mut by_seq: Vec<(u64, String)> = self
+                    .latest
+                    .iter()
+                    .map(|(k, (_, seq))| (*seq, k.clone()))
+                    .chain(previous.into_iter().map(|(k, seq)| (seq, k)))
+                    .collect();
+                by_seq.sort_unstable();
+                let n = (by_seq.len() as f64 * fraction) as usize;
+                self.shed_keys = by_seq
+                    .into_iter()
+                    .take(n)
+                    .map(|(seq, k)| (k, seq))
+                    .collect();
+            }
+        }
+        let shed: Vec<String> = self
+            .latest
+            .iter()
+            .filter(|(k, (p, _))| p.volume < self.shed_volume || self.shed_keys.contains_key(*k))
+            .map(|(k, _)| k.clone())
+            .collect();
+        for key in shed {
+            self.reject(&key, "load_shed");
+        }
+    }
+
+    /// Take the changes recorded since the previous call.
+    pub fn drain(&mut self) -> Delta {
+        Delta {
+            changed: self
+                .dirty
+                .drain()
+                .filter_map(|k| self.latest.get(&k).map(|(p, _)| p.clone()))
+                .collect(),
+            removed: self.removed.drain().collect(),
+        }
+    }
+}
+
+/// True when `event_time` is older than the update already stored for the symbol,
+/// e.g. a delayed frame arriving after a fresher one.
+pub(crate) fn is_out_of_order(stored: Option<&PairPrice>, event_time: u64) -> bool {
+    match stored {
+        Some(prev) => event_time != 0 && event_time < prev.event_time,
+        None => false,
+    }
+}
+
+/// Helper: parse f64 from JSON value
+pub(crate) fn parse_f64(v: Option<&Value>) -> Option<f64> {
+    v.and_then(|val| val.as_f64().or_else(|| val.as_str()?.parse::<f64>().ok()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(base: &str, price: f64, volume: f64, event_time: u64) -> PairPrice {
+        PairPrice {
+            base: base.into(),
+            quote: "USDT".into(),
+            price,
+            is_spot: true,
+            volume,
+            event_time,
+            bid: None,
+            ask: None,
+        }
+    }
+
+    fn buffer() -> TickerBuffer {
+        TickerBuffer {
+            exchange: "test".into(),
+            ..TickerBuffer::default()
+        }
+    }
+
+    fn tracked(buf: &TickerBuffer) -> Vec<String> {
+        let mut keys: Vec<String> = buf.latest.keys().cloned().collect();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn shedding_ranks_by_volume() {
+        let mut buf = buffer();
+        for (i, base) in ["A", "B", "C", "D"].iter().enumerate() {
+            buf.record(pair(base, 1.0, (i + 1) as f64 * 10.0, 1));
+        }
+        buf.shed_lowest(0.5);
+        assert_eq!(tracked(&buf), ["C/USDT", "D/USDT"]);
+        // Still below the floor while shedding.
+        buf.record(pair("A", 2.0, 10.0, 2));
+        assert_eq!(tracked(&buf), ["C/USDT", "D/USDT"]);
+        buf.shed_lowest(0.0);
+        buf.record(pair("A", 2.0, 10.0, 2));
+        assert_eq!(tracked(&buf), ["A/USDT", "C/USDT", "D/USDT"]);
+    }
+
+    #[test]
+    fn shedding_without_volume_drops_least_recently_changed() {
+        let mut buf = buffer();
+        for base in ["A", "B", "C", "D"] {
+            buf.record(pair(base, 1.0, 0.0, 1));
+        }
+        buf.record(pair("A", 2.0, 0.0, 2));
+        buf.shed_lowest(0.5);
+        assert_eq!(tracked(&buf), ["A/USDT", "D/USDT"]);
+        // Shed symbols stay out even when they update.
+        buf.record(pair("B", 3.0, 0.0, 3));
+        assert_eq!(tracked(&buf), ["A/USDT", "D/USDT"]);
+        // A lower fraction lets the most recent of them back in.
+        buf.shed_lowest(0.25);
+        buf.record(pair("C", 3.0, 0.0, 3));
+        assert_eq!(tracked(&buf), ["A/USDT", "C/USDT", "D/USDT"]);
+        buf.shed_lowest(0.0);
+        buf.record(pair("B", 4.0, 0.0, 4));
+        assert_eq!(tracked(&buf).len(), 4);
+    }
+
+    #[test]
+    fn pairs_file_names_stay_relative() {
+        assert!(is_pairs_file_name("snap.json"));
+        assert!(is_pairs_file_name("2024/snap.json"));
+        assert!(!is_pairs_file_name("/etc/passwd.json"));
+        assert!(!is_pairs_file_name("../snap.json"));
+        assert!(!is_pairs_file_name("a/../../snap.json"));
+        assert!(!is_pairs_file_name("./snap.json"));
+        assert!(!is_pairs_file_name("snap.txt"));
+        assert!(!is_pairs_file_name(""));
+    }
+
+    #[tokio::test]
+    async fn pairs_file_outside_dir_is_rejected() {
+        let dir = std::env::temp_dir().join(format!("pairs-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("ok.json"), "[]").unwrap();
+        std::fs::write(dir.join("bad.json"), "not json").unwrap();
+        let d = dir.to_str().unwrap();
+        assert!(load_pairs_file(d, "ok.json").await.unwrap().is_empty());
+        let err = load_pairs_file(d, "bad.json").await.unwrap_err();
+        assert_eq!(err, "invalid pairs file");
+        assert!(load_pairs_file(d, "missing.json").await.is_err());
+        assert!(load_pairs_file(d, "../ok.json").await.is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Keys flushed by the next drain, sorted.
+    fn changed(buf: &mut TickerBuffer) -> Vec<String> {
+        let mut keys: Vec<String> = buf
+            .drain()
+            .changed
+            .iter()
+            .map(|p| pair_key(&p.base, &p.quote))
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn older_event_times_are_out_of_order() {
+        let stored = pair("A", 1.0, 0.0, 200);
+        assert!(is_out_of_order(Some(&stored), 100));
+        assert!(!is_out_of_order(Some(&stored), 300));
+        // Venues without event times, and first updates, always apply.
+        assert!(!is_out_of_order(Some(&stored), 0));
+        assert!(!is_out_of_order(None, 100));
+    }
+
+    #[test]
+    fn flushes_only_changed_symbols() {
+        let mut buf = buffer();
+        buf.record(pair("A", 1.0, 10.0, 5));
+        buf.record(pair("B", 1.0, 10.0, 5));
+        assert_eq!(changed(&mut buf), ["A/USDT", "B/USDT"]);
+        // Same price and volume, or older than what's stored: nothing to write.
+        buf.record(pair("A", 1.0, 10.0, 6));
+        buf.record(pair("B", 2.0, 10.0, 4));
+        assert!(changed(&mut buf).is_empty());
+        buf.record(pair("B", 2.0, 10.0, 6));
+        assert_eq!(changed(&mut buf), ["B/USDT"]);
+    }
+
+    #[test]
+    fn min_volume_keeps_thin_pairs_out() {
+        let mut buf = TickerBuffer {
+            min_volume: 50.0,
+            ..buffer()
+        };
+        buf.record(pair("A", 1.0, 10.0, 1));
+        buf.record(pair("B", 1.0, 100.0, 1));
+        assert_eq!(tracked(&buf), ["B/USDT"]);
+        buf.drain();
+        // A tracked pair falling below the floor is removed from the store.
+        buf.record(pair("B", 1.0, 20.0, 2));
+        let delta = buf.drain();
+        assert!(tracked(&buf).is_empty());
+        assert_eq!(delta.removed, ["B/USDT"]);
+    }
+
+    #[test]
+    fn max_symbols_evicts_least_recently_changed() {
+        let mut buf = TickerBuffer {
+            max_symbols: 2,
+            ..buffer()
+        };
+        buf.record(pair("A", 1.0, 0.0, 1));
+        buf.record(pair("B", 1.0, 0.0, 1));
+        buf.record(pair("A", 2.0, 0.0, 2));
+        buf.record(pair("C", 1.0, 0.0, 2));
+        assert_eq!(tracked(&buf), ["A/USDT", "C/USDT"]);
+        let delta = buf.drain();
+        assert_eq!(delta.removed, ["B/USDT"]);
+        // An evicted symbol comes back by pushing out the next oldest.
+        buf.record(pair("B", 2.0, 0.0, 3));
+        assert_eq!(tracked(&buf), ["B/USDT", "C/USDT"]);
+    }
+
+    #[test]
+    fn rate_limit_wording_marks_errors() {
+        assert!(VenueError::new("Too many requests").rate_limited);
+        assert!(VenueError::new("HTTP 429").rate_limited);
+        assert!(VenueError::new("Request limit exceeded").rate_limited);
+        assert!(!VenueError::new("invalid symbol").rate_limited);
+    }
+
+    #[test]
+    fn notice_text_tries_the_usual_fields() {
+        let v = serde_json::json!({"e": "serverShutdown", "msg": "restarting"});
+        assert_eq!(notice_text(&v), "restarting");
+        let v = serde_json::json!({"type": "notice", "data": {"at": 1}});
+        assert_eq!(notice_text(&v), r#"{"at":1}"#);
+        let v = serde_json::json!({"e": "serverShutdown"});
+        assert_eq!(notice_text(&v), r#"{"e":"serverShutdown"}"#);
+    }
+}
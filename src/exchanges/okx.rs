@@ -0,0 +1,151 @@
+use super::{notice_text, Parsed, VenueError};
+use crate::coverage::coverage;
+use crate::models::PairPrice;
+use crate::rest;
+use crate::rest::okx::Ticker;
+use crate::store::SharedPrices;
+use crate::ws_manager::{run_connector, ExchangeConnector, Heartbeat, Subscriptions};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::time::Duration;
+use tracing::{info, warn};
+
+const EXCHANGE: &str = "okx";
+const WS_URL: &str = "wss://ws.okx.com:8443/ws/v5/public";
+/// Keeps each subscribe request well under OKX's 64KB frame limit.
+const SUBSCRIBE_CHUNK: usize = 100;
+
+/// Long-running OKX spot worker on the `tickers` channel, one `instId` per
+/// live spot instrument.
+pub async fn run_okx_ws(prices: SharedPrices) {
+    let subs = Subscriptions::new(SUBSCRIBE_CHUNK, subscribe_message);
+    run_connector(Okx { subs }, prices).await;
+}
+
+struct Okx {
+    subs: Subscriptions,
+}
+
+impl ExchangeConnector for Okx {
+    fn name(&self) -> &'static str {
+        EXCHANGE
+    }
+
+    async fn connect(&mut self) -> Result<String, String> {
+        match rest::okx::instruments().await {
+            Ok(list) => {
+                let live: Vec<String> = list
+                    .into_iter()
+                    .filter(|i| i.trading)
+                    .map(|i| i.symbol)
+                    .collect();
+                coverage().set_listed(EXCHANGE, live.len());
+                self.subs.replace(live);
+            }
+            Err(e) if self.subs.len() == 0 => {
+                return Err(format!("instruments fetch failed: {}", e));
+            }
+            Err(e) => warn!(
+                "okx instruments fetch failed: {} (restoring {} known subscriptions)",
+                e,
+                self.subs.len()
+            ),
+        }
+        info!("okx: subscribing to {} spot instruments", self.subs.len());
+        Ok(WS_URL.to_string())
+    }
+
+    fn subscribe(&self) -> Vec<String> {
+        self.subs.messages()
+    }
+
+    fn keepalive(&self) -> Heartbeat {
+        Heartbeat::Text {
+            interval: Duration::from_secs(25),
+            message: ping_message,
+        }
+    }
+
+    fn parse_message(&mut self, txt: &str) -> Parsed {
+        parse_message(txt)
+    }
+}
+
+/// OKX drops connections idle for 30s; a bare "ping" is answered with "pong".
+fn ping_message() -> String {
+    "ping".to_string()
+}
+
+fn subscribe_message(inst_ids: &[String]) -> String {
+    let args: Vec<Value> = inst_ids
+        .iter()
+        .map(|id| json!({ "channel": "tickers", "instId": id }))
+        .collect();
+    json!({ "op": "subscribe", "args": args }).to_string()
+}
+
+fn parse_message(txt: &str) -> Parsed {
+    let v: Value = match serde_json::from_str(txt) {
+        Ok(v) => v,
+        Err(_) => return Parsed::Prices(Vec::new()),
+    };
+    match v.get("event").and_then(|e| e.as_str()) {
+        Some("notice") => return Parsed::Notice(notice_text(&v)),
+        Some("error") => return Parsed::Error(error_response(&v)),
+        _ => {}
+    }
+    parse_tickers(&v).into()
+}
+
+/// e.g. `{"event":"error","code":"60018","msg":"Wrong URL or channel..."}`.
+fn error_response(v: &Value) -> VenueError {
+    let code = v.get("code").and_then(|c| c.as_str()).unwrap_or("?");
+    let msg = v.get("msg").and_then(|m| m.as_str()).unwrap_or("error");
+    VenueError::new(format!("code {}: {}", code, msg))
+}
+
+fn parse_tickers(v: &Value) -> Vec<PairPrice> {
+    let is_tickers = v
+        .get("arg")
+        .and_then(|a| a.get("channel"))
+        .and_then(|c| c.as_str())
+        == Some("tickers");
+    let Some(data) = v
+        .get("data")
+        .and_then(|d| d.as_array())
+        .filter(|_| is_tickers)
+    else {
+        return Vec::new();
+    };
+    data.iter()
+        .filter_map(|t| {
+            let ticker = Ticker::deserialize(t).ok()?;
+            let inst = ticker.inst_id.clone();
+            let price = ticker.into_price();
+            if price.is_none() {
+                coverage().record_dropped(EXCHANGE, &inst, "unparsed");
+            }
+            price
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_tickers_channel_pushes_are_priced() {
+        let frame = r#"{"arg":{"channel":"tickers","instId":"BTC-USDT"},"data":[{"instType":"SPOT","instId":"BTC-USDT","last":"65000.1","lastSz":"0.01","askPx":"65000.2","askSz":"1.5","bidPx":"65000","bidSz":"2","open24h":"64000","high24h":"65500","low24h":"63800","sodUtc0":"64200","sodUtc8":"64100","volCcy24h":"650000000","vol24h":"10000.5","ts":"1700000000000"}]}"#;
+        let prices = parse_message(frame).prices();
+        assert_eq!(prices.len(), 1);
+        let p = &prices[0];
+        assert_eq!((p.base.as_str(), p.quote.as_str()), ("BTC", "USDT"));
+        assert_eq!((p.price, p.volume), (65000.1, 10000.5));
+        assert_eq!((p.bid, p.ask), (Some(65000.0), Some(65000.2)));
+        assert_eq!(p.event_time, 1_700_000_000_000);
+
+        let trades = frame.replace(r#""channel":"tickers""#, r#""channel":"trades""#);
+        assert!(parse_message(&trades).prices().is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+use super::TickerBuffer;
+use crate::config::{config, OsmosisPoolSpec};
+use crate::coverage::coverage;
+use crate::health::health;
+use crate::models::PairPrice;
+use crate::store::SharedPrices;
+use crate::utils::now_ms;
+use crate::ws_manager::publish;
+use futures::future::join_all;
+use serde::Deserialize;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+const EXCHANGE: &str = "osmosis";
+
+#[derive(Deserialize)]
+struct SpotPrice {
+    spot_price: String,
+}
+
+/// Long-running Osmosis worker: asks an LCD (Cosmos REST) endpoint for the
+/// spot price of every configured pool each poll interval. Pools report no
+/// volume.
+pub async fn run_osmosis(prices: SharedPrices) {
+    let cfg = &config().osmosis;
+    if cfg.lcd_url.is_empty() || cfg.pools.is_empty() {
+        error!("osmosis: lcd_url and pools must be configured");
+        return;
+    }
+    let http = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .unwrap_or_default();
+    let mut buffer = TickerBuffer::new(EXCHANGE);
+    let mut poll = tokio::time::interval(cfg.poll_interval());
+    coverage().set_listed(EXCHANGE, cfg.pools.len());
+    info!("osmosis: polling {} pools", cfg.pools.len());
+
+    loop {
+        poll.tick().await;
+        let results = join_all(
+            cfg.pools
+                .iter()
+                .map(|pool| spot_price(&http, &cfg.lcd_url, pool)),
+        )
+        .await;
+        let event_time = now_ms();
+        let mut ps = Vec::new();
+        let mut failures = 0;
+        for (pool, result) in cfg.pools.iter().zip(results) {
+            match result {
+                Ok(price) => ps.push(PairPrice {
+                    base: pool.token0.to_uppercase(),
+                    quote: pool.token1.to_uppercase(),
+                    price,
+                    is_spot: true,
+                    volume: 0.0,
+                    event_time,
+                    bid: None,
+                    ask: None,
+                }),
+                Err(e) => {
+                    warn!("osmosis: pool {}: {}", pool.pool_id, e);
+                    health().record_error(EXCHANGE, &e, false);
+                    failures += 1;
+                }
+            }
+        }
+        health().set_connected(EXCHANGE, failures < cfg.pools.len());
+        if !ps.is_empty() {
+            publish(EXCHANGE, ps, &mut buffer, &prices);
+        }
+    }
+}
+
+/// `GET /osmosis/poolmanager/v1beta1/pools/{id}/prices`: token1 per token0
+/// in base denom units, scaled here to whole tokens.
+async fn spot_price(
+    http: &reqwest::Client,
+    lcd: &str,
+    pool: &OsmosisPoolSpec,
+) -> Result<f64, String> {
+    let url = format!(
+        "{}/osmosis/poolmanager/v1beta1/pools/{}/prices",
+        lcd.trim_end_matches('/'),
+        pool.pool_id
+    );
+    let reply: SpotPrice = http
+        .get(url)
+        .query(&[
+            ("base_asset_denom", pool.denom0.as_str()),
+            ("quote_asset_denom", pool.denom1.as_str()),
+        ])
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+    let raw: f64 = reply
+        .spot_price
+        .parse()
+        .map_err(|_| format!("bad spot_price {}", reply.spot_price))?;
+    let price = raw * 10f64.powi(pool.decimals0 as i32 - pool.decimals1 as i32);
+    if price.is_finite() && price > 0.0 {
+        Ok(price)
+    } else {
+        Err(format!("no price for pool {}", pool.pool_id))
+    }
+}
@@ -0,0 +1,22 @@
+use super::evm_pools::{decimals_scale, run_pool_poller, PoolReader};
+use crate::config::{DexPoolsConfig, PoolSpec};
+use crate::evm::{uint_f64, word};
+use crate::store::SharedPrices;
+
+/// Long-running PancakeSwap (v2 pairs on BSC) worker: reads `getReserves()`
+/// of every configured pair over JSON-RPC each poll interval.
+pub async fn run_pancakeswap(cfg: DexPoolsConfig, prices: SharedPrices) {
+    let reader = PoolReader {
+        exchange: "pancakeswap",
+        calldata: |_| "0x0902f1ac".to_string(),
+        price: reserves_price,
+    };
+    run_pool_poller(reader, cfg, prices).await;
+}
+
+/// Mid price `reserve1 / reserve0`; an empty pair has none.
+fn reserves_price(data: &[u8], pool: &PoolSpec) -> Option<f64> {
+    let reserve0 = uint_f64(word(data, 0)?);
+    let reserve1 = uint_f64(word(data, 1)?);
+    (reserve0 > 0.0).then(|| reserve1 / reserve0 * decimals_scale(pool))
+}
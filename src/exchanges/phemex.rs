@@ -0,0 +1,154 @@
+use super::{Parsed, VenueError};
+use crate::coverage::coverage;
+use crate::rest;
+use crate::rest::phemex::Product;
+use crate::store::SharedPrices;
+use crate::ws_manager::{run_connector, ExchangeConnector, Heartbeat};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tokio::time::Duration;
+use tracing::{info, warn};
+
+const EXCHANGE: &str = "phemex";
+const WS_URL: &str = "wss://ws.phemex.com";
+
+/// Long-running Phemex spot worker on `spot_market24h`, which pushes every
+/// spot symbol's 24h ticker. Prices arrive as scaled integers and are
+/// divided by each product's scale from `/public/products`.
+pub async fn run_phemex_ws(prices: SharedPrices) {
+    run_connector(Phemex::default(), prices).await;
+}
+
+#[derive(Default)]
+struct Phemex {
+    products: HashMap<String, Product>,
+}
+
+impl ExchangeConnector for Phemex {
+    fn name(&self) -> &'static str {
+        EXCHANGE
+    }
+
+    async fn connect(&mut self) -> Result<String, String> {
+        match rest::phemex::products().await {
+            Ok(list) => {
+                self.products = list
+                    .into_iter()
+                    .filter(|p| p.listed)
+                    .map(|p| (p.symbol.clone(), p))
+                    .collect();
+                coverage().set_listed(EXCHANGE, self.products.len());
+            }
+            Err(e) if self.products.is_empty() => {
+                return Err(format!("products fetch failed: {}", e));
+            }
+            Err(e) => warn!(
+                "phemex products fetch failed: {} (keeping {} known scales)",
+                e,
+                self.products.len()
+            ),
+        }
+        info!("phemex: {} spot products", self.products.len());
+        Ok(WS_URL.to_string())
+    }
+
+    fn subscribe(&self) -> Vec<String> {
+        vec![subscribe_message()]
+    }
+
+    fn keepalive(&self) -> Heartbeat {
+        Heartbeat::Text {
+            interval: Duration::from_secs(20),
+            message: ping_message,
+        }
+    }
+
+    fn parse_message(&mut self, txt: &str) -> Parsed {
+        parse_message(txt, &self.products)
+    }
+}
+
+/// Phemex closes connections without a ping for 30s.
+fn ping_message() -> String {
+    json!({ "id": 0, "method": "server.ping", "params": [] }).to_string()
+}
+
+fn subscribe_message() -> String {
+    json!({ "id": 1, "method": "spot_market24h.subscribe", "params": [] }).to_string()
+}
+
+fn parse_message(txt: &str, products: &HashMap<String, Product>) -> Parsed {
+    let v: Value = match serde_json::from_str(txt) {
+        Ok(v) => v,
+        Err(_) => return Parsed::Prices(Vec::new()),
+    };
+    if let Some(err) = v.get("error").filter(|e| !e.is_null()) {
+        let msg = err
+            .get("message")
+            .and_then(|m| m.as_str())
+            .unwrap_or("error");
+        return Parsed::Error(VenueError::new(msg));
+    }
+    let Some(t) = v.get("spot_market24h") else {
+        return Parsed::Prices(Vec::new());
+    };
+    let Some(symbol) = t.get("symbol").and_then(|s| s.as_str()) else {
+        return Parsed::Prices(Vec::new());
+    };
+    // Without its product the scale is unknown and the integers meaningless.
+    let Some(product) = products.get(symbol) else {
+        coverage().record_dropped(EXCHANGE, symbol, "unparsed");
+        return Parsed::Prices(Vec::new());
+    };
+    product
+        .ticker_price(t)
+        .into_iter()
+        .collect::<Vec<_>>()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn product(
+        symbol: &str,
+        base: &str,
+        quote: &str,
+        price_scale: i32,
+        value_scale: i32,
+    ) -> Product {
+        Product {
+            symbol: symbol.into(),
+            base: base.into(),
+            quote: quote.into(),
+            price_scale,
+            value_scale,
+            listed: true,
+        }
+    }
+
+    #[test]
+    fn integer_fields_are_unscaled_per_product() {
+        let products: HashMap<String, Product> = [
+            product("sBTCUSDT", "BTC", "USDT", 8, 8),
+            product("sETHBTC", "ETH", "BTC", 4, 2),
+        ]
+        .into_iter()
+        .map(|p| (p.symbol.clone(), p))
+        .collect();
+        let btc = r#"{"spot_market24h":{"askEp":6500020000000,"bidEp":6500000000000,"lastEp":6500010000000,"symbol":"sBTCUSDT","timestamp":1700000000123456789,"volumeEv":152037000000}}"#;
+        let p = &parse_message(btc, &products).prices()[0];
+        assert_eq!((p.price, p.volume), (65000.1, 1520.37));
+        assert_eq!((p.bid, p.ask), (Some(65000.0), Some(65000.2)));
+        assert_eq!(p.event_time, 1_700_000_000_123);
+
+        let eth = r#"{"spot_market24h":{"lastEp":500,"symbol":"sETHBTC","timestamp":1700000000123456789,"volumeEv":12345}}"#;
+        let p = &parse_message(eth, &products).prices()[0];
+        assert_eq!((p.price, p.volume), (0.05, 123.45));
+
+        // Without its product the integers can't be read.
+        let unknown = r#"{"spot_market24h":{"lastEp":100000000,"symbol":"sNEWUSDT","volumeEv":0}}"#;
+        assert!(parse_message(unknown, &products).prices().is_empty());
+    }
+}
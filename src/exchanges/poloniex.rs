@@ -0,0 +1,110 @@
+use super::{Parsed, VenueError};
+use crate::coverage::coverage;
+use crate::models::PairPrice;
+use crate::rest::poloniex::Ticker;
+use crate::store::SharedPrices;
+use crate::ws_manager::{run_connector, ExchangeConnector, Heartbeat};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::time::Duration;
+
+const EXCHANGE: &str = "poloniex";
+const WS_URL: &str = "wss://ws.poloniex.com/ws/public";
+
+/// Long-running Poloniex worker on the v3 `ticker` channel for every symbol.
+pub async fn run_poloniex_ws(prices: SharedPrices) {
+    run_connector(Poloniex, prices).await;
+}
+
+struct Poloniex;
+
+impl ExchangeConnector for Poloniex {
+    fn name(&self) -> &'static str {
+        EXCHANGE
+    }
+
+    async fn connect(&mut self) -> Result<String, String> {
+        super::refresh_symbols(EXCHANGE).await;
+        Ok(WS_URL.to_string())
+    }
+
+    fn subscribe(&self) -> Vec<String> {
+        vec![subscribe_message()]
+    }
+
+    fn keepalive(&self) -> Heartbeat {
+        Heartbeat::Text {
+            interval: Duration::from_secs(20),
+            message: ping_message,
+        }
+    }
+
+    fn parse_message(&mut self, txt: &str) -> Parsed {
+        parse_message(txt)
+    }
+}
+
+/// Poloniex closes connections that send nothing for 30s.
+fn ping_message() -> String {
+    json!({ "event": "ping" }).to_string()
+}
+
+fn subscribe_message() -> String {
+    json!({ "event": "subscribe", "channel": ["ticker"], "symbols": ["all"] }).to_string()
+}
+
+#[derive(Deserialize)]
+struct Push {
+    channel: String,
+    #[serde(default)]
+    data: Vec<Ticker>,
+}
+
+fn parse_message(txt: &str) -> Parsed {
+    let v: Value = match serde_json::from_str(txt) {
+        Ok(v) => v,
+        Err(_) => return Parsed::Prices(Vec::new()),
+    };
+    if v.get("event").and_then(|e| e.as_str()) == Some("error") {
+        let msg = v.get("message").and_then(|m| m.as_str()).unwrap_or("error");
+        return Parsed::Error(VenueError::new(msg));
+    }
+    let Ok(push) = serde_json::from_value::<Push>(v) else {
+        return Parsed::Prices(Vec::new());
+    };
+    if push.channel != "ticker" {
+        return Parsed::Prices(Vec::new());
+    }
+    push.data
+        .into_iter()
+        .filter_map(|t| {
+            let symbol = t.symbol.clone();
+            let p = t.into_price();
+            if p.is_none() {
+                coverage().record_dropped(EXCHANGE, &symbol, "unparsed");
+            }
+            p
+        })
+        .collect::<Vec<PairPrice>>()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_symbol_ticker_pushes_are_priced() {
+        let frame = r#"{"channel":"ticker","data":[{"symbol":"BTC_USDT","open":"64000","low":"63800","high":"65500","close":"65000.1","quantity":"10000.5","amount":"650000000","tradeCount":1200,"startTime":1699913600000,"closeTime":1700000000000,"displayName":"BTC/USDT","dailyChange":"0.0156","bid":"65000","bidQuantity":"1","ask":"65000.2","askQuantity":"2","ts":1700000000123,"markPrice":"65000.1"},{"symbol":"ETH_BTC","close":"0.05","quantity":"100","ts":1700000000124}]}"#;
+        let pairs: Vec<String> = parse_message(frame)
+            .prices()
+            .iter()
+            .map(|p| format!("{}/{}", p.base, p.quote))
+            .collect();
+        assert_eq!(pairs, ["BTC/USDT", "ETH/BTC"]);
+        let error = r#"{"event":"error","message":"Subscription failed"}"#;
+        assert!(
+            matches!(parse_message(error), Parsed::Error(e) if e.message == "Subscription failed")
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use super::TickerBuffer;
+use crate::config::config;
+use crate::health::health;
+use crate::models::PairPrice;
+use crate::rest;
+use crate::store::{pair_key, SharedPrices};
+use crate::ws_manager::store_polled;
+use std::collections::HashMap;
+use tokio::time::Duration;
+use tracing::{info, warn};
+
+/// Polls the venue's REST tickers while its stream is down or has been
+/// quiet for `fallback.stale_after_ms`, and stops once frames flow again.
+/// The venue shows `fallback` (and `degraded`) in `/health` meanwhile.
+pub async fn run_rest_fallback(exchange: &'static str, prices: SharedPrices) {
+    let cfg = config().fallback.clone();
+    let mut buffer = TickerBuffer::new(exchange);
+    let mut active = false;
+    // Give the stream its first connect before judging it.
+    tokio::time::sleep(Duration::from_millis(cfg.stale_after_ms)).await;
+    let mut tick = tokio::time::interval(cfg.poll_interval());
+
+    loop {
+        tick.tick().await;
+        if !health().stream_stale(exchange, cfg.stale_after_ms) {
+            if active {
+                info!("{} stream is live again, stopping REST fallback", exchange);
+                health().set_fallback(exchange, false);
+                active = false;
+            }
+            continue;
+        }
+        if !active {
+            warn!("{} stream is down or stale, polling REST tickers", exchange);
+            health().set_fallback(exchange, true);
+            active = true;
+        }
+        match rest::poll_tickers(exchange).await {
+            Ok(mut ps) => {
+                keep_known_volume(exchange, &mut ps, &prices);
+                health().record_poll(exchange);
+                store_polled(exchange, ps, &mut buffer, &prices);
+            }
+            Err(e) => {
+                warn!("{} REST fallback poll failed: {}", exchange, e);
+                health().record_error(exchange, &format!("fallback: {}", e), false);
+            }
+        }
+    }
+}
+
+/// Book-ticker polls carry no volume; reuse what the stream last reported
+/// so `min_volume` doesn't drop every pair.
+fn keep_known_volume(exchange: &str, ps: &mut [PairPrice], prices: &SharedPrices) {
+    if ps.iter().all(|p| p.volume > 0.0) {
+        return;
+    }
+    let known: HashMap<String, f64> = prices
+        .snapshot(exchange)
+        .into_iter()
+        .map(|p| (pair_key(&p.base, &p.quote), p.volume))
+        .collect();
+    for p in ps.iter_mut().filter(|p| p.volume == 0.0) {
+        if let Some(&v) = known.get(&pair_key(&p.base, &p.quote)) {
+            p.volume = v;
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use super::evm_pools::decimals_scale;
+use super::{Parsed, VenueError};
+use crate::config::{config, SolanaPoolKind, SolanaPoolSpec};
+use crate::coverage::coverage;
+use crate::models::PairPrice;
+use crate::store::SharedPrices;
+use crate::utils::now_ms;
+use crate::ws_manager::{run_connector, ExchangeConnector, Heartbeat};
+use base64::engine::general_purpose::STANDARD as B64;
+use base64::Engine;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tracing::error;
+
+const EXCHANGE: &str = "solana-dex";
+
+/// Long-running worker on a Solana RPC WebSocket: one `accountSubscribe`
+/// per configured Raydium CLMM / Orca Whirlpool pool, priced from the
+/// pool's `sqrt_price` on every account update.
+pub async fn run_solana_dex(prices: SharedPrices) {
+    let cfg = &config().solana_dex;
+    if cfg.ws_url.is_empty() || cfg.pools.is_empty() {
+        error!("solana-dex: ws_url and pools must be configured");
+        return;
+    }
+    coverage().set_listed(EXCHANGE, cfg.pools.len());
+    let solana = SolanaDex {
+        pools: &cfg.pools,
+        subscriptions: HashMap::new(),
+    };
+    run_connector(solana, prices).await;
+}
+
+struct SolanaDex {
+    pools: &'static [SolanaPoolSpec],
+    /// Subscription id -> pool index; ids are per connection.
+    subscriptions: HashMap<u64, usize>,
+}
+
+impl ExchangeConnector for SolanaDex {
+    fn name(&self) -> &'static str {
+        EXCHANGE
+    }
+
+    async fn connect(&mut self) -> Result<String, String> {
+        self.subscriptions.clear();
+        Ok(config().solana_dex.ws_url.clone())
+    }
+
+    fn subscribe(&self) -> Vec<String> {
+        self.pools
+            .iter()
+            .enumerate()
+            .map(|(id, p)| subscribe_message(id, &p.pool.address))
+            .collect()
+    }
+
+    fn keepalive(&self) -> Heartbeat {
+        Heartbeat::ServerPing
+    }
+
+    fn parse_message(&mut self, txt: &str) -> Parsed {
+        parse_message(txt, self.pools, &mut self.subscriptions)
+    }
+}
+
+/// The request id is the pool's index in config, so the confirmation can
+/// be tied back to it.
+fn subscribe_message(id: usize, address: &str) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": "accountSubscribe",
+        "params": [address, { "encoding": "base64", "commitment": "confirmed" }],
+    })
+    .to_string()
+}
+
+fn parse_message(
+    txt: &str,
+    pools: &[SolanaPoolSpec],
+    subscriptions: &mut HashMap<u64, usize>,
+) -> Parsed {
+    let v: Value = match serde_json::from_str(txt) {
+        Ok(v) => v,
+        Err(_) => return Parsed::Prices(Vec::new()),
+    };
+    if let Some(err) = v.get("error") {
+        let msg = err
+            .get("message")
+            .and_then(|m| m.as_str())
+            .unwrap_or("error");
+        return Parsed::Error(VenueError::new(msg));
+    }
+    // `{"id": 3, "result": 23784}` confirms pool 3 as subscription 23784.
+    if let (Some(id), Some(sub)) = (
+        v.get("id").and_then(Value::as_u64),
+        v.get("result").and_then(Value::as_u64),
+    ) {
+        subscriptions.insert(sub, id as usize);
+        return Parsed::Prices(Vec::new());
+    }
+    if v.get("method").and_then(|m| m.as_str()) != Some("accountNotification") {
+        return Parsed::Prices(Vec::new());
+    }
+    let Some(params) = v.get("params") else {
+        return Parsed::Prices(Vec::new());
+    };
+    let Some(spec) = params
+        .get("subscription")
+        .and_then(Value::as_u64)
+        .and_then(|sub| subscriptions.get(&sub))
+        .and_then(|idx| pools.get(*idx))
+    else {
+        return Parsed::Prices(Vec::new());
+    };
+    let price = params
+        .pointer("/result/value/data/0")
+        .and_then(|d| d.as_str())
+        .and_then(|d| B64.decode(d).ok())
+        .and_then(|data| pool_price(&data, spec));
+    let Some(price) = price else {
+        coverage().record_dropped(EXCHANGE, &spec.pool.address, "unparsed");
+        return Parsed::Prices(Vec::new());
+    };
+    vec![PairPrice {
+        base: spec.pool.token0.to_uppercase(),
+        quote: spec.pool.token1.to_uppercase(),
+        price,
+        is_spot: true,
+        volume: 0.0,
+        event_time: now_ms(),
+        bid: None,
+        ask: None,
+    }]
+    .into()
+}
+
+/// Price of token0 (Raydium `token_mint_0`, Orca `token_mint_a`) in token1
+/// from the Q64.64 `sqrt_price` stored in the pool account.
+fn pool_price(data: &[u8], spec: &SolanaPoolSpec) -> Option<f64> {
+    let offset = match spec.kind {
+        SolanaPoolKind::RaydiumClmm => 253,
+        SolanaPoolKind::OrcaWhirlpool => 65,
+    };
+    let bytes: [u8; 16] = data.get(offset..offset + 16)?.try_into().ok()?;
+    let ratio = u128::from_le_bytes(bytes) as f64 / 2f64.powi(64);
+    let price = ratio * ratio * decimals_scale(&spec.pool);
+    (price.is_finite() && price > 0.0).then_some(price)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pools_are_priced_once_their_subscription_is_confirmed() {
+        let pools: Vec<SolanaPoolSpec> = vec![serde_json::from_value(json!({
+            "kind": "orca-whirlpool",
+            "address": "Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE",
+            "token0": "sol",
+            "token1": "usdc",
+            "decimals0": 9,
+            "decimals1": 6,
+        }))
+        .unwrap()];
+        let mut subscriptions = HashMap::new();
+        // sqrt_price 0.5 in Q64.64: 0.25 per raw unit, 250 after decimals.
+        let mut account = vec![0u8; 653];
+        account[65..81].copy_from_slice(&(1u128 << 63).to_le_bytes());
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "accountNotification",
+            "params": {
+                "result": {
+                    "context": { "slot": 5199307 },
+                    "value": { "data": [B64.encode(&account), "base64"], "space": 653 }
+                },
+                "subscription": 23784
+            }
+        })
+        .to_string();
+        assert!(parse_message(&notification, &pools, &mut subscriptions)
+            .prices()
+            .is_empty());
+
+        let confirm = r#"{"jsonrpc":"2.0","result":23784,"id":0}"#;
+        assert!(parse_message(confirm, &pools, &mut subscriptions)
+            .prices()
+            .is_empty());
+        let prices = parse_message(&notification, &pools, &mut subscriptions).prices();
+        assert_eq!(
+            (prices[0].base.as_str(), prices[0].quote.as_str()),
+            ("SOL", "USDC")
+        );
+        assert!((prices[0].price - 250.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,105 @@
+use super::mock::{run_local_ws, serve, ticker};
+use crate::config::SyntheticConfig;
+use crate::store::SharedPrices;
+use crate::tasks;
+use crate::utils::now_ms;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::json;
+use std::collections::HashMap;
+use tokio::net::TcpListener;
+use tokio::sync::broadcast;
+use tokio::time::Duration;
+use tracing::{error, info};
+
+pub const EXCHANGE: &str = "synthetic";
+
+/// Start the synthetic load generator (WS server) and a worker ingesting
+/// it, for soak-testing the pipeline at configurable message rates.
+pub async fn run_synthetic(cfg: SyntheticConfig, prices: SharedPrices) {
+    let listener = match TcpListener::bind(&cfg.bind).await {
+        Ok(l) => l,
+        Err(e) => {
+            error!("synthetic exchange: cannot bind {}: {}", cfg.bind, e);
+            return;
+        }
+    };
+    info!(
+        "synthetic exchange: {} pairs, {} updates/s on ws://{}",
+        cfg.pairs, cfg.updates_per_sec, cfg.bind
+    );
+
+    let (tx, _) = broadcast::channel::<String>(256);
+    tasks::spawn("synthetic:generate", generate(cfg.clone(), tx.clone()));
+    tasks::spawn("synthetic:serve", serve(listener, tx));
+    run_local_ws(EXCHANGE, format!("ws://{}", cfg.bind), prices).await;
+}
+
+/// Every asset is listed against every quote, and the quotes against each
+/// other (sent every frame). Fair prices random-walk and each frame carries
+/// `updates_per_sec * frame_ms / 1000` random pairs. At `arb_per_sec` an
+/// asset's price against a non-primary quote is skewed by `arb_pct` for
+/// `arb_hold_ms`, opening a cycle.
+async fn generate(cfg: SyntheticConfig, tx: broadcast::Sender<String>) {
+    let quotes = cfg.quotes.len().max(1);
+    let assets = cfg.pairs.div_ceil(quotes).max(2);
+    let mut rng = StdRng::from_entropy();
+    let mut fair_assets: Vec<f64> = (0..assets).map(|_| rng.gen_range(0.01..1000.0)).collect();
+    let mut fair_quotes: Vec<f64> = (0..quotes)
+        .map(|i| {
+            if i == 0 {
+                1.0
+            } else {
+                rng.gen_range(100.0..50_000.0)
+            }
+        })
+        .collect();
+    let quote_name = |q: usize| cfg.quotes.get(q).cloned().unwrap_or_else(|| "USDT".into());
+
+    let frame_ms = cfg.frame_ms.max(1);
+    let per_frame = (cfg.updates_per_sec * frame_ms / 1000).max(1) as usize;
+    let arb_chance = (cfg.arb_per_sec * frame_ms as f64 / 1000.0).clamp(0.0, 1.0);
+    let mut timer = tokio::time::interval(Duration::from_millis(frame_ms));
+    // (asset, quote) -> skewed until (ms)
+    let mut skews: HashMap<(usize, usize), u64> = HashMap::new();
+
+    loop {
+        timer.tick().await;
+        let step = cfg.volatility_pct / 100.0;
+        for p in fair_assets.iter_mut().chain(fair_quotes.iter_mut().skip(1)) {
+            *p *= 1.0 + rng.gen_range(-step..=step);
+        }
+
+        let ts = now_ms();
+        skews.retain(|_, until| *until > ts);
+        let mut picks: Vec<(usize, usize)> = (0..per_frame)
+            .map(|_| (rng.gen_range(0..assets), rng.gen_range(0..quotes)))
+            .collect();
+        if quotes > 1 && rng.gen_bool(arb_chance) {
+            let pick = (rng.gen_range(0..assets), rng.gen_range(1..quotes));
+            skews.insert(pick, ts + cfg.arb_hold_ms);
+            picks.push(pick);
+        }
+
+        let mut data = Vec::with_capacity(picks.len() + quotes * quotes);
+        for (a, q) in picks {
+            let skew = if skews.contains_key(&(a, q)) {
+                1.0 + cfg.arb_pct / 100.0
+            } else {
+                1.0
+            };
+            let price = fair_assets[a] / fair_quotes[q] * skew;
+            data.push(ticker(&format!("S{}", a), &quote_name(q), price, ts));
+        }
+        // Quote crosses (BTC/USDT, ETH/BTC, ...) close the cycles.
+        for q in 1..quotes {
+            for base in 0..q {
+                let price = fair_quotes[q] / fair_quotes[base];
+                data.push(ticker(&quote_name(q), &quote_name(base), price, ts));
+            }
+        }
+
+        let frame = json!({ "type": "tickers", "data": data }).to_string();
+        let _ = tx.send(frame);
+    }
+}
@@ -0,0 +1,21 @@
+use super::evm_pools::{decimals_scale, run_pool_poller, PoolReader};
+use crate::config::{DexPoolsConfig, PoolSpec};
+use crate::evm::{uint_f64, word};
+use crate::store::SharedPrices;
+
+/// Long-running Uniswap v3 worker: reads `slot0()` of every configured pool
+/// over JSON-RPC each poll interval.
+pub async fn run_uniswap(cfg: DexPoolsConfig, prices: SharedPrices) {
+    let reader = PoolReader {
+        exchange: "uniswap",
+        calldata: |_| "0x3850c7bd".to_string(),
+        price: slot0_price,
+    };
+    run_pool_poller(reader, cfg, prices).await;
+}
+
+/// `(sqrtPriceX96 / 2^96)^2` from the first `slot0` word.
+fn slot0_price(data: &[u8], pool: &PoolSpec) -> Option<f64> {
+    let ratio = uint_f64(word(data, 0)?) / 2f64.powi(96);
+    Some(ratio * ratio * decimals_scale(pool))
+}
@@ -0,0 +1,142 @@
+use super::{parse_f64, Parsed, VenueError};
+use crate::coverage::coverage;
+use crate::models::PairPrice;
+use crate::rest;
+use crate::rest::whitebit::split_market;
+use crate::store::SharedPrices;
+use crate::ws_manager::{run_connector, ExchangeConnector, Heartbeat, Subscriptions};
+use serde_json::{json, Value};
+use tokio::time::Duration;
+use tracing::{info, warn};
+
+const EXCHANGE: &str = "whitebit";
+const WS_URL: &str = "wss://api.whitebit.com/ws";
+/// Each `market_subscribe` replaces the previous one, so every market has to
+/// go in one request.
+const SUBSCRIBE_CHUNK: usize = 5000;
+
+/// Long-running WhiteBIT worker on the `market` (24h stats) channel for every
+/// spot market with trading enabled.
+pub async fn run_whitebit_ws(prices: SharedPrices) {
+    let subs = Subscriptions::new(SUBSCRIBE_CHUNK, subscribe_message);
+    run_connector(Whitebit { subs }, prices).await;
+}
+
+struct Whitebit {
+    subs: Subscriptions,
+}
+
+impl ExchangeConnector for Whitebit {
+    fn name(&self) -> &'static str {
+        EXCHANGE
+    }
+
+    async fn connect(&mut self) -> Result<String, String> {
+        match rest::whitebit::instruments().await {
+            Ok(list) => {
+                let enabled: Vec<String> = list
+                    .into_iter()
+                    .filter(|i| i.trading)
+                    .map(|i| i.symbol)
+                    .collect();
+                coverage().set_listed(EXCHANGE, enabled.len());
+                self.subs.replace(enabled);
+            }
+            Err(e) if self.subs.len() == 0 => {
+                return Err(format!("markets fetch failed: {}", e));
+            }
+            Err(e) => warn!(
+                "whitebit markets fetch failed: {} (restoring {} known subscriptions)",
+                e,
+                self.subs.len()
+            ),
+        }
+        info!("whitebit: subscribing to {} markets", self.subs.len());
+        Ok(WS_URL.to_string())
+    }
+
+    fn subscribe(&self) -> Vec<String> {
+        self.subs.messages()
+    }
+
+    fn keepalive(&self) -> Heartbeat {
+        Heartbeat::Text {
+            interval: Duration::from_secs(30),
+            message: ping_message,
+        }
+    }
+
+    fn parse_message(&mut self, txt: &str) -> Parsed {
+        parse_message(txt)
+    }
+}
+
+/// Connections without a request for 60s are closed.
+fn ping_message() -> String {
+    json!({ "id": 0, "method": "ping", "params": [] }).to_string()
+}
+
+fn subscribe_message(markets: &[String]) -> String {
+    json!({ "id": 1, "method": "market_subscribe", "params": markets }).to_string()
+}
+
+fn parse_message(txt: &str) -> Parsed {
+    let v: Value = match serde_json::from_str(txt) {
+        Ok(v) => v,
+        Err(_) => return Parsed::Prices(Vec::new()),
+    };
+    if let Some(err) = v.get("error").filter(|e| !e.is_null()) {
+        let msg = err
+            .get("message")
+            .and_then(|m| m.as_str())
+            .unwrap_or("error");
+        return Parsed::Error(VenueError::new(msg));
+    }
+    if v.get("method").and_then(|m| m.as_str()) != Some("market_update") {
+        return Parsed::Prices(Vec::new());
+    }
+    v.get("params")
+        .and_then(parse_market_update)
+        .into_iter()
+        .collect::<Vec<_>>()
+        .into()
+}
+
+/// `"params": ["BTC_USDT", {"last":"..","volume":"..","deal":".."}]`; volume
+/// is in the base asset, deal in the quote.
+fn parse_market_update(params: &Value) -> Option<PairPrice> {
+    let market = params.get(0)?.as_str()?;
+    let stats = params.get(1)?;
+    let Some((base, quote)) = split_market(market) else {
+        coverage().record_dropped(EXCHANGE, market, "unparsed");
+        return None;
+    };
+    Some(PairPrice {
+        base,
+        quote,
+        price: parse_f64(stats.get("last")).filter(|p| *p > 0.0)?,
+        is_spot: true,
+        volume: parse_f64(stats.get("volume")).unwrap_or(0.0),
+        event_time: 0,
+        bid: None,
+        ask: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn market_updates_split_underscores_and_take_base_volume() {
+        let frame = r#"{"id":null,"method":"market_update","params":["BTC_USDT",{"period":86400,"last":"65000.1","open":"64000","close":"65000.1","high":"65500","low":"63800","volume":"1520.37","deal":"98824050"}]}"#;
+        let prices = parse_message(frame).prices();
+        assert_eq!(prices.len(), 1);
+        let p = &prices[0];
+        assert_eq!((p.base.as_str(), p.quote.as_str()), ("BTC", "USDT"));
+        assert_eq!((p.price, p.volume), (65000.1, 1520.37));
+        // Markets that haven't traded report a zero last price.
+        let untraded = frame.replace(r#""last":"65000.1""#, r#""last":"0""#);
+        assert!(parse_message(&untraded).prices().is_empty());
+    }
+}
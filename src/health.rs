@@ -0,0 +1,200 @@
+use crate::utils::now_ms;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::RwLock;
+use utoipa::ToSchema;
+
+/// How long a system/maintenance notice keeps an exchange marked degraded.
+const NOTICE_TTL_MS: u64 = 15 * 60 * 1000;
+
+/// Event-time lag (local receive time minus exchange timestamp) outside this
+/// range means the venue's clock, or ours, can't be trusted for staleness.
+const MIN_SANE_LAG_MS: f64 = -250.0;
+const MAX_SANE_LAG_MS: f64 = 5_000.0;
+/// Smoothing factor for the event-lag moving average.
+const LAG_EMA_ALPHA: f64 = 0.05;
+
+/// Errors kept for `recent_errors`, across all exchanges.
+const RECENT_ERRORS: usize = 100;
+
+/// Process-wide connector health, written by the workers and read by `/health`.
+static HEALTH: Lazy<HealthRegistry> = Lazy::new(HealthRegistry::default);
+
+pub fn health() -> &'static HealthRegistry {
+    &HEALTH
+}
+
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct ExchangeHealth {
+    pub connected: bool,
+    pub last_message_ms: u64,
+    pub messages_total: u64,
+    pub last_error: Option<String>,
+    pub last_error_ms: u64,
+    pub errors_total: u64,
+    pub rate_limited_total: u64,
+    pub resubscribes_total: u64,
+    pub reconnects_total: u64,
+    /// Latest system/maintenance notice from the venue.
+    pub notice: Option<String>,
+    pub notice_ms: u64,
+    /// Prices currently come from REST polling because the stream is down
+    /// or stale.
+    pub fallback: bool,
+    pub fallback_since_ms: u64,
+    pub fallback_polls_total: u64,
+    /// Disconnected, on the REST fallback, or a notice was received within
+    /// the last 15 minutes.
+    pub degraded: bool,
+    /// Receive time minus exchange event time for the freshest update of the
+    /// latest frame; mixes network latency with clock skew (negative = the
+    /// venue's clock runs ahead of ours).
+    pub last_event_lag_ms: Option<i64>,
+    /// Moving average of `last_event_lag_ms`.
+    pub event_lag_ema_ms: Option<f64>,
+    /// Average lag is outside the sane range, so event-time-based staleness
+    /// for this venue is unreliable.
+    pub clock_suspect: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HealthReport {
+    pub status: &'static str,
+    pub exchanges: BTreeMap<String, ExchangeHealth>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorEvent {
+    pub at_ms: u64,
+    pub exchange: String,
+    pub message: String,
+}
+
+#[derive(Debug, Default)]
+pub struct HealthRegistry {
+    inner: RwLock<BTreeMap<String, ExchangeHealth>>,
+    errors: RwLock<VecDeque<ErrorEvent>>,
+}
+
+impl HealthRegistry {
+    fn update(&self, exchange: &str, f: impl FnOnce(&mut ExchangeHealth)) {
+        let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        f(guard.entry(exchange.to_string()).or_default());
+    }
+
+    pub fn set_connected(&self, exchange: &str, connected: bool) {
+        self.update(exchange, |h| {
+            if connected && h.last_message_ms > 0 {
+                h.reconnects_total += 1;
+            }
+            h.connected = connected;
+        });
+    }
+
+    pub fn record_message(&self, exchange: &str) {
+        self.update(exchange, |h| {
+            h.last_message_ms = now_ms();
+            h.messages_total += 1;
+        });
+    }
+
+    pub fn record_error(&self, exchange: &str, message: &str, rate_limited: bool) {
+        {
+            let mut errors = self.errors.write().unwrap_or_else(|e| e.into_inner());
+            if errors.len() == RECENT_ERRORS {
+                errors.pop_front();
+            }
+            errors.push_back(ErrorEvent {
+                at_ms: now_ms(),
+                exchange: exchange.to_string(),
+                message: message.to_string(),
+            });
+        }
+        self.update(exchange, |h| {
+            h.last_error = Some(message.to_string());
+            h.last_error_ms = now_ms();
+            h.errors_total += 1;
+            if rate_limited {
+                h.rate_limited_total += 1;
+            }
+        });
+    }
+
+    /// Record the event lag of one frame (see `ExchangeHealth::last_event_lag_ms`).
+    pub fn record_event_lag(&self, exchange: &str, lag_ms: i64) {
+        self.update(exchange, |h| {
+            h.last_event_lag_ms = Some(lag_ms);
+            h.event_lag_ema_ms = Some(match h.event_lag_ema_ms {
+                Some(ema) => ema + LAG_EMA_ALPHA * (lag_ms as f64 - ema),
+                None => lag_ms as f64,
+            });
+        });
+    }
+
+    pub fn record_notice(&self, exchange: &str, message: &str) {
+        self.update(exchange, |h| {
+            h.notice = Some(message.to_string());
+            h.notice_ms = now_ms();
+        });
+    }
+
+    /// Whether the stream is down or has delivered nothing for `quiet_ms`.
+    pub fn stream_stale(&self, exchange: &str, quiet_ms: u64) -> bool {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        guard
+            .get(exchange)
+            .is_none_or(|h| !h.connected || now_ms().saturating_sub(h.last_message_ms) > quiet_ms)
+    }
+
+    pub fn set_fallback(&self, exchange: &str, on: bool) {
+        self.update(exchange, |h| {
+            if on && !h.fallback {
+                h.fallback_since_ms = now_ms();
+            }
+            h.fallback = on;
+        });
+    }
+
+    pub fn record_poll(&self, exchange: &str) {
+        self.update(exchange, |h| h.fallback_polls_total += 1);
+    }
+
+    pub fn record_resubscribe(&self, exchange: &str) {
+        self.update(exchange, |h| h.resubscribes_total += 1);
+    }
+
+    /// The latest connector errors, oldest first.
+    pub fn recent_errors(&self) -> Vec<ErrorEvent> {
+        let guard = self.errors.read().unwrap_or_else(|e| e.into_inner());
+        guard.iter().cloned().collect()
+    }
+
+    /// Exchanges whose worker currently has an open session.
+    pub fn connected_exchanges(&self) -> Vec<String> {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        guard
+            .iter()
+            .filter(|(_, h)| h.connected)
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    pub fn report(&self) -> HealthReport {
+        let mut exchanges = self.inner.read().unwrap_or_else(|e| e.into_inner()).clone();
+        let now = now_ms();
+        for h in exchanges.values_mut() {
+            let recent_notice =
+                h.notice.is_some() && now.saturating_sub(h.notice_ms) < NOTICE_TTL_MS;
+            h.degraded = !h.connected || h.fallback || recent_notice;
+            h.clock_suspect = h
+                .event_lag_ema_ms
+                .is_some_and(|ema| !(MIN_SANE_LAG_MS..=MAX_SANE_LAG_MS).contains(&ema));
+        }
+        let any_degraded = exchanges.values().any(|h| h.degraded);
+        HealthReport {
+            status: if any_degraded { "degraded" } else { "ok" },
+            exchanges,
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use crate::config::config;
+use crate::models::TriangularResult;
+use crate::utils::now_ms;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::mpsc;
+use std::sync::RwLock;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+/// Past scans, kept in memory and appended to `history.path` so IDs and
+/// results survive restarts. The file is rewritten down to the retained
+/// scans once it holds twice as many.
+static HISTORY: Lazy<ScanHistory> = Lazy::new(ScanHistory::load);
+
+pub fn history() -> &'static ScanHistory {
+    &HISTORY
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ScanRecord {
+    pub id: u64,
+    pub at_ms: u64,
+    /// The request as received (body or resolved query parameters).
+    pub params: Value,
+    pub results: Vec<TriangularResult>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    records: VecDeque<ScanRecord>,
+    next_id: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct ScanHistory {
+    inner: RwLock<Inner>,
+    /// Lines for the writer thread; `None` without `history.path`.
+    file: Option<mpsc::Sender<String>>,
+}
+
+impl ScanHistory {
+    fn load() -> Self {
+        let cfg = &config().history;
+        let mut inner = Inner {
+            next_id: 1,
+            ..Inner::default()
+        };
+        let mut file = None;
+        if let Some(path) = cfg.path.as_deref() {
+            let mut lines = 0;
+            if let Ok(text) = std::fs::read_to_string(path) {
+                for line in text.lines().filter(|l| !l.trim().is_empty()) {
+                    lines += 1;
+                    match serde_json::from_str::<ScanRecord>(line) {
+                        Ok(rec) => {
+                            inner.next_id = inner.next_id.max(rec.id + 1);
+                            inner.records.push_back(rec);
+                        }
+                        Err(e) => warn!("skipping bad scan history line in {}: {}", path, e),
+                    }
+                }
+                while inner.records.len() > cfg.max_entries {
+                    inner.records.pop_front();
+                }
+                info!("loaded {} past scans from {}", inner.records.len(), path);
+            }
+            let kept = inner
+                .records
+                .iter()
+                .filter_map(|r| serde_json::to_string(r).ok())
+                .collect();
+            let mut writer = Writer {
+                path: path.to_string(),
+                max_entries: cfg.max_entries,
+                kept,
+                lines,
+            };
+            let (tx, rx) = mpsc::channel::<String>();
+            let spawned = std::thread::Builder::new()
+                .name("scan-history".into())
+                .spawn(move || {
+                    if writer.lines > writer.max_entries {
+                        writer.compact();
+                    }
+                    for line in rx {
+                        writer.append(line);
+                    }
+                });
+            match spawned {
+                Ok(_) => file = Some(tx),
+                Err(e) => warn!("scan history writer: {} (not persisting scans)", e),
+            }
+        }
+        Self {
+            inner: RwLock::new(inner),
+            file,
+        }
+    }
+
+    /// Store a finished scan and return its ID.
+    pub fn record(&self, params: Value, results: &[TriangularResult]) -> u64 {
+        let cfg = &config().history;
+        let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        let rec = ScanRecord {
+            id: guard.next_id,
+            at_ms: now_ms(),
+            params,
+            results: results.to_vec(),
+        };
+        guard.next_id += 1;
+
+        if let Some(tx) = &self.file {
+            match serde_json::to_string(&rec) {
+                Ok(line) => {
+                    let _ = tx.send(line);
+                }
+                Err(e) => warn!("cannot persist scan {}: {}", rec.id, e),
+            }
+        }
+
+        let id = rec.id;
+        guard.records.push_back(rec);
+        while guard.records.len() > cfg.max_entries {
+            guard.records.pop_front();
+        }
+        id
+    }
+
+    pub fn get(&self, id: u64) -> Option<ScanRecord> {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        guard.records.iter().find(|r| r.id == id).cloned()
+    }
+
+    /// Scans taken at or after `since_ms`, oldest first, at most `limit`.
+    pub fn since(&self, since_ms: u64, limit: usize) -> Vec<ScanRecord> {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        guard
+            .records
+            .iter()
+            .filter(|r| r.at_ms >= since_ms)
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// The newest `limit` scans, oldest first.
+    pub fn latest(&self, limit: usize) -> Vec<ScanRecord> {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        let skip = guard.records.len().saturating_sub(limit);
+        guard.records.iter().skip(skip).cloned().collect()
+    }
+}
+
+/// Owns `history.path` on its own thread, so handlers never wait on disk.
+struct Writer {
+    path: String,
+    max_entries: usize,
+    /// The newest `max_entries` lines, the content after a compaction.
+    kept: VecDeque<String>,
+    /// Lines currently in the file.
+    lines: usize,
+}
+
+impl Writer {
+    fn append(&mut self, line: String) {
+        let appended = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut f| writeln!(f, "{}", line));
+        match appended {
+            Ok(()) => self.lines += 1,
+            Err(e) => warn!("cannot persist scan to {}: {}", self.path, e),
+        }
+        self.kept.push_back(line);
+        while self.kept.len() > self.max_entries {
+            self.kept.pop_front();
+        }
+        if self.lines > self.max_entries.saturating_mul(2) {
+            self.compact();
+        }
+    }
+
+    /// Rewrite the file with only the retained scans, via a temp file so a
+    /// crash mid-write leaves the old file intact.
+    fn compact(&mut self) {
+        let tmp = format!("{}.tmp", self.path);
+        let mut text = String::new();
+        for line in &self.kept {
+            text.push_str(line);
+            text.push('\n');
+        }
+        match std::fs::write(&tmp, text).and_then(|_| std::fs::rename(&tmp, &self.path)) {
+            Ok(()) => self.lines = self.kept.len(),
+            Err(e) => warn!("cannot compact {}: {}", self.path, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latest_returns_the_newest_scans_oldest_first() {
+        let history = ScanHistory::default();
+        for _ in 0..4 {
+            history.record(Value::Null, &[]);
+        }
+        let ids: Vec<u64> = history.latest(2).iter().map(|r| r.id).collect();
+        assert_eq!(ids, [2, 3]);
+        assert_eq!(history.latest(10).len(), 4);
+    }
+}
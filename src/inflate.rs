@@ -0,0 +1,68 @@
+//! gzip decoding for venues that compress their WS frames (BingX, CoinEx).
+
+use flate2::read::GzDecoder;
+use std::io::Read;
+
+/// Refuse to inflate past this, so a corrupt or hostile frame can't exhaust
+/// memory.
+const MAX_OUTPUT: u64 = 16 << 20;
+
+/// Whether `data` starts with the gzip magic bytes.
+pub fn is_gzip(data: &[u8]) -> bool {
+    data.starts_with(&[0x1f, 0x8b])
+}
+
+/// Decompress one gzip member; `None` if it is malformed or inflates past
+/// `MAX_OUTPUT`.
+pub fn gunzip(data: &[u8]) -> Option<Vec<u8>> {
+    gunzip_limited(data, MAX_OUTPUT)
+}
+
+fn gunzip_limited(data: &[u8], limit: u64) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    // One byte past the limit tells an exact fit from an overflow.
+    GzDecoder::new(data)
+        .take(limit + 1)
+        .read_to_end(&mut out)
+        .ok()?;
+    (out.len() as u64 <= limit).then_some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+        enc.write_all(data).unwrap();
+        enc.finish().unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let frame = br#"{"dataType":"BTC-USDT@ticker","data":{"c":"65000.1"}}"#;
+        let packed = gzip(frame);
+        assert!(is_gzip(&packed));
+        assert_eq!(gunzip(&packed).unwrap(), frame);
+    }
+
+    #[test]
+    fn corrupt_input_is_rejected() {
+        let mut packed = gzip(b"ping");
+        assert!(gunzip(&packed[..packed.len() - 6]).is_none());
+        let last = packed.len() - 1;
+        packed[last] ^= 0xff;
+        assert!(gunzip(&packed).is_none());
+        assert!(gunzip(b"\x1f\x8bnot gzip").is_none());
+    }
+
+    #[test]
+    fn output_is_capped() {
+        let packed = gzip(&vec![b'a'; 4096]);
+        assert!(gunzip_limited(&packed, 4096).is_some());
+        assert!(gunzip_limited(&packed, 4095).is_none());
+    }
+}
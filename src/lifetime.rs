@@ -0,0 +1,201 @@
+//! Cumulative counters that survive restarts, saved to `lifetime.path` and
+//! served by `/stats/lifetime` so long-term dashboards don't reset.
+
+use crate::config::config;
+use crate::health::health;
+use crate::models::TriangularResult;
+use crate::tasks;
+use crate::utils::now_ms;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+use tokio::time::Duration;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+static LIFETIME: Lazy<LifetimeStats> = Lazy::new(LifetimeStats::load);
+
+pub fn lifetime() -> &'static LifetimeStats {
+    &LIFETIME
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct LifetimeCounters {
+    /// When counting started (first run with this file).
+    pub since_ms: u64,
+    pub starts: u64,
+    /// Process uptime summed over all runs.
+    pub uptime_ms: u64,
+    pub scans_total: u64,
+    /// Results with a positive profit after fees, summed over all scans.
+    pub opportunities_total: u64,
+    pub exchanges: BTreeMap<String, ExchangeLifetime>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct ExchangeLifetime {
+    /// Time the connector spent connected.
+    pub connected_ms: u64,
+    pub messages_total: u64,
+    pub reconnects_total: u64,
+    pub errors_total: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LifetimeReport {
+    pub started_ms: u64,
+    #[serde(flatten)]
+    pub counters: LifetimeCounters,
+}
+
+/// Health totals as of the last tick, to add only what changed since.
+#[derive(Debug, Default, Clone, Copy)]
+struct Seen {
+    messages: u64,
+    reconnects: u64,
+    errors: u64,
+}
+
+/// Counts for the current process only.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct RunCounters {
+    pub scans: u64,
+    pub opportunities: u64,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    counters: LifetimeCounters,
+    run: RunCounters,
+    last_tick_ms: u64,
+    seen: BTreeMap<String, Seen>,
+}
+
+#[derive(Debug, Default)]
+pub struct LifetimeStats {
+    inner: RwLock<Inner>,
+    started_ms: u64,
+}
+
+impl LifetimeStats {
+    fn load() -> Self {
+        let now = now_ms();
+        let mut counters = LifetimeCounters::default();
+        if let Some(path) = config().lifetime.path.as_deref() {
+            match std::fs::read_to_string(path) {
+                Ok(text) => match serde_json::from_str(&text) {
+                    Ok(c) => counters = c,
+                    Err(e) => warn!("ignoring bad lifetime stats in {}: {}", path, e),
+                },
+                Err(_) => info!("starting lifetime stats in {}", path),
+            }
+        }
+        if counters.since_ms == 0 {
+            counters.since_ms = now;
+        }
+        counters.starts += 1;
+        Self {
+            inner: RwLock::new(Inner {
+                counters,
+                run: RunCounters::default(),
+                last_tick_ms: now,
+                seen: BTreeMap::new(),
+            }),
+            started_ms: now,
+        }
+    }
+
+    /// Count one finished live scan and its profitable passing results.
+    pub fn record_scan(&self, results: &[TriangularResult]) {
+        let found = results
+            .iter()
+            .filter(|r| r.passes() && r.profit_after > 0.0)
+            .count() as u64;
+        let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        guard.counters.scans_total += 1;
+        guard.counters.opportunities_total += found;
+        guard.run.scans += 1;
+        guard.run.opportunities += found;
+    }
+
+    pub fn run(&self) -> RunCounters {
+        self.inner.read().unwrap_or_else(|e| e.into_inner()).run
+    }
+
+    pub fn started_ms(&self) -> u64 {
+        self.started_ms
+    }
+
+    /// Fold in uptime and connector activity since the last tick.
+    fn tick(&self) {
+        let now = now_ms();
+        let exchanges = health().report().exchanges;
+        let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        let inner = &mut *guard;
+        let elapsed = now.saturating_sub(inner.last_tick_ms);
+        inner.last_tick_ms = now;
+        inner.counters.uptime_ms += elapsed;
+        for (name, h) in exchanges {
+            let seen = inner.seen.entry(name.clone()).or_default();
+            let total = inner.counters.exchanges.entry(name).or_default();
+            if h.connected {
+                total.connected_ms += elapsed;
+            }
+            total.messages_total += h.messages_total.saturating_sub(seen.messages);
+            total.reconnects_total += h.reconnects_total.saturating_sub(seen.reconnects);
+            total.errors_total += h.errors_total.saturating_sub(seen.errors);
+            *seen = Seen {
+                messages: h.messages_total,
+                reconnects: h.reconnects_total,
+                errors: h.errors_total,
+            };
+        }
+    }
+
+    pub fn report(&self) -> LifetimeReport {
+        self.tick();
+        LifetimeReport {
+            started_ms: self.started_ms,
+            counters: self
+                .inner
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .counters
+                .clone(),
+        }
+    }
+
+    /// Write the counters to `lifetime.path` (via a temp file, so a crash
+    /// mid-write keeps the previous copy).
+    pub fn save(&self) {
+        self.tick();
+        let Some(path) = config().lifetime.path.as_deref() else {
+            return;
+        };
+        let json = {
+            let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+            serde_json::to_string_pretty(&guard.counters).unwrap_or_default()
+        };
+        let tmp = format!("{}.tmp", path);
+        if let Err(e) = std::fs::write(&tmp, json).and_then(|_| std::fs::rename(&tmp, path)) {
+            warn!("saving lifetime stats to {}: {}", path, e);
+        }
+    }
+}
+
+/// Save the counters every `lifetime.save_every_ms`.
+pub fn start() {
+    let every = Duration::from_millis(config().lifetime.save_every_ms.max(1_000));
+    Lazy::force(&LIFETIME);
+    tasks::spawn("lifetime", async move {
+        let mut timer = tokio::time::interval(every);
+        timer.tick().await;
+        loop {
+            timer.tick().await;
+            lifetime().save();
+        }
+    });
+}
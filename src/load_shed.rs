@@ -0,0 +1,207 @@
+use crate::config::config;
+use crate::exchanges::Parsed;
+use crate::metrics::metrics;
+use crate::models::PairPrice;
+use crate::store::pair_key;
+use std::collections::HashMap;
+use tokio::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Highest shed level. Each level doubles the flush interval and drops
+/// another quarter of the connector's lowest-volume (or, without volumes,
+/// least recently changed) symbols.
+const MAX_LEVEL: u32 = 3;
+
+/// Consecutive comfortable flushes needed to step one level back down.
+const RECOVER_AFTER: u32 = 20;
+
+/// Per-session cap on price frames ingested per second. Frames are parsed
+/// as they arrive; prices from frames over the cap are held back, keeping
+/// only the latest per symbol, and ingested at the next flush. A burst then
+/// costs at most one buffered update per symbol, and what is flushed is the
+/// newest price seen. Book frames count like any other; replies, notices
+/// and errors are never held, so keepalives are answered on time.
+pub struct RateCap {
+    limit: u32,
+    window_start: Instant,
+    count: u32,
+    held: HashMap<String, PairPrice>,
+}
+
+impl RateCap {
+    /// `limit` of 0 admits everything.
+    pub fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            window_start: Instant::now(),
+            count: 0,
+            held: HashMap::new(),
+        }
+    }
+
+    /// The frame, if it should be ingested now; `None` when its prices were
+    /// held back. Once prices are held, newer frames are held too until the
+    /// flush so a symbol never goes back to an older price.
+    pub fn admit(&mut self, exchange: &str, parsed: Parsed) -> Option<Parsed> {
+        if self.limit == 0 || !matches!(parsed, Parsed::Prices(_) | Parsed::Book(_)) {
+            return Some(parsed);
+        }
+        if self.window_start.elapsed() >= Duration::from_secs(1) {
+            self.window_start = Instant::now();
+            self.count = 0;
+        }
+        if self.held.is_empty() && self.count < self.limit {
+            self.count += 1;
+            return Some(parsed);
+        }
+        if let Parsed::Prices(ps) | Parsed::Book(ps) = parsed {
+            let mut replaced = 0;
+            for p in ps {
+                if self.held.insert(pair_key(&p.base, &p.quote), p).is_some() {
+                    replaced += 1;
+                }
+            }
+            if replaced > 0 {
+                metrics().record_coalesced(exchange, replaced);
+            }
+        }
+        None
+    }
+
+    /// The latest held price of each symbol since the last call.
+    pub fn take_held(&mut self) -> Vec<PairPrice> {
+        self.held.drain().map(|(_, p)| p).collect()
+    }
+}
+
+/// Per-session load shedding: watches how long each flush holds the price
+/// store and how many symbols were waiting, and backs off when either
+/// crosses the configured thresholds instead of letting latency grow.
+pub struct LoadShedder {
+    base: Duration,
+    level: u32,
+    calm_streak: u32,
+}
+
+impl LoadShedder {
+    pub fn new(base: Duration) -> Self {
+        Self {
+            base,
+            level: 0,
+            calm_streak: 0,
+        }
+    }
+
+    pub fn flush_interval(&self) -> Duration {
+        self.base * 2u32.pow(self.level)
+    }
+
+    /// Share of symbols to stop tracking at the current level.
+    pub fn drop_fraction(&self) -> f64 {
+        self.level as f64 * 0.25
+    }
+
+    /// Feed one flush's store write time and backlog; returns true when the
+    /// shed level changed and the caller should re-apply the interval and
+    /// symbol drop.
+    pub fn observe(&mut self, exchange: &str, write: Duration, backlog: usize) -> bool {
+        let cfg = &config().load_shed;
+        if !cfg.enabled {
+            return false;
+        }
+        let write_ms = write.as_millis() as u64;
+        let over = write_ms > cfg.max_write_ms || backlog > cfg.max_backlog;
+        let calm = write_ms * 2 <= cfg.max_write_ms && backlog * 2 <= cfg.max_backlog;
+
+        if over {
+            self.calm_streak = 0;
+            if self.level == MAX_LEVEL {
+                return false;
+            }
+            self.level += 1;
+            warn!(
+                "{} load shedding: store write {}ms, backlog {} -> level {} (flush every {:?}, dropping lowest {:.0}% of symbols)",
+                exchange,
+                write_ms,
+                backlog,
+                self.level,
+                self.flush_interval(),
+                self.drop_fraction() * 100.0
+            );
+        } else {
+            self.calm_streak = if calm { self.calm_streak + 1 } else { 0 };
+            if self.level == 0 || self.calm_streak < RECOVER_AFTER {
+                return false;
+            }
+            self.calm_streak = 0;
+            self.level -= 1;
+            info!(
+                "{} load shedding eased to level {} (flush every {:?})",
+                exchange,
+                self.level,
+                self.flush_interval()
+            );
+        }
+        metrics().record_shed_level(exchange, self.level);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prices(updates: &[(&str, f64)]) -> Parsed {
+        Parsed::Prices(
+            updates
+                .iter()
+                .map(|&(base, price)| PairPrice {
+                    base: base.into(),
+                    quote: "USDT".into(),
+                    price,
+                    is_spot: true,
+                    volume: 0.0,
+                    event_time: 0,
+                    bid: None,
+                    ask: None,
+                })
+                .collect(),
+        )
+    }
+
+    fn held(cap: &mut RateCap) -> Vec<(String, f64)> {
+        let mut held: Vec<_> = cap
+            .take_held()
+            .into_iter()
+            .map(|p| (p.base, p.price))
+            .collect();
+        held.sort_by(|a, b| a.0.cmp(&b.0));
+        held
+    }
+
+    #[test]
+    fn prices_over_the_cap_keep_the_latest_per_symbol() {
+        let mut cap = RateCap::new(1);
+        assert!(cap.admit("test", prices(&[("BTC", 1.0)])).is_some());
+        assert!(cap
+            .admit("test", prices(&[("BTC", 2.0), ("ETH", 1.0)]))
+            .is_none());
+        assert!(cap.admit("test", prices(&[("BTC", 3.0)])).is_none());
+        // Replies skip the cap.
+        assert!(matches!(
+            cap.admit("test", Parsed::Reply("pong".into())),
+            Some(Parsed::Reply(_))
+        ));
+        assert_eq!(held(&mut cap), [("BTC".into(), 3.0), ("ETH".into(), 1.0)]);
+        assert!(held(&mut cap).is_empty());
+    }
+
+    #[test]
+    fn zero_limit_admits_everything() {
+        let mut cap = RateCap::new(0);
+        for n in 0..10 {
+            assert!(cap.admit("test", prices(&[("BTC", n as f64)])).is_some());
+        }
+        assert!(held(&mut cap).is_empty());
+    }
+}
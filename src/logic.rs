@@ -1,32 +1,441 @@
-use crate::models::{PairPrice, TriangularResult};
+use crate::config::{AnomalyConfig, ConfidenceConfig, SimulationConfig, VolatilityConfig};
+use crate::models::{PairPrice, ProfitDistribution, ScanParams, SortKey, TriangularResult};
+use crate::store::{pair_key, Freshness};
+use crate::symbols::symbols;
+use crate::utils::now_ms;
+use rand::Rng;
 use std::collections::{HashMap, HashSet};
 
-/// Find triangular arbitrage opportunities.
-pub fn find_triangular_opportunities(
-    _exchange: &str,
-    pairs: Vec<PairPrice>,
-    min_profit_after: f64,
-    fee_per_leg_pct: f64,   // now configurable
-    neighbor_limit: usize,  // now configurable
-) -> Vec<TriangularResult> {
-    let mut adj: HashMap<String, HashMap<String, f64>> = HashMap::new();
-    let mut vol_map: HashMap<String, HashMap<String, f64>> = HashMap::new();
+/// "A/B" -> "B/A", for looking up a leg quoted the other way round.
+pub fn reverse_pair(leg: &str) -> String {
+    leg.split_once('/')
+        .map(|(a, b)| format!("{}/{}", b, a))
+        .unwrap_or_default()
+}
+
+/// Flag suspicious results and move them below the clean ones, keeping the
+/// order within each group. `recent_pairs` holds "BASE/QUOTE" keys of
+/// recently listed symbols.
+pub fn flag_anomalies(
+    results: &mut Vec<TriangularResult>,
+    rules: &AnomalyConfig,
+    recent_pairs: &HashSet<String>,
+) {
+    for r in results.iter_mut() {
+        if r.profit_after > rules.max_profit_pct {
+            r.flags.push("profit_above_ceiling".to_string());
+        }
+        let reported = r.liquidity_legs.iter().any(|v| *v > 0.0);
+        if reported && r.liquidity_legs.iter().any(|v| *v < rules.min_leg_volume) {
+            r.flags.push("near_zero_volume".to_string());
+        }
+        for leg in &r.pairs {
+            let reversed = reverse_pair(leg);
+            if recent_pairs.contains(leg) || recent_pairs.contains(&reversed) {
+                r.flags.push(format!("new_listing:{}", leg));
+            }
+        }
+    }
+    let (clean, flagged): (Vec<_>, Vec<_>) = results.drain(..).partition(|r| r.flags.is_empty());
+    results.extend(clean);
+    results.extend(flagged);
+}
+
+/// Flag results with a leg still priced from a checkpoint (`restored_pairs`
+/// holds "BASE/QUOTE" keys) rather than live data. Run before
+/// `flag_anomalies` so flagged results are ranked down.
+pub fn flag_restored(results: &mut [TriangularResult], restored_pairs: &HashSet<String>) {
+    if restored_pairs.is_empty() {
+        return;
+    }
+    for r in results.iter_mut() {
+        for leg in &r.pairs {
+            if restored_pairs.contains(leg) || restored_pairs.contains(&reverse_pair(leg)) {
+                r.flags.push(format!("restored:{}", leg));
+            }
+        }
+    }
+}
+
+/// What scoring knows about the data behind one leg.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LegQuality {
+    pub age_ms: Option<u64>,
+    pub interval_ms: Option<f64>,
+    pub spread_pct: Option<f64>,
+    /// Price and 24h base volume of the stored "BASE/QUOTE" pair.
+    pub price: f64,
+    pub volume: f64,
+}
+
+/// Leg quality per upper-case "BASE/QUOTE" of the scanned pairs, matched to
+/// the store under the same key. Age uses the exchange timestamp, falling
+/// back to the store's last write; update frequency is only known for live
+/// data.
+pub fn leg_qualities(
+    pairs: &[PairPrice],
+    freshness: &HashMap<String, Freshness>,
+) -> HashMap<String, LegQuality> {
+    let now = now_ms();
+    pairs
+        .iter()
+        .map(|p| {
+            let key = pair_key(&p.base.to_uppercase(), &p.quote.to_uppercase());
+            let stored = freshness.get(&key);
+            let seen = match p.event_time {
+                0 => stored.map(|f| f.updated_ms),
+                t => Some(t),
+            };
+            let quality = LegQuality {
+                age_ms: seen.map(|t| now.saturating_sub(t)),
+                interval_ms: stored.and_then(|f| f.interval_ms),
+                spread_pct: p.spread_pct(),
+                price: p.price,
+                volume: p.volume,
+            };
+            (key, quality)
+        })
+        .collect()
+}
+
+/// Set each result's `confidence` to the score of its weakest leg.
+/// `lookup(venue, pair)` gives a leg's quality; merged results pass the
+/// leg's venue.
+pub fn score_confidence(
+    results: &mut [TriangularResult],
+    rules: &ConfidenceConfig,
+    lookup: impl Fn(Option<&str>, &str) -> Option<LegQuality>,
+) {
+    for r in results.iter_mut() {
+        let weakest = r
+            .pairs
+            .iter()
+            .enumerate()
+            .map(|(i, leg)| {
+                let venue = r.venues.get(i).map(String::as_str);
+                let reversed = reverse_pair(leg);
+                let q = lookup(venue, leg)
+                    .or_else(|| lookup(venue, &reversed))
+                    .unwrap_or_default();
+                leg_confidence(&q, rules)
+            })
+            .fold(1.0, f64::min);
+        r.confidence = Some((weakest * 1000.0).round() / 1000.0);
+    }
+}
+
+fn leg_confidence(q: &LegQuality, rules: &ConfidenceConfig) -> f64 {
+    let age = q.age_ms.map_or(rules.unknown_factor, |age| {
+        0.5f64.powf(age as f64 / rules.age_half_life_ms.max(1) as f64)
+    });
+    let frequency = q.interval_ms.map_or(rules.unknown_factor, |interval| {
+        let reference = rules.interval_ref_ms as f64;
+        reference / (reference + interval)
+    });
+    let spread = q.spread_pct.map_or(rules.unknown_factor, |spread| {
+        rules.spread_ref_pct / (rules.spread_ref_pct + spread)
+    });
+    age * frequency * spread
+}
+
+/// Set `volatility_score` from each leg's realized volatility (%), as
+/// returned by `lookup(venue, pair)`, and flag legs above `rules.max_pct`.
+/// Run before `flag_anomalies` so flagged results are ranked down.
+pub fn score_volatility(
+    results: &mut [TriangularResult],
+    rules: &VolatilityConfig,
+    lookup: impl Fn(Option<&str>, &str) -> Option<f64>,
+) {
+    for r in results.iter_mut() {
+        let mut weakest: Option<f64> = None;
+        for (i, leg) in r.pairs.iter().enumerate() {
+            let venue = r.venues.get(i).map(String::as_str);
+            let reversed = reverse_pair(leg);
+            let Some(vol) = lookup(venue, leg).or_else(|| lookup(venue, &reversed)) else {
+                continue;
+            };
+            if vol > rules.max_pct {
+                r.flags.push(format!("volatile_leg:{}", leg));
+            }
+            let score = rules.ref_pct / (rules.ref_pct + vol);
+            weakest = Some(weakest.map_or(score, |w: f64| w.min(score)));
+        }
+        r.volatility_score = weakest.map(|s| (s * 1000.0).round() / 1000.0);
+    }
+}
 
-    for p in pairs.iter() {
-        if !p.is_spot || !p.price.is_finite() || p.price <= 0.0 {
+/// Set `execution_order`: the legs in the order a manual trader holding
+/// inventory should fill them. The leg with the least liquidity (24h volume
+/// valued in the triangle's start asset) goes first, locking in the part
+/// hardest to complete; ties go to the more volatile leg. Without volume data
+/// legs are ordered by volatility alone; without either, no hint is set.
+pub fn suggest_execution_order(
+    results: &mut [TriangularResult],
+    quality: impl Fn(Option<&str>, &str) -> Option<LegQuality>,
+    volatility: impl Fn(Option<&str>, &str) -> Option<f64>,
+) {
+    for r in results.iter_mut() {
+        let Some(start) = r.pairs.first().and_then(|l| l.split('/').next()) else {
             continue;
+        };
+        // Per leg: the stored pair ("BASE/QUOTE" as traded) and its volatility.
+        let legs: Vec<_> = r
+            .pairs
+            .iter()
+            .enumerate()
+            .map(|(i, leg)| {
+                let venue = r.venues.get(i).map(String::as_str);
+                let reversed = reverse_pair(leg);
+                let stored = quality(venue, leg)
+                    .map(|q| (leg.clone(), q))
+                    .or_else(|| quality(venue, &reversed).map(|q| (reversed.clone(), q)));
+                let vol = volatility(venue, leg).or_else(|| volatility(venue, &reversed));
+                (stored, vol)
+            })
+            .collect();
+
+        // Price of `asset` in the start asset, from the triangle's own legs.
+        let in_start = |asset: &str| -> Option<f64> {
+            if asset == start {
+                return Some(1.0);
+            }
+            legs.iter()
+                .filter_map(|(s, _)| s.as_ref())
+                .find_map(|(key, q)| match key.split_once('/')? {
+                    (b, qt) if b == asset && qt == start => Some(q.price),
+                    (b, qt) if b == start && qt == asset && q.price > 0.0 => Some(1.0 / q.price),
+                    _ => None,
+                })
+        };
+        let liquidity: Option<Vec<f64>> = legs
+            .iter()
+            .map(|(stored, _)| {
+                let (key, q) = stored.as_ref().filter(|(_, q)| q.volume > 0.0)?;
+                Some(q.volume * in_start(key.split('/').next()?)?)
+            })
+            .collect();
+
+        let mut order: Vec<usize> = (0..legs.len()).collect();
+        let by_volatility = |a: &usize, b: &usize| {
+            let va = legs[*a].1.unwrap_or(-1.0);
+            let vb = legs[*b].1.unwrap_or(-1.0);
+            vb.total_cmp(&va)
+        };
+        match liquidity {
+            Some(liq) => {
+                order.sort_by(|a, b| liq[*a].total_cmp(&liq[*b]).then(by_volatility(a, b)))
+            }
+            None if legs.iter().any(|(_, v)| v.is_some()) => order.sort_by(by_volatility),
+            None => continue,
         }
+        r.execution_order = Some(order.into_iter().map(|i| r.pairs[i].clone()).collect());
+    }
+}
+
+/// Set `profit_distribution` from `runs` simulated executions of each
+/// result (see `SimulationConfig`); `volatility(venue, pair)` gives a leg's
+/// realized volatility in % per second.
+pub fn simulate_profits(
+    results: &mut [TriangularResult],
+    model: &SimulationConfig,
+    runs: usize,
+    volatility: impl Fn(Option<&str>, &str) -> Option<f64>,
+) {
+    if runs == 0 {
+        return;
+    }
+    let mut rng = rand::thread_rng();
+    for r in results.iter_mut() {
+        let fee = r.fees / r.pairs.len().max(1) as f64;
+        let vols: Vec<f64> = r
+            .pairs
+            .iter()
+            .enumerate()
+            .map(|(i, leg)| {
+                let venue = r.venues.get(i).map(String::as_str);
+                volatility(venue, leg)
+                    .or_else(|| volatility(venue, &reverse_pair(leg)))
+                    .unwrap_or(model.default_volatility_pct)
+            })
+            .collect();
+
+        let mut profits: Vec<f64> = (0..runs)
+            .map(|_| {
+                let mut value = 1.0 + r.profit_before / 100.0;
+                let mut elapsed_ms = 0.0;
+                for vol in &vols {
+                    elapsed_ms +=
+                        normal(&mut rng, model.latency_mean_ms, model.latency_stdev_ms).max(0.0);
+                    let drift = normal(&mut rng, 0.0, vol * (elapsed_ms / 1000.0).sqrt());
+                    let slippage =
+                        normal(&mut rng, model.slippage_mean_pct, model.slippage_stdev_pct)
+                            .max(0.0);
+                    value *= (1.0 - fee / 100.0) * (1.0 + drift / 100.0) * (1.0 - slippage / 100.0);
+                }
+                (value - 1.0) * 100.0
+            })
+            .collect();
+        profits.sort_by(f64::total_cmp);
+
+        let pick = |q: f64| profits[((runs - 1) as f64 * q).round() as usize];
+        let round = |v: f64| (v * 10_000.0).round() / 10_000.0;
+        r.profit_distribution = Some(ProfitDistribution {
+            runs,
+            p5: round(pick(0.05)),
+            p50: round(pick(0.5)),
+            p95: round(pick(0.95)),
+            mean: round(profits.iter().sum::<f64>() / runs as f64),
+            loss_rate: profits.iter().filter(|p| **p < 0.0).count() as f64 / runs as f64,
+        });
+    }
+}
+
+/// Sample N(mean, stdev) by Box-Muller.
+fn normal(rng: &mut impl Rng, mean: f64, stdev: f64) -> f64 {
+    let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+    let u2: f64 = rng.gen();
+    mean + stdev * (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+/// Re-rank results by `key`, keeping flagged results below clean ones.
+/// Results come out of the search already ranked by profit.
+pub fn sort_results(results: &mut [TriangularResult], key: SortKey) {
+    if key == SortKey::Confidence {
+        results.sort_by(|x, y| {
+            y.flags.is_empty().cmp(&x.flags.is_empty()).then(
+                y.confidence
+                    .unwrap_or(0.0)
+                    .total_cmp(&x.confidence.unwrap_or(0.0)),
+            )
+        });
+    }
+}
+
+/// Conversion graph: `adj[a][b]` is how much `b` one `a` buys, before fees.
+#[derive(Default)]
+struct Graph {
+    adj: HashMap<String, HashMap<String, f64>>,
+    vol_map: HashMap<String, HashMap<String, f64>>,
+    /// Fee per leg (%) and venue of each directed edge.
+    edge: HashMap<(String, String), (f64, Option<String>)>,
+}
+
+impl Graph {
+    /// Add both directions of a pair, keeping the existing edge when it
+    /// converts better after fees (best venue wins in merged graphs). With
+    /// `book`, selling the base converts at the bid and buying it at the ask.
+    fn add(&mut self, p: &PairPrice, fee_pct: f64, venue: Option<&str>, book: bool) {
         let a = p.base.to_uppercase();
         let b = p.quote.to_uppercase();
-
-        adj.entry(a.clone()).or_default().insert(b.clone(), p.price);
-        if p.price > 0.0 && p.price.is_finite() {
-            adj.entry(b.clone()).or_default().insert(a.clone(), 1.0 / p.price);
+        let (sell, buy) = p.side_prices(book);
+        for (from, to, rate) in [(&a, &b, sell), (&b, &a, 1.0 / buy)] {
+            let net = rate * (1.0 - fee_pct / 100.0);
+            let key = (from.clone(), to.clone());
+            let better = match (
+                self.adj.get(from).and_then(|m| m.get(to)),
+                self.edge.get(&key),
+            ) {
+                (Some(&old), Some((old_fee, _))) => net > old * (1.0 - old_fee / 100.0),
+                _ => true,
+            };
+            if !better {
+                continue;
+            }
+            self.adj
+                .entry(from.clone())
+                .or_default()
+                .insert(to.clone(), rate);
+            self.vol_map
+                .entry(from.clone())
+                .or_default()
+                .insert(to.clone(), p.volume);
+            self.edge.insert(key, (fee_pct, venue.map(str::to_string)));
         }
+    }
+}
+
+/// Pairs that may enter the graph: spot (unless `derivatives`), sane price,
+/// no older than `oldest_event`, quoted in an allowed currency and within
+/// the asset allowlist (empty sets allow any).
+fn usable(
+    p: &PairPrice,
+    oldest_event: u64,
+    quotes: &HashSet<String>,
+    assets: &HashSet<String>,
+    derivatives: bool,
+) -> bool {
+    if (!p.is_spot && !derivatives) || !p.price.is_finite() || p.price <= 0.0 {
+        return false;
+    }
+    if !quotes.is_empty() && !quotes.contains(&p.quote.to_uppercase()) {
+        return false;
+    }
+    if p.event_time > 0 && p.event_time < oldest_event {
+        return false;
+    }
+    assets.is_empty()
+        || (assets.contains(&p.base.to_uppercase()) && assets.contains(&p.quote.to_uppercase()))
+}
+
+fn oldest_event(params: &ScanParams) -> u64 {
+    match params.max_age_ms {
+        0 => 0,
+        max_age => now_ms().saturating_sub(max_age),
+    }
+}
+
+fn upper_set(list: &[String]) -> HashSet<String> {
+    list.iter().map(|a| a.to_uppercase()).collect()
+}
+
+/// Find triangular arbitrage opportunities.
+pub fn find_triangular_opportunities(
+    exchange: &str,
+    pairs: Vec<PairPrice>,
+    params: &ScanParams,
+) -> Vec<TriangularResult> {
+    let oldest = oldest_event(params);
+    let quotes = upper_set(&params.quotes);
+    let assets = upper_set(&params.assets);
+    let mut graph = Graph::default();
+    for p in pairs
+        .iter()
+        .filter(|p| usable(p, oldest, &quotes, &assets, params.derivatives))
+        .filter(|p| !symbols().halted(exchange, &p.base, &p.quote))
+    {
+        graph.add(p, params.fee_per_leg_pct, None, params.book_prices);
+    }
+    search(&graph, params)
+}
 
-        vol_map.entry(a.clone()).or_default().insert(b.clone(), p.volume);
-        vol_map.entry(b.clone()).or_default().insert(a.clone(), p.volume);
+/// One graph across venues: each edge takes the venue converting best after
+/// that venue's fee, so triangles may span exchanges. Results carry the
+/// venue of every leg.
+pub fn find_merged_opportunities(
+    sources: Vec<(String, Vec<PairPrice>, f64)>,
+    params: &ScanParams,
+) -> Vec<TriangularResult> {
+    let oldest = oldest_event(params);
+    let quotes = upper_set(&params.quotes);
+    let assets = upper_set(&params.assets);
+    let mut graph = Graph::default();
+    for (venue, pairs, fee_pct) in &sources {
+        for p in pairs
+            .iter()
+            .filter(|p| usable(p, oldest, &quotes, &assets, params.derivatives))
+            .filter(|p| !symbols().halted(venue, &p.base, &p.quote))
+        {
+            graph.add(p, *fee_pct, Some(venue), params.book_prices);
+        }
     }
+    search(&graph, params)
+}
+
+fn search(graph: &Graph, params: &ScanParams) -> Vec<TriangularResult> {
+    let min_profit_after = params.min_profit;
+    let neighbor_limit = params.neighbor_limit;
+    let adj = &graph.adj;
+    let vol_map = &graph.vol_map;
 
     let mut neighbors: HashMap<String, Vec<String>> = HashMap::new();
     for (base, targets) in adj.iter() {
@@ -60,9 +469,6 @@ pub fn find_triangular_opportunities(
     let mut seen: HashSet<(String, String, String)> = HashSet::new();
     let mut out: Vec<TriangularResult> = Vec::new();
 
-    let fee_factor = (1.0 - fee_per_leg_pct / 100.0).powi(3);
-    let total_fee_pct = 3.0 * fee_per_leg_pct;
-
     for a in neighbors.keys() {
         let neigh_a = neighbors.get(a).cloned().unwrap_or_default();
         for b in neigh_a.iter() {
@@ -98,25 +504,48 @@ pub fn find_triangular_opportunities(
                     continue;
                 }
                 let profit_before = (gross - 1.0) * 100.0;
-                if profit_before <= 0.0 {
-                    continue;
-                }
+
+                let legs = [(a, b), (b, c), (c, a)].map(|(x, y)| {
+                    graph
+                        .edge
+                        .get(&(x.clone(), y.clone()))
+                        .cloned()
+                        .unwrap_or((params.fee_per_leg_pct, None))
+                });
+                let fee_factor: f64 = legs.iter().map(|(f, _)| 1.0 - f / 100.0).product();
+                let total_fee_pct: f64 = legs.iter().map(|(f, _)| f).sum();
 
                 let net = gross * fee_factor;
                 let profit_after = (net - 1.0) * 100.0;
-                if profit_after < min_profit_after {
+                let passes = profit_after >= min_profit_after;
+                if !passes && params.top_n == 0 {
                     continue;
                 }
 
-                let v_ab = vol_map.get(a).and_then(|m| m.get(b)).copied().unwrap_or(0.0);
-                let v_bc = vol_map.get(b).and_then(|m| m.get(c)).copied().unwrap_or(0.0);
-                let v_ca = vol_map.get(c).and_then(|m| m.get(a)).copied().unwrap_or(0.0);
+                let v_ab = vol_map
+                    .get(a)
+                    .and_then(|m| m.get(b))
+                    .copied()
+                    .unwrap_or(0.0);
+                let v_bc = vol_map
+                    .get(b)
+                    .and_then(|m| m.get(c))
+                    .copied()
+                    .unwrap_or(0.0);
+                let v_ca = vol_map
+                    .get(c)
+                    .and_then(|m| m.get(a))
+                    .copied()
+                    .unwrap_or(0.0);
                 let liquidity_score = v_ab.min(v_bc).min(v_ca);
+                if liquidity_score < params.min_leg_volume {
+                    continue;
+                }
 
                 let r1 = (a.clone(), b.clone(), c.clone());
                 let r2 = (b.clone(), c.clone(), a.clone());
                 let r3 = (c.clone(), a.clone(), b.clone());
-                let mut rots = vec![r1, r2, r3];
+                let mut rots = [r1, r2, r3];
                 rots.sort();
                 let key = rots[0].clone();
 
@@ -131,15 +560,22 @@ pub fn find_triangular_opportunities(
                     format!("{}/{}", c, a),
                 ];
 
-                 out.push(TriangularResult {
-    triangle: triangle_fmt,
-    pairs: pairs_fmt,
-    profit_before,
-    fees: total_fee_pct,
-    profit_after,
-    score_liquidity: liquidity_score,
-    liquidity_legs: [v_ab, v_bc, v_ca],   // NEW: pass per-leg volumes
-                     
+                out.push(TriangularResult {
+                    triangle: triangle_fmt,
+                    pairs: pairs_fmt,
+                    profit_before,
+                    fees: total_fee_pct,
+                    profit_after,
+                    score_liquidity: liquidity_score,
+                    liquidity_legs: [v_ab, v_bc, v_ca],
+                    venues: legs.iter().filter_map(|(_, v)| v.clone()).collect(),
+                    passes_threshold: (params.top_n > 0).then_some(passes),
+                    context: None,
+                    flags: Vec::new(),
+                    confidence: None,
+                    volatility_score: None,
+                    execution_order: None,
+                    profit_distribution: None,
                 });
             }
         }
@@ -151,14 +587,168 @@ pub fn find_triangular_opportunities(
             .partial_cmp(&x.profit_after)
             .unwrap_or(std::cmp::Ordering::Equal)
         {
-            std::cmp::Ordering::Equal => {
-                y.score_liquidity
-                    .partial_cmp(&x.score_liquidity)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            }
+            std::cmp::Ordering::Equal => y
+                .score_liquidity
+                .partial_cmp(&x.score_liquidity)
+                .unwrap_or(std::cmp::Ordering::Equal),
             ord => ord,
         }
     });
+    if params.top_n > 0 {
+        out.truncate(params.top_n);
+    }
 
     out
-                        }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(base: &str, quote: &str, price: f64) -> PairPrice {
+        PairPrice {
+            base: base.into(),
+            quote: quote.into(),
+            price,
+            is_spot: true,
+            volume: 1000.0,
+            event_time: 0,
+            bid: None,
+            ask: None,
+        }
+    }
+
+    #[test]
+    fn merged_triangle_spans_venues_at_each_venue_fee() {
+        // ETH/USDT only trades on "b"; the other two legs only on "a".
+        let sources = vec![
+            (
+                "a".to_string(),
+                vec![pair("BTC", "USDT", 100.0), pair("ETH", "BTC", 0.05)],
+                0.1,
+            ),
+            ("b".to_string(), vec![pair("ETH", "USDT", 5.2)], 0.2),
+        ];
+        let results = find_merged_opportunities(sources, &ScanParams::default());
+        assert_eq!(results.len(), 1);
+        let r = &results[0];
+
+        for (leg, venue) in r.pairs.iter().zip(&r.venues) {
+            let expected = match leg.as_str() {
+                "ETH/USDT" | "USDT/ETH" => "b",
+                _ => "a",
+            };
+            assert_eq!(venue, expected, "venue of {}", leg);
+        }
+        assert!((r.fees - 0.4).abs() < 1e-9);
+        assert!((r.profit_before - 4.0).abs() < 1e-9);
+        let net = 1.04 * 0.999 * 0.998 * 0.999;
+        assert!((r.profit_after - (net - 1.0) * 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn top_n_keeps_triangles_losing_before_fees() {
+        // 0.1% gross edge one way round, -0.1% the other; both lose after fees.
+        let pairs = vec![
+            pair("BTC", "USDT", 100.0),
+            pair("ETH", "BTC", 0.05),
+            pair("ETH", "USDT", 5.005),
+        ];
+        let params = ScanParams {
+            top_n: 5,
+            ..ScanParams::default()
+        };
+        let results = find_triangular_opportunities("test", pairs.clone(), &params);
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.passes_threshold == Some(false)));
+        assert!(results.iter().any(|r| r.profit_before < 0.0));
+        assert!(results[0].profit_after > results[1].profit_after);
+
+        let found = find_triangular_opportunities("test", pairs, &ScanParams::default());
+        assert!(found.is_empty());
+    }
+
+    #[test]
+    fn scan_params_drop_stale_pairs_and_thin_triangles() {
+        // BTC -> ETH -> USDT -> BTC gains 4% before fees.
+        let pairs = vec![
+            pair("BTC", "USDT", 100.0),
+            pair("ETH", "BTC", 0.05),
+            pair("ETH", "USDT", 5.2),
+        ];
+        let found = find_triangular_opportunities("test", pairs.clone(), &ScanParams::default());
+        assert_eq!(found.len(), 1);
+
+        // Every leg trades 1000.
+        let thick_only = ScanParams {
+            min_leg_volume: 1000.5,
+            ..ScanParams::default()
+        };
+        assert!(find_triangular_opportunities("test", pairs.clone(), &thick_only).is_empty());
+
+        let mut stale = pairs;
+        stale[2].event_time = 1;
+        let fresh_only = ScanParams {
+            max_age_ms: 60_000,
+            ..ScanParams::default()
+        };
+        assert!(find_triangular_opportunities("test", stale, &fresh_only).is_empty());
+    }
+
+    #[test]
+    fn leg_confidence_halves_at_each_reference() {
+        let rules = ConfidenceConfig::default();
+        let perfect = LegQuality {
+            age_ms: Some(0),
+            interval_ms: Some(0.0),
+            spread_pct: Some(0.0),
+            ..LegQuality::default()
+        };
+        assert_eq!(leg_confidence(&perfect, &rules), 1.0);
+        let halved = [
+            LegQuality {
+                age_ms: Some(rules.age_half_life_ms),
+                ..perfect
+            },
+            LegQuality {
+                interval_ms: Some(rules.interval_ref_ms as f64),
+                ..perfect
+            },
+            LegQuality {
+                spread_pct: Some(rules.spread_ref_pct),
+                ..perfect
+            },
+        ];
+        for q in &halved {
+            assert!((leg_confidence(q, &rules) - 0.5).abs() < 1e-9, "{:?}", q);
+        }
+        let unknown = leg_confidence(&LegQuality::default(), &rules);
+        assert!((unknown - rules.unknown_factor.powi(3)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn derivatives_enter_the_graph_only_when_enabled() {
+        let mut perp = pair("ETH", "USDT", 5.2);
+        perp.is_spot = false;
+        let pairs = vec![pair("BTC", "USDT", 100.0), pair("ETH", "BTC", 0.05), perp];
+        assert!(
+            find_triangular_opportunities("test", pairs.clone(), &ScanParams::default()).is_empty()
+        );
+        let params = ScanParams {
+            derivatives: true,
+            ..ScanParams::default()
+        };
+        assert!(!find_triangular_opportunities("test", pairs, &params).is_empty());
+    }
+
+    #[test]
+    fn lower_case_pairs_find_their_stored_freshness() {
+        let stored = Freshness {
+            updated_ms: now_ms(),
+            interval_ms: Some(500.0),
+        };
+        let freshness = HashMap::from([("BTC/USDT".to_string(), stored)]);
+        let quality = leg_qualities(&[pair("btc", "usdt", 100.0)], &freshness);
+        assert_eq!(quality["BTC/USDT"].interval_ms, Some(500.0));
+    }
+}
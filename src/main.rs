@@ -1,32 +1,113 @@
-use axum::{routing::get, Router};
+use axum::Router;
 use std::net::SocketAddr;
-use tower_http::services::ServeDir;
-use tower_http::cors::{Any, CorsLayer};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 use tokio::net::TcpListener;
+use tower_http::cors::{Any, CorsLayer};
+use tower_http::services::ServeDir;
 
-mod models;
+mod access_log;
+mod admin;
+mod alerts;
+mod alloc;
+mod backtest;
+mod bench;
+mod blacklist;
+mod bus;
+mod candles;
+mod ccxt;
+mod chaos;
+mod checkpoint;
+mod config;
+mod coverage;
+mod dump;
+mod evm;
 mod exchanges;
+mod health;
+mod history;
+mod inflate;
+mod lifetime;
+mod load_shed;
 mod logic;
-mod utils;
+mod metrics;
+mod models;
+mod noise;
+mod openapi;
+mod orderbook;
+mod portfolio;
+mod precision;
+mod protocol;
+mod rest;
 mod routes;
+mod runtime;
+mod scan_cache;
+mod scheduler;
+mod sheets;
+mod shutdown;
+mod stats;
+mod store;
+mod symbols;
+mod tasks;
+mod template;
+mod ticks;
+mod utils;
+mod ws_manager;
 
-#[tokio::main]
-async fn main() {
+fn main() {
     // init tracing/logger
-    tracing_subscriber::registry()
-        .with(tracing_subscriber::EnvFilter::new(
-            std::env::var("RUST_LOG").unwrap_or_else(|_| "info".into()),
-        ))
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    utils::init_tracing();
+    if let Err(e) = config::init() {
+        tracing::error!("{}", e);
+        std::process::exit(1);
+    }
+
+    let rt = runtime::main_runtime(&config::config().runtime);
+    rt.block_on(serve());
+}
+
+async fn serve() {
+    // Live price ingestion
+    let prices = store::PriceStore::shared();
+    let rt_cfg = &config::config().runtime;
+    checkpoint::start(prices.clone());
+    match config::config().role {
+        config::Role::All => runtime::start_connectors(rt_cfg, prices.clone()),
+        config::Role::Collector => {
+            bus::start_publisher(prices.clone());
+            runtime::start_connectors(rt_cfg, prices.clone());
+        }
+        config::Role::Api => bus::start_subscriber(prices.clone()),
+    }
+    scheduler::scheduler().start(prices.clone(), routes::run_job);
+    sheets::start();
+    lifetime::start();
+    dump::start_signal_handler(prices.clone());
 
     // Build app
-    let app = Router::new()
-        .merge(routes::routes()) // <-- routes.rs must provide pub fn routes() -> Router
+    let state = routes::AppState::new(prices.clone());
+    let admin_routes = routes::admin_routes(state.clone())
+        .layer(axum::middleware::from_fn(admin::require_header))
+        .layer(axum::middleware::from_fn(admin::allowlist));
+    // CORS covers the public API only; the admin routes answer no preflight.
+    let mut app =
+        Router::new().merge(routes::routes(state).layer(CorsLayer::new().allow_origin(Any)));
+    match config::config().admin.bind.clone() {
+        Some(bind) => {
+            let admin_app = admin_routes.layer(axum::middleware::from_fn(access_log::log));
+            let listener = TcpListener::bind(&bind)
+                .await
+                .expect("Failed to bind admin address");
+            tracing::info!("Admin endpoints listening on http://{}", bind);
+            tasks::spawn("admin-http", async move {
+                let service = admin_app.into_make_service_with_connect_info::<SocketAddr>();
+                if let Err(e) = axum::serve(listener, service).await {
+                    tracing::error!("admin server error: {}", e);
+                }
+            });
+        }
+        None => app = app.merge(admin_routes),
+    }
+    let app = app
         .nest_service("/", ServeDir::new("static"))
-        .route("/health", get(|| async { "ok" }))
-        .layer(CorsLayer::new().allow_origin(Any));
+        .layer(axum::middleware::from_fn(access_log::log));
 
     // Port from env or default
     let port = std::env::var("PORT")
@@ -37,7 +118,15 @@ async fn main() {
     let addr: SocketAddr = format!("0.0.0.0:{}", port).parse().expect("invalid addr");
     tracing::info!("Server listening on http://{}", addr);
 
-    let listener = TcpListener::bind(addr).await.expect("Failed to bind address");
-    axum::serve(listener, app).await.expect("server error");
-        }
-        
+    let listener = TcpListener::bind(addr)
+        .await
+        .expect("Failed to bind address");
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown::signal())
+    .await
+    .expect("server error");
+    shutdown::finish(&prices);
+}
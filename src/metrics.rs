@@ -0,0 +1,228 @@
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+use std::time::Duration;
+use utoipa::ToSchema;
+
+/// Process-wide ingestion metrics, served by `/metrics`.
+static METRICS: Lazy<MetricsRegistry> = Lazy::new(MetricsRegistry::default);
+
+pub fn metrics() -> &'static MetricsRegistry {
+    &METRICS
+}
+
+/// Upper bucket bounds (ms) shared by the interval/latency histograms.
+const BUCKETS_MS: [u64; 14] = [
+    5, 10, 25, 50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 30_000, 60_000, 300_000,
+];
+
+/// Fixed-bucket histogram; the last count is the overflow bucket.
+#[derive(Debug, Clone)]
+pub struct Histogram {
+    counts: [u64; BUCKETS_MS.len() + 1],
+    count: u64,
+    sum_ms: u64,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self {
+            counts: [0; BUCKETS_MS.len() + 1],
+            count: 0,
+            sum_ms: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct HistogramReport {
+    pub count: u64,
+    pub mean_ms: f64,
+    pub p50_ms: Option<u64>,
+    pub p90_ms: Option<u64>,
+    pub p99_ms: Option<u64>,
+    /// (upper bound ms, count); `None` bound is the overflow bucket.
+    pub buckets: Vec<(Option<u64>, u64)>,
+}
+
+impl Histogram {
+    pub fn observe(&mut self, ms: u64) {
+        let idx = BUCKETS_MS
+            .iter()
+            .position(|b| ms <= *b)
+            .unwrap_or(BUCKETS_MS.len());
+        self.counts[idx] += 1;
+        self.count += 1;
+        self.sum_ms = self.sum_ms.saturating_add(ms);
+    }
+
+    /// Upper bound of the bucket containing quantile `q` (None = overflow/empty).
+    fn quantile(&self, q: f64) -> Option<u64> {
+        if self.count == 0 {
+            return None;
+        }
+        let target = (self.count as f64 * q).ceil() as u64;
+        let mut seen = 0;
+        for (i, c) in self.counts.iter().enumerate() {
+            seen += c;
+            if seen >= target {
+                return BUCKETS_MS.get(i).copied();
+            }
+        }
+        None
+    }
+
+    pub fn report(&self) -> HistogramReport {
+        HistogramReport {
+            count: self.count,
+            mean_ms: if self.count > 0 {
+                self.sum_ms as f64 / self.count as f64
+            } else {
+                0.0
+            },
+            p50_ms: self.quantile(0.50),
+            p90_ms: self.quantile(0.90),
+            p99_ms: self.quantile(0.99),
+            buckets: self
+                .counts
+                .iter()
+                .enumerate()
+                .map(|(i, c)| (BUCKETS_MS.get(i).copied(), *c))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct ExchangeMetrics {
+    flushes_total: u64,
+    symbols_changed_total: u64,
+    last_flush_changed: usize,
+    /// Consecutive flushes in which no symbol changed price.
+    unchanged_flush_streak: u64,
+    update_interval: Histogram,
+    ingest_latency: Histogram,
+    store_write: Histogram,
+    shed_level: u32,
+    shed_events_total: u64,
+    frames_coalesced_total: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExchangeMetricsReport {
+    pub flushes_total: u64,
+    pub symbols_changed_total: u64,
+    pub last_flush_changed: usize,
+    pub avg_changed_per_flush: f64,
+    pub unchanged_flush_streak: u64,
+    /// Time between consecutive price changes of the same symbol.
+    pub update_interval: HistogramReport,
+    /// Exchange event time to write into the price store.
+    pub ingest_latency: HistogramReport,
+    /// Time each flush spent waiting for and holding the store write lock.
+    pub store_write: HistogramReport,
+    /// Current load-shedding level (0 = normal).
+    pub shed_level: u32,
+    /// Times shedding stepped up a level.
+    pub shed_events_total: u64,
+    /// Prices held by `max_messages_per_sec` and replaced by a newer one.
+    pub frames_coalesced_total: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MetricsReport {
+    pub exchanges: BTreeMap<String, ExchangeMetricsReport>,
+}
+
+#[derive(Debug, Default)]
+pub struct MetricsRegistry {
+    inner: RwLock<BTreeMap<String, ExchangeMetrics>>,
+}
+
+impl MetricsRegistry {
+    fn update(&self, exchange: &str, f: impl FnOnce(&mut ExchangeMetrics)) {
+        let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        f(guard.entry(exchange.to_string()).or_default());
+    }
+
+    /// One flush into the price store: how many symbols changed, for
+    /// symbols seen before how long since their previous change, and how
+    /// long the write took.
+    pub fn record_flush(
+        &self,
+        exchange: &str,
+        changed: usize,
+        intervals_ms: &[u64],
+        write: Duration,
+    ) {
+        self.update(exchange, |m| {
+            m.store_write.observe(write.as_millis() as u64);
+            m.flushes_total += 1;
+            m.symbols_changed_total += changed as u64;
+            m.last_flush_changed = changed;
+            m.unchanged_flush_streak = if changed == 0 {
+                m.unchanged_flush_streak + 1
+            } else {
+                0
+            };
+            for ms in intervals_ms {
+                m.update_interval.observe(*ms);
+            }
+        });
+    }
+
+    /// Event-time-to-ingest latency for the symbols written in one flush.
+    pub fn record_ingest_latency(&self, exchange: &str, latencies_ms: &[u64]) {
+        if latencies_ms.is_empty() {
+            return;
+        }
+        self.update(exchange, |m| {
+            for ms in latencies_ms {
+                m.ingest_latency.observe(*ms);
+            }
+        });
+    }
+
+    pub fn record_shed_level(&self, exchange: &str, level: u32) {
+        self.update(exchange, |m| {
+            if level > m.shed_level {
+                m.shed_events_total += 1;
+            }
+            m.shed_level = level;
+        });
+    }
+
+    pub fn record_coalesced(&self, exchange: &str, frames: u64) {
+        self.update(exchange, |m| m.frames_coalesced_total += frames);
+    }
+
+    pub fn report(&self) -> MetricsReport {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        let exchanges = guard
+            .iter()
+            .map(|(name, m)| {
+                let avg = if m.flushes_total > 0 {
+                    m.symbols_changed_total as f64 / m.flushes_total as f64
+                } else {
+                    0.0
+                };
+                let report = ExchangeMetricsReport {
+                    flushes_total: m.flushes_total,
+                    symbols_changed_total: m.symbols_changed_total,
+                    last_flush_changed: m.last_flush_changed,
+                    avg_changed_per_flush: avg,
+                    unchanged_flush_streak: m.unchanged_flush_streak,
+                    update_interval: m.update_interval.report(),
+                    ingest_latency: m.ingest_latency.report(),
+                    store_write: m.store_write.report(),
+                    shed_level: m.shed_level,
+                    shed_events_total: m.shed_events_total,
+                    frames_coalesced_total: m.frames_coalesced_total,
+                };
+                (name.clone(), report)
+            })
+            .collect();
+        MetricsReport { exchanges }
+    }
+}
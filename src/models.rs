@@ -1,17 +1,108 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 /// Represents a trading pair price snapshot from an exchange.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PairPrice {
     pub base: String,
     pub quote: String,
     pub price: f64,
     pub is_spot: bool,
     pub volume: f64,
+    /// Exchange event time in milliseconds (0 when the venue doesn't send one).
+    #[serde(default)]
+    pub event_time: u64,
+    /// Best bid / ask, when the venue's stream carries them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bid: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ask: Option<f64>,
 }
 
-/// Result of a detected triangular arbitrage opportunity.
+impl PairPrice {
+    /// Bid/ask spread as a percentage of the mid price.
+    pub fn spread_pct(&self) -> Option<f64> {
+        let (bid, ask) = (self.bid?, self.ask?);
+        let mid = (bid + ask) / 2.0;
+        (bid > 0.0 && ask >= bid).then(|| (ask - bid) / mid * 100.0)
+    }
+
+    /// Prices for selling and for buying the base: the best bid and ask when
+    /// `book` is set and the venue sent a sane pair, else the last price for
+    /// both.
+    pub fn side_prices(&self, book: bool) -> (f64, f64) {
+        match (self.bid, self.ask) {
+            (Some(bid), Some(ask)) if book && bid > 0.0 && ask >= bid && ask.is_finite() => {
+                (bid, ask)
+            }
+            _ => (self.price, self.price),
+        }
+    }
+}
+
+/// Tuning for one triangular scan, shared by the HTTP routes and logic.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanParams {
+    /// Minimum profit after fees, in percent.
+    pub min_profit: f64,
+    pub fee_per_leg_pct: f64,
+    /// Per asset, only the highest-volume neighbours are explored.
+    pub neighbor_limit: usize,
+    /// Ignore pairs whose event time is older than this (0 = no limit).
+    pub max_age_ms: u64,
+    /// Ignore triangles whose thinnest leg trades less than this volume.
+    pub min_leg_volume: f64,
+    /// Only build triangles from these assets (empty = any).
+    pub assets: Vec<String>,
+    /// Only pairs quoted in these currencies enter the graph (empty = any).
+    pub quotes: Vec<String>,
+    /// Let non-spot markets (`is_spot: false`, e.g. dYdX perpetuals) into
+    /// the graph.
+    pub derivatives: bool,
+    /// Price each leg at the side it trades (bid when selling the base, ask
+    /// when buying it) for pairs that carry a best bid/ask.
+    pub book_prices: bool,
+    /// Return the best `top_n` triangles even below `min_profit`, marking
+    /// whether each passes (0 = only triangles that pass).
+    pub top_n: usize,
+    /// Order of the returned results.
+    pub sort: SortKey,
+    /// Monte Carlo runs per result for `profit_distribution` (0 = off).
+    pub simulate: usize,
+}
+
+/// What scan results are ranked by; flagged results stay below clean ones.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SortKey {
+    /// Profit after fees, then liquidity.
+    #[default]
+    Profit,
+    Confidence,
+}
+
+impl Default for ScanParams {
+    fn default() -> Self {
+        Self {
+            min_profit: 0.0,
+            fee_per_leg_pct: 0.10,
+            neighbor_limit: 100,
+            max_age_ms: 0,
+            min_leg_volume: 0.0,
+            assets: Vec::new(),
+            quotes: Vec::new(),
+            derivatives: false,
+            book_prices: false,
+            top_n: 0,
+            sort: SortKey::Profit,
+            simulate: 0,
+        }
+    }
+}
+
+/// Result of a detected triangular arbitrage opportunity.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TriangularResult {
     pub triangle: String,
     pub pairs: Vec<String>,
@@ -19,5 +110,62 @@ pub struct TriangularResult {
     pub fees: f64,
     pub profit_after: f64,
     pub score_liquidity: f64,
-    pub liquidity_legs: [f64; 3],   // NEW
+    /// Volume of each leg as the venue reports it, in triangle order.
+    pub liquidity_legs: [f64; 3],
+    /// Venue of each leg, for triangles from a merged multi-exchange graph.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub venues: Vec<String>,
+    /// In top-N mode: whether `profit_after` reaches `min_profit`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub passes_threshold: Option<bool>,
+    /// How this triangle did in earlier scans; absent when first seen.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<TriangleContext>,
+    /// Reasons this result looks suspicious (implausible profit, near-zero
+    /// volume or recently listed legs).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub flags: Vec<String>,
+    /// 0..1 trust in the quoted prices, from the data age, update frequency
+    /// and spread of the weakest leg.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+    /// 0..1, lower for more volatile legs (weakest leg counts); absent
+    /// without recent candles.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volatility_score: Option<f64>,
+    /// Legs in the suggested fill order, scarcest liquidity first.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_order: Option<Vec<String>>,
+    /// Simulated profit after latency and slippage, when requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profit_distribution: Option<ProfitDistribution>,
+}
+
+impl TriangularResult {
+    /// Whether the result reaches `min_profit`; top-N scans also return
+    /// near-misses that don't.
+    pub fn passes(&self) -> bool {
+        self.passes_threshold != Some(false)
+    }
+}
+
+/// Percentiles of `profit_after` (%) over Monte Carlo execution runs.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ProfitDistribution {
+    pub runs: usize,
+    pub p5: f64,
+    pub p50: f64,
+    pub p95: f64,
+    pub mean: f64,
+    /// Share of runs that lost money.
+    pub loss_rate: f64,
+}
+
+/// Earlier appearances of a triangle on the same exchange, so a fresh
+/// dislocation can be told apart from a permanently "profitable" artifact.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TriangleContext {
+    pub avg_profit_1h: f64,
+    /// Scans in the last hour that returned this triangle.
+    pub seen_1h: u64,
 }
@@ -0,0 +1,129 @@
+use crate::config::NoiseConfig;
+use crate::logic::reverse_pair;
+use crate::models::TriangularResult;
+use crate::stats::canonical;
+use crate::utils::now_ms;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::RwLock;
+use utoipa::ToSchema;
+
+/// Triangles that keep showing profit only because one leg stopped updating
+/// while the other two moved. Each such scan is a strike; enough strikes
+/// suppress the triangle until the stale leg moves again.
+static NOISE: Lazy<NoiseTracker> = Lazy::new(NoiseTracker::default);
+
+pub fn noise() -> &'static NoiseTracker {
+    &NOISE
+}
+
+#[derive(Debug, Default)]
+struct Record {
+    stale_leg: String,
+    /// When each strike happened, oldest first.
+    strikes: VecDeque<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct NoisyTriangle {
+    /// "exchange:A>B>C".
+    pub triangle: String,
+    pub stale_leg: String,
+    pub strikes: usize,
+    pub last_strike_ms: u64,
+    pub suppressed: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct NoiseTracker {
+    inner: RwLock<HashMap<String, Record>>,
+}
+
+impl NoiseTracker {
+    /// Count strikes for this scan's results and flag (or drop) suppressed
+    /// triangles. `leg_age(venue, pair)` is the age of a leg's data in ms.
+    /// Run before `flag_anomalies` so flagged results are ranked down.
+    pub fn apply(
+        &self,
+        exchange: &str,
+        results: &mut Vec<TriangularResult>,
+        rules: &NoiseConfig,
+        leg_age: impl Fn(Option<&str>, &str) -> Option<u64>,
+    ) {
+        let now = now_ms();
+        let cutoff = now.saturating_sub(rules.window_ms);
+        let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        guard.retain(|_, rec| {
+            while rec.strikes.front().is_some_and(|at| *at < cutoff) {
+                rec.strikes.pop_front();
+            }
+            !rec.strikes.is_empty()
+        });
+
+        let mut suppressed = Vec::with_capacity(results.len());
+        for r in results.iter_mut() {
+            let ages: Vec<Option<u64>> = r
+                .pairs
+                .iter()
+                .enumerate()
+                .map(|(i, leg)| {
+                    let venue = r.venues.get(i).map(String::as_str);
+                    leg_age(venue, leg).or_else(|| leg_age(venue, &reverse_pair(leg)))
+                })
+                .collect();
+            let key = format!("{}:{}", exchange, canonical(r));
+            let stale: Vec<usize> = (0..ages.len())
+                .filter(|&i| ages[i].is_some_and(|a| a > rules.stale_leg_ms))
+                .collect();
+            let others_fresh = ages
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !stale.contains(i))
+                .all(|(_, a)| a.is_some_and(|a| a <= rules.fresh_leg_ms));
+
+            if stale.len() == 1 && others_fresh {
+                let rec = guard.entry(key.clone()).or_default();
+                let leg = &r.pairs[stale[0]];
+                if rec.stale_leg != *leg {
+                    rec.stale_leg = leg.clone();
+                    rec.strikes.clear();
+                }
+                rec.strikes.push_back(now);
+            } else if ages
+                .iter()
+                .all(|a| a.is_some_and(|a| a <= rules.stale_leg_ms))
+            {
+                // Every leg is moving again.
+                guard.remove(&key);
+            }
+
+            let is_suppressed = guard
+                .get(&key)
+                .is_some_and(|rec| rec.strikes.len() >= rules.strikes);
+            if is_suppressed && !rules.drop {
+                r.flags.push("noisy_triangle".to_string());
+            }
+            suppressed.push(is_suppressed && rules.drop);
+        }
+        let mut dropped = suppressed.into_iter();
+        results.retain(|_| !dropped.next().unwrap_or(false));
+    }
+
+    /// Triangles with strikes in the window, most strikes first.
+    pub fn list(&self, rules: &NoiseConfig) -> Vec<NoisyTriangle> {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        let mut out: Vec<NoisyTriangle> = guard
+            .iter()
+            .map(|(key, rec)| NoisyTriangle {
+                triangle: key.clone(),
+                stale_leg: rec.stale_leg.clone(),
+                strikes: rec.strikes.len(),
+                last_strike_ms: rec.strikes.back().copied().unwrap_or(0),
+                suppressed: rec.strikes.len() >= rules.strikes,
+            })
+            .collect();
+        out.sort_by(|a, b| b.strikes.cmp(&a.strikes).then(a.triangle.cmp(&b.triangle)));
+        out
+    }
+}
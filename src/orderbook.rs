@@ -0,0 +1,605 @@
+//! Local L2 order books for the most traded pairs of selected venues, built
+//! from a REST snapshot and kept current from the venue's diff stream.
+//! The logic layer asks [`OrderBooks::fill`] what a given notional would
+//! actually trade at instead of trusting the top-of-book price.
+
+use crate::config::{config, OrderBookConfig};
+use crate::exchanges::parse_f64;
+use crate::rest::{self, Depth};
+use crate::store::pair_key;
+use crate::utils::now_ms;
+use crate::ws_manager::Backoff;
+use futures::{SinkExt, StreamExt};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::RwLock;
+use tokio::sync::mpsc;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+
+/// Venues with a diff-stream implementation.
+pub const SUPPORTED: &[&str] = &["binance", "binanceus", "coinbase"];
+
+const COINBASE_WS_URL: &str = "wss://advanced-trade-ws.coinbase.com";
+
+/// Process-wide local books, written by the book workers.
+static BOOKS: Lazy<OrderBooks> = Lazy::new(OrderBooks::default);
+
+pub fn books() -> &'static OrderBooks {
+    &BOOKS
+}
+
+/// One side of a book keyed by `f64::to_bits` of the price, which orders
+/// positive prices the same way the floats do.
+type Levels = BTreeMap<u64, f64>;
+
+#[derive(Debug, Default)]
+struct Book {
+    bids: Levels,
+    asks: Levels,
+    last_update_id: u64,
+    updated_ms: u64,
+}
+
+impl Book {
+    fn from_snapshot(last_update_id: u64, depth: &Depth) -> Self {
+        let mut book = Self {
+            last_update_id,
+            updated_ms: now_ms(),
+            ..Self::default()
+        };
+        apply_levels(&mut book.bids, &depth.bids);
+        apply_levels(&mut book.asks, &depth.asks);
+        book
+    }
+
+    /// Best-first levels of one side.
+    fn side(&self, side: Side) -> Box<dyn Iterator<Item = (f64, f64)> + '_> {
+        let level = |(p, q): (&u64, &f64)| (f64::from_bits(*p), *q);
+        match side {
+            Side::Buy => Box::new(self.asks.iter().map(level)),
+            Side::Sell => Box::new(self.bids.iter().rev().map(level)),
+        }
+    }
+}
+
+/// A quantity of zero removes the level.
+fn apply_levels(levels: &mut Levels, updates: &[(f64, f64)]) {
+    for &(price, qty) in updates {
+        if price.is_nan() || price <= 0.0 {
+            continue;
+        }
+        if qty > 0.0 {
+            levels.insert(price.to_bits(), qty);
+        } else {
+            levels.remove(&price.to_bits());
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    /// Spend quote, walking the asks.
+    Buy,
+    /// Sell base worth the notional, walking the bids.
+    Sell,
+}
+
+/// What trading `notional` (in quote) against the local book would give.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Fill {
+    pub side: Side,
+    /// Volume-weighted price over the consumed levels.
+    pub avg_price: f64,
+    pub base_qty: f64,
+    /// Quote actually filled; below the request when the book ran out.
+    pub notional: f64,
+    pub levels: usize,
+    /// The book held enough depth for the whole notional.
+    pub complete: bool,
+    /// Distance of `avg_price` from the best level, in percent.
+    pub slippage_pct: f64,
+    pub updated_ms: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct OrderBooks {
+    /// exchange -> "BASE/QUOTE" -> book.
+    inner: RwLock<HashMap<String, HashMap<String, Book>>>,
+}
+
+impl OrderBooks {
+    fn replace(&self, exchange: &str, symbol: &str, book: Book) {
+        let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        guard
+            .entry(exchange.to_string())
+            .or_default()
+            .insert(symbol.to_string(), book);
+    }
+
+    fn remove(&self, exchange: &str, symbol: &str) {
+        let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        if let Some(venue) = guard.get_mut(exchange) {
+            venue.remove(symbol);
+        }
+    }
+
+    /// Apply a diff if it continues the book's sequence; `false` means the
+    /// book has a gap (or is missing) and needs a fresh snapshot.
+    fn apply(&self, exchange: &str, symbol: &str, diff: &Diff) -> bool {
+        let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        let Some(book) = guard.get_mut(exchange).and_then(|v| v.get_mut(symbol)) else {
+            return false;
+        };
+        if diff.last <= book.last_update_id {
+            return true;
+        }
+        if diff.first > book.last_update_id + 1 {
+            return false;
+        }
+        apply_levels(&mut book.bids, &diff.bids);
+        apply_levels(&mut book.asks, &diff.asks);
+        book.last_update_id = diff.last;
+        book.updated_ms = now_ms();
+        true
+    }
+
+    /// Apply changes from a feed that orders them itself (Coinbase checks
+    /// its connection-wide sequence instead); `false` when the book is
+    /// missing.
+    fn update(
+        &self,
+        exchange: &str,
+        symbol: &str,
+        bids: &[(f64, f64)],
+        asks: &[(f64, f64)],
+    ) -> bool {
+        let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        let Some(book) = guard.get_mut(exchange).and_then(|v| v.get_mut(symbol)) else {
+            return false;
+        };
+        apply_levels(&mut book.bids, bids);
+        apply_levels(&mut book.asks, asks);
+        book.updated_ms = now_ms();
+        true
+    }
+
+    /// Average price and size of trading `notional` quote of `base/quote`
+    /// on `exchange`, or `None` when no local book is kept for the pair.
+    pub fn fill(
+        &self,
+        exchange: &str,
+        base: &str,
+        quote: &str,
+        side: Side,
+        notional: f64,
+    ) -> Option<Fill> {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        let book = guard.get(exchange)?.get(&pair_key(base, quote))?;
+        let best = book.side(side).next()?.0;
+        let (mut filled, mut base_qty, mut levels) = (0.0, 0.0, 0);
+        for (price, qty) in book.side(side) {
+            if filled >= notional {
+                break;
+            }
+            let take = qty.min((notional - filled) / price);
+            filled += take * price;
+            base_qty += take;
+            levels += 1;
+        }
+        let avg_price = if base_qty > 0.0 {
+            filled / base_qty
+        } else {
+            best
+        };
+        Some(Fill {
+            side,
+            avg_price,
+            base_qty,
+            notional: filled,
+            levels,
+            complete: filled >= notional * (1.0 - 1e-9),
+            slippage_pct: (avg_price - best).abs() / best * 100.0,
+            updated_ms: book.updated_ms,
+        })
+    }
+
+    /// Top `limit` levels of a locally kept book, best first.
+    pub fn depth(&self, exchange: &str, base: &str, quote: &str, limit: usize) -> Option<Depth> {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        let book = guard.get(exchange)?.get(&pair_key(base, quote))?;
+        Some(Depth {
+            bids: book.side(Side::Sell).take(limit).collect(),
+            asks: book.side(Side::Buy).take(limit).collect(),
+        })
+    }
+}
+
+/// One `depthUpdate` event: changes between update ids `first..=last`.
+struct Diff {
+    first: u64,
+    last: u64,
+    bids: Vec<(f64, f64)>,
+    asks: Vec<(f64, f64)>,
+}
+
+/// Spawn a book worker for every configured venue that has one.
+pub fn start(cfg: &OrderBookConfig) {
+    for exchange in &cfg.exchanges {
+        let Some(&name) = SUPPORTED.iter().find(|s| **s == exchange.as_str()) else {
+            warn!("order books are not supported for {}", exchange);
+            continue;
+        };
+        info!("starting {} order book worker (top {})", name, cfg.top_n);
+        let task = format!("{}-books", name);
+        match name {
+            "coinbase" => crate::tasks::spawn(&task, run_coinbase_books(cfg.clone())),
+            _ => crate::tasks::spawn(&task, run_binance_books(name, cfg.clone())),
+        };
+    }
+}
+
+/// Binance-style diff depth (`<symbol>@depth@100ms`) for the `top_n` pairs
+/// by quote volume, synced as Binance documents: buffer the stream, fetch a
+/// snapshot, drop events it already covers, and resync on any id gap.
+async fn run_binance_books(exchange: &'static str, cfg: OrderBookConfig) {
+    let ws_base = match exchange {
+        "binanceus" => "wss://stream.binance.us:9443/stream?streams=",
+        _ => config().connector(exchange).endpoint(
+            "wss://stream.binance.com:9443/stream?streams=",
+            "wss://stream.testnet.binance.vision/stream?streams=",
+        ),
+    };
+    let mut backoff = Backoff::default();
+    loop {
+        let symbols = match top_symbols(exchange, cfg.top_n, binance_stream).await {
+            Ok(s) if !s.is_empty() => s,
+            Ok(_) => {
+                warn!("{} books: no tickers to rank", exchange);
+                backoff.wait().await;
+                continue;
+            }
+            Err(e) => {
+                error!("{} books: ticker fetch failed: {}", exchange, e);
+                backoff.wait().await;
+                continue;
+            }
+        };
+        let streams: Vec<String> = symbols
+            .keys()
+            .map(|s| format!("{}@depth@100ms", s))
+            .collect();
+        let url = format!("{}{}", ws_base, streams.join("/"));
+        let mut ws = match connect_async(url.as_str()).await {
+            Ok((ws, _)) => ws,
+            Err(e) => {
+                error!("{} books connect error: {:?}", exchange, e);
+                backoff.wait().await;
+                continue;
+            }
+        };
+        info!("{} books: streaming {} symbols", exchange, symbols.len());
+        backoff = Backoff::default();
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        // Diffs received while a symbol waits for its snapshot.
+        let mut pending: HashMap<String, Vec<Diff>> = HashMap::new();
+        for stream_sym in symbols.keys() {
+            pending.insert(stream_sym.clone(), Vec::new());
+            request_snapshot(exchange, stream_sym, &symbols, cfg.snapshot_limit, &tx);
+        }
+
+        loop {
+            tokio::select! {
+                msg = ws.next() => match msg {
+                    Some(Ok(Message::Text(txt))) => {
+                        let Some((stream_sym, diff)) = parse_diff(&txt) else {
+                            continue;
+                        };
+                        let Some((base, quote)) = symbols.get(&stream_sym) else {
+                            continue;
+                        };
+                        if let Some(buffered) = pending.get_mut(&stream_sym) {
+                            buffered.push(diff);
+                        } else if !books().apply(exchange, &pair_key(base, quote), &diff) {
+                            warn!("{} books: gap in {}, resyncing", exchange, stream_sym);
+                            books().remove(exchange, &pair_key(base, quote));
+                            pending.insert(stream_sym.clone(), vec![diff]);
+                            request_snapshot(exchange, &stream_sym, &symbols, cfg.snapshot_limit, &tx);
+                        }
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => {
+                        error!("{} books read error: {:?}", exchange, e);
+                        break;
+                    }
+                    None => break,
+                },
+                Some((stream_sym, snapshot)) = rx.recv() => {
+                    let Some((base, quote)) = symbols.get(&stream_sym) else {
+                        continue;
+                    };
+                    let key = pair_key(base, quote);
+                    match snapshot {
+                        Ok((last_update_id, depth)) => {
+                            books().replace(exchange, &key, Book::from_snapshot(last_update_id, &depth));
+                            let buffered = pending.remove(&stream_sym).unwrap_or_default();
+                            if !buffered.iter().all(|d| books().apply(exchange, &key, d)) {
+                                warn!("{} books: snapshot for {} is behind the stream, retrying", exchange, stream_sym);
+                                books().remove(exchange, &key);
+                                pending.insert(stream_sym.clone(), Vec::new());
+                                request_snapshot(exchange, &stream_sym, &symbols, cfg.snapshot_limit, &tx);
+                            }
+                        }
+                        Err(e) => {
+                            warn!("{} books: snapshot for {} failed: {}", exchange, stream_sym, e);
+                            request_snapshot(exchange, &stream_sym, &symbols, cfg.snapshot_limit, &tx);
+                        }
+                    }
+                }
+            }
+        }
+        for (base, quote) in symbols.values() {
+            books().remove(exchange, &pair_key(base, quote));
+        }
+        backoff.wait().await;
+    }
+}
+
+type Snapshot = (String, Result<(u64, Depth), String>);
+
+/// Fetch a snapshot in the background; the venue's REST client paces it.
+fn request_snapshot(
+    exchange: &'static str,
+    stream_sym: &str,
+    symbols: &HashMap<String, (String, String)>,
+    limit: usize,
+    tx: &mpsc::UnboundedSender<Snapshot>,
+) {
+    let Some((base, quote)) = symbols.get(stream_sym).cloned() else {
+        return;
+    };
+    let (stream_sym, tx) = (stream_sym.to_string(), tx.clone());
+    tokio::spawn(async move {
+        let snapshot = match exchange {
+            "binanceus" => rest::binanceus::snapshot(&base, &quote, limit).await,
+            _ => rest::binance::snapshot(&base, &quote, limit).await,
+        };
+        let _ = tx.send((stream_sym, snapshot));
+    });
+}
+
+/// Venue symbol (as `symbol` spells it) -> (base, quote) for the `n` pairs
+/// with the highest quote volume.
+async fn top_symbols(
+    exchange: &str,
+    n: usize,
+    symbol: fn(&str, &str) -> String,
+) -> Result<HashMap<String, (String, String)>, String> {
+    let mut tickers = rest::tickers(exchange).await?;
+    tickers.sort_by(|a, b| (b.volume * b.price).total_cmp(&(a.volume * a.price)));
+    Ok(tickers
+        .into_iter()
+        .take(n)
+        .map(|t| (symbol(&t.base, &t.quote), (t.base, t.quote)))
+        .collect())
+}
+
+/// Lowercase stream symbol, e.g. "btcusdt".
+fn binance_stream(base: &str, quote: &str) -> String {
+    format!("{}{}", base, quote).to_lowercase()
+}
+
+/// Product id, e.g. "BTC-USD".
+fn coinbase_product(base: &str, quote: &str) -> String {
+    format!("{}-{}", base, quote)
+}
+
+/// `{"stream":"btcusdt@depth@100ms","data":{"e":"depthUpdate","s":"BTCUSDT",
+/// "U":157,"u":160,"b":[["65000.1","0.5"]],"a":[...]}}`.
+fn parse_diff(txt: &str) -> Option<(String, Diff)> {
+    let v: Value = serde_json::from_str(txt).ok()?;
+    let data = v.get("data")?;
+    let sym = data.get("s")?.as_str()?.to_lowercase();
+    let levels = |side: &str| -> Vec<(f64, f64)> {
+        data.get(side)
+            .and_then(|l| l.as_array())
+            .map(|l| {
+                l.iter()
+                    .filter_map(|lvl| Some((parse_f64(lvl.get(0))?, parse_f64(lvl.get(1))?)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let diff = Diff {
+        first: data.get("U")?.as_u64()?,
+        last: data.get("u")?.as_u64()?,
+        bids: levels("b"),
+        asks: levels("a"),
+    };
+    Some((sym, diff))
+}
+
+/// Coinbase Advanced Trade `level2` for the `top_n` products by quote
+/// volume. The channel opens with a full snapshot of each product, so no
+/// REST snapshot is needed. `sequence_num` counts every message on the
+/// connection; a skipped number means an update was lost, and the
+/// connection is re-established so every book starts from a new snapshot.
+async fn run_coinbase_books(cfg: OrderBookConfig) {
+    const EXCHANGE: &str = "coinbase";
+    let mut backoff = Backoff::default();
+    loop {
+        let products = match top_symbols(EXCHANGE, cfg.top_n, coinbase_product).await {
+            Ok(p) if !p.is_empty() => p,
+            Ok(_) => {
+                warn!("coinbase books: no tickers to rank");
+                backoff.wait().await;
+                continue;
+            }
+            Err(e) => {
+                error!("coinbase books: ticker fetch failed: {}", e);
+                backoff.wait().await;
+                continue;
+            }
+        };
+        let mut ws = match connect_async(COINBASE_WS_URL).await {
+            Ok((ws, _)) => ws,
+            Err(e) => {
+                error!("coinbase books connect error: {:?}", e);
+                backoff.wait().await;
+                continue;
+            }
+        };
+        let ids: Vec<&String> = products.keys().collect();
+        // Heartbeats keep the connection open while the books are quiet.
+        let subscribe = [
+            json!({ "type": "subscribe", "channel": "level2", "product_ids": ids }),
+            json!({ "type": "subscribe", "channel": "heartbeats" }),
+        ];
+        let mut sent = true;
+        for frame in subscribe {
+            sent &= ws.send(Message::Text(frame.to_string())).await.is_ok();
+        }
+        if !sent {
+            error!("coinbase books: subscribe failed");
+            backoff.wait().await;
+            continue;
+        }
+        info!("coinbase books: streaming {} products", products.len());
+        backoff = Backoff::default();
+
+        let mut last_seq: Option<u64> = None;
+        while let Some(msg) = ws.next().await {
+            let txt = match msg {
+                Ok(Message::Text(txt)) => txt,
+                Ok(_) => continue,
+                Err(e) => {
+                    error!("coinbase books read error: {:?}", e);
+                    break;
+                }
+            };
+            let Some((seq, events)) = parse_l2(&txt) else {
+                continue;
+            };
+            if last_seq.is_some_and(|last| seq != last + 1) {
+                warn!("coinbase books: sequence gap at {}, reconnecting", seq);
+                break;
+            }
+            last_seq = Some(seq);
+            for event in events {
+                let Some((base, quote)) = products.get(&event.product) else {
+                    continue;
+                };
+                let key = pair_key(base, quote);
+                if event.snapshot {
+                    let depth = Depth {
+                        bids: event.bids,
+                        asks: event.asks,
+                    };
+                    books().replace(EXCHANGE, &key, Book::from_snapshot(seq, &depth));
+                } else if !books().update(EXCHANGE, &key, &event.bids, &event.asks) {
+                    warn!(
+                        "coinbase books: update for {} before its snapshot",
+                        event.product
+                    );
+                }
+            }
+        }
+        for (base, quote) in products.values() {
+            books().remove(EXCHANGE, &pair_key(base, quote));
+        }
+        backoff.wait().await;
+    }
+}
+
+/// One product's part of an `l2_data` message.
+struct L2Event {
+    product: String,
+    snapshot: bool,
+    bids: Vec<(f64, f64)>,
+    asks: Vec<(f64, f64)>,
+}
+
+/// The connection sequence number of any message, with the level2 events
+/// it carries: `{"channel":"l2_data","sequence_num":4,"events":[{"type":
+/// "update","product_id":"BTC-USD","updates":[{"side":"bid","price_level":
+/// "21921.73","new_quantity":"0.06"}]}]}`. Asks are `"side":"offer"`.
+fn parse_l2(txt: &str) -> Option<(u64, Vec<L2Event>)> {
+    let v: Value = serde_json::from_str(txt).ok()?;
+    let seq = v.get("sequence_num")?.as_u64()?;
+    if v.get("channel").and_then(|c| c.as_str()) != Some("l2_data") {
+        return Some((seq, Vec::new()));
+    }
+    let events = v
+        .get("events")
+        .and_then(|e| e.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|e| {
+            let mut event = L2Event {
+                product: e.get("product_id")?.as_str()?.to_string(),
+                snapshot: e.get("type").and_then(|t| t.as_str()) == Some("snapshot"),
+                bids: Vec::new(),
+                asks: Vec::new(),
+            };
+            for u in e.get("updates")?.as_array()? {
+                let Some(level) =
+                    parse_f64(u.get("price_level")).zip(parse_f64(u.get("new_quantity")))
+                else {
+                    continue;
+                };
+                match u.get("side").and_then(|s| s.as_str()) {
+                    Some("bid") => event.bids.push(level),
+                    Some("offer") | Some("ask") => event.asks.push(level),
+                    _ => {}
+                }
+            }
+            Some(event)
+        })
+        .collect();
+    Some((seq, events))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coinbase_snapshot_then_update_builds_the_book() {
+        let snapshot = r#"{"channel":"l2_data","client_id":"","timestamp":"2023-02-09T20:32:50.714964855Z","sequence_num":0,"events":[{"type":"snapshot","product_id":"BTC-USD","updates":[{"side":"bid","event_time":"1970-01-01T00:00:00Z","price_level":"100","new_quantity":"1"},{"side":"bid","event_time":"1970-01-01T00:00:00Z","price_level":"99","new_quantity":"2"},{"side":"offer","event_time":"1970-01-01T00:00:00Z","price_level":"101","new_quantity":"1"},{"side":"offer","event_time":"1970-01-01T00:00:00Z","price_level":"102","new_quantity":"3"}]}]}"#;
+        let update = r#"{"channel":"l2_data","client_id":"","timestamp":"2023-02-09T20:32:51.000000000Z","sequence_num":1,"events":[{"type":"update","product_id":"BTC-USD","updates":[{"side":"offer","event_time":"2023-02-09T20:32:50.7Z","price_level":"101","new_quantity":"0"}]}]}"#;
+
+        let (seq, events) = parse_l2(snapshot).unwrap();
+        assert_eq!(seq, 0);
+        assert!(events[0].snapshot);
+        assert_eq!(events[0].bids, [(100.0, 1.0), (99.0, 2.0)]);
+        assert_eq!(events[0].asks, [(101.0, 1.0), (102.0, 3.0)]);
+
+        let books = OrderBooks::default();
+        let depth = Depth {
+            bids: events[0].bids.clone(),
+            asks: events[0].asks.clone(),
+        };
+        books.replace("coinbase", "BTC/USD", Book::from_snapshot(seq, &depth));
+        let (seq, events) = parse_l2(update).unwrap();
+        assert_eq!(seq, 1);
+        assert!(books.update("coinbase", "BTC/USD", &events[0].bids, &events[0].asks));
+
+        let depth = books.depth("coinbase", "BTC", "USD", 10).unwrap();
+        assert_eq!(depth.asks, [(102.0, 3.0)]);
+        assert_eq!(depth.bids, [(100.0, 1.0), (99.0, 2.0)]);
+    }
+
+    #[test]
+    fn coinbase_heartbeats_carry_the_sequence() {
+        let heartbeat = r#"{"channel":"heartbeats","client_id":"","timestamp":"2023-06-23T20:31:26.122969572Z","sequence_num":7,"events":[{"current_time":"2023-06-23 20:31:56.121961769 +0000 UTC m=+91717.525857105","heartbeat_counter":3049}]}"#;
+        let (seq, events) = parse_l2(heartbeat).unwrap();
+        assert_eq!(seq, 7);
+        assert!(events.is_empty());
+    }
+}
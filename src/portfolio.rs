@@ -0,0 +1,265 @@
+//! Balances of the configured accounts valued in USD from the live price
+//! store, with exposure per asset across all accounts.
+
+use crate::config::{config, AccountConfig};
+use crate::exchanges::{mock, synthetic};
+use crate::rest::{self, Balance};
+use crate::store::{pair_key, SharedPrices};
+use crate::utils::now_ms;
+use futures::future::join_all;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use tokio::sync::Mutex;
+use utoipa::ToSchema;
+
+/// Assets valued at 1 USD.
+const USD_LIKE: [&str; 5] = ["USD", "USDT", "USDC", "FDUSD", "TUSD"];
+/// Feeds whose prices are made up, never used for valuation.
+const SIMULATED: [&str; 2] = [mock::EXCHANGE, synthetic::EXCHANGE];
+/// Assets tried as a bridge when an asset has no direct USD quote.
+const BRIDGES: [&str; 2] = ["BTC", "ETH"];
+/// Balances are re-fetched at most this often: each fetch is a signed
+/// request counted against the keys' rate limit.
+const BALANCES_TTL_MS: u64 = 60_000;
+
+/// Per-account fetch result, in `accounts` order.
+type AccountBalances = Vec<Result<Vec<Balance>, String>>;
+
+/// Last fetch time and result. Held across the fetch so concurrent requests
+/// wait for one round of calls.
+static BALANCES: Lazy<Mutex<Option<(u64, AccountBalances)>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Portfolio {
+    /// When the balances were fetched; prices are always the latest.
+    pub fetched_ms: u64,
+    pub total_usd: f64,
+    /// Per asset across accounts, largest USD value first.
+    pub exposure: Vec<Exposure>,
+    pub accounts: Vec<AccountHoldings>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AccountHoldings {
+    pub name: String,
+    pub exchange: String,
+    pub total_usd: f64,
+    pub holdings: Vec<Holding>,
+    /// Why the balances couldn't be fetched; the account counts as empty.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Holding {
+    pub asset: String,
+    pub free: f64,
+    pub locked: f64,
+    /// Absent when no USD price is known for the asset.
+    pub usd: Option<f64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Exposure {
+    pub asset: String,
+    pub amount: f64,
+    pub usd: Option<f64>,
+    /// Share of the valued total, 0..1.
+    pub share: Option<f64>,
+}
+
+/// Every configured account's balances (cached for [`BALANCES_TTL_MS`])
+/// valued at the latest prices.
+pub async fn portfolio(prices: &SharedPrices) -> Portfolio {
+    let accounts = &config().accounts;
+    let (fetched_ms, fetched) = cached_balances(accounts).await;
+    let usd = UsdPrices::new(prices);
+
+    let accounts: Vec<AccountHoldings> = accounts
+        .iter()
+        .zip(fetched)
+        .map(|(account, balances)| holdings(account, balances, &usd))
+        .collect();
+
+    let mut amounts: BTreeMap<String, f64> = BTreeMap::new();
+    for h in accounts.iter().flat_map(|a| &a.holdings) {
+        *amounts.entry(h.asset.clone()).or_default() += h.free + h.locked;
+    }
+    let total_usd = accounts.iter().map(|a| a.total_usd).fold(0.0, |a, b| a + b);
+    let mut exposure: Vec<Exposure> = amounts
+        .into_iter()
+        .map(|(asset, amount)| {
+            let value = usd.price(None, &asset).map(|p| p * amount);
+            Exposure {
+                share: value.filter(|_| total_usd > 0.0).map(|v| v / total_usd),
+                usd: value,
+                asset,
+                amount,
+            }
+        })
+        .collect();
+    exposure.sort_by(|a, b| b.usd.unwrap_or(0.0).total_cmp(&a.usd.unwrap_or(0.0)));
+
+    Portfolio {
+        fetched_ms,
+        total_usd,
+        exposure,
+        accounts,
+    }
+}
+
+async fn cached_balances(accounts: &[AccountConfig]) -> (u64, AccountBalances) {
+    let mut cache = BALANCES.lock().await;
+    if let Some((fetched_ms, balances)) = cache.as_ref() {
+        if now_ms().saturating_sub(*fetched_ms) < BALANCES_TTL_MS {
+            return (*fetched_ms, balances.clone());
+        }
+    }
+    let balances = join_all(accounts.iter().map(rest::balances)).await;
+    let fetched_ms = now_ms();
+    *cache = Some((fetched_ms, balances.clone()));
+    (fetched_ms, balances)
+}
+
+fn holdings(
+    account: &AccountConfig,
+    balances: Result<Vec<Balance>, String>,
+    usd: &UsdPrices,
+) -> AccountHoldings {
+    let (balances, error) = match balances {
+        Ok(b) => (b, None),
+        Err(e) => (Vec::new(), Some(e)),
+    };
+    let venue = account.exchange.to_lowercase();
+    let holdings: Vec<Holding> = balances
+        .into_iter()
+        .map(|b| Holding {
+            usd: usd
+                .price(Some(&venue), &b.asset)
+                .map(|p| p * (b.free + b.locked)),
+            asset: b.asset,
+            free: b.free,
+            locked: b.locked,
+        })
+        .collect();
+    AccountHoldings {
+        name: account.name.clone(),
+        exchange: account.exchange.clone(),
+        total_usd: holdings
+            .iter()
+            .filter_map(|h| h.usd)
+            .fold(0.0, |a, b| a + b),
+        holdings,
+        error,
+    }
+}
+
+/// Live spot prices of every "BASE/QUOTE", per exchange and as the median
+/// across exchanges. Derivatives, prices restored from a checkpoint and the
+/// simulated feeds are left out.
+struct UsdPrices {
+    by_venue: HashMap<String, HashMap<String, f64>>,
+    median: HashMap<String, f64>,
+}
+
+impl UsdPrices {
+    fn new(prices: &SharedPrices) -> Self {
+        let mut by_venue = HashMap::new();
+        for exchange in prices.exchanges() {
+            if SIMULATED.contains(&exchange.as_str()) {
+                continue;
+            }
+            let restored = prices.restored_symbols(&exchange);
+            let pairs: HashMap<String, f64> = prices
+                .snapshot(&exchange)
+                .into_iter()
+                .filter(|p| p.is_spot && p.price > 0.0)
+                .map(|p| (pair_key(&p.base, &p.quote), p.price))
+                .filter(|(key, _)| !restored.contains(key))
+                .collect();
+            by_venue.insert(exchange, pairs);
+        }
+        Self::from_venues(by_venue)
+    }
+
+    fn from_venues(by_venue: HashMap<String, HashMap<String, f64>>) -> Self {
+        let mut quotes: HashMap<&str, Vec<f64>> = HashMap::new();
+        for (key, price) in by_venue.values().flatten() {
+            quotes.entry(key).or_default().push(*price);
+        }
+        let median = quotes
+            .into_iter()
+            .map(|(key, mut ps)| {
+                ps.sort_by(f64::total_cmp);
+                let mid = ps.len() / 2;
+                let median = if ps.len() % 2 == 0 {
+                    (ps[mid - 1] + ps[mid]) / 2.0
+                } else {
+                    ps[mid]
+                };
+                (key.to_string(), median)
+            })
+            .collect();
+        Self { by_venue, median }
+    }
+
+    /// USD price of `asset`: stablecoins are 1, others go through a USD
+    /// quote directly or via BTC/ETH. Each pair is taken from `venue` when
+    /// it quotes it, else from the median across venues.
+    fn price(&self, venue: Option<&str>, asset: &str) -> Option<f64> {
+        if USD_LIKE.contains(&asset) {
+            return Some(1.0);
+        }
+        self.direct(venue, asset).or_else(|| {
+            BRIDGES.iter().find_map(|bridge| {
+                let in_bridge = self.pair(venue, asset, bridge)?;
+                Some(in_bridge * self.direct(venue, bridge)?)
+            })
+        })
+    }
+
+    fn direct(&self, venue: Option<&str>, asset: &str) -> Option<f64> {
+        USD_LIKE.iter().find_map(|usd| {
+            self.pair(venue, asset, usd).or_else(|| {
+                let inverse = self.pair(venue, usd, asset)?;
+                Some(1.0 / inverse)
+            })
+        })
+    }
+
+    fn pair(&self, venue: Option<&str>, base: &str, quote: &str) -> Option<f64> {
+        let key = pair_key(base, quote);
+        venue
+            .and_then(|venue| self.by_venue.get(venue)?.get(&key))
+            .or_else(|| self.median.get(&key))
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_account_venue_wins_over_the_median() {
+        let venue = |pairs: &[(&str, f64)]| -> HashMap<String, f64> {
+            pairs.iter().map(|&(k, p)| (k.to_string(), p)).collect()
+        };
+        let usd = UsdPrices::from_venues(HashMap::from([
+            (
+                "a".to_string(),
+                venue(&[("BTC/USDT", 100.0), ("SOL/BTC", 0.5)]),
+            ),
+            ("b".to_string(), venue(&[("BTC/USDT", 110.0)])),
+            ("c".to_string(), venue(&[("BTC/USDT", 130.0)])),
+        ]));
+        assert_eq!(usd.price(Some("a"), "BTC"), Some(100.0));
+        assert_eq!(usd.price(Some("b"), "BTC"), Some(110.0));
+        assert_eq!(usd.price(None, "BTC"), Some(110.0));
+        // SOL goes through BTC: on "b" at b's BTC price, elsewhere the median.
+        assert_eq!(usd.price(Some("b"), "SOL"), Some(55.0));
+        assert_eq!(usd.price(Some("d"), "SOL"), Some(55.0));
+        assert_eq!(usd.price(Some("a"), "SOL"), Some(50.0));
+    }
+}
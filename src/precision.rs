@@ -0,0 +1,99 @@
+//! Response-level number formatting: `?decimals=N` rounds every fractional
+//! number (prices, profits, scores) and `?numbers=string` emits them as
+//! strings, so clients don't have to cope with f64 artifacts like
+//! `0.30000000000000004`. Integers (IDs, timestamps, counts) are left alone.
+
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use serde_json::{Number, Value};
+use utoipa::{IntoParams, ToSchema};
+
+/// Most decimal places honoured; f64 carries no more than this anyway.
+const MAX_DECIMALS: u32 = 15;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum NumberFormat {
+    #[default]
+    Number,
+    String,
+}
+
+/// Query parameters accepted by the scan, history and stats endpoints.
+#[derive(Debug, Clone, Copy, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct Precision {
+    /// Decimal places for fractional numbers (at most 15); unset keeps full
+    /// precision.
+    pub decimals: Option<u32>,
+    #[serde(default)]
+    pub numbers: NumberFormat,
+}
+
+impl Precision {
+    fn is_default(&self) -> bool {
+        self.decimals.is_none() && self.numbers == NumberFormat::Number
+    }
+
+    /// `value` as a JSON response with this formatting applied; serialized
+    /// directly (keeping field order) when no formatting was asked for.
+    pub fn json<T: Serialize>(&self, value: &T) -> Response {
+        if self.is_default() {
+            return Json(value).into_response();
+        }
+        let mut v = serde_json::to_value(value).unwrap_or(Value::Null);
+        self.apply(&mut v);
+        Json(v).into_response()
+    }
+
+    /// Rewrite every fractional number in `value` in place.
+    pub fn apply(&self, value: &mut Value) {
+        if self.is_default() {
+            return;
+        }
+        match value {
+            Value::Number(n) if n.is_f64() => {
+                if let Some(f) = n.as_f64() {
+                    *value = self.format(f);
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|v| self.apply(v)),
+            Value::Object(map) => map.values_mut().for_each(|v| self.apply(v)),
+            _ => {}
+        }
+    }
+
+    fn format(&self, f: f64) -> Value {
+        match (self.numbers, self.decimals.map(|d| d.min(MAX_DECIMALS))) {
+            (NumberFormat::String, Some(d)) => Value::String(format!("{:.*}", d as usize, f)),
+            (NumberFormat::String, None) => Value::String(f.to_string()),
+            (NumberFormat::Number, Some(d)) => {
+                let scale = 10f64.powi(d as i32);
+                Number::from_f64((f * scale).round() / scale)
+                    .map(Value::Number)
+                    .unwrap_or(Value::Null)
+            }
+            (NumberFormat::Number, None) => Number::from_f64(f)
+                .map(Value::Number)
+                .unwrap_or(Value::Null),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimals_are_capped() {
+        let precision = Precision {
+            decimals: Some(u32::MAX),
+            numbers: NumberFormat::String,
+        };
+        assert_eq!(
+            precision.format(0.5),
+            Value::String("0.500000000000000".into())
+        );
+    }
+}
@@ -0,0 +1,71 @@
+use crate::exchanges::Delta;
+use crate::models::PairPrice;
+use serde::{Deserialize, Serialize};
+
+/// Bumped whenever `PriceUpdate` changes incompatibly; nodes drop messages
+/// of other versions instead of misreading them.
+pub const VERSION: u32 = 1;
+
+/// One store flush, as published by a collector node and applied by API
+/// nodes to their own store.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceUpdate {
+    pub v: u32,
+    pub exchange: String,
+    /// Collector time of the flush.
+    pub at_ms: u64,
+    pub changed: Vec<PairPrice>,
+    /// "BASE/QUOTE" keys no longer tracked.
+    pub removed: Vec<String>,
+    /// `changed` is everything the collector holds for the exchange, sent
+    /// periodically so subscribers that missed deltas converge.
+    #[serde(default)]
+    pub full: bool,
+}
+
+impl PriceUpdate {
+    pub fn new(exchange: &str, delta: &Delta, at_ms: u64) -> Self {
+        Self {
+            v: VERSION,
+            exchange: exchange.to_string(),
+            at_ms,
+            changed: delta.changed.clone(),
+            removed: delta.removed.clone(),
+            full: false,
+        }
+    }
+
+    pub fn full(exchange: &str, pairs: Vec<PairPrice>, at_ms: u64) -> Self {
+        Self {
+            v: VERSION,
+            exchange: exchange.to_string(),
+            at_ms,
+            changed: pairs,
+            removed: Vec::new(),
+            full: true,
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn decode(payload: &[u8]) -> Result<Self, String> {
+        let update: Self = serde_json::from_slice(payload).map_err(|e| e.to_string())?;
+        if update.v != VERSION {
+            return Err(format!(
+                "protocol version {} (expected {})",
+                update.v, VERSION
+            ));
+        }
+        Ok(update)
+    }
+
+    pub fn into_delta(self) -> (String, Delta) {
+        let delta = Delta {
+            changed: self.changed,
+            removed: self.removed,
+        };
+        (self.exchange, delta)
+    }
+}
@@ -0,0 +1,118 @@
+use super::{as_f64, levels, num, opt_num, Depth, Instrument, RestClient};
+use crate::models::PairPrice;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::Value;
+
+static CLIENT: Lazy<RestClient> = Lazy::new(|| RestClient::new("https://ascendex.com", 10));
+
+/// AscendEX wraps every payload as `{"code":0,"data":...}`.
+#[derive(Deserialize)]
+struct Envelope<T> {
+    code: i64,
+    #[serde(default)]
+    message: String,
+    data: Option<T>,
+}
+
+impl<T> Envelope<T> {
+    fn into_data(self) -> Result<T, String> {
+        match (self.code, self.data) {
+            (0, Some(data)) => Ok(data),
+            (code, _) => Err(format!("ascendex error {}: {}", code, self.message)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProductInfo {
+    /// "BTC/USDT".
+    symbol: String,
+    base_asset: String,
+    quote_asset: String,
+    status: String,
+}
+
+#[derive(Deserialize)]
+struct Ticker {
+    symbol: String,
+    #[serde(deserialize_with = "num")]
+    close: f64,
+    #[serde(default, deserialize_with = "opt_num")]
+    volume: Option<f64>,
+    /// `["price", "size"]`.
+    #[serde(default)]
+    bid: Vec<Value>,
+    #[serde(default)]
+    ask: Vec<Value>,
+}
+
+#[derive(Deserialize)]
+struct DepthData {
+    data: Book,
+}
+
+#[derive(Deserialize)]
+struct Book {
+    bids: Vec<Vec<Value>>,
+    asks: Vec<Vec<Value>>,
+}
+
+/// "BTC/USDT" -> ("BTC", "USDT").
+fn split_symbol(symbol: &str) -> Option<(String, String)> {
+    let (base, quote) = symbol.split_once('/')?;
+    Some((base.to_uppercase(), quote.to_uppercase()))
+}
+
+/// `GET /api/pro/v1/cash/products`.
+pub async fn instruments() -> Result<Vec<Instrument>, String> {
+    let env: Envelope<Vec<ProductInfo>> = CLIENT.get("/api/pro/v1/cash/products", &[]).await?;
+    Ok(env
+        .into_data()?
+        .into_iter()
+        .map(|p| Instrument {
+            trading: p.status == "Normal",
+            symbol: p.symbol,
+            base: p.base_asset.to_uppercase(),
+            quote: p.quote_asset.to_uppercase(),
+            ..Default::default()
+        })
+        .collect())
+}
+
+/// `GET /api/pro/v1/spot/ticker` (every symbol when none is given).
+pub async fn tickers() -> Result<Vec<PairPrice>, String> {
+    let env: Envelope<Vec<Ticker>> = CLIENT.get("/api/pro/v1/spot/ticker", &[]).await?;
+    Ok(env
+        .into_data()?
+        .into_iter()
+        .filter_map(|t| {
+            let (base, quote) = split_symbol(&t.symbol)?;
+            Some(PairPrice {
+                base,
+                quote,
+                price: t.close,
+                is_spot: true,
+                volume: t.volume.unwrap_or(0.0),
+                event_time: 0,
+                bid: t.bid.first().and_then(as_f64),
+                ask: t.ask.first().and_then(as_f64),
+            })
+        })
+        .collect())
+}
+
+/// `GET /api/pro/v1/depth`, truncated to `limit` levels.
+pub async fn depth(base: &str, quote: &str, limit: usize) -> Result<Depth, String> {
+    let symbol = format!("{}/{}", base, quote).to_uppercase();
+    let env: Envelope<DepthData> = CLIENT
+        .get("/api/pro/v1/depth", &[("symbol", &symbol)])
+        .await?;
+    let book = env.into_data()?.data;
+    let limit = limit.max(1);
+    Ok(Depth {
+        bids: levels(&book.bids).into_iter().take(limit).collect(),
+        asks: levels(&book.asks).into_iter().take(limit).collect(),
+    })
+}
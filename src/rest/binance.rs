@@ -0,0 +1,239 @@
+use super::{as_f64, hex, hmac, levels, num, opt_num, Balance, Depth, Instrument, RestClient};
+use crate::config::{config, AccountConfig};
+use crate::models::PairPrice;
+use crate::utils::now_ms;
+use once_cell::sync::Lazy;
+use ring::hmac::HMAC_SHA256;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+static CLIENT: Lazy<RestClient> = Lazy::new(|| {
+    let base = config()
+        .connector("binance")
+        .endpoint("https://api.binance.com", "https://testnet.binance.vision");
+    RestClient::new(base, 10)
+});
+
+#[derive(Deserialize)]
+struct ExchangeInfo {
+    symbols: Vec<SymbolInfo>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SymbolInfo {
+    symbol: String,
+    status: String,
+    base_asset: String,
+    quote_asset: String,
+    #[serde(default)]
+    filters: Vec<Value>,
+}
+
+impl SymbolInfo {
+    /// A field of the first filter of `kind`, e.g. `PRICE_FILTER.tickSize`.
+    fn filter(&self, kind: &str, field: &str) -> Option<f64> {
+        self.filters
+            .iter()
+            .find(|f| f.get("filterType").and_then(|t| t.as_str()) == Some(kind))
+            .and_then(|f| as_f64(f.get(field)?))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Ticker24h {
+    symbol: String,
+    #[serde(deserialize_with = "num")]
+    last_price: f64,
+    #[serde(default, deserialize_with = "opt_num")]
+    bid_price: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    ask_price: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    volume: Option<f64>,
+    #[serde(default)]
+    close_time: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BookTicker {
+    symbol: String,
+    #[serde(deserialize_with = "num")]
+    bid_price: f64,
+    #[serde(deserialize_with = "num")]
+    ask_price: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Book {
+    #[serde(default)]
+    last_update_id: u64,
+    bids: Vec<Vec<Value>>,
+    asks: Vec<Vec<Value>>,
+}
+
+#[derive(Deserialize)]
+struct Account {
+    balances: Vec<AssetBalance>,
+}
+
+#[derive(Deserialize)]
+struct AssetBalance {
+    asset: String,
+    #[serde(deserialize_with = "num")]
+    free: f64,
+    #[serde(deserialize_with = "num")]
+    locked: f64,
+}
+
+/// `GET /api/v3/account`, HMAC-SHA256 signed over the query string.
+pub async fn balances(account: &AccountConfig) -> Result<Vec<Balance>, String> {
+    let query = format!("timestamp={}&recvWindow=5000", now_ms());
+    let signature = hex(&hmac(HMAC_SHA256, &account.api_secret, &query));
+    let query = format!("{}&signature={}", query, signature);
+    let headers = [("X-MBX-APIKEY", account.api_key.clone())];
+    let info: Account = CLIENT
+        .get_signed("/api/v3/account", &query, &headers)
+        .await?;
+    Ok(info
+        .balances
+        .into_iter()
+        .map(|b| Balance {
+            asset: b.asset.to_uppercase(),
+            free: b.free,
+            locked: b.locked,
+        })
+        .collect())
+}
+
+/// `GET /api/v3/exchangeInfo`.
+pub async fn instruments() -> Result<Vec<Instrument>, String> {
+    instruments_at(&CLIENT).await
+}
+
+/// `GET /api/v3/ticker/24hr` for every symbol, split using the instrument list.
+pub async fn tickers() -> Result<Vec<PairPrice>, String> {
+    tickers_at(&CLIENT).await
+}
+
+/// `GET /api/v3/ticker/bookTicker` for every symbol: best bid/ask priced at
+/// the mid, without volume.
+pub async fn book_tickers() -> Result<Vec<PairPrice>, String> {
+    book_tickers_at(&CLIENT).await
+}
+
+/// `GET /api/v3/depth`.
+pub async fn depth(base: &str, quote: &str, limit: usize) -> Result<Depth, String> {
+    depth_at(&CLIENT, base, quote, limit).await
+}
+
+/// `instruments()` against another host serving the same API (Binance.US).
+pub(crate) async fn instruments_at(client: &RestClient) -> Result<Vec<Instrument>, String> {
+    let info: ExchangeInfo = client.get("/api/v3/exchangeInfo", &[]).await?;
+    Ok(info
+        .symbols
+        .into_iter()
+        .map(|s| Instrument {
+            trading: s.status == "TRADING",
+            tick_size: s.filter("PRICE_FILTER", "tickSize"),
+            step_size: s.filter("LOT_SIZE", "stepSize"),
+            min_notional: s
+                .filter("NOTIONAL", "minNotional")
+                .or_else(|| s.filter("MIN_NOTIONAL", "minNotional")),
+            symbol: s.symbol,
+            base: s.base_asset.to_uppercase(),
+            quote: s.quote_asset.to_uppercase(),
+            status: Some(s.status),
+        })
+        .collect())
+}
+
+pub(crate) async fn tickers_at(client: &RestClient) -> Result<Vec<PairPrice>, String> {
+    let split: HashMap<String, Instrument> = instruments_at(client)
+        .await?
+        .into_iter()
+        .filter(|i| i.trading)
+        .map(|i| (i.symbol.clone(), i))
+        .collect();
+    let raw: Vec<Ticker24h> = client.get("/api/v3/ticker/24hr", &[]).await?;
+    Ok(raw
+        .into_iter()
+        .filter_map(|t| {
+            let inst = split.get(&t.symbol)?;
+            Some(PairPrice {
+                base: inst.base.clone(),
+                quote: inst.quote.clone(),
+                price: t.last_price,
+                is_spot: true,
+                volume: t.volume.unwrap_or(0.0),
+                event_time: t.close_time,
+                bid: t.bid_price,
+                ask: t.ask_price,
+            })
+        })
+        .collect())
+}
+
+pub(crate) async fn book_tickers_at(client: &RestClient) -> Result<Vec<PairPrice>, String> {
+    let split: HashMap<String, Instrument> = instruments_at(client)
+        .await?
+        .into_iter()
+        .filter(|i| i.trading)
+        .map(|i| (i.symbol.clone(), i))
+        .collect();
+    let raw: Vec<BookTicker> = client.get("/api/v3/ticker/bookTicker", &[]).await?;
+    let now = now_ms();
+    Ok(raw
+        .into_iter()
+        .filter(|t| t.bid_price > 0.0 && t.ask_price > 0.0)
+        .filter_map(|t| {
+            let inst = split.get(&t.symbol)?;
+            Some(PairPrice {
+                base: inst.base.clone(),
+                quote: inst.quote.clone(),
+                price: (t.bid_price + t.ask_price) / 2.0,
+                is_spot: true,
+                volume: 0.0,
+                event_time: now,
+                bid: Some(t.bid_price),
+                ask: Some(t.ask_price),
+            })
+        })
+        .collect())
+}
+
+pub(crate) async fn depth_at(
+    client: &RestClient,
+    base: &str,
+    quote: &str,
+    limit: usize,
+) -> Result<Depth, String> {
+    Ok(snapshot_at(client, base, quote, limit).await?.1)
+}
+
+/// `GET /api/v3/depth` with the `lastUpdateId` a diff stream resumes from.
+pub async fn snapshot(base: &str, quote: &str, limit: usize) -> Result<(u64, Depth), String> {
+    snapshot_at(&CLIENT, base, quote, limit).await
+}
+
+pub(crate) async fn snapshot_at(
+    client: &RestClient,
+    base: &str,
+    quote: &str,
+    limit: usize,
+) -> Result<(u64, Depth), String> {
+    let symbol = format!("{}{}", base, quote).to_uppercase();
+    let limit = limit.clamp(1, 5000).to_string();
+    let book: Book = client
+        .get("/api/v3/depth", &[("symbol", &symbol), ("limit", &limit)])
+        .await?;
+    let depth = Depth {
+        bids: levels(&book.bids),
+        asks: levels(&book.asks),
+    };
+    Ok((book.last_update_id, depth))
+}
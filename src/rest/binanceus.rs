@@ -0,0 +1,27 @@
+//! Binance.US serves the Binance REST API from its own host.
+
+use super::{binance, Depth, Instrument, RestClient};
+use crate::models::PairPrice;
+use once_cell::sync::Lazy;
+
+static CLIENT: Lazy<RestClient> = Lazy::new(|| RestClient::new("https://api.binance.us", 10));
+
+pub async fn instruments() -> Result<Vec<Instrument>, String> {
+    binance::instruments_at(&CLIENT).await
+}
+
+pub async fn tickers() -> Result<Vec<PairPrice>, String> {
+    binance::tickers_at(&CLIENT).await
+}
+
+pub async fn book_tickers() -> Result<Vec<PairPrice>, String> {
+    binance::book_tickers_at(&CLIENT).await
+}
+
+pub async fn depth(base: &str, quote: &str, limit: usize) -> Result<Depth, String> {
+    binance::depth_at(&CLIENT, base, quote, limit).await
+}
+
+pub async fn snapshot(base: &str, quote: &str, limit: usize) -> Result<(u64, Depth), String> {
+    binance::snapshot_at(&CLIENT, base, quote, limit).await
+}
@@ -0,0 +1,145 @@
+use super::{levels, num, opt_num, Depth, Instrument, RestClient};
+use crate::models::PairPrice;
+use crate::utils::now_ms;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::Value;
+
+static CLIENT: Lazy<RestClient> = Lazy::new(|| RestClient::new("https://open-api.bingx.com", 10));
+
+/// BingX wraps every payload as `{"code":0,"msg":"","data":...}`.
+#[derive(Deserialize)]
+struct Envelope<T> {
+    code: i64,
+    #[serde(default)]
+    msg: String,
+    data: Option<T>,
+}
+
+impl<T> Envelope<T> {
+    fn into_data(self) -> Result<T, String> {
+        match (self.code, self.data) {
+            (0, Some(data)) => Ok(data),
+            (code, _) => Err(format!("bingx error {}: {}", code, self.msg)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Symbols {
+    symbols: Vec<SymbolInfo>,
+}
+
+#[derive(Deserialize)]
+struct SymbolInfo {
+    /// "BTC-USDT".
+    symbol: String,
+    /// 1 online, 0 offline, 5 pre-open, 25 suspended.
+    status: i64,
+}
+
+/// Percent fields ("priceChangePercent": "-1.23%") are not read, so they
+/// can't fail the parse.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Ticker24h {
+    symbol: String,
+    #[serde(deserialize_with = "num")]
+    last_price: f64,
+    #[serde(default, deserialize_with = "opt_num")]
+    bid_price: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    ask_price: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    volume: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    quote_volume: Option<f64>,
+    #[serde(default)]
+    close_time: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct Book {
+    bids: Vec<Vec<Value>>,
+    asks: Vec<Vec<Value>>,
+}
+
+/// `GET /openApi/spot/v1/common/symbols`.
+pub async fn instruments() -> Result<Vec<Instrument>, String> {
+    let ts = now_ms().to_string();
+    let env: Envelope<Symbols> = CLIENT
+        .get("/openApi/spot/v1/common/symbols", &[("timestamp", &ts)])
+        .await?;
+    Ok(env
+        .into_data()?
+        .symbols
+        .into_iter()
+        .filter_map(|s| {
+            let (base, quote) = s.symbol.split_once('-')?;
+            Some(Instrument {
+                base: base.to_uppercase(),
+                quote: quote.to_uppercase(),
+                trading: s.status == 1,
+                symbol: s.symbol,
+                ..Default::default()
+            })
+        })
+        .collect())
+}
+
+async fn raw_tickers() -> Result<Vec<Ticker24h>, String> {
+    let ts = now_ms().to_string();
+    let env: Envelope<Vec<Ticker24h>> = CLIENT
+        .get("/openApi/spot/v1/ticker/24hr", &[("timestamp", &ts)])
+        .await?;
+    env.into_data()
+}
+
+/// `GET /openApi/spot/v1/ticker/24hr`.
+pub async fn tickers() -> Result<Vec<PairPrice>, String> {
+    Ok(raw_tickers()
+        .await?
+        .into_iter()
+        .filter_map(|t| {
+            let (base, quote) = t.symbol.split_once('-')?;
+            Some(PairPrice {
+                base: base.to_uppercase(),
+                quote: quote.to_uppercase(),
+                price: t.last_price,
+                is_spot: true,
+                volume: t.volume.unwrap_or(0.0),
+                event_time: t.close_time.unwrap_or(0),
+                bid: t.bid_price,
+                ask: t.ask_price,
+            })
+        })
+        .collect())
+}
+
+/// Symbols ordered by 24h quote volume, most traded first.
+pub async fn most_traded() -> Result<Vec<String>, String> {
+    let mut raw = raw_tickers().await?;
+    raw.sort_by(|a, b| {
+        let (a, b) = (a.quote_volume.unwrap_or(0.0), b.quote_volume.unwrap_or(0.0));
+        b.total_cmp(&a)
+    });
+    Ok(raw.into_iter().map(|t| t.symbol).collect())
+}
+
+/// `GET /openApi/spot/v1/market/depth`.
+pub async fn depth(base: &str, quote: &str, limit: usize) -> Result<Depth, String> {
+    let symbol = format!("{}-{}", base, quote).to_uppercase();
+    let limit = limit.clamp(1, 1000).to_string();
+    let ts = now_ms().to_string();
+    let env: Envelope<Book> = CLIENT
+        .get(
+            "/openApi/spot/v1/market/depth",
+            &[("symbol", &symbol), ("limit", &limit), ("timestamp", &ts)],
+        )
+        .await?;
+    let book = env.into_data()?;
+    Ok(Depth {
+        bids: levels(&book.bids),
+        asks: levels(&book.asks),
+    })
+}
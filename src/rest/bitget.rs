@@ -0,0 +1,127 @@
+use super::{levels, num, opt_num, Depth, Instrument, RestClient};
+use crate::models::PairPrice;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+static CLIENT: Lazy<RestClient> = Lazy::new(|| RestClient::new("https://api.bitget.com", 10));
+
+/// Bitget wraps every payload as `{"code":"00000","msg":"success","data":...}`.
+#[derive(Deserialize)]
+struct Envelope<T> {
+    code: String,
+    #[serde(default)]
+    msg: String,
+    data: Option<T>,
+}
+
+impl<T> Envelope<T> {
+    fn into_data(self) -> Result<T, String> {
+        match (self.code.as_str(), self.data) {
+            ("00000", Some(data)) => Ok(data),
+            (code, _) => Err(format!("bitget error {}: {}", code, self.msg)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SymbolInfo {
+    symbol: String,
+    base_coin: String,
+    quote_coin: String,
+    status: String,
+}
+
+/// Shared by the REST tickers endpoint and the WS `ticker` channel (which
+/// names the symbol `instId`).
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Ticker {
+    #[serde(alias = "instId")]
+    pub symbol: String,
+    #[serde(deserialize_with = "num")]
+    pub last_pr: f64,
+    #[serde(default, deserialize_with = "opt_num")]
+    pub bid_pr: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    pub ask_pr: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    pub base_volume: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    pub ts: Option<f64>,
+}
+
+impl Ticker {
+    pub fn into_price(self, base: &str, quote: &str) -> PairPrice {
+        PairPrice {
+            base: base.to_string(),
+            quote: quote.to_string(),
+            price: self.last_pr,
+            is_spot: true,
+            volume: self.base_volume.unwrap_or(0.0),
+            event_time: self.ts.unwrap_or(0.0) as u64,
+            bid: self.bid_pr,
+            ask: self.ask_pr,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Book {
+    bids: Vec<Vec<Value>>,
+    asks: Vec<Vec<Value>>,
+}
+
+/// `GET /api/v2/spot/public/symbols`.
+pub async fn instruments() -> Result<Vec<Instrument>, String> {
+    let env: Envelope<Vec<SymbolInfo>> = CLIENT.get("/api/v2/spot/public/symbols", &[]).await?;
+    Ok(env
+        .into_data()?
+        .into_iter()
+        .map(|s| Instrument {
+            trading: s.status == "online",
+            symbol: s.symbol,
+            base: s.base_coin.to_uppercase(),
+            quote: s.quote_coin.to_uppercase(),
+            ..Default::default()
+        })
+        .collect())
+}
+
+/// `GET /api/v2/spot/market/tickers`, split using the instrument list.
+pub async fn tickers() -> Result<Vec<PairPrice>, String> {
+    let split: HashMap<String, Instrument> = instruments()
+        .await?
+        .into_iter()
+        .filter(|i| i.trading)
+        .map(|i| (i.symbol.clone(), i))
+        .collect();
+    let env: Envelope<Vec<Ticker>> = CLIENT.get("/api/v2/spot/market/tickers", &[]).await?;
+    Ok(env
+        .into_data()?
+        .into_iter()
+        .filter_map(|t| {
+            let inst = split.get(&t.symbol)?;
+            Some(t.into_price(&inst.base, &inst.quote))
+        })
+        .collect())
+}
+
+/// `GET /api/v2/spot/market/orderbook`.
+pub async fn depth(base: &str, quote: &str, limit: usize) -> Result<Depth, String> {
+    let symbol = format!("{}{}", base, quote).to_uppercase();
+    let limit = limit.clamp(1, 150).to_string();
+    let env: Envelope<Book> = CLIENT
+        .get(
+            "/api/v2/spot/market/orderbook",
+            &[("symbol", &symbol), ("limit", &limit)],
+        )
+        .await?;
+    let book = env.into_data()?;
+    Ok(Depth {
+        bids: levels(&book.bids),
+        asks: levels(&book.asks),
+    })
+}
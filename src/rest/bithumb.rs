@@ -0,0 +1,120 @@
+use super::{num, opt_num, Depth, Instrument, RestClient};
+use crate::models::PairPrice;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+static CLIENT: Lazy<RestClient> = Lazy::new(|| RestClient::new("https://api.bithumb.com", 10));
+
+/// Quote markets covered; Bithumb also lists USDT markets.
+const QUOTES: [&str; 2] = ["KRW", "BTC"];
+/// Markets per `/v1/ticker` request.
+const TICKER_BATCH: usize = 100;
+
+#[derive(Deserialize)]
+struct Market {
+    market: String,
+}
+
+/// Shared by the REST ticker endpoint and the WS `ticker` type.
+#[derive(Deserialize)]
+pub(crate) struct Ticker {
+    /// "QUOTE-BASE", e.g. "KRW-BTC".
+    #[serde(alias = "code")]
+    pub market: String,
+    #[serde(deserialize_with = "num")]
+    pub trade_price: f64,
+    #[serde(default, deserialize_with = "opt_num")]
+    pub acc_trade_volume_24h: Option<f64>,
+    #[serde(default)]
+    pub timestamp: Option<u64>,
+}
+
+impl Ticker {
+    pub fn into_price(self) -> Option<PairPrice> {
+        let (base, quote) = split_market(&self.market)?;
+        Some(PairPrice {
+            base,
+            quote,
+            price: self.trade_price,
+            is_spot: true,
+            volume: self.acc_trade_volume_24h.unwrap_or(0.0),
+            event_time: self.timestamp.unwrap_or(0),
+            bid: None,
+            ask: None,
+        })
+    }
+}
+
+/// Shared by the REST orderbook endpoint and the WS `orderbook` type.
+#[derive(Deserialize)]
+pub(crate) struct Orderbook {
+    #[serde(alias = "code")]
+    pub market: String,
+    pub orderbook_units: Vec<Unit>,
+    #[serde(default)]
+    pub timestamp: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct Unit {
+    #[serde(deserialize_with = "num")]
+    pub ask_price: f64,
+    #[serde(deserialize_with = "num")]
+    pub bid_price: f64,
+    #[serde(deserialize_with = "num")]
+    pub ask_size: f64,
+    #[serde(deserialize_with = "num")]
+    pub bid_size: f64,
+}
+
+/// "KRW-BTC" -> ("BTC", "KRW"); Bithumb puts the quote first.
+pub fn split_market(market: &str) -> Option<(String, String)> {
+    let (quote, base) = market.split_once('-')?;
+    Some((base.to_uppercase(), quote.to_uppercase()))
+}
+
+/// `GET /v1/market/all`, KRW and BTC markets only.
+pub async fn instruments() -> Result<Vec<Instrument>, String> {
+    let markets: Vec<Market> = CLIENT.get("/v1/market/all", &[]).await?;
+    Ok(markets
+        .into_iter()
+        .filter_map(|m| {
+            let (base, quote) = split_market(&m.market)?;
+            QUOTES.contains(&quote.as_str()).then_some(Instrument {
+                symbol: m.market,
+                base,
+                quote,
+                trading: true,
+                ..Default::default()
+            })
+        })
+        .collect())
+}
+
+/// `GET /v1/ticker` in batches of markets.
+pub async fn tickers() -> Result<Vec<PairPrice>, String> {
+    let markets: Vec<String> = instruments().await?.into_iter().map(|i| i.symbol).collect();
+    let mut out = Vec::with_capacity(markets.len());
+    for batch in markets.chunks(TICKER_BATCH) {
+        let raw: Vec<Ticker> = CLIENT
+            .get("/v1/ticker", &[("markets", &batch.join(","))])
+            .await?;
+        out.extend(raw.into_iter().filter_map(Ticker::into_price));
+    }
+    Ok(out)
+}
+
+/// `GET /v1/orderbook` for one market, truncated to `limit` levels.
+pub async fn depth(base: &str, quote: &str, limit: usize) -> Result<Depth, String> {
+    let market = format!("{}-{}", quote, base).to_uppercase();
+    let books: Vec<Orderbook> = CLIENT.get("/v1/orderbook", &[("markets", &market)]).await?;
+    let book = books
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("bithumb: no order book for {}", market))?;
+    let units = book.orderbook_units.iter().take(limit.max(1));
+    Ok(Depth {
+        bids: units.clone().map(|u| (u.bid_price, u.bid_size)).collect(),
+        asks: units.map(|u| (u.ask_price, u.ask_size)).collect(),
+    })
+}
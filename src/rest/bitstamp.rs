@@ -0,0 +1,88 @@
+use super::{levels, num, opt_num, Depth, Instrument, RestClient};
+use crate::models::PairPrice;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::Value;
+
+static CLIENT: Lazy<RestClient> = Lazy::new(|| RestClient::new("https://www.bitstamp.net", 8));
+
+#[derive(Deserialize)]
+struct PairInfo {
+    /// "BTC/USD".
+    name: String,
+    /// "btcusd", used in channel names and URLs.
+    url_symbol: String,
+    trading: String,
+}
+
+#[derive(Deserialize)]
+struct Ticker {
+    pair: String,
+    #[serde(deserialize_with = "num")]
+    last: f64,
+    #[serde(default, deserialize_with = "opt_num")]
+    bid: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    ask: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    volume: Option<f64>,
+    /// Unix seconds.
+    #[serde(default, deserialize_with = "opt_num")]
+    timestamp: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct Book {
+    bids: Vec<Vec<Value>>,
+    asks: Vec<Vec<Value>>,
+}
+
+/// `GET /api/v2/trading-pairs-info/`; `symbol` is the lower-case URL symbol.
+pub async fn instruments() -> Result<Vec<Instrument>, String> {
+    let pairs: Vec<PairInfo> = CLIENT.get("/api/v2/trading-pairs-info/", &[]).await?;
+    Ok(pairs
+        .into_iter()
+        .filter_map(|p| {
+            let (base, quote) = p.name.split_once('/')?;
+            Some(Instrument {
+                trading: p.trading == "Enabled",
+                base: base.to_uppercase(),
+                quote: quote.to_uppercase(),
+                symbol: p.url_symbol,
+                ..Default::default()
+            })
+        })
+        .collect())
+}
+
+/// `GET /api/v2/ticker/` (every pair in one response).
+pub async fn tickers() -> Result<Vec<PairPrice>, String> {
+    let raw: Vec<Ticker> = CLIENT.get("/api/v2/ticker/", &[]).await?;
+    Ok(raw
+        .into_iter()
+        .filter_map(|t| {
+            let (base, quote) = t.pair.split_once('/')?;
+            Some(PairPrice {
+                base: base.to_uppercase(),
+                quote: quote.to_uppercase(),
+                price: t.last,
+                is_spot: true,
+                volume: t.volume.unwrap_or(0.0),
+                event_time: (t.timestamp.unwrap_or(0.0) * 1000.0) as u64,
+                bid: t.bid,
+                ask: t.ask,
+            })
+        })
+        .collect())
+}
+
+/// `GET /api/v2/order_book/{pair}/`, truncated to `limit` levels.
+pub async fn depth(base: &str, quote: &str, limit: usize) -> Result<Depth, String> {
+    let path = format!("/api/v2/order_book/{}{}/", base, quote).to_lowercase();
+    let book: Book = CLIENT.get(&path, &[]).await?;
+    let limit = limit.max(1);
+    Ok(Depth {
+        bids: levels(&book.bids).into_iter().take(limit).collect(),
+        asks: levels(&book.asks).into_iter().take(limit).collect(),
+    })
+}
@@ -0,0 +1,194 @@
+use super::{as_f64, hex, hmac, levels, num, opt_num, Balance, Depth, Instrument, RestClient};
+use crate::config::{config, AccountConfig};
+use crate::models::PairPrice;
+use crate::utils::now_ms;
+use once_cell::sync::Lazy;
+use ring::hmac::HMAC_SHA256;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+static CLIENT: Lazy<RestClient> = Lazy::new(|| {
+    let base = config()
+        .connector("bybit")
+        .endpoint("https://api.bybit.com", "https://api-testnet.bybit.com");
+    RestClient::new(base, 10)
+});
+
+/// Bybit wraps every payload as `{"retCode":0,"result":{...}}`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Envelope<T> {
+    ret_code: i64,
+    #[serde(default)]
+    ret_msg: String,
+    result: Option<T>,
+}
+
+impl<T> Envelope<T> {
+    fn into_result(self) -> Result<T, String> {
+        match (self.ret_code, self.result) {
+            (0, Some(result)) => Ok(result),
+            (code, _) => Err(format!("bybit error {}: {}", code, self.ret_msg)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct List<T> {
+    list: Vec<T>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InstrumentInfo {
+    symbol: String,
+    base_coin: String,
+    quote_coin: String,
+    status: String,
+    #[serde(default)]
+    price_filter: Value,
+    #[serde(default)]
+    lot_size_filter: Value,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Ticker {
+    symbol: String,
+    #[serde(deserialize_with = "num")]
+    last_price: f64,
+    #[serde(default, deserialize_with = "opt_num")]
+    bid1_price: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    ask1_price: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    volume24h: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct Book {
+    b: Vec<Vec<Value>>,
+    a: Vec<Vec<Value>>,
+}
+
+#[derive(Deserialize)]
+struct Wallet {
+    coin: Vec<CoinBalance>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CoinBalance {
+    coin: String,
+    #[serde(default, deserialize_with = "opt_num")]
+    wallet_balance: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    locked: Option<f64>,
+}
+
+/// `GET /v5/account/wallet-balance` of the unified account, signed over
+/// timestamp + key + recv window + query.
+pub async fn balances(account: &AccountConfig) -> Result<Vec<Balance>, String> {
+    const RECV_WINDOW: &str = "5000";
+    let query = "accountType=UNIFIED";
+    let timestamp = now_ms().to_string();
+    let payload = format!("{}{}{}{}", timestamp, account.api_key, RECV_WINDOW, query);
+    let headers = [
+        ("X-BAPI-API-KEY", account.api_key.clone()),
+        ("X-BAPI-TIMESTAMP", timestamp),
+        ("X-BAPI-RECV-WINDOW", RECV_WINDOW.to_string()),
+        (
+            "X-BAPI-SIGN",
+            hex(&hmac(HMAC_SHA256, &account.api_secret, &payload)),
+        ),
+    ];
+    let env: Envelope<List<Wallet>> = CLIENT
+        .get_signed("/v5/account/wallet-balance", query, &headers)
+        .await?;
+    Ok(env
+        .into_result()?
+        .list
+        .into_iter()
+        .flat_map(|w| w.coin)
+        .map(|c| {
+            let total = c.wallet_balance.unwrap_or(0.0);
+            let locked = c.locked.unwrap_or(0.0).min(total);
+            Balance {
+                asset: c.coin.to_uppercase(),
+                free: total - locked,
+                locked,
+            }
+        })
+        .collect())
+}
+
+/// `GET /v5/market/instruments-info?category=spot`.
+pub async fn instruments() -> Result<Vec<Instrument>, String> {
+    let env: Envelope<List<InstrumentInfo>> = CLIENT
+        .get("/v5/market/instruments-info", &[("category", "spot")])
+        .await?;
+    Ok(env
+        .into_result()?
+        .list
+        .into_iter()
+        .map(|i| Instrument {
+            trading: i.status == "Trading",
+            tick_size: i.price_filter.get("tickSize").and_then(as_f64),
+            step_size: i.lot_size_filter.get("basePrecision").and_then(as_f64),
+            min_notional: i.lot_size_filter.get("minOrderAmt").and_then(as_f64),
+            symbol: i.symbol,
+            base: i.base_coin.to_uppercase(),
+            quote: i.quote_coin.to_uppercase(),
+            status: Some(i.status),
+        })
+        .collect())
+}
+
+/// `GET /v5/market/tickers?category=spot`, split using the instrument list.
+pub async fn tickers() -> Result<Vec<PairPrice>, String> {
+    let split: HashMap<String, Instrument> = instruments()
+        .await?
+        .into_iter()
+        .filter(|i| i.trading)
+        .map(|i| (i.symbol.clone(), i))
+        .collect();
+    let env: Envelope<List<Ticker>> = CLIENT
+        .get("/v5/market/tickers", &[("category", "spot")])
+        .await?;
+    Ok(env
+        .into_result()?
+        .list
+        .into_iter()
+        .filter_map(|t| {
+            let inst = split.get(&t.symbol)?;
+            Some(PairPrice {
+                base: inst.base.clone(),
+                quote: inst.quote.clone(),
+                price: t.last_price,
+                is_spot: true,
+                volume: t.volume24h.unwrap_or(0.0),
+                event_time: 0,
+                bid: t.bid1_price,
+                ask: t.ask1_price,
+            })
+        })
+        .collect())
+}
+
+/// `GET /v5/market/orderbook?category=spot`.
+pub async fn depth(base: &str, quote: &str, limit: usize) -> Result<Depth, String> {
+    let symbol = format!("{}{}", base, quote).to_uppercase();
+    let limit = limit.clamp(1, 200).to_string();
+    let env: Envelope<Book> = CLIENT
+        .get(
+            "/v5/market/orderbook",
+            &[("category", "spot"), ("symbol", &symbol), ("limit", &limit)],
+        )
+        .await?;
+    let book = env.into_result()?;
+    Ok(Depth {
+        bids: levels(&book.b),
+        asks: levels(&book.a),
+    })
+}
@@ -0,0 +1,77 @@
+use super::{opt_num, Instrument, RestClient};
+use crate::models::PairPrice;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+static CLIENT: Lazy<RestClient> = Lazy::new(|| RestClient::new("https://api.coinbase.com", 10));
+
+#[derive(Deserialize)]
+struct Products {
+    products: Vec<Product>,
+}
+
+#[derive(Deserialize)]
+struct Product {
+    product_id: String,
+    base_currency_id: String,
+    quote_currency_id: String,
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    trading_disabled: bool,
+    #[serde(default)]
+    product_type: String,
+    #[serde(default, deserialize_with = "opt_num")]
+    price: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    volume_24h: Option<f64>,
+}
+
+async fn products() -> Result<Vec<Product>, String> {
+    let list: Products = CLIENT
+        .get(
+            "/api/v3/brokerage/market/products",
+            &[("product_type", "SPOT")],
+        )
+        .await?;
+    Ok(list
+        .products
+        .into_iter()
+        .filter(|p| p.product_type.is_empty() || p.product_type == "SPOT")
+        .collect())
+}
+
+/// `GET /api/v3/brokerage/market/products` (public, no auth).
+pub async fn instruments() -> Result<Vec<Instrument>, String> {
+    Ok(products()
+        .await?
+        .into_iter()
+        .map(|p| Instrument {
+            trading: p.status == "online" && !p.trading_disabled,
+            symbol: p.product_id,
+            base: p.base_currency_id.to_uppercase(),
+            quote: p.quote_currency_id.to_uppercase(),
+            ..Default::default()
+        })
+        .collect())
+}
+
+/// Last price and 24h volume from the product list; it carries no bid/ask.
+pub async fn tickers() -> Result<Vec<PairPrice>, String> {
+    Ok(products()
+        .await?
+        .into_iter()
+        .filter_map(|p| {
+            Some(PairPrice {
+                price: p.price.filter(|x| *x > 0.0)?,
+                base: p.base_currency_id.to_uppercase(),
+                quote: p.quote_currency_id.to_uppercase(),
+                is_spot: true,
+                volume: p.volume_24h.unwrap_or(0.0),
+                event_time: 0,
+                bid: None,
+                ask: None,
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,125 @@
+use super::{levels, num, opt_num, Depth, Instrument, RestClient};
+use crate::models::PairPrice;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+static CLIENT: Lazy<RestClient> = Lazy::new(|| RestClient::new("https://api.coinex.com", 10));
+
+/// CoinEx wraps every payload as `{"code":0,"data":...,"message":"OK"}`.
+#[derive(Deserialize)]
+struct Envelope<T> {
+    code: i64,
+    #[serde(default)]
+    message: String,
+    data: Option<T>,
+}
+
+impl<T> Envelope<T> {
+    fn into_data(self) -> Result<T, String> {
+        match (self.code, self.data) {
+            (0, Some(data)) => Ok(data),
+            (code, _) => Err(format!("coinex error {}: {}", code, self.message)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct MarketInfo {
+    /// "BTCUSDT".
+    market: String,
+    base_ccy: String,
+    quote_ccy: String,
+    #[serde(default = "yes")]
+    is_api_trading_available: bool,
+}
+
+fn yes() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+struct Ticker {
+    market: String,
+    #[serde(deserialize_with = "num")]
+    last: f64,
+    #[serde(default, deserialize_with = "opt_num")]
+    volume: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct DepthData {
+    depth: Book,
+}
+
+#[derive(Deserialize)]
+struct Book {
+    bids: Vec<Vec<Value>>,
+    asks: Vec<Vec<Value>>,
+}
+
+/// `GET /v2/spot/market`.
+pub async fn instruments() -> Result<Vec<Instrument>, String> {
+    let env: Envelope<Vec<MarketInfo>> = CLIENT.get("/v2/spot/market", &[]).await?;
+    Ok(env
+        .into_data()?
+        .into_iter()
+        .map(|m| Instrument {
+            trading: m.is_api_trading_available,
+            symbol: m.market,
+            base: m.base_ccy.to_uppercase(),
+            quote: m.quote_ccy.to_uppercase(),
+            ..Default::default()
+        })
+        .collect())
+}
+
+/// `GET /v2/spot/ticker` for every market, split using the market list.
+pub async fn tickers() -> Result<Vec<PairPrice>, String> {
+    let split: HashMap<String, Instrument> = instruments()
+        .await?
+        .into_iter()
+        .filter(|i| i.trading)
+        .map(|i| (i.symbol.clone(), i))
+        .collect();
+    let env: Envelope<Vec<Ticker>> = CLIENT.get("/v2/spot/ticker", &[]).await?;
+    Ok(env
+        .into_data()?
+        .into_iter()
+        .filter_map(|t| {
+            let inst = split.get(&t.market)?;
+            Some(PairPrice {
+                base: inst.base.clone(),
+                quote: inst.quote.clone(),
+                price: t.last,
+                is_spot: true,
+                volume: t.volume.unwrap_or(0.0),
+                event_time: 0,
+                bid: None,
+                ask: None,
+            })
+        })
+        .collect())
+}
+
+/// `GET /v2/spot/depth`; CoinEx only accepts a few limits.
+pub async fn depth(base: &str, quote: &str, limit: usize) -> Result<Depth, String> {
+    let market = format!("{}{}", base, quote).to_uppercase();
+    let limit = [5, 10, 20, 50]
+        .into_iter()
+        .find(|l| *l >= limit)
+        .unwrap_or(50)
+        .to_string();
+    let env: Envelope<DepthData> = CLIENT
+        .get(
+            "/v2/spot/depth",
+            &[("market", &market), ("limit", &limit), ("interval", "0")],
+        )
+        .await?;
+    let book = env.into_data()?.depth;
+    Ok(Depth {
+        bids: levels(&book.bids),
+        asks: levels(&book.asks),
+    })
+}
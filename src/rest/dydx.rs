@@ -0,0 +1,123 @@
+use super::{num, opt_num, Depth, Instrument, RestClient};
+use crate::models::PairPrice;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+static CLIENT: Lazy<RestClient> = Lazy::new(|| RestClient::new("https://indexer.dydx.trade", 10));
+
+#[derive(Deserialize)]
+struct Markets {
+    markets: HashMap<String, Market>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Market {
+    /// "BTC-USD".
+    ticker: String,
+    status: String,
+    #[serde(default, deserialize_with = "opt_num")]
+    oracle_price: Option<f64>,
+    /// 24h notional in USD.
+    #[serde(default, rename = "volume24H", deserialize_with = "opt_num")]
+    volume_24h: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct Book {
+    bids: Vec<Level>,
+    asks: Vec<Level>,
+}
+
+#[derive(Deserialize)]
+struct Level {
+    #[serde(deserialize_with = "num")]
+    price: f64,
+    #[serde(deserialize_with = "num")]
+    size: f64,
+}
+
+/// "BTC-USD" -> ("BTC", "USD").
+pub fn split_ticker(ticker: &str) -> Option<(String, String)> {
+    let (base, quote) = ticker.split_once('-')?;
+    Some((base.to_uppercase(), quote.to_uppercase()))
+}
+
+/// A perpetual's oracle price as a non-spot pair; the USD notional volume is
+/// converted to base units like every other venue's.
+pub fn perp_price(
+    ticker: &str,
+    oracle_price: f64,
+    volume_usd: Option<f64>,
+    event_time: u64,
+) -> Option<PairPrice> {
+    let (base, quote) = split_ticker(ticker)?;
+    Some(PairPrice {
+        base,
+        quote,
+        price: oracle_price,
+        is_spot: false,
+        volume: volume_usd.unwrap_or(0.0) / oracle_price,
+        event_time,
+        bid: None,
+        ask: None,
+    })
+}
+
+async fn markets() -> Result<Vec<Market>, String> {
+    let list: Markets = CLIENT.get("/v4/perpetualMarkets", &[]).await?;
+    Ok(list.markets.into_values().collect())
+}
+
+/// `GET /v4/perpetualMarkets`.
+pub async fn instruments() -> Result<Vec<Instrument>, String> {
+    Ok(markets()
+        .await?
+        .into_iter()
+        .filter_map(|m| {
+            let (base, quote) = split_ticker(&m.ticker)?;
+            Some(Instrument {
+                trading: m.status == "ACTIVE",
+                symbol: m.ticker,
+                base,
+                quote,
+                ..Default::default()
+            })
+        })
+        .collect())
+}
+
+/// Oracle prices of every active perpetual, `is_spot: false`.
+pub async fn tickers() -> Result<Vec<PairPrice>, String> {
+    Ok(markets()
+        .await?
+        .into_iter()
+        .filter(|m| m.status == "ACTIVE")
+        .filter_map(|m| {
+            let price = m.oracle_price.filter(|p| *p > 0.0)?;
+            perp_price(&m.ticker, price, m.volume_24h, 0)
+        })
+        .collect())
+}
+
+/// `GET /v4/orderbooks/perpetualMarket/{ticker}`, truncated to `limit` levels.
+pub async fn depth(base: &str, quote: &str, limit: usize) -> Result<Depth, String> {
+    let path = format!(
+        "/v4/orderbooks/perpetualMarket/{}-{}",
+        base.to_uppercase(),
+        quote.to_uppercase()
+    );
+    let book: Book = CLIENT.get(&path, &[]).await?;
+    let side = |levels: Vec<Level>| {
+        levels
+            .into_iter()
+            .take(limit.max(1))
+            .map(|l| (l.price, l.size))
+            .collect()
+    };
+    Ok(Depth {
+        bids: side(book.bids),
+        asks: side(book.asks),
+    })
+}
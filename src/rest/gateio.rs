@@ -0,0 +1,136 @@
+use super::{hex, hmac, levels, num, opt_num, Balance, Depth, Instrument, RestClient};
+use crate::config::AccountConfig;
+use crate::models::PairPrice;
+use crate::utils::now_ms;
+use once_cell::sync::Lazy;
+use ring::digest;
+use ring::hmac::HMAC_SHA512;
+use serde::Deserialize;
+use serde_json::Value;
+
+static CLIENT: Lazy<RestClient> = Lazy::new(|| RestClient::new("https://api.gateio.ws/api/v4", 10));
+
+#[derive(Deserialize)]
+struct CurrencyPair {
+    id: String,
+    base: String,
+    quote: String,
+    trade_status: String,
+    /// Price decimals.
+    #[serde(default)]
+    precision: Option<i32>,
+    /// Amount decimals.
+    #[serde(default)]
+    amount_precision: Option<i32>,
+    #[serde(default, deserialize_with = "opt_num")]
+    min_quote_amount: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct Ticker {
+    currency_pair: String,
+    #[serde(deserialize_with = "num")]
+    last: f64,
+    #[serde(default, deserialize_with = "opt_num")]
+    highest_bid: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    lowest_ask: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    base_volume: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct Book {
+    bids: Vec<Vec<Value>>,
+    asks: Vec<Vec<Value>>,
+}
+
+#[derive(Deserialize)]
+struct SpotAccount {
+    currency: String,
+    #[serde(deserialize_with = "num")]
+    available: f64,
+    #[serde(deserialize_with = "num")]
+    locked: f64,
+}
+
+/// `GET /spot/accounts`, signed with HMAC-SHA512 over method, path, query,
+/// body hash and timestamp.
+pub async fn balances(account: &AccountConfig) -> Result<Vec<Balance>, String> {
+    const PATH: &str = "/spot/accounts";
+    let timestamp = (now_ms() / 1000).to_string();
+    let body_hash = hex(digest::digest(&digest::SHA512, b"").as_ref());
+    let payload = format!("GET\n/api/v4{}\n\n{}\n{}", PATH, body_hash, timestamp);
+    let headers = [
+        ("KEY", account.api_key.clone()),
+        ("Timestamp", timestamp),
+        (
+            "SIGN",
+            hex(&hmac(HMAC_SHA512, &account.api_secret, &payload)),
+        ),
+    ];
+    let list: Vec<SpotAccount> = CLIENT.get_signed(PATH, "", &headers).await?;
+    Ok(list
+        .into_iter()
+        .map(|a| Balance {
+            asset: a.currency.to_uppercase(),
+            free: a.available,
+            locked: a.locked,
+        })
+        .collect())
+}
+
+/// `GET /spot/currency_pairs`.
+pub async fn instruments() -> Result<Vec<Instrument>, String> {
+    let pairs: Vec<CurrencyPair> = CLIENT.get("/spot/currency_pairs", &[]).await?;
+    Ok(pairs
+        .into_iter()
+        .map(|p| Instrument {
+            trading: p.trade_status == "tradable",
+            tick_size: p.precision.map(|d| 10f64.powi(-d)),
+            step_size: p.amount_precision.map(|d| 10f64.powi(-d)),
+            min_notional: p.min_quote_amount,
+            symbol: p.id,
+            base: p.base.to_uppercase(),
+            quote: p.quote.to_uppercase(),
+            status: Some(p.trade_status),
+        })
+        .collect())
+}
+
+/// `GET /spot/tickers`.
+pub async fn tickers() -> Result<Vec<PairPrice>, String> {
+    let raw: Vec<Ticker> = CLIENT.get("/spot/tickers", &[]).await?;
+    Ok(raw
+        .into_iter()
+        .filter_map(|t| {
+            let (base, quote) = t.currency_pair.split_once('_')?;
+            Some(PairPrice {
+                base: base.to_uppercase(),
+                quote: quote.to_uppercase(),
+                price: t.last,
+                is_spot: true,
+                volume: t.base_volume.unwrap_or(0.0),
+                event_time: 0,
+                bid: t.highest_bid,
+                ask: t.lowest_ask,
+            })
+        })
+        .collect())
+}
+
+/// `GET /spot/order_book`.
+pub async fn depth(base: &str, quote: &str, limit: usize) -> Result<Depth, String> {
+    let pair = format!("{}_{}", base, quote).to_uppercase();
+    let limit = limit.clamp(1, 100).to_string();
+    let book: Book = CLIENT
+        .get(
+            "/spot/order_book",
+            &[("currency_pair", &pair), ("limit", &limit)],
+        )
+        .await?;
+    Ok(Depth {
+        bids: levels(&book.bids),
+        asks: levels(&book.asks),
+    })
+}
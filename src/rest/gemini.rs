@@ -0,0 +1,118 @@
+use super::{num, Depth, Instrument, RestClient};
+use crate::models::PairPrice;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+static CLIENT: Lazy<RestClient> = Lazy::new(|| RestClient::new("https://api.gemini.com", 5));
+
+/// Symbol details never change once listed, and Gemini only serves them one
+/// symbol per request, so they're fetched once and kept.
+static DETAILS: Lazy<Mutex<HashMap<String, Instrument>>> = Lazy::new(Default::default);
+
+#[derive(Deserialize)]
+struct SymbolDetails {
+    symbol: String,
+    base_currency: String,
+    quote_currency: String,
+    status: String,
+}
+
+#[derive(Deserialize)]
+struct PriceFeed {
+    pair: String,
+    #[serde(deserialize_with = "num")]
+    price: f64,
+}
+
+#[derive(Deserialize)]
+struct Level {
+    #[serde(deserialize_with = "num")]
+    price: f64,
+    #[serde(deserialize_with = "num")]
+    amount: f64,
+}
+
+#[derive(Deserialize)]
+struct Book {
+    bids: Vec<Level>,
+    asks: Vec<Level>,
+}
+
+/// `GET /v1/symbols`, then `GET /v1/symbols/details/:symbol` for symbols not
+/// seen before. Symbols come back upper case ("BTCUSD").
+pub async fn instruments() -> Result<Vec<Instrument>, String> {
+    let symbols: Vec<String> = CLIENT.get("/v1/symbols", &[]).await?;
+    let mut list = Vec::with_capacity(symbols.len());
+    for sym in symbols {
+        let sym = sym.to_uppercase();
+        let known = DETAILS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&sym)
+            .cloned();
+        let inst = match known {
+            Some(inst) => inst,
+            None => {
+                let path = format!("/v1/symbols/details/{}", sym.to_lowercase());
+                let d: SymbolDetails = CLIENT.get(&path, &[]).await?;
+                let inst = Instrument {
+                    trading: d.status == "open",
+                    symbol: d.symbol.to_uppercase(),
+                    base: d.base_currency.to_uppercase(),
+                    quote: d.quote_currency.to_uppercase(),
+                    ..Default::default()
+                };
+                DETAILS
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .insert(sym, inst.clone());
+                inst
+            }
+        };
+        list.push(inst);
+    }
+    Ok(list)
+}
+
+/// `GET /v1/pricefeed`: last price of every pair, no volume or book.
+pub async fn tickers() -> Result<Vec<PairPrice>, String> {
+    let split: HashMap<String, Instrument> = instruments()
+        .await?
+        .into_iter()
+        .filter(|i| i.trading)
+        .map(|i| (i.symbol.clone(), i))
+        .collect();
+    let feed: Vec<PriceFeed> = CLIENT.get("/v1/pricefeed", &[]).await?;
+    Ok(feed
+        .into_iter()
+        .filter_map(|p| {
+            let inst = split.get(&p.pair.to_uppercase())?;
+            Some(PairPrice {
+                base: inst.base.clone(),
+                quote: inst.quote.clone(),
+                price: p.price,
+                is_spot: true,
+                volume: 0.0,
+                event_time: 0,
+                bid: None,
+                ask: None,
+            })
+        })
+        .collect())
+}
+
+/// `GET /v1/book/:symbol`.
+pub async fn depth(base: &str, quote: &str, limit: usize) -> Result<Depth, String> {
+    let path = format!("/v1/book/{}{}", base, quote).to_lowercase();
+    let limit = limit.max(1).to_string();
+    let book: Book = CLIENT
+        .get(&path, &[("limit_bids", &limit), ("limit_asks", &limit)])
+        .await?;
+    let side = |levels: Vec<Level>| levels.into_iter().map(|l| (l.price, l.amount)).collect();
+    Ok(Depth {
+        bids: side(book.bids),
+        asks: side(book.asks),
+    })
+}
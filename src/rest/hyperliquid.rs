@@ -0,0 +1,137 @@
+use super::{num, opt_num, Depth, Instrument, RestClient};
+use crate::models::PairPrice;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::json;
+
+static CLIENT: Lazy<RestClient> = Lazy::new(|| RestClient::new("https://api.hyperliquid.xyz", 10));
+
+#[derive(Deserialize)]
+struct SpotMeta {
+    tokens: Vec<Token>,
+    universe: Vec<SpotPair>,
+}
+
+#[derive(Deserialize)]
+struct Token {
+    name: String,
+    index: usize,
+}
+
+#[derive(Deserialize)]
+struct SpotPair {
+    /// "PURR/USDC" for the first listings, "@<index>" for the rest.
+    name: String,
+    /// Token indices, base first.
+    tokens: [usize; 2],
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AssetCtx {
+    coin: String,
+    #[serde(default, deserialize_with = "opt_num")]
+    mid_px: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    mark_px: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    day_base_vlm: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct L2Book {
+    /// `[bids, asks]`.
+    levels: [Vec<Level>; 2],
+}
+
+#[derive(Deserialize)]
+struct Level {
+    #[serde(deserialize_with = "num")]
+    px: f64,
+    #[serde(deserialize_with = "num")]
+    sz: f64,
+}
+
+impl SpotMeta {
+    /// Spot pairs by their market key ("@107", "PURR/USDC") with token names
+    /// resolved from the token indices.
+    fn instruments(&self) -> Vec<Instrument> {
+        let name = |index: usize| {
+            self.tokens
+                .iter()
+                .find(|t| t.index == index)
+                .map(|t| t.name.to_uppercase())
+        };
+        self.universe
+            .iter()
+            .filter_map(|p| {
+                Some(Instrument {
+                    symbol: p.name.clone(),
+                    base: name(p.tokens[0])?,
+                    quote: name(p.tokens[1])?,
+                    trading: true,
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+}
+
+/// `POST /info {"type":"spotMeta"}`; `symbol` is the key `allMids` uses.
+pub async fn instruments() -> Result<Vec<Instrument>, String> {
+    let meta: SpotMeta = CLIENT
+        .post_json("/info", &json!({ "type": "spotMeta" }))
+        .await?;
+    Ok(meta.instruments())
+}
+
+/// `POST /info {"type":"spotMetaAndAssetCtxs"}`: mid (or mark) price and 24h
+/// base volume per spot pair.
+pub async fn tickers() -> Result<Vec<PairPrice>, String> {
+    let (meta, ctxs): (SpotMeta, Vec<AssetCtx>) = CLIENT
+        .post_json("/info", &json!({ "type": "spotMetaAndAssetCtxs" }))
+        .await?;
+    let instruments = meta.instruments();
+    Ok(ctxs
+        .into_iter()
+        .filter_map(|c| {
+            let inst = instruments.iter().find(|i| i.symbol == c.coin)?;
+            Some(PairPrice {
+                base: inst.base.clone(),
+                quote: inst.quote.clone(),
+                price: c.mid_px.or(c.mark_px).filter(|p| *p > 0.0)?,
+                is_spot: true,
+                volume: c.day_base_vlm.unwrap_or(0.0),
+                event_time: 0,
+                bid: None,
+                ask: None,
+            })
+        })
+        .collect())
+}
+
+/// `POST /info {"type":"l2Book"}` for the spot pair trading `base`/`quote`.
+pub async fn depth(base: &str, quote: &str, limit: usize) -> Result<Depth, String> {
+    let (base, quote) = (base.to_uppercase(), quote.to_uppercase());
+    let coin = instruments()
+        .await?
+        .into_iter()
+        .find(|i| i.base == base && i.quote == quote)
+        .map(|i| i.symbol)
+        .ok_or_else(|| format!("hyperliquid: no spot pair {}/{}", base, quote))?;
+    let book: L2Book = CLIENT
+        .post_json("/info", &json!({ "type": "l2Book", "coin": coin }))
+        .await?;
+    let [bids, asks] = book.levels;
+    let side = |levels: Vec<Level>| {
+        levels
+            .into_iter()
+            .take(limit.max(1))
+            .map(|l| (l.px, l.sz))
+            .collect()
+    };
+    Ok(Depth {
+        bids: side(bids),
+        asks: side(asks),
+    })
+}
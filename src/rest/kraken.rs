@@ -0,0 +1,112 @@
+use super::{as_f64, Instrument, RestClient};
+use crate::models::PairPrice;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+static CLIENT: Lazy<RestClient> = Lazy::new(|| RestClient::new("https://api.kraken.com", 1));
+
+/// Kraken wraps every payload as `{"error":[],"result":{...}}`.
+#[derive(Deserialize)]
+struct Envelope<T> {
+    #[serde(default)]
+    error: Vec<String>,
+    result: Option<T>,
+}
+
+impl<T> Envelope<T> {
+    fn into_result(self) -> Result<T, String> {
+        match self.result {
+            Some(result) if self.error.is_empty() => Ok(result),
+            _ => Err(format!("kraken error: {}", self.error.join(", "))),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct AssetPair {
+    /// "XBT/USD"; absent for dark-pool pairs.
+    wsname: Option<String>,
+    #[serde(default)]
+    status: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Ticker {
+    /// [price, whole lot volume, lot volume]
+    a: Vec<Value>,
+    b: Vec<Value>,
+    /// Last trade: [price, volume]
+    c: Vec<Value>,
+    /// Volume: [today, last 24h]
+    v: Vec<Value>,
+}
+
+/// Kraken's legacy asset codes, as they appear in `wsname`.
+pub fn normalize_asset(asset: &str) -> String {
+    match asset.to_uppercase().as_str() {
+        "XBT" => "BTC".into(),
+        "XDG" => "DOGE".into(),
+        other => other.into(),
+    }
+}
+
+/// `wsname` ("XBT/USD") to the normalized base and quote.
+fn split_wsname(wsname: &str) -> Option<(String, String)> {
+    let (base, quote) = wsname.split_once('/')?;
+    Some((normalize_asset(base), normalize_asset(quote)))
+}
+
+/// REST pair name -> (base, quote), for every pair with a WS name.
+async fn asset_pairs() -> Result<HashMap<String, (String, String, bool)>, String> {
+    let env: Envelope<HashMap<String, AssetPair>> = CLIENT.get("/0/public/AssetPairs", &[]).await?;
+    Ok(env
+        .into_result()?
+        .into_iter()
+        .filter_map(|(name, p)| {
+            let (base, quote) = split_wsname(p.wsname.as_deref()?)?;
+            let online = p.status.as_deref().is_none_or(|s| s == "online");
+            Some((name, (base, quote, online)))
+        })
+        .collect())
+}
+
+/// `GET /0/public/AssetPairs`. `symbol` is the v2 WS form, "BTC/USD".
+pub async fn instruments() -> Result<Vec<Instrument>, String> {
+    Ok(asset_pairs()
+        .await?
+        .into_values()
+        .map(|(base, quote, trading)| Instrument {
+            symbol: format!("{}/{}", base, quote),
+            base,
+            quote,
+            trading,
+            ..Default::default()
+        })
+        .collect())
+}
+
+/// `GET /0/public/Ticker` for every pair, named through `AssetPairs`.
+pub async fn tickers() -> Result<Vec<PairPrice>, String> {
+    let pairs = asset_pairs().await?;
+    let env: Envelope<HashMap<String, Ticker>> = CLIENT.get("/0/public/Ticker", &[]).await?;
+    let first = |v: &[Value]| v.first().and_then(as_f64);
+    Ok(env
+        .into_result()?
+        .into_iter()
+        .filter_map(|(name, t)| {
+            let (base, quote, _) = pairs.get(&name)?.clone();
+            Some(PairPrice {
+                base,
+                quote,
+                price: first(&t.c)?,
+                is_spot: true,
+                volume: t.v.get(1).and_then(as_f64).unwrap_or(0.0),
+                event_time: 0,
+                bid: first(&t.b),
+                ask: first(&t.a),
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,203 @@
+use super::{hmac, levels, num, opt_num, Balance, Depth, Instrument, RestClient};
+use crate::config::{config, AccountConfig};
+use crate::models::PairPrice;
+use crate::utils::now_ms;
+use base64::engine::general_purpose::STANDARD as B64;
+use base64::Engine;
+use once_cell::sync::Lazy;
+use ring::hmac::HMAC_SHA256;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// The sandbox's bullet also hands out sandbox WS servers, so this base
+/// switches the whole connector.
+static CLIENT: Lazy<RestClient> = Lazy::new(|| {
+    let base = config().connector("kucoin").endpoint(
+        "https://api.kucoin.com",
+        "https://openapi-sandbox.kucoin.com",
+    );
+    RestClient::new(base, 10)
+});
+
+/// KuCoin wraps every payload as `{"code":"200000","data":...}`.
+#[derive(Deserialize)]
+struct Envelope<T> {
+    code: String,
+    #[serde(default)]
+    msg: String,
+    data: Option<T>,
+}
+
+impl<T> Envelope<T> {
+    fn into_data(self) -> Result<T, String> {
+        match (self.code.as_str(), self.data) {
+            ("200000", Some(data)) => Ok(data),
+            (code, _) => Err(format!("kucoin error {}: {}", code, self.msg)),
+        }
+    }
+}
+
+/// Public WS token and the servers it is valid for.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bullet {
+    pub token: String,
+    pub instance_servers: Vec<InstanceServer>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceServer {
+    pub endpoint: String,
+    /// Ping interval the server expects, in ms.
+    #[serde(default)]
+    pub ping_interval: Option<u64>,
+    /// How long the server waits for a ping before closing, in ms.
+    #[serde(default)]
+    pub ping_timeout: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SymbolInfo {
+    symbol: String,
+    base_currency: String,
+    quote_currency: String,
+    enable_trading: bool,
+    #[serde(default, deserialize_with = "opt_num")]
+    price_increment: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    base_increment: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    min_funds: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct AllTickers {
+    time: u64,
+    ticker: Vec<Ticker>,
+}
+
+#[derive(Deserialize)]
+struct Ticker {
+    symbol: String,
+    #[serde(default, deserialize_with = "opt_num")]
+    last: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    buy: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    sell: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    vol: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct Book {
+    bids: Vec<Vec<Value>>,
+    asks: Vec<Vec<Value>>,
+}
+
+#[derive(Deserialize)]
+struct AccountBalance {
+    currency: String,
+    #[serde(deserialize_with = "num")]
+    available: f64,
+    #[serde(deserialize_with = "num")]
+    holds: f64,
+}
+
+/// `GET /api/v1/accounts` (all account types summed per currency), signed
+/// per KuCoin's v2 key scheme.
+pub async fn balances(account: &AccountConfig) -> Result<Vec<Balance>, String> {
+    const PATH: &str = "/api/v1/accounts";
+    let timestamp = now_ms().to_string();
+    let sign = |message: &str| B64.encode(hmac(HMAC_SHA256, &account.api_secret, message));
+    let headers = [
+        ("KC-API-KEY", account.api_key.clone()),
+        ("KC-API-SIGN", sign(&format!("{}GET{}", timestamp, PATH))),
+        ("KC-API-TIMESTAMP", timestamp),
+        ("KC-API-PASSPHRASE", sign(&account.passphrase)),
+        ("KC-API-KEY-VERSION", "2".to_string()),
+    ];
+    let env: Envelope<Vec<AccountBalance>> = CLIENT.get_signed(PATH, "", &headers).await?;
+    let mut totals: BTreeMap<String, Balance> = BTreeMap::new();
+    for a in env.into_data()? {
+        let asset = a.currency.to_uppercase();
+        let entry = totals.entry(asset.clone()).or_insert(Balance {
+            asset,
+            free: 0.0,
+            locked: 0.0,
+        });
+        entry.free += a.available;
+        entry.locked += a.holds;
+    }
+    Ok(totals.into_values().collect())
+}
+
+/// `POST /api/v1/bullet-public`.
+pub async fn bullet_public() -> Result<Bullet, String> {
+    let env: Envelope<Bullet> = CLIENT.post("/api/v1/bullet-public").await?;
+    env.into_data()
+}
+
+/// `GET /api/v2/symbols`.
+pub async fn instruments() -> Result<Vec<Instrument>, String> {
+    let env: Envelope<Vec<SymbolInfo>> = CLIENT.get("/api/v2/symbols", &[]).await?;
+    Ok(env
+        .into_data()?
+        .into_iter()
+        .map(|s| Instrument {
+            symbol: s.symbol,
+            base: s.base_currency.to_uppercase(),
+            quote: s.quote_currency.to_uppercase(),
+            trading: s.enable_trading,
+            status: None,
+            tick_size: s.price_increment,
+            step_size: s.base_increment,
+            min_notional: s.min_funds,
+        })
+        .collect())
+}
+
+/// `GET /api/v1/market/allTickers`.
+pub async fn tickers() -> Result<Vec<PairPrice>, String> {
+    let env: Envelope<AllTickers> = CLIENT.get("/api/v1/market/allTickers", &[]).await?;
+    let all = env.into_data()?;
+    Ok(all
+        .ticker
+        .into_iter()
+        .filter_map(|t| {
+            let (base, quote) = t.symbol.split_once('-')?;
+            Some(PairPrice {
+                base: base.to_uppercase(),
+                quote: quote.to_uppercase(),
+                price: t.last?,
+                is_spot: true,
+                volume: t.vol.unwrap_or(0.0),
+                event_time: all.time,
+                bid: t.buy,
+                ask: t.sell,
+            })
+        })
+        .collect())
+}
+
+/// `GET /api/v1/market/orderbook/level2_{20,100}`.
+pub async fn depth(base: &str, quote: &str, limit: usize) -> Result<Depth, String> {
+    let symbol = format!("{}-{}", base, quote).to_uppercase();
+    let path = if limit <= 20 {
+        "/api/v1/market/orderbook/level2_20"
+    } else {
+        "/api/v1/market/orderbook/level2_100"
+    };
+    let env: Envelope<Book> = CLIENT.get(path, &[("symbol", &symbol)]).await?;
+    let book = env.into_data()?;
+    let mut depth = Depth {
+        bids: levels(&book.bids),
+        asks: levels(&book.asks),
+    };
+    depth.bids.truncate(limit);
+    depth.asks.truncate(limit);
+    Ok(depth)
+}
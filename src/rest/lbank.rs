@@ -0,0 +1,109 @@
+use super::{levels, opt_num, Depth, Instrument, RestClient};
+use crate::models::PairPrice;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::Value;
+
+static CLIENT: Lazy<RestClient> = Lazy::new(|| RestClient::new("https://api.lbkex.com", 10));
+
+/// LBank wraps every payload as `{"result":"true","data":...,"error_code":0}`.
+#[derive(Deserialize)]
+struct Envelope<T> {
+    #[serde(default)]
+    error_code: i64,
+    data: Option<T>,
+}
+
+impl<T> Envelope<T> {
+    fn into_data(self) -> Result<T, String> {
+        match (self.error_code, self.data) {
+            (0, Some(data)) => Ok(data),
+            (code, _) => Err(format!("lbank error {}", code)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Ticker24h {
+    symbol: String,
+    ticker: TickerStats,
+    #[serde(default)]
+    timestamp: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct TickerStats {
+    #[serde(default, deserialize_with = "opt_num")]
+    latest: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    vol: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct Book {
+    bids: Vec<Vec<Value>>,
+    asks: Vec<Vec<Value>>,
+}
+
+/// "eth_btc" -> ("ETH", "BTC").
+pub fn split_pair(pair: &str) -> Option<(String, String)> {
+    let (base, quote) = pair.split_once('_')?;
+    Some((base.to_uppercase(), quote.to_uppercase()))
+}
+
+/// `GET /v2/currencyPairs.do`: lower-case pairs, all tradable.
+pub async fn instruments() -> Result<Vec<Instrument>, String> {
+    let env: Envelope<Vec<String>> = CLIENT.get("/v2/currencyPairs.do", &[]).await?;
+    Ok(env
+        .into_data()?
+        .into_iter()
+        .filter_map(|pair| {
+            let (base, quote) = split_pair(&pair)?;
+            Some(Instrument {
+                symbol: pair,
+                base,
+                quote,
+                trading: true,
+                ..Default::default()
+            })
+        })
+        .collect())
+}
+
+/// `GET /v2/ticker/24hr.do?symbol=all`.
+pub async fn tickers() -> Result<Vec<PairPrice>, String> {
+    let env: Envelope<Vec<Ticker24h>> = CLIENT
+        .get("/v2/ticker/24hr.do", &[("symbol", "all")])
+        .await?;
+    Ok(env
+        .into_data()?
+        .into_iter()
+        .filter_map(|t| {
+            let (base, quote) = split_pair(&t.symbol)?;
+            Some(PairPrice {
+                base,
+                quote,
+                price: t.ticker.latest.filter(|p| *p > 0.0)?,
+                is_spot: true,
+                volume: t.ticker.vol.unwrap_or(0.0),
+                event_time: t.timestamp.unwrap_or(0),
+                bid: None,
+                ask: None,
+            })
+        })
+        .collect())
+}
+
+/// `GET /v2/depth.do`.
+pub async fn depth(base: &str, quote: &str, limit: usize) -> Result<Depth, String> {
+    let symbol = format!("{}_{}", base, quote).to_lowercase();
+    let size = limit.clamp(1, 200).to_string();
+    let env: Envelope<Book> = CLIENT
+        .get("/v2/depth.do", &[("symbol", &symbol), ("size", &size)])
+        .await?;
+    let book = env.into_data()?;
+    Ok(Depth {
+        bids: levels(&book.bids),
+        asks: levels(&book.asks),
+    })
+}
@@ -0,0 +1,123 @@
+use super::{levels, num, opt_num, Depth, Instrument, RestClient};
+use crate::models::PairPrice;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+static CLIENT: Lazy<RestClient> = Lazy::new(|| RestClient::new("https://api.mexc.com", 10));
+
+#[derive(Deserialize)]
+struct ExchangeInfo {
+    symbols: Vec<SymbolInfo>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SymbolInfo {
+    symbol: String,
+    /// "1" (online) in current responses, "ENABLED" in older ones.
+    status: String,
+    base_asset: String,
+    quote_asset: String,
+    #[serde(default = "yes")]
+    is_spot_trading_allowed: bool,
+}
+
+fn yes() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Ticker24h {
+    symbol: String,
+    #[serde(deserialize_with = "num")]
+    last_price: f64,
+    #[serde(default, deserialize_with = "opt_num")]
+    bid_price: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    ask_price: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    volume: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    quote_volume: Option<f64>,
+    #[serde(default)]
+    close_time: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct Book {
+    bids: Vec<Vec<Value>>,
+    asks: Vec<Vec<Value>>,
+}
+
+/// `GET /api/v3/exchangeInfo`.
+pub async fn instruments() -> Result<Vec<Instrument>, String> {
+    let info: ExchangeInfo = CLIENT.get("/api/v3/exchangeInfo", &[]).await?;
+    Ok(info
+        .symbols
+        .into_iter()
+        .map(|s| Instrument {
+            trading: (s.status == "1" || s.status == "ENABLED") && s.is_spot_trading_allowed,
+            symbol: s.symbol,
+            base: s.base_asset.to_uppercase(),
+            quote: s.quote_asset.to_uppercase(),
+            ..Default::default()
+        })
+        .collect())
+}
+
+async fn raw_tickers() -> Result<Vec<Ticker24h>, String> {
+    CLIENT.get("/api/v3/ticker/24hr", &[]).await
+}
+
+/// `GET /api/v3/ticker/24hr` for every symbol, split using the instrument list.
+pub async fn tickers() -> Result<Vec<PairPrice>, String> {
+    let split: HashMap<String, Instrument> = instruments()
+        .await?
+        .into_iter()
+        .filter(|i| i.trading)
+        .map(|i| (i.symbol.clone(), i))
+        .collect();
+    Ok(raw_tickers()
+        .await?
+        .into_iter()
+        .filter_map(|t| {
+            let inst = split.get(&t.symbol)?;
+            Some(PairPrice {
+                base: inst.base.clone(),
+                quote: inst.quote.clone(),
+                price: t.last_price,
+                is_spot: true,
+                volume: t.volume.unwrap_or(0.0),
+                event_time: t.close_time.unwrap_or(0),
+                bid: t.bid_price,
+                ask: t.ask_price,
+            })
+        })
+        .collect())
+}
+
+/// Symbols ordered by 24h quote volume, most traded first.
+pub async fn most_traded() -> Result<Vec<String>, String> {
+    let mut raw = raw_tickers().await?;
+    raw.sort_by(|a, b| {
+        let (a, b) = (a.quote_volume.unwrap_or(0.0), b.quote_volume.unwrap_or(0.0));
+        b.total_cmp(&a)
+    });
+    Ok(raw.into_iter().map(|t| t.symbol).collect())
+}
+
+/// `GET /api/v3/depth`.
+pub async fn depth(base: &str, quote: &str, limit: usize) -> Result<Depth, String> {
+    let symbol = format!("{}{}", base, quote).to_uppercase();
+    let limit = limit.clamp(1, 5000).to_string();
+    let book: Book = CLIENT
+        .get("/api/v3/depth", &[("symbol", &symbol), ("limit", &limit)])
+        .await?;
+    Ok(Depth {
+        bids: levels(&book.bids),
+        asks: levels(&book.asks),
+    })
+}
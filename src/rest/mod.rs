@@ -0,0 +1,343 @@
+//! Typed clients for the venues' public REST endpoints (instrument lists,
+//! tickers, order book depth, KuCoin's WS token), rate limited per venue and
+//! shared by the connectors and the routes.
+
+use crate::config::AccountConfig;
+use crate::models::PairPrice;
+use ring::hmac;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use tokio::sync::Mutex;
+use tokio::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+pub mod ascendex;
+pub mod binance;
+pub mod binanceus;
+pub mod bingx;
+pub mod bitget;
+pub mod bithumb;
+pub mod bitstamp;
+pub mod bybit;
+pub mod coinbase;
+pub mod coinex;
+pub mod dydx;
+pub mod gateio;
+pub mod gemini;
+pub mod hyperliquid;
+pub mod kraken;
+pub mod kucoin;
+pub mod lbank;
+pub mod mexc;
+pub mod okx;
+pub mod phemex;
+pub mod poloniex;
+pub mod whitebit;
+
+/// One tradable market as the venue lists it. Trading rules are filled in
+/// where the venue's instrument list carries them.
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct Instrument {
+    /// The venue's own symbol, e.g. "BTCUSDT" or "BTC-USDT".
+    pub symbol: String,
+    pub base: String,
+    pub quote: String,
+    pub trading: bool,
+    /// The venue's own status, e.g. "TRADING", "BREAK" or "live".
+    pub status: Option<String>,
+    /// Price increment.
+    pub tick_size: Option<f64>,
+    /// Base quantity increment.
+    pub step_size: Option<f64>,
+    /// Smallest order value in quote.
+    pub min_notional: Option<f64>,
+}
+
+/// Order book levels as (price, quantity), best first.
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct Depth {
+    pub bids: Vec<(f64, f64)>,
+    pub asks: Vec<(f64, f64)>,
+}
+
+/// Holding of one asset in an account.
+#[derive(Debug, Clone, Serialize)]
+pub struct Balance {
+    pub asset: String,
+    pub free: f64,
+    pub locked: f64,
+}
+
+/// Non-zero balances of an account on a supported venue (signed request).
+pub async fn balances(account: &AccountConfig) -> Result<Vec<Balance>, String> {
+    let list = match account.exchange.as_str() {
+        "binance" => binance::balances(account).await,
+        "bybit" => bybit::balances(account).await,
+        "kucoin" => kucoin::balances(account).await,
+        "gateio" => gateio::balances(account).await,
+        other => Err(format!("no REST client for {}", other)),
+    }?;
+    Ok(list
+        .into_iter()
+        .filter(|b| b.free + b.locked > 0.0)
+        .collect())
+}
+
+/// Instrument list of a supported venue.
+pub async fn instruments(exchange: &str) -> Result<Vec<Instrument>, String> {
+    match exchange {
+        "binance" => binance::instruments().await,
+        "bybit" => bybit::instruments().await,
+        "kucoin" => kucoin::instruments().await,
+        "gateio" => gateio::instruments().await,
+        "okx" => okx::instruments().await,
+        "kraken" => kraken::instruments().await,
+        "coinbase" => coinbase::instruments().await,
+        "mexc" => mexc::instruments().await,
+        "bitget" => bitget::instruments().await,
+        "gemini" => gemini::instruments().await,
+        "bithumb" => bithumb::instruments().await,
+        "bitstamp" => bitstamp::instruments().await,
+        "poloniex" => poloniex::instruments().await,
+        "bingx" => bingx::instruments().await,
+        "whitebit" => whitebit::instruments().await,
+        "lbank" => lbank::instruments().await,
+        "phemex" => phemex::instruments().await,
+        "ascendex" => ascendex::instruments().await,
+        "coinex" => coinex::instruments().await,
+        "binanceus" => binanceus::instruments().await,
+        "hyperliquid" => hyperliquid::instruments().await,
+        "dydx" => dydx::instruments().await,
+        other => Err(format!("no REST client for {}", other)),
+    }
+}
+
+/// Latest ticker of every spot market of a supported venue.
+pub async fn tickers(exchange: &str) -> Result<Vec<PairPrice>, String> {
+    match exchange {
+        "binance" => binance::tickers().await,
+        "bybit" => bybit::tickers().await,
+        "kucoin" => kucoin::tickers().await,
+        "gateio" => gateio::tickers().await,
+        "okx" => okx::tickers().await,
+        "kraken" => kraken::tickers().await,
+        "coinbase" => coinbase::tickers().await,
+        "mexc" => mexc::tickers().await,
+        "bitget" => bitget::tickers().await,
+        "gemini" => gemini::tickers().await,
+        "bithumb" => bithumb::tickers().await,
+        "bitstamp" => bitstamp::tickers().await,
+        "poloniex" => poloniex::tickers().await,
+        "bingx" => bingx::tickers().await,
+        "whitebit" => whitebit::tickers().await,
+        "lbank" => lbank::tickers().await,
+        "phemex" => phemex::tickers().await,
+        "ascendex" => ascendex::tickers().await,
+        "coinex" => coinex::tickers().await,
+        "binanceus" => binanceus::tickers().await,
+        "hyperliquid" => hyperliquid::tickers().await,
+        "dydx" => dydx::tickers().await,
+        other => Err(format!("no REST client for {}", other)),
+    }
+}
+
+/// Venues `poll_tickers` can serve.
+const POLLED_VENUES: &[&str] = &[
+    "binance",
+    "bybit",
+    "kucoin",
+    "gateio",
+    "okx",
+    "kraken",
+    "coinbase",
+    "mexc",
+    "bitget",
+    "gemini",
+    "bithumb",
+    "bitstamp",
+    "poloniex",
+    "bingx",
+    "whitebit",
+    "lbank",
+    "phemex",
+    "ascendex",
+    "coinex",
+    "binanceus",
+    "hyperliquid",
+    "dydx",
+];
+
+pub fn can_poll(exchange: &str) -> bool {
+    POLLED_VENUES.contains(&exchange)
+}
+
+/// The cheapest full-market price poll of a venue, used while its stream is
+/// down: Binance's `bookTicker` (bid/ask only), otherwise `tickers`.
+pub async fn poll_tickers(exchange: &str) -> Result<Vec<PairPrice>, String> {
+    match exchange {
+        "binance" => binance::book_tickers().await,
+        "binanceus" => binanceus::book_tickers().await,
+        other => tickers(other).await,
+    }
+}
+
+/// Top `limit` order book levels of one pair.
+pub async fn depth(exchange: &str, base: &str, quote: &str, limit: usize) -> Result<Depth, String> {
+    match exchange {
+        "binance" => binance::depth(base, quote, limit).await,
+        "bybit" => bybit::depth(base, quote, limit).await,
+        "kucoin" => kucoin::depth(base, quote, limit).await,
+        "gateio" => gateio::depth(base, quote, limit).await,
+        "okx" => okx::depth(base, quote, limit).await,
+        "mexc" => mexc::depth(base, quote, limit).await,
+        "bitget" => bitget::depth(base, quote, limit).await,
+        "gemini" => gemini::depth(base, quote, limit).await,
+        "bithumb" => bithumb::depth(base, quote, limit).await,
+        "bitstamp" => bitstamp::depth(base, quote, limit).await,
+        "poloniex" => poloniex::depth(base, quote, limit).await,
+        "bingx" => bingx::depth(base, quote, limit).await,
+        "whitebit" => whitebit::depth(base, quote, limit).await,
+        "lbank" => lbank::depth(base, quote, limit).await,
+        "phemex" => phemex::depth(base, quote, limit).await,
+        "ascendex" => ascendex::depth(base, quote, limit).await,
+        "coinex" => coinex::depth(base, quote, limit).await,
+        "binanceus" => binanceus::depth(base, quote, limit).await,
+        "hyperliquid" => hyperliquid::depth(base, quote, limit).await,
+        "dydx" => dydx::depth(base, quote, limit).await,
+        other => Err(format!("no REST client for {}", other)),
+    }
+}
+
+/// Spaces requests to one venue at least `gap` apart.
+struct RateLimiter {
+    gap: Duration,
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    fn per_second(requests: u32) -> Self {
+        Self {
+            gap: Duration::from_secs(1) / requests.max(1),
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    async fn acquire(&self) {
+        let mut next = self.next.lock().await;
+        let now = Instant::now();
+        if *next > now {
+            tokio::time::sleep_until(*next).await;
+        }
+        *next = (*next).max(now) + self.gap;
+    }
+}
+
+/// JSON-over-HTTP client for one venue's base URL.
+pub(crate) struct RestClient {
+    base: &'static str,
+    http: reqwest::Client,
+    limiter: RateLimiter,
+}
+
+impl RestClient {
+    fn new(base: &'static str, requests_per_second: u32) -> Self {
+        Self {
+            base,
+            http: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            limiter: RateLimiter::per_second(requests_per_second),
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<T, String> {
+        self.limiter.acquire().await;
+        let request = self.http.get(format!("{}{}", self.base, path)).query(query);
+        send(request, path).await
+    }
+
+    /// GET with a pre-encoded query string (signatures cover it verbatim)
+    /// and extra headers.
+    async fn get_signed<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &str,
+        headers: &[(&str, String)],
+    ) -> Result<T, String> {
+        self.limiter.acquire().await;
+        let url = match query {
+            "" => format!("{}{}", self.base, path),
+            q => format!("{}{}?{}", self.base, path, q),
+        };
+        let request = headers
+            .iter()
+            .fold(self.http.get(url), |r, (k, v)| r.header(*k, v));
+        send(request, path).await
+    }
+
+    async fn post<T: DeserializeOwned>(&self, path: &str) -> Result<T, String> {
+        self.limiter.acquire().await;
+        send(self.http.post(format!("{}{}", self.base, path)), path).await
+    }
+
+    /// POST with a JSON body (RPC-style APIs such as Hyperliquid's `/info`).
+    async fn post_json<T: DeserializeOwned>(&self, path: &str, body: &Value) -> Result<T, String> {
+        self.limiter.acquire().await;
+        let request = self.http.post(format!("{}{}", self.base, path)).json(body);
+        send(request, path).await
+    }
+}
+
+async fn send<T: DeserializeOwned>(
+    request: reqwest::RequestBuilder,
+    path: &str,
+) -> Result<T, String> {
+    request
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("{}: {}", path, e))?
+        .json()
+        .await
+        .map_err(|e| format!("{}: {}", path, e))
+}
+
+fn hmac(algorithm: hmac::Algorithm, secret: &str, message: &str) -> Vec<u8> {
+    let key = hmac::Key::new(algorithm, secret.as_bytes());
+    hmac::sign(&key, message.as_bytes()).as_ref().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Venues send numbers as JSON strings; accept either.
+fn num<'de, D: Deserializer<'de>>(d: D) -> Result<f64, D::Error> {
+    let v = Value::deserialize(d)?;
+    as_f64(&v).ok_or_else(|| serde::de::Error::custom(format!("not a number: {}", v)))
+}
+
+fn opt_num<'de, D: Deserializer<'de>>(d: D) -> Result<Option<f64>, D::Error> {
+    Ok(as_f64(&Value::deserialize(d)?))
+}
+
+fn as_f64(v: &Value) -> Option<f64> {
+    match v {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+/// `[["price", "qty"], ...]` levels; malformed ones are skipped.
+fn levels(raw: &[Vec<Value>]) -> Vec<(f64, f64)> {
+    raw.iter()
+        .filter_map(|l| Some((as_f64(l.first()?)?, as_f64(l.get(1)?)?)))
+        .collect()
+}
@@ -0,0 +1,131 @@
+use super::{levels, num, opt_num, Depth, Instrument, RestClient};
+use crate::models::PairPrice;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::Value;
+
+static CLIENT: Lazy<RestClient> = Lazy::new(|| RestClient::new("https://www.okx.com", 10));
+
+/// OKX wraps every payload as `{"code":"0","msg":"","data":[...]}`.
+#[derive(Deserialize)]
+struct Envelope<T> {
+    code: String,
+    #[serde(default)]
+    msg: String,
+    #[serde(default = "Vec::new")]
+    data: Vec<T>,
+}
+
+impl<T> Envelope<T> {
+    fn into_data(self) -> Result<Vec<T>, String> {
+        match self.code.as_str() {
+            "0" => Ok(self.data),
+            code => Err(format!("okx error {}: {}", code, self.msg)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InstrumentInfo {
+    inst_id: String,
+    base_ccy: String,
+    quote_ccy: String,
+    state: String,
+    #[serde(default, deserialize_with = "opt_num")]
+    tick_sz: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    lot_sz: Option<f64>,
+}
+
+/// Shared by the REST tickers endpoint and the WS `tickers` channel.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Ticker {
+    pub inst_id: String,
+    #[serde(deserialize_with = "num")]
+    pub last: f64,
+    #[serde(default, deserialize_with = "opt_num")]
+    pub bid_px: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    pub ask_px: Option<f64>,
+    /// 24h volume in the base currency.
+    #[serde(default, deserialize_with = "opt_num")]
+    pub vol24h: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    pub ts: Option<f64>,
+}
+
+impl Ticker {
+    /// `instId` is always "BASE-QUOTE".
+    pub fn into_price(self) -> Option<PairPrice> {
+        let (base, quote) = self.inst_id.split_once('-')?;
+        Some(PairPrice {
+            base: base.to_uppercase(),
+            quote: quote.to_uppercase(),
+            price: self.last,
+            is_spot: true,
+            volume: self.vol24h.unwrap_or(0.0),
+            event_time: self.ts.unwrap_or(0.0) as u64,
+            bid: self.bid_px,
+            ask: self.ask_px,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct Book {
+    bids: Vec<Vec<Value>>,
+    asks: Vec<Vec<Value>>,
+}
+
+/// `GET /api/v5/public/instruments?instType=SPOT`.
+pub async fn instruments() -> Result<Vec<Instrument>, String> {
+    let env: Envelope<InstrumentInfo> = CLIENT
+        .get("/api/v5/public/instruments", &[("instType", "SPOT")])
+        .await?;
+    Ok(env
+        .into_data()?
+        .into_iter()
+        .map(|i| Instrument {
+            trading: i.state == "live",
+            tick_size: i.tick_sz,
+            step_size: i.lot_sz,
+            min_notional: None,
+            symbol: i.inst_id,
+            base: i.base_ccy.to_uppercase(),
+            quote: i.quote_ccy.to_uppercase(),
+            status: Some(i.state),
+        })
+        .collect())
+}
+
+/// `GET /api/v5/market/tickers?instType=SPOT`.
+pub async fn tickers() -> Result<Vec<PairPrice>, String> {
+    let env: Envelope<Ticker> = CLIENT
+        .get("/api/v5/market/tickers", &[("instType", "SPOT")])
+        .await?;
+    Ok(env
+        .into_data()?
+        .into_iter()
+        .filter_map(Ticker::into_price)
+        .collect())
+}
+
+/// `GET /api/v5/market/books`.
+pub async fn depth(base: &str, quote: &str, limit: usize) -> Result<Depth, String> {
+    let inst = format!("{}-{}", base, quote).to_uppercase();
+    let size = limit.clamp(1, 400).to_string();
+    let env: Envelope<Book> = CLIENT
+        .get("/api/v5/market/books", &[("instId", &inst), ("sz", &size)])
+        .await?;
+    let book = env
+        .into_data()?
+        .into_iter()
+        .next()
+        .ok_or("okx: empty order book")?;
+    Ok(Depth {
+        bids: levels(&book.bids),
+        asks: levels(&book.asks),
+    })
+}
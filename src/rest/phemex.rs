@@ -0,0 +1,207 @@
+use super::{as_f64, Depth, Instrument, RestClient};
+use crate::models::PairPrice;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+static CLIENT: Lazy<RestClient> = Lazy::new(|| RestClient::new("https://api.phemex.com", 10));
+
+#[derive(Deserialize)]
+struct ProductsEnvelope {
+    code: i64,
+    #[serde(default)]
+    msg: String,
+    data: Option<ProductsData>,
+}
+
+#[derive(Deserialize)]
+struct ProductsData {
+    currencies: Vec<CurrencyInfo>,
+    products: Vec<ProductInfo>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CurrencyInfo {
+    currency: String,
+    value_scale: i32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProductInfo {
+    symbol: String,
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    base_currency: String,
+    #[serde(default)]
+    quote_currency: String,
+    #[serde(default)]
+    price_scale: i32,
+    #[serde(default)]
+    status: String,
+}
+
+/// Market-data envelope: `{"error":null,"id":0,"result":...}`.
+#[derive(Deserialize)]
+struct MdEnvelope<T> {
+    error: Option<Value>,
+    result: Option<T>,
+}
+
+impl<T> MdEnvelope<T> {
+    fn into_result(self) -> Result<T, String> {
+        match (self.error, self.result) {
+            (None, Some(result)) => Ok(result),
+            (error, _) => Err(format!("phemex error: {}", error.unwrap_or(Value::Null))),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct BookResult {
+    book: Book,
+}
+
+#[derive(Deserialize)]
+struct Book {
+    bids: Vec<Vec<Value>>,
+    asks: Vec<Vec<Value>>,
+}
+
+/// One spot product with the scales its integer fields use: prices (`*Ep`)
+/// are multiplied by 10^price_scale, base quantities (`*Ev`) by
+/// 10^value_scale.
+#[derive(Debug, Clone)]
+pub(crate) struct Product {
+    /// "sBTCUSDT".
+    pub symbol: String,
+    pub base: String,
+    pub quote: String,
+    pub price_scale: i32,
+    pub value_scale: i32,
+    pub listed: bool,
+}
+
+impl Product {
+    pub fn price(&self, ep: &Value) -> Option<f64> {
+        Some(as_f64(ep)? / 10f64.powi(self.price_scale))
+    }
+
+    pub fn quantity(&self, ev: &Value) -> Option<f64> {
+        Some(as_f64(ev)? / 10f64.powi(self.value_scale))
+    }
+
+    /// A spot 24h ticker (REST `result[]` or WS `spot_market24h`), with its
+    /// nanosecond timestamp.
+    pub fn ticker_price(&self, t: &Value) -> Option<PairPrice> {
+        let price = t.get("lastEp").and_then(|p| self.price(p))?;
+        Some(PairPrice {
+            base: self.base.clone(),
+            quote: self.quote.clone(),
+            price,
+            is_spot: true,
+            volume: t
+                .get("volumeEv")
+                .and_then(|v| self.quantity(v))
+                .unwrap_or(0.0),
+            event_time: t.get("timestamp").and_then(|t| t.as_u64()).unwrap_or(0) / 1_000_000,
+            bid: t
+                .get("bidEp")
+                .and_then(|p| self.price(p))
+                .filter(|p| *p > 0.0),
+            ask: t
+                .get("askEp")
+                .and_then(|p| self.price(p))
+                .filter(|p| *p > 0.0),
+        })
+    }
+}
+
+/// `GET /public/products`, spot products only.
+pub(crate) async fn products() -> Result<Vec<Product>, String> {
+    let env: ProductsEnvelope = CLIENT.get("/public/products", &[]).await?;
+    let data = match (env.code, env.data) {
+        (0, Some(data)) => data,
+        (code, _) => return Err(format!("phemex error {}: {}", code, env.msg)),
+    };
+    let value_scales: HashMap<String, i32> = data
+        .currencies
+        .into_iter()
+        .map(|c| (c.currency, c.value_scale))
+        .collect();
+    Ok(data
+        .products
+        .into_iter()
+        .filter(|p| p.kind == "Spot")
+        .filter_map(|p| {
+            Some(Product {
+                value_scale: *value_scales.get(&p.base_currency)?,
+                price_scale: p.price_scale,
+                listed: p.status == "Listed",
+                base: p.base_currency.to_uppercase(),
+                quote: p.quote_currency.to_uppercase(),
+                symbol: p.symbol,
+            })
+        })
+        .collect())
+}
+
+fn by_symbol(products: Vec<Product>) -> HashMap<String, Product> {
+    products
+        .into_iter()
+        .map(|p| (p.symbol.clone(), p))
+        .collect()
+}
+
+pub async fn instruments() -> Result<Vec<Instrument>, String> {
+    Ok(products()
+        .await?
+        .into_iter()
+        .map(|p| Instrument {
+            trading: p.listed,
+            symbol: p.symbol,
+            base: p.base,
+            quote: p.quote,
+            ..Default::default()
+        })
+        .collect())
+}
+
+/// `GET /md/spot/ticker/24hr/all`, unscaled with the product list.
+pub async fn tickers() -> Result<Vec<PairPrice>, String> {
+    let products = by_symbol(products().await?);
+    let env: MdEnvelope<Vec<Value>> = CLIENT.get("/md/spot/ticker/24hr/all", &[]).await?;
+    Ok(env
+        .into_result()?
+        .iter()
+        .filter_map(|t| {
+            let product = products.get(t.get("symbol")?.as_str()?)?;
+            product.ticker_price(t)
+        })
+        .collect())
+}
+
+/// `GET /md/orderbook`, truncated to `limit` levels.
+pub async fn depth(base: &str, quote: &str, limit: usize) -> Result<Depth, String> {
+    let symbol = format!("s{}{}", base.to_uppercase(), quote.to_uppercase());
+    let products = by_symbol(products().await?);
+    let product = products
+        .get(&symbol)
+        .ok_or_else(|| format!("phemex: unknown product {}", symbol))?;
+    let env: MdEnvelope<BookResult> = CLIENT.get("/md/orderbook", &[("symbol", &symbol)]).await?;
+    let book = env.into_result()?.book;
+    let side = |levels: &[Vec<Value>]| -> Vec<(f64, f64)> {
+        levels
+            .iter()
+            .filter_map(|l| Some((product.price(l.first()?)?, product.quantity(l.get(1)?)?)))
+            .take(limit.max(1))
+            .collect()
+    };
+    Ok(Depth {
+        bids: side(&book.bids),
+        asks: side(&book.asks),
+    })
+}
@@ -0,0 +1,99 @@
+use super::{as_f64, num, opt_num, Depth, Instrument, RestClient};
+use crate::models::PairPrice;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::Value;
+
+static CLIENT: Lazy<RestClient> = Lazy::new(|| RestClient::new("https://api.poloniex.com", 10));
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Market {
+    symbol: String,
+    base_currency_name: String,
+    quote_currency_name: String,
+    state: String,
+}
+
+/// Shared by the REST `ticker24h` endpoint and the WS `ticker` channel.
+#[derive(Deserialize)]
+pub(crate) struct Ticker {
+    /// "BTC_USDT".
+    pub symbol: String,
+    #[serde(deserialize_with = "num")]
+    pub close: f64,
+    /// 24h volume in the base currency.
+    #[serde(default, deserialize_with = "opt_num")]
+    pub quantity: Option<f64>,
+    #[serde(default)]
+    pub ts: Option<u64>,
+}
+
+impl Ticker {
+    pub fn into_price(self) -> Option<PairPrice> {
+        let (base, quote) = self.symbol.split_once('_')?;
+        Some(PairPrice {
+            base: base.to_uppercase(),
+            quote: quote.to_uppercase(),
+            price: self.close,
+            is_spot: true,
+            volume: self.quantity.unwrap_or(0.0),
+            event_time: self.ts.unwrap_or(0),
+            bid: None,
+            ask: None,
+        })
+    }
+}
+
+/// Levels come flattened: `["price", "qty", "price", "qty", ...]`.
+#[derive(Deserialize)]
+struct Book {
+    bids: Vec<Value>,
+    asks: Vec<Value>,
+}
+
+fn flat_levels(raw: &[Value]) -> Vec<(f64, f64)> {
+    raw.chunks_exact(2)
+        .filter_map(|l| Some((as_f64(&l[0])?, as_f64(&l[1])?)))
+        .collect()
+}
+
+/// `GET /markets`.
+pub async fn instruments() -> Result<Vec<Instrument>, String> {
+    let markets: Vec<Market> = CLIENT.get("/markets", &[]).await?;
+    Ok(markets
+        .into_iter()
+        .map(|m| Instrument {
+            trading: m.state == "NORMAL",
+            symbol: m.symbol,
+            base: m.base_currency_name.to_uppercase(),
+            quote: m.quote_currency_name.to_uppercase(),
+            ..Default::default()
+        })
+        .collect())
+}
+
+/// `GET /markets/ticker24h`.
+pub async fn tickers() -> Result<Vec<PairPrice>, String> {
+    let raw: Vec<Ticker> = CLIENT.get("/markets/ticker24h", &[]).await?;
+    Ok(raw
+        .into_iter()
+        .filter(|t| t.close > 0.0)
+        .filter_map(Ticker::into_price)
+        .collect())
+}
+
+/// `GET /markets/{symbol}/orderBook`; Poloniex only accepts a few limits.
+pub async fn depth(base: &str, quote: &str, limit: usize) -> Result<Depth, String> {
+    let path = format!("/markets/{}_{}/orderBook", base, quote).to_uppercase();
+    let limit = [5, 10, 20, 50, 100, 150]
+        .into_iter()
+        .find(|l| *l >= limit)
+        .unwrap_or(150)
+        .to_string();
+    let book: Book = CLIENT.get(&path, &[("limit", &limit)]).await?;
+    Ok(Depth {
+        bids: flat_levels(&book.bids),
+        asks: flat_levels(&book.asks),
+    })
+}
@@ -0,0 +1,95 @@
+use super::{levels, opt_num, Depth, Instrument, RestClient};
+use crate::models::PairPrice;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+static CLIENT: Lazy<RestClient> = Lazy::new(|| RestClient::new("https://whitebit.com", 10));
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Market {
+    /// "BTC_USDT".
+    name: String,
+    stock: String,
+    money: String,
+    trades_enabled: bool,
+    #[serde(rename = "type", default)]
+    kind: String,
+}
+
+#[derive(Deserialize)]
+struct Ticker {
+    #[serde(default, deserialize_with = "opt_num")]
+    last_price: Option<f64>,
+    #[serde(default, deserialize_with = "opt_num")]
+    base_volume: Option<f64>,
+    #[serde(default, rename = "isFrozen")]
+    is_frozen: bool,
+}
+
+#[derive(Deserialize)]
+struct Book {
+    bids: Vec<Vec<Value>>,
+    asks: Vec<Vec<Value>>,
+}
+
+/// "BTC_USDT" -> ("BTC", "USDT").
+pub fn split_market(market: &str) -> Option<(String, String)> {
+    let (base, quote) = market.split_once('_')?;
+    Some((base.to_uppercase(), quote.to_uppercase()))
+}
+
+/// `GET /api/v4/public/markets`, spot markets only.
+pub async fn instruments() -> Result<Vec<Instrument>, String> {
+    let markets: Vec<Market> = CLIENT.get("/api/v4/public/markets", &[]).await?;
+    Ok(markets
+        .into_iter()
+        .filter(|m| m.kind.is_empty() || m.kind == "spot")
+        .map(|m| Instrument {
+            trading: m.trades_enabled,
+            symbol: m.name,
+            base: m.stock.to_uppercase(),
+            quote: m.money.to_uppercase(),
+            ..Default::default()
+        })
+        .collect())
+}
+
+/// `GET /api/v4/public/ticker`, keyed by market name.
+pub async fn tickers() -> Result<Vec<PairPrice>, String> {
+    let raw: HashMap<String, Ticker> = CLIENT.get("/api/v4/public/ticker", &[]).await?;
+    Ok(raw
+        .into_iter()
+        .filter(|(_, t)| !t.is_frozen)
+        .filter_map(|(market, t)| {
+            let (base, quote) = split_market(&market)?;
+            Some(PairPrice {
+                base,
+                quote,
+                price: t.last_price.filter(|p| *p > 0.0)?,
+                is_spot: true,
+                volume: t.base_volume.unwrap_or(0.0),
+                event_time: 0,
+                bid: None,
+                ask: None,
+            })
+        })
+        .collect())
+}
+
+/// `GET /api/v4/public/orderbook/{market}`.
+pub async fn depth(base: &str, quote: &str, limit: usize) -> Result<Depth, String> {
+    let path = format!(
+        "/api/v4/public/orderbook/{}_{}",
+        base.to_uppercase(),
+        quote.to_uppercase()
+    );
+    let limit = limit.clamp(1, 100).to_string();
+    let book: Book = CLIENT.get(&path, &[("limit", &limit)]).await?;
+    Ok(Depth {
+        bids: levels(&book.bids),
+        asks: levels(&book.asks),
+    })
+}
@@ -1,28 +1,852 @@
-use axum::{routing::post, Json, Router};
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::{get, post},
+    Json, Router,
+};
 use futures::future::join_all;
-use serde::Deserialize;
+use futures::stream::{FuturesUnordered, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::convert::Infallible;
+use std::sync::Arc;
 use tracing::info;
 
-use crate::exchanges::collect_exchange_snapshot;
-use crate::logic::find_triangular_opportunities;
-use crate::models::{PairPrice, TriangularResult};
+use crate::alerts::alerts;
+use crate::backtest::{
+    backtests, csv_report, html_report, sweep, Backtest, BacktestParams, Sweep, SweepRequest,
+};
+use crate::bench::{self, BenchReport, BenchRequest};
+use crate::blacklist::{blacklist, BlacklistEntry};
+use crate::candles::{candles, Candle, Interval};
+use crate::ccxt;
+use crate::config::{config, JobConfig};
+use crate::coverage::{coverage, ExchangeCoverage};
+use crate::dump;
+use crate::exchanges::{collect_exchange_snapshot, load_pairs_file};
+use crate::health::{health, HealthReport};
+use crate::history::{history, ScanRecord};
+use crate::lifetime::{lifetime, LifetimeReport};
+use crate::logic::{
+    find_merged_opportunities, find_triangular_opportunities, flag_anomalies, flag_restored,
+    leg_qualities, score_confidence, score_volatility, simulate_profits, sort_results,
+    suggest_execution_order,
+};
+use crate::metrics::{metrics, MetricsReport};
+use crate::models::{PairPrice, ScanParams, SortKey, TriangularResult};
+use crate::noise::{noise, NoisyTriangle};
+use crate::openapi;
+use crate::orderbook::{books, Fill, Side};
+use crate::portfolio::{portfolio, Portfolio};
+use crate::precision::Precision;
+use crate::rest::{self, Depth};
+use crate::scan_cache::ScanCache;
+use crate::scheduler::{scheduler, JobAction, JobState};
+use crate::sheets;
+use crate::stats::stats;
+use crate::store::{pair_key, SharedPrices};
+use crate::symbols::symbols;
+use crate::tasks::{self, tasks, TasksReport};
+use crate::ticks::{ticks, Sample};
+use crate::utils::{now_ms, parse_duration_ms};
+use crate::ws_manager::{connector_statuses, ConnectorStatus};
+use utoipa::{IntoParams, ToSchema};
 
-pub fn routes() -> Router {
-    Router::new().route("/scan", post(scan_handler))
+/// Shared handles available to every handler.
+#[derive(Clone)]
+pub struct AppState {
+    pub prices: SharedPrices,
+    pub scan_cache: Arc<ScanCache>,
 }
 
-#[derive(Debug, Deserialize)]
+impl AppState {
+    pub fn new(prices: SharedPrices) -> Self {
+        Self {
+            prices,
+            scan_cache: Arc::default(),
+        }
+    }
+}
+
+pub fn routes(state: AppState) -> Router {
+    Router::new()
+        .route("/scan", post(scan_handler).get(scan_get_handler))
+        .route("/health", get(health_handler))
+        .route("/metrics", get(metrics_handler))
+        .route("/stats/lifetime", get(lifetime_handler))
+        .route("/coverage", get(coverage_handler))
+        .route("/connectors", get(connectors_handler))
+        .route("/suppressions", get(suppressions_handler))
+        .route("/backtests/:id", get(backtest_by_id_handler))
+        .route("/backtests/:id/report", get(backtest_report_handler))
+        .route(
+            "/prices/:exchange/:base/:quote/changes",
+            get(price_changes_handler),
+        )
+        .route("/candles/:exchange/:base/:quote", get(candles_handler))
+        .route("/depth/:exchange/:base/:quote", get(depth_handler))
+        .route("/books/:exchange/:base/:quote/fill", get(book_fill_handler))
+        .route("/symbols/:exchange", get(symbols_handler))
+        .route("/symbols/:exchange/:base/:quote", get(symbol_handler))
+        .route("/ccxt/:exchange/markets", get(ccxt_markets_handler))
+        .route("/ccxt/:exchange/tickers", get(ccxt_tickers_handler))
+        .route("/scan/stream", get(scan_stream_handler))
+        .route("/scan/all", post(scan_all_handler))
+        .route("/scans", get(scans_handler))
+        .route("/scans/:id", get(scan_by_id_handler))
+        .merge(openapi::swagger_ui())
+        .with_state(state)
+}
+
+/// Control-plane routes (`/admin/*`); served on the main listener or on
+/// `admin.bind`, behind `admin.allow` either way.
+pub fn admin_routes(state: AppState) -> Router {
+    Router::new()
+        .route("/admin/jobs", get(jobs_handler))
+        .route("/admin/bench", post(bench_handler))
+        .route("/admin/tasks", get(tasks_handler))
+        .route("/admin/dump", post(dump_handler))
+        .route("/admin/portfolio", get(portfolio_handler))
+        .route("/admin/backtest", post(backtest_handler))
+        .route("/admin/backtest/sweep", post(backtest_sweep_handler))
+        .route("/admin/jobs/:name/:action", post(job_action_handler))
+        .route(
+            "/admin/blacklist",
+            get(blacklist_list_handler)
+                .post(blacklist_add_handler)
+                .delete(blacklist_remove_handler),
+        )
+        .with_state(state)
+}
+
+/// Per-exchange connection state, error/rate-limit counters and last error.
+#[utoipa::path(
+    get, path = "/health", responses((status = 200, body = HealthReport))
+)]
+async fn health_handler() -> Json<HealthReport> {
+    Json(health().report())
+}
+
+/// Ingestion metrics: per-flush price churn and per-symbol update intervals.
+#[utoipa::path(
+    get, path = "/metrics", params(Precision), responses((status = 200, body = MetricsReport))
+)]
+async fn metrics_handler(Query(precision): Query<Precision>) -> Response {
+    precision.json(&metrics().report())
+}
+
+/// Per exchange: instruments listed versus tracked, and which symbols were
+/// dropped by parsing or ingestion filters.
+#[utoipa::path(
+    get, path = "/coverage", responses((status = 200, body = BTreeMap<String, ExchangeCoverage>))
+)]
+async fn coverage_handler(
+    State(state): State<AppState>,
+) -> Json<BTreeMap<String, ExchangeCoverage>> {
+    Json(coverage().report(&state.prices))
+}
+
+/// Built-in connectors and whether this config runs them.
+#[utoipa::path(
+    get, path = "/connectors", responses((status = 200, body = Vec<ConnectorStatus>))
+)]
+async fn connectors_handler() -> Json<Vec<ConnectorStatus>> {
+    Json(connector_statuses())
+}
+
+/// Balances of the configured accounts with USD value and per-asset exposure.
+#[utoipa::path(
+    get, path = "/admin/portfolio", tag = "admin", responses((status = 200, body = Portfolio))
+)]
+async fn portfolio_handler(State(state): State<AppState>) -> Json<Portfolio> {
+    Json(portfolio(&state.prices).await)
+}
+
+/// Replay the recorded scans under the given fees, slippage and threshold.
+#[utoipa::path(
+    post, path = "/admin/backtest", tag = "admin", request_body(content = Option<BacktestParams>),
+    responses((status = 200, body = Backtest), (status = 500, body = String))
+)]
+async fn backtest_handler(
+    body: Option<Json<BacktestParams>>,
+) -> Result<Json<Backtest>, (StatusCode, String)> {
+    let params = body.map(|Json(p)| p).unwrap_or_default();
+    // Replays the whole history; keep it off the async workers.
+    tokio::task::spawn_blocking(move || backtests().start(params))
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Backtest a grid of fee, threshold and slippage values; one cell each.
+#[utoipa::path(
+    post, path = "/admin/backtest/sweep", tag = "admin", request_body = SweepRequest,
+    responses((status = 200, body = Sweep), (status = 400, body = String))
+)]
+async fn backtest_sweep_handler(
+    Json(req): Json<SweepRequest>,
+) -> Result<Json<Sweep>, (StatusCode, String)> {
+    // Up to thousands of replays; keep them off the async workers.
+    tokio::task::spawn_blocking(move || sweep(&req))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map(Json)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))
+}
+
+#[utoipa::path(
+    get, path = "/backtests/{id}", params(("id" = u64, Path)),
+    responses((status = 200, body = Backtest), (status = 404))
+)]
+async fn backtest_by_id_handler(Path(id): Path<u64>) -> Result<Json<Backtest>, StatusCode> {
+    backtests().get(id).map(Json).ok_or(StatusCode::NOT_FOUND)
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ReportQuery {
+    /// "html" (default) or "csv".
+    format: Option<String>,
+}
+
+/// Downloadable backtest report: HTML summary with equity curve, or the
+/// trades as CSV.
+#[utoipa::path(
+    get, path = "/backtests/{id}/report", params(("id" = u64, Path), ReportQuery),
+    responses(
+        (status = 200, content((String = "text/html"), (String = "text/csv"))),
+        (status = 400, body = String),
+        (status = 404, body = String),
+    )
+)]
+async fn backtest_report_handler(
+    Path(id): Path<u64>,
+    Query(q): Query<ReportQuery>,
+) -> Result<Response, (StatusCode, String)> {
+    let b = backtests()
+        .get(id)
+        .ok_or((StatusCode::NOT_FOUND, format!("no backtest {}", id)))?;
+    let (body, content_type, ext) = match q.format.as_deref().unwrap_or("html") {
+        "html" => (html_report(&b), "text/html; charset=utf-8", "html"),
+        "csv" => (csv_report(&b), "text/csv", "csv"),
+        other => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("unknown format \"{}\"", other),
+            ))
+        }
+    };
+    let disposition = format!("attachment; filename=\"backtest-{}.{}\"", id, ext);
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// Scan a synthetic universe repeatedly and report latency and allocations.
+#[utoipa::path(
+    post, path = "/admin/bench", tag = "admin", request_body(content = Option<BenchRequest>),
+    responses((status = 200, body = BenchReport), (status = 400, body = String))
+)]
+async fn bench_handler(
+    body: Option<Json<BenchRequest>>,
+) -> Result<Json<BenchReport>, (StatusCode, String)> {
+    let req = body.map(|Json(r)| r).unwrap_or_default();
+    tokio::task::spawn_blocking(move || bench::run(&req))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map(Json)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))
+}
+
+/// Write a diagnostic snapshot to `dump.dir` and return where it went.
+#[utoipa::path(
+    post, path = "/admin/dump", tag = "admin", responses((status = 200, body = Object), (status = 500, body = String))
+)]
+async fn dump_handler(
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let prices = state.prices.clone();
+    let path = tokio::task::spawn_blocking(move || dump::write(&prices))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(json!({ "path": path.display().to_string() })))
+}
+
+/// Counters accumulated over every run, persisted across restarts.
+#[utoipa::path(
+    get, path = "/stats/lifetime", params(Precision), responses((status = 200, body = LifetimeReport))
+)]
+async fn lifetime_handler(Query(precision): Query<Precision>) -> Response {
+    precision.json(&lifetime().report())
+}
+
+/// Poll timings of named background tasks (`tokio-console` feature).
+#[utoipa::path(
+    get, path = "/admin/tasks", tag = "admin", responses((status = 200, body = TasksReport))
+)]
+async fn tasks_handler() -> Json<TasksReport> {
+    Json(tasks().report())
+}
+
+/// Scheduled scans: next run, last duration and result count, failures.
+#[utoipa::path(
+    get, path = "/admin/jobs", tag = "admin", responses((status = 200, body = BTreeMap<String, JobState>))
+)]
+async fn jobs_handler() -> Json<BTreeMap<String, JobState>> {
+    Json(scheduler().report())
+}
+
+/// `pause`, `resume` or `run` (trigger now, even when paused).
+#[utoipa::path(
+    post, path = "/admin/jobs/{name}/{action}", tag = "admin",
+    params(("name" = String, Path), ("action" = String, Path, description = "`pause`, `resume` or `run`")),
+    responses(
+        (status = 200, body = BTreeMap<String, JobState>),
+        (status = 400, body = String),
+        (status = 404, body = String),
+    )
+)]
+async fn job_action_handler(
+    Path((name, action)): Path<(String, String)>,
+) -> Result<Json<BTreeMap<String, JobState>>, (StatusCode, String)> {
+    let action = match action.as_str() {
+        "pause" => JobAction::Pause,
+        "resume" => JobAction::Resume,
+        "run" => JobAction::RunNow,
+        other => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("unknown action \"{}\" (pause, resume, run)", other),
+            ))
+        }
+    };
+    if !scheduler().act(&name, action) {
+        return Err((StatusCode::NOT_FOUND, format!("no job \"{}\"", name)));
+    }
+    info!("job {}: {:?}", name, action);
+    Ok(Json(scheduler().report()))
+}
+
+/// One scheduled scan over the live store, recorded in scan history.
+pub fn run_job(job: &JobConfig, prices: &SharedPrices) -> Result<usize, String> {
+    let mut tuning = Tuning {
+        preset: job.preset.clone(),
+        min_profit: job.min_profit,
+        ..Tuning::default()
+    };
+    let preset_exchanges = tuning.apply_preset().map_err(|(_, e)| e)?;
+    let exchanges = match (&job.exchanges, preset_exchanges) {
+        (list, _) if !list.is_empty() => list.clone(),
+        (_, preset) if !preset.is_empty() => preset,
+        _ => prices.exchanges(),
+    };
+    let results: Vec<TriangularResult> = exchanges
+        .iter()
+        .flat_map(|exch| {
+            let params = tuning.params_for(exch);
+            let results = scan_pairs(exch, prices.snapshot(exch), &params, prices);
+            publish_live(exch, &results);
+            results
+        })
+        .collect();
+    let params = json!({ "job": job.name, "exchanges": exchanges, "tuning": tuning });
+    history().record(params, &results);
+    sheets::export_summary(&job.name, &results);
+    Ok(results.len())
+}
+
+/// Triangles seen profitable only while one leg was stale, and whether
+/// they are suppressed.
+#[utoipa::path(
+    get, path = "/suppressions", responses((status = 200, body = Vec<NoisyTriangle>))
+)]
+async fn suppressions_handler() -> Json<Vec<NoisyTriangle>> {
+    Json(noise().list(&config().scan.noise))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ChangesQuery {
+    /// e.g. "60s", "5m"; default one minute.
+    window: Option<String>,
+}
+
+/// Recent samples of one pair and how much it moved over the window.
+#[derive(Debug, Serialize, ToSchema)]
+struct PriceChanges {
+    exchange: String,
+    symbol: String,
+    window_ms: u64,
+    first: f64,
+    last: f64,
+    change_pct: f64,
+    samples: Vec<Sample>,
+}
+
+/// Recent price samples of one leg, to verify its movement around an
+/// opportunity.
+#[utoipa::path(
+    get, path = "/prices/{exchange}/{base}/{quote}/changes", params(("exchange" = String, Path), ("base" = String, Path), ("quote" = String, Path), ChangesQuery),
+    responses((status = 200, body = PriceChanges), (status = 400, body = String), (status = 404, body = String))
+)]
+async fn price_changes_handler(
+    Path((exchange, base, quote)): Path<(String, String, String)>,
+    Query(q): Query<ChangesQuery>,
+) -> Result<Json<PriceChanges>, (StatusCode, String)> {
+    let window_ms = match q.window.as_deref() {
+        Some(text) => parse_duration_ms(text).ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                format!("bad window \"{}\" (e.g. 60s, 5m)", text),
+            )
+        })?,
+        None => 60_000,
+    };
+    let exchange = exchange.to_lowercase();
+    let symbol = pair_key(&base.to_uppercase(), &quote.to_uppercase());
+    let samples = ticks().since(&exchange, &symbol, now_ms().saturating_sub(window_ms));
+    let (Some(first), Some(last)) = (samples.first(), samples.last()) else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            format!(
+                "no samples for {} on {} in the last {}ms",
+                symbol, exchange, window_ms
+            ),
+        ));
+    };
+    Ok(Json(PriceChanges {
+        change_pct: (last.price / first.price - 1.0) * 100.0,
+        first: first.price,
+        last: last.price,
+        exchange,
+        symbol,
+        window_ms,
+        samples,
+    }))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct CandlesQuery {
+    /// "1s" or "1m"; default "1m".
+    interval: Option<Interval>,
+    limit: Option<usize>,
+}
+
+/// Recent OHLCV bars of one pair, oldest first.
+#[utoipa::path(
+    get, path = "/candles/{exchange}/{base}/{quote}", params(("exchange" = String, Path), ("base" = String, Path), ("quote" = String, Path), CandlesQuery),
+    responses((status = 200, body = Vec<Candle>))
+)]
+async fn candles_handler(
+    Path((exchange, base, quote)): Path<(String, String, String)>,
+    Query(q): Query<CandlesQuery>,
+) -> Json<Vec<Candle>> {
+    let symbol = pair_key(&base.to_uppercase(), &quote.to_uppercase());
+    let interval = q.interval.unwrap_or(Interval::Minute);
+    Json(candles().latest(
+        &exchange.to_lowercase(),
+        &symbol,
+        interval,
+        q.limit.unwrap_or(60),
+    ))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct DepthQuery {
+    /// Levels per side; default 20.
+    limit: Option<usize>,
+}
+
+/// Order book of one pair: the local book when one is kept, otherwise a
+/// snapshot from the venue's REST API.
+#[utoipa::path(
+    get, path = "/depth/{exchange}/{base}/{quote}", params(("exchange" = String, Path), ("base" = String, Path), ("quote" = String, Path), DepthQuery),
+    responses((status = 200, body = Depth), (status = 502, body = String))
+)]
+async fn depth_handler(
+    Path((exchange, base, quote)): Path<(String, String, String)>,
+    Query(q): Query<DepthQuery>,
+) -> Result<Json<Depth>, (StatusCode, String)> {
+    let (exchange, base, quote) = (
+        exchange.to_lowercase(),
+        base.to_uppercase(),
+        quote.to_uppercase(),
+    );
+    let limit = q.limit.unwrap_or(20);
+    if let Some(depth) = books().depth(&exchange, &base, &quote, limit) {
+        return Ok(Json(depth));
+    }
+    rest::depth(&exchange, &base, &quote, limit)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::BAD_GATEWAY, e))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct FillQuery {
+    side: Side,
+    /// In quote currency.
+    notional: f64,
+}
+
+/// Average price of trading `notional` against the local order book.
+#[utoipa::path(
+    get, path = "/books/{exchange}/{base}/{quote}/fill", params(("exchange" = String, Path), ("base" = String, Path), ("quote" = String, Path), FillQuery),
+    responses((status = 200, body = Fill), (status = 404, body = String))
+)]
+async fn book_fill_handler(
+    Path((exchange, base, quote)): Path<(String, String, String)>,
+    Query(q): Query<FillQuery>,
+) -> Result<Json<Fill>, (StatusCode, String)> {
+    let (exchange, base, quote) = (
+        exchange.to_lowercase(),
+        base.to_uppercase(),
+        quote.to_uppercase(),
+    );
+    books()
+        .fill(&exchange, &base, &quote, q.side, q.notional)
+        .map(Json)
+        .ok_or_else(|| {
+            let msg = format!("no local book for {} {}", exchange, pair_key(&base, &quote));
+            (StatusCode::NOT_FOUND, msg)
+        })
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct SymbolList {
+    fetched_ms: u64,
+    instruments: Vec<rest::Instrument>,
+}
+
+/// Instrument metadata of one exchange, fetched on first use.
+#[utoipa::path(
+    get, path = "/symbols/{exchange}", params(("exchange" = String, Path)),
+    responses((status = 200, body = SymbolList), (status = 404, body = String))
+)]
+async fn symbols_handler(
+    Path(exchange): Path<String>,
+) -> Result<Json<SymbolList>, (StatusCode, String)> {
+    let (fetched_ms, instruments) = symbols()
+        .load(&exchange.to_lowercase())
+        .await
+        .map_err(|e| (StatusCode::NOT_FOUND, e))?;
+    Ok(Json(SymbolList {
+        fetched_ms,
+        instruments,
+    }))
+}
+
+/// Instrument metadata of one pair.
+#[utoipa::path(
+    get, path = "/symbols/{exchange}/{base}/{quote}", params(("exchange" = String, Path), ("base" = String, Path), ("quote" = String, Path)),
+    responses((status = 200, body = rest::Instrument), (status = 404, body = String))
+)]
+async fn symbol_handler(
+    Path((exchange, base, quote)): Path<(String, String, String)>,
+) -> Result<Json<rest::Instrument>, (StatusCode, String)> {
+    let (exchange, base, quote) = (
+        exchange.to_lowercase(),
+        base.to_uppercase(),
+        quote.to_uppercase(),
+    );
+    symbols()
+        .load(&exchange)
+        .await
+        .map_err(|e| (StatusCode::NOT_FOUND, e))?;
+    symbols()
+        .get(&exchange, &base, &quote)
+        .map(Json)
+        .ok_or_else(|| {
+            let msg = format!("{} does not list {}", exchange, pair_key(&base, &quote));
+            (StatusCode::NOT_FOUND, msg)
+        })
+}
+
+/// Markets of one exchange in ccxt's unified structure, keyed by symbol.
+#[utoipa::path(
+    get, path = "/ccxt/{exchange}/markets", params(("exchange" = String, Path)),
+    responses((status = 200, body = BTreeMap<String, ccxt::Market>))
+)]
+async fn ccxt_markets_handler(
+    State(state): State<AppState>,
+    Path(exchange): Path<String>,
+) -> Json<BTreeMap<String, ccxt::Market>> {
+    Json(ccxt::markets(&exchange.to_lowercase(), &state.prices).await)
+}
+
+/// Latest tickers of one exchange as ccxt's `fetchTickers()` returns them.
+#[utoipa::path(
+    get, path = "/ccxt/{exchange}/tickers", params(("exchange" = String, Path)),
+    responses((status = 200, body = BTreeMap<String, ccxt::Ticker>))
+)]
+async fn ccxt_tickers_handler(
+    State(state): State<AppState>,
+    Path(exchange): Path<String>,
+) -> Json<BTreeMap<String, ccxt::Ticker>> {
+    Json(ccxt::tickers(&exchange.to_lowercase(), &state.prices))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 struct ScanRequest {
+    #[serde(default)]
     exchanges: Vec<String>,
-    min_profit: f64,
+    #[serde(default)]
     collect_seconds: u64,
+    #[serde(default)]
+    source: ScanSource,
+    /// `source: "file"`: JSON array of `PairPrice` to scan, a `.json` file
+    /// relative to `scan.pairs_dir`.
+    path: Option<String>,
+    /// `source: "file"`: the same entries inline, instead of `path`.
+    pairs: Option<Vec<PairPrice>>,
+    /// Skip the result cache and recompute.
+    #[serde(default)]
+    fresh: bool,
+    /// Scan one graph combining all requested exchanges, so triangles may
+    /// span venues, instead of each exchange on its own.
+    #[serde(default)]
+    merge: bool,
+    #[serde(flatten)]
+    tuning: Tuning,
+}
+
+/// Scan tuning accepted by every scan endpoint; unset fields fall back to
+/// `ScanParams::default()` and the configured fee table.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+struct Tuning {
+    /// Name of a configured preset supplying any field not set here.
+    preset: Option<String>,
+    min_profit: Option<f64>,
+    /// Fee per leg (%) for every exchange in the request.
+    fee_per_leg_pct: Option<f64>,
+    neighbor_limit: Option<usize>,
+    max_age_ms: Option<u64>,
+    min_leg_volume: Option<f64>,
+    /// Per-exchange fee per leg (%); wins over `fee_per_leg_pct`.
+    #[serde(default)]
+    fees: BTreeMap<String, f64>,
+    /// Only build triangles from these assets.
+    #[serde(default)]
+    assets: Vec<String>,
+    /// Best N triangles regardless of `min_profit`, each marked pass/fail.
+    top_n: Option<usize>,
+    /// `profit` (default) or `confidence`.
+    sort: Option<SortKey>,
+    /// Monte Carlo runs per result for `profit_distribution`.
+    simulate: Option<usize>,
+    /// Price legs at the bid/ask; defaults to `scan.book_prices`.
+    book_prices: Option<bool>,
+}
+
+impl Tuning {
+    /// Fill unset fields from the named preset; returns the preset's
+    /// exchanges for requests that don't name any.
+    fn apply_preset(&mut self) -> Result<Vec<String>, (StatusCode, String)> {
+        let Some(name) = self.preset.as_deref() else {
+            return Ok(Vec::new());
+        };
+        let preset = config().scan.presets.get(name).ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                format!("unknown preset \"{}\"", name),
+            )
+        })?;
+        self.min_profit = self.min_profit.or(preset.min_profit);
+        self.fee_per_leg_pct = self.fee_per_leg_pct.or(preset.fee_per_leg_pct);
+        self.neighbor_limit = self.neighbor_limit.or(preset.neighbor_limit);
+        self.max_age_ms = self.max_age_ms.or(preset.max_age_ms);
+        self.min_leg_volume = self.min_leg_volume.or(preset.min_leg_volume);
+        if self.assets.is_empty() {
+            self.assets = preset.assets.clone();
+        }
+        Ok(preset.exchanges.clone())
+    }
+
+    fn params_for(&self, exchange: &str) -> ScanParams {
+        let exchange = exchange.to_lowercase();
+        let defaults = ScanParams::default();
+        let sim = &config().scan.simulation;
+        ScanParams {
+            min_profit: self.min_profit.unwrap_or(defaults.min_profit),
+            fee_per_leg_pct: self
+                .fees
+                .get(&exchange)
+                .copied()
+                .or(self.fee_per_leg_pct)
+                .unwrap_or_else(|| config().scan.fee_for(&exchange)),
+            neighbor_limit: self.neighbor_limit.unwrap_or(defaults.neighbor_limit),
+            max_age_ms: self.max_age_ms.unwrap_or(defaults.max_age_ms),
+            min_leg_volume: self.min_leg_volume.unwrap_or(defaults.min_leg_volume),
+            assets: self.assets.clone(),
+            quotes: config().scan.quotes.clone(),
+            derivatives: config().scan.derivatives,
+            book_prices: self.book_prices.unwrap_or(config().scan.book_prices),
+            top_n: self.top_n.unwrap_or(defaults.top_n),
+            sort: self.sort.unwrap_or(defaults.sort),
+            simulate: self.simulate.unwrap_or(sim.runs).min(sim.max_runs),
+        }
+    }
 }
 
-async fn scan_handler(Json(req): Json<ScanRequest>) -> Json<Vec<TriangularResult>> {
+/// Where a scan gets its pairs: live collection per exchange, or a fixed
+/// set so a suspicious result can be reproduced and shared exactly.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+enum ScanSource {
+    #[default]
+    Live,
+    File,
+}
+
+#[utoipa::path(
+    post, path = "/scan", params(Precision), request_body = ScanRequest,
+    responses(
+        (status = 200, body = Vec<TriangularResult>, headers(("x-scan-id" = u64))),
+        (status = 400, body = String),
+    )
+)]
+async fn scan_handler(
+    State(state): State<AppState>,
+    Query(precision): Query<Precision>,
+    Json(mut req): Json<ScanRequest>,
+) -> Result<Response, (StatusCode, String)> {
+    let preset_exchanges = req.tuning.apply_preset()?;
+    if req.exchanges.is_empty() {
+        req.exchanges = preset_exchanges;
+    }
     info!(
-        "scan request: exchanges={:?} min_profit={} collect_seconds={}",
-        req.exchanges, req.min_profit, req.collect_seconds
+        "scan request: exchanges={:?} collect_seconds={} source={:?} tuning={:?}",
+        req.exchanges, req.collect_seconds, req.source, req.tuning
     );
+    let params = serde_json::to_value(&req).unwrap_or_default();
+
+    if let ScanSource::File = req.source {
+        let pairs = match (req.pairs, req.path.as_deref()) {
+            (Some(pairs), _) => pairs,
+            (None, Some(path)) => {
+                let dir = config().scan.pairs_dir.as_deref().ok_or_else(|| {
+                    (
+                        StatusCode::BAD_REQUEST,
+                        "pairs files are disabled; send inline `pairs`".to_string(),
+                    )
+                })?;
+                load_pairs_file(dir, path)
+                    .await
+                    .map_err(|e| (StatusCode::BAD_REQUEST, e))?
+            }
+            (None, None) => {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    "source \"file\" needs `path` or inline `pairs`".to_string(),
+                ))
+            }
+        };
+        info!("file: {} pairs", pairs.len());
+        let file_params = req.tuning.params_for("file");
+        let results = scan_pairs("file", pairs, &file_params, &state.prices);
+        info!("scan complete: {} total opportunities", results.len());
+        let id = history().record(params, &results);
+        return Ok(scan_response(id, results, &precision));
+    }
+
+    let ttl = config().scan.cache_ttl();
+    let key = format!(
+        "post|{:?}|{}|{}|{:?}",
+        req.exchanges, req.collect_seconds, req.merge, req.tuning
+    );
+    if !req.fresh {
+        if let Some((id, cached)) = state.scan_cache.get(&key, ttl) {
+            info!(
+                "serving cached scan {} ({} opportunities)",
+                id,
+                cached.len()
+            );
+            return Ok(scan_response(id, cached, &precision));
+        }
+    }
+
+    if req.merge {
+        let sources = join_all(req.exchanges.iter().map(|exch| {
+            let prices = state.prices.clone();
+            let fee = req.tuning.params_for(exch).fee_per_leg_pct;
+            async move {
+                let mut pairs = collect_exchange_snapshot(exch, req.collect_seconds, &prices).await;
+                blacklist().retain(exch, &mut pairs);
+                info!("{}: collected {} pairs", exch, pairs.len());
+                (exch.to_lowercase(), pairs, fee)
+            }
+        }))
+        .await;
+        let recent = req
+            .exchanges
+            .iter()
+            .flat_map(|exch| recent_listings(&exch.to_lowercase(), &state.prices))
+            .collect();
+        let restored = req
+            .exchanges
+            .iter()
+            .flat_map(|exch| state.prices.restored_symbols(&exch.to_lowercase()))
+            .collect();
+        let quality: HashMap<_, _> = sources
+            .iter()
+            .map(|(venue, pairs, _)| {
+                let legs = leg_qualities(pairs, &state.prices.freshness(venue));
+                (venue.clone(), legs)
+            })
+            .collect();
+        let merged_params = req.tuning.params_for("merged");
+        let mut results = find_merged_opportunities(sources, &merged_params);
+        let vol = &config().scan.volatility;
+        score_volatility(&mut results, vol, |venue, leg| {
+            candles().realized_volatility(venue?, leg, vol.window_bars)
+        });
+        noise().apply(
+            "merged",
+            &mut results,
+            &config().scan.noise,
+            |venue, leg| quality.get(venue?)?.get(leg)?.age_ms,
+        );
+        flag_restored(&mut results, &restored);
+        flag_anomalies(&mut results, &config().scan.anomaly, &recent);
+        score_confidence(&mut results, &config().scan.confidence, |venue, leg| {
+            quality.get(venue?)?.get(leg).copied()
+        });
+        suggest_execution_order(
+            &mut results,
+            |venue, leg| quality.get(venue?)?.get(leg).copied(),
+            |venue, leg| candles().realized_volatility(venue?, leg, vol.window_bars),
+        );
+        simulate_profits(
+            &mut results,
+            &config().scan.simulation,
+            merged_params.simulate,
+            |venue, leg| candles().realized_volatility(venue?, leg, vol.window_bars),
+        );
+        sort_results(&mut results, merged_params.sort);
+        stats().annotate("merged", &mut results);
+        publish_live("merged", &results);
+        info!(
+            "merged scan complete: {} total opportunities",
+            results.len()
+        );
+        let id = history().record(params, &results);
+        state.scan_cache.put(key, id, results.clone(), ttl);
+        return Ok(scan_response(id, results, &precision));
+    }
 
     // Run exchange snapshots in parallel
     let futures = req
@@ -30,21 +854,15 @@ async fn scan_handler(Json(req): Json<ScanRequest>) -> Json<Vec<TriangularResult
         .iter()
         .map(|exch| {
             let exch = exch.clone();
+            let prices = state.prices.clone();
+            let params = req.tuning.params_for(&exch);
             async move {
                 let pairs: Vec<PairPrice> =
-                    collect_exchange_snapshot(&exch, req.collect_seconds).await;
+                    collect_exchange_snapshot(&exch, req.collect_seconds, &prices).await;
                 info!("{}: collected {} pairs", exch, pairs.len());
-
-                let opps = find_triangular_opportunities(
-                    &exch,
-                    pairs,
-                    req.min_profit,
-                    0.10,  // fee per leg %
-                    100,   // neighbor limit
-                );
-
-                info!("{}: found {} opportunities", exch, opps.len());
-                opps
+                let results = scan_pairs(&exch, pairs, &params, &prices);
+                publish_live(&exch, &results);
+                results
             }
         })
         .collect::<Vec<_>>();
@@ -53,6 +871,381 @@ async fn scan_handler(Json(req): Json<ScanRequest>) -> Json<Vec<TriangularResult
     let results: Vec<TriangularResult> = results_nested.into_iter().flatten().collect();
 
     info!("scan complete: {} total opportunities", results.len());
+    let id = history().record(params, &results);
+    state.scan_cache.put(key, id, results.clone(), ttl);
+
+    Ok(scan_response(id, results, &precision))
+}
+
+/// Query form of a live scan, e.g. `/scan?exchanges=binance,bybit&min_profit=0.3`.
+/// Tuning fields are listed out rather than flattened: flattened structs
+/// lose number parsing in query strings.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ScanQuery {
+    /// Comma-separated; defaults to every exchange with live prices.
+    exchanges: Option<String>,
+    /// Skip the result cache and recompute.
+    #[serde(default)]
+    fresh: bool,
+    /// Only used by `/scan/stream`; `GET /scan` reads the live store.
+    #[serde(default)]
+    collect_seconds: u64,
+    preset: Option<String>,
+    min_profit: Option<f64>,
+    fee_per_leg_pct: Option<f64>,
+    neighbor_limit: Option<usize>,
+    max_age_ms: Option<u64>,
+    min_leg_volume: Option<f64>,
+    /// Comma-separated.
+    assets: Option<String>,
+    top_n: Option<usize>,
+    sort: Option<SortKey>,
+    simulate: Option<usize>,
+    book_prices: Option<bool>,
+}
+
+impl ScanQuery {
+    /// Tuning with the preset applied, plus the exchanges to scan.
+    fn resolve(
+        &self,
+        prices: &SharedPrices,
+    ) -> Result<(Tuning, Vec<String>), (StatusCode, String)> {
+        let mut tuning = Tuning {
+            preset: self.preset.clone(),
+            min_profit: self.min_profit,
+            fee_per_leg_pct: self.fee_per_leg_pct,
+            neighbor_limit: self.neighbor_limit,
+            max_age_ms: self.max_age_ms,
+            min_leg_volume: self.min_leg_volume,
+            fees: BTreeMap::new(),
+            assets: self
+                .assets
+                .as_deref()
+                .map(|list| split_list(list, str::to_uppercase))
+                .unwrap_or_default(),
+            top_n: self.top_n,
+            sort: self.sort,
+            simulate: self.simulate,
+            book_prices: self.book_prices,
+        };
+        let preset_exchanges = tuning.apply_preset()?;
+        let exchanges = match self.exchanges.as_deref() {
+            Some(list) => split_list(list, str::to_lowercase),
+            None if !preset_exchanges.is_empty() => preset_exchanges,
+            None => prices.exchanges(),
+        };
+        Ok((tuning, exchanges))
+    }
+}
+
+/// Scan the live price store without a JSON body, for browsers and curl.
+#[utoipa::path(
+    get, path = "/scan", params(ScanQuery, Precision),
+    responses(
+        (status = 200, body = Vec<TriangularResult>, headers(("x-scan-id" = u64))),
+        (status = 400, body = String),
+    )
+)]
+async fn scan_get_handler(
+    State(state): State<AppState>,
+    Query(q): Query<ScanQuery>,
+    Query(precision): Query<Precision>,
+) -> Result<Response, (StatusCode, String)> {
+    let (tuning, exchanges) = q.resolve(&state.prices)?;
+    info!("scan query: exchanges={:?} tuning={:?}", exchanges, tuning);
+
+    let ttl = config().scan.cache_ttl();
+    let key = format!("get|{:?}|{:?}", exchanges, tuning);
+    if !q.fresh {
+        if let Some((id, cached)) = state.scan_cache.get(&key, ttl) {
+            info!(
+                "serving cached scan {} ({} opportunities)",
+                id,
+                cached.len()
+            );
+            return Ok(scan_response(id, cached, &precision));
+        }
+    }
 
-    Json(results)
+    let results: Vec<TriangularResult> = exchanges
+        .iter()
+        .flat_map(|exch| {
+            let params = tuning.params_for(exch);
+            let results = scan_pairs(exch, state.prices.snapshot(exch), &params, &state.prices);
+            publish_live(exch, &results);
+            results
+        })
+        .collect();
+    info!("scan complete: {} total opportunities", results.len());
+    let params = json!({ "exchanges": exchanges, "tuning": tuning });
+    let id = history().record(params, &results);
+    state.scan_cache.put(key, id, results.clone(), ttl);
+    Ok(scan_response(id, results, &precision))
+}
+
+/// Results of a scan over several exchanges, keyed by exchange.
+#[derive(Debug, Serialize, ToSchema)]
+struct GroupedScan {
+    id: u64,
+    generated_ms: u64,
+    total: usize,
+    exchanges: BTreeMap<String, Vec<TriangularResult>>,
+}
+
+/// Scan every exchange with a live connection, from the price store. The
+/// body is optional and takes the usual tuning fields.
+#[utoipa::path(
+    post, path = "/scan/all", params(Precision), request_body(content = Option<Tuning>),
+    responses((status = 200, body = GroupedScan), (status = 400, body = String))
+)]
+async fn scan_all_handler(
+    State(state): State<AppState>,
+    Query(precision): Query<Precision>,
+    body: Option<Json<Tuning>>,
+) -> Result<Response, (StatusCode, String)> {
+    let mut tuning = body.map(|Json(t)| t).unwrap_or_default();
+    tuning.apply_preset()?;
+    let exchanges = health().connected_exchanges();
+    info!("scan all: exchanges={:?} tuning={:?}", exchanges, tuning);
+
+    let grouped: BTreeMap<String, Vec<TriangularResult>> = exchanges
+        .iter()
+        .map(|exch| {
+            let params = tuning.params_for(exch);
+            let results = scan_pairs(exch, state.prices.snapshot(exch), &params, &state.prices);
+            publish_live(exch, &results);
+            (exch.clone(), results)
+        })
+        .collect();
+    let all: Vec<TriangularResult> = grouped.values().flatten().cloned().collect();
+    info!("scan complete: {} total opportunities", all.len());
+    let params = json!({ "exchanges": exchanges, "tuning": tuning });
+    let id = history().record(params, &all);
+
+    Ok(precision.json(&GroupedScan {
+        id,
+        generated_ms: now_ms(),
+        total: all.len(),
+        exchanges: grouped,
+    }))
+}
+
+/// Split a comma-separated query value, normalizing each entry.
+fn split_list(list: &str, normalize: fn(&str) -> String) -> Vec<String> {
+    list.split(',')
+        .map(|e| normalize(e.trim()))
+        .filter(|e| !e.is_empty())
+        .collect()
+}
+
+/// Live scan as Server-Sent Events: an `exchange` event with each venue's
+/// opportunities as soon as its collection finishes, then `done` with the
+/// scan ID, so long `collect_seconds` scans don't hold the connection silent.
+#[utoipa::path(
+    get, path = "/scan/stream", params(ScanQuery, Precision),
+    responses((status = 200, content_type = "text/event-stream", body = String), (status = 400, body = String))
+)]
+async fn scan_stream_handler(
+    State(state): State<AppState>,
+    Query(q): Query<ScanQuery>,
+    Query(precision): Query<Precision>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, String)> {
+    let (tuning, exchanges) = q.resolve(&state.prices)?;
+    info!(
+        "streaming scan: exchanges={:?} collect_seconds={} tuning={:?}",
+        exchanges, q.collect_seconds, tuning
+    );
+    let (tx, rx) = futures::channel::mpsc::unbounded();
+
+    tasks::spawn("scan:stream", async move {
+        let mut pending: FuturesUnordered<_> = exchanges
+            .iter()
+            .map(|exch| {
+                let prices = state.prices.clone();
+                let params = tuning.params_for(exch);
+                async move {
+                    let pairs = collect_exchange_snapshot(exch, q.collect_seconds, &prices).await;
+                    let collected = pairs.len();
+                    let results = scan_pairs(exch, pairs, &params, &prices);
+                    publish_live(exch, &results);
+                    (exch, collected, results)
+                }
+            })
+            .collect();
+
+        let mut all = Vec::new();
+        while let Some((exch, collected, results)) = pending.next().await {
+            let mut data = json!({ "exchange": exch, "pairs": collected, "results": results });
+            precision.apply(&mut data);
+            let event = Event::default().event("exchange").data(data.to_string());
+            if tx.unbounded_send(Ok(event)).is_err() {
+                info!("streaming scan: client went away");
+                return;
             }
+            all.extend(results);
+        }
+
+        let params = json!({
+            "exchanges": exchanges,
+            "collect_seconds": q.collect_seconds,
+            "tuning": tuning,
+        });
+        let id = history().record(params, &all);
+        info!("scan complete: {} total opportunities", all.len());
+        let data = json!({ "id": id, "total": all.len() });
+        let _ = tx.unbounded_send(Ok(Event::default().event("done").data(data.to_string())));
+    });
+
+    Ok(Sse::new(rx).keep_alive(KeepAlive::default()))
+}
+
+/// Scan results with the scan's ID in `X-Scan-Id`, for `/scans/{id}` later.
+fn scan_response(id: u64, results: Vec<TriangularResult>, precision: &Precision) -> Response {
+    ([("x-scan-id", id.to_string())], precision.json(&results)).into_response()
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ScansQuery {
+    /// Unix ms; without it, the newest `limit` scans.
+    since: Option<u64>,
+    limit: Option<usize>,
+}
+
+/// Past scans (parameters and results) taken at or after `since`, oldest
+/// first.
+#[utoipa::path(
+    get, path = "/scans", params(ScansQuery, Precision), responses((status = 200, body = Vec<ScanRecord>))
+)]
+async fn scans_handler(
+    Query(q): Query<ScansQuery>,
+    Query(precision): Query<Precision>,
+) -> Response {
+    let limit = q.limit.unwrap_or(100);
+    let scans = match q.since {
+        Some(since) => history().since(since, limit),
+        None => history().latest(limit),
+    };
+    precision.json(&scans)
+}
+
+#[utoipa::path(
+    get, path = "/scans/{id}", params(("id" = u64, Path), Precision),
+    responses((status = 200, body = ScanRecord), (status = 404))
+)]
+async fn scan_by_id_handler(
+    Path(id): Path<u64>,
+    Query(precision): Query<Precision>,
+) -> Result<Response, StatusCode> {
+    let record = history().get(id).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(precision.json(&record))
+}
+
+/// Symbols and assets excluded from ingestion and scans.
+#[utoipa::path(
+    get, path = "/admin/blacklist", tag = "admin", responses((status = 200, body = Vec<BlacklistEntry>))
+)]
+async fn blacklist_list_handler() -> Json<Vec<BlacklistEntry>> {
+    Json(blacklist().list())
+}
+
+/// Add `{ "symbol": "LUNA" | "ABC/USDT", "exchange": "binance"? }`; answers
+/// 201 when new, 200 when it was already listed, with the full list.
+#[utoipa::path(
+    post, path = "/admin/blacklist", tag = "admin", request_body = BlacklistEntry,
+    responses(
+        (status = 200, body = Vec<BlacklistEntry>),
+        (status = 201, body = Vec<BlacklistEntry>),
+        (status = 400, body = String),
+    )
+)]
+async fn blacklist_add_handler(
+    Json(entry): Json<BlacklistEntry>,
+) -> Result<(StatusCode, Json<Vec<BlacklistEntry>>), (StatusCode, String)> {
+    if entry.symbol.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "`symbol` is empty".to_string()));
+    }
+    let status = if blacklist().add(&entry) {
+        info!("blacklisted {:?}", entry);
+        StatusCode::CREATED
+    } else {
+        StatusCode::OK
+    };
+    Ok((status, Json(blacklist().list())))
+}
+
+/// `DELETE /admin/blacklist?symbol=LUNA&exchange=binance`.
+#[utoipa::path(
+    delete, path = "/admin/blacklist", tag = "admin", params(BlacklistEntry),
+    responses((status = 200, body = Vec<BlacklistEntry>), (status = 404))
+)]
+async fn blacklist_remove_handler(
+    Query(entry): Query<BlacklistEntry>,
+) -> Result<Json<Vec<BlacklistEntry>>, StatusCode> {
+    if !blacklist().remove(&entry) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    info!("removed {:?} from blacklist", entry);
+    Ok(Json(blacklist().list()))
+}
+
+fn recent_listings(exchange: &str, prices: &SharedPrices) -> HashSet<String> {
+    let rules = &config().scan.anomaly;
+    prices.recent_listings(exchange, rules.new_listing_ms, rules.listing_grace_ms)
+}
+
+/// Side effects of a live or scheduled scan. Scans of a pairs file skip
+/// them: their prices are whatever the caller sent.
+fn publish_live(exchange: &str, results: &[TriangularResult]) {
+    stats().record(exchange, results);
+    lifetime().record_scan(results);
+    alerts().notify(exchange, results);
+    sheets::export_results(exchange, results);
+}
+
+fn scan_pairs(
+    exchange: &str,
+    mut pairs: Vec<PairPrice>,
+    params: &ScanParams,
+    prices: &SharedPrices,
+) -> Vec<TriangularResult> {
+    blacklist().retain(exchange, &mut pairs);
+    let quality = leg_qualities(&pairs, &prices.freshness(&exchange.to_lowercase()));
+    let mut opps = find_triangular_opportunities(exchange, pairs, params);
+    let recent = recent_listings(&exchange.to_lowercase(), prices);
+    let vol = &config().scan.volatility;
+    score_volatility(&mut opps, vol, |_, leg| {
+        candles().realized_volatility(&exchange.to_lowercase(), leg, vol.window_bars)
+    });
+    // A pairs file holds whatever prices the caller sent, so its triangles
+    // neither count towards nor get suppressed by the live noise record.
+    if exchange != "file" {
+        noise().apply(exchange, &mut opps, &config().scan.noise, |_, leg| {
+            quality.get(leg)?.age_ms
+        });
+    }
+    flag_restored(
+        &mut opps,
+        &prices.restored_symbols(&exchange.to_lowercase()),
+    );
+    flag_anomalies(&mut opps, &config().scan.anomaly, &recent);
+    score_confidence(&mut opps, &config().scan.confidence, |_, leg| {
+        quality.get(leg).copied()
+    });
+    suggest_execution_order(
+        &mut opps,
+        |_, leg| quality.get(leg).copied(),
+        |_, leg| candles().realized_volatility(&exchange.to_lowercase(), leg, vol.window_bars),
+    );
+    simulate_profits(
+        &mut opps,
+        &config().scan.simulation,
+        params.simulate,
+        |_, leg| candles().realized_volatility(&exchange.to_lowercase(), leg, vol.window_bars),
+    );
+    sort_results(&mut opps, params.sort);
+    stats().annotate(exchange, &mut opps);
+    info!("{}: found {} opportunities", exchange, opps.len());
+    opps
+}
@@ -0,0 +1,58 @@
+//! Tokio runtimes sized from `config().runtime`.
+
+use crate::config::RuntimeConfig;
+use crate::store::SharedPrices;
+use crate::ws_manager::start_all_workers;
+use tokio::runtime::{Builder, Runtime};
+use tracing::{error, info};
+
+/// The main runtime: HTTP server, scans, jobs.
+pub fn main_runtime(cfg: &RuntimeConfig) -> Runtime {
+    let mut builder = Builder::new_multi_thread();
+    builder
+        .enable_all()
+        .max_blocking_threads(cfg.max_blocking_threads.max(1));
+    if cfg.worker_threads > 0 {
+        builder.worker_threads(cfg.worker_threads);
+    }
+    let rt = builder.build().expect("failed to build tokio runtime");
+    info!(
+        "runtime: {} worker thread(s), up to {} blocking",
+        rt.metrics().num_workers(),
+        cfg.max_blocking_threads.max(1)
+    );
+    rt
+}
+
+/// Start the connector workers, on a dedicated runtime in its own thread
+/// when configured, otherwise on the calling runtime.
+pub fn start_connectors(cfg: &RuntimeConfig, prices: SharedPrices) {
+    if !cfg.dedicated_connectors {
+        start_all_workers(prices);
+        return;
+    }
+    let threads = cfg.connector_threads.max(1);
+    let rt = match Builder::new_multi_thread()
+        .enable_all()
+        .worker_threads(threads)
+        .thread_name("connectors")
+        .build()
+    {
+        Ok(rt) => rt,
+        Err(e) => {
+            error!("connector runtime: {} (using the main runtime)", e);
+            start_all_workers(prices);
+            return;
+        }
+    };
+    info!("connectors: dedicated runtime with {} thread(s)", threads);
+    std::thread::Builder::new()
+        .name("connectors".into())
+        .spawn(move || {
+            rt.block_on(async move {
+                start_all_workers(prices);
+                std::future::pending::<()>().await
+            })
+        })
+        .expect("failed to spawn connector thread");
+}
@@ -0,0 +1,42 @@
+use crate::models::TriangularResult;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+struct Entry {
+    at: Instant,
+    /// History ID of the scan that produced `results`.
+    id: u64,
+    results: Vec<TriangularResult>,
+}
+
+/// Most recent scan result per parameter set, so clients polling the same
+/// scan every second don't each trigger a full recomputation.
+#[derive(Debug, Default)]
+pub struct ScanCache {
+    inner: Mutex<HashMap<String, Entry>>,
+}
+
+impl ScanCache {
+    /// Cached scan ID and result for `key` if younger than `ttl`.
+    pub fn get(&self, key: &str, ttl: Duration) -> Option<(u64, Vec<TriangularResult>)> {
+        let guard = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        guard
+            .get(key)
+            .filter(|e| e.at.elapsed() < ttl)
+            .map(|e| (e.id, e.results.clone()))
+    }
+
+    /// Store a fresh result, dropping entries that outlived `ttl`.
+    pub fn put(&self, key: String, id: u64, results: Vec<TriangularResult>, ttl: Duration) {
+        let mut guard = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        guard.retain(|_, e| e.at.elapsed() < ttl);
+        let entry = Entry {
+            at: Instant::now(),
+            id,
+            results,
+        };
+        guard.insert(key, entry);
+    }
+}
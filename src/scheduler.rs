@@ -0,0 +1,170 @@
+use crate::config::{config, JobConfig};
+use crate::store::SharedPrices;
+use crate::tasks;
+use crate::utils::now_ms;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+use tokio::sync::Notify;
+use tokio::time::Duration;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+/// Scans run on a timer from `jobs` in config, inspected and controlled
+/// through `/admin/jobs`.
+static SCHEDULER: Lazy<Scheduler> = Lazy::new(Scheduler::default);
+
+pub fn scheduler() -> &'static Scheduler {
+    &SCHEDULER
+}
+
+/// Runs one job and returns how many opportunities it found.
+pub type JobRunner = fn(&JobConfig, &SharedPrices) -> Result<usize, String>;
+
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct JobState {
+    pub every_ms: u64,
+    pub paused: bool,
+    pub next_run_ms: u64,
+    pub last_run_ms: u64,
+    pub last_duration_ms: u64,
+    pub last_results: usize,
+    pub runs_total: u64,
+    pub failures_total: u64,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct Scheduler {
+    jobs: RwLock<BTreeMap<String, JobState>>,
+    /// Wakes a job's task for a pause change or an immediate run.
+    wake: RwLock<HashMap<String, Arc<Notify>>>,
+    /// Jobs asked to run now, regardless of their timer or pause.
+    triggered: RwLock<HashMap<String, bool>>,
+}
+
+/// What `/admin/jobs/{name}/...` can ask of a job.
+#[derive(Debug, Clone, Copy)]
+pub enum JobAction {
+    Pause,
+    Resume,
+    RunNow,
+}
+
+impl Scheduler {
+    /// Spawn a task per configured job.
+    pub fn start(&self, prices: SharedPrices, run: JobRunner) {
+        for job in &config().jobs {
+            let every = Duration::from_millis(job.every_ms.max(1_000));
+            let notify = Arc::new(Notify::new());
+            self.write_jobs().insert(
+                job.name.clone(),
+                JobState {
+                    every_ms: every.as_millis() as u64,
+                    next_run_ms: now_ms() + every.as_millis() as u64,
+                    ..JobState::default()
+                },
+            );
+            self.wake
+                .write()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(job.name.clone(), notify.clone());
+            info!("scheduling job {} every {:?}", job.name, every);
+            tasks::spawn(
+                &format!("job:{}", job.name),
+                run_job(job.clone(), every, notify, prices.clone(), run),
+            );
+        }
+    }
+
+    pub fn report(&self) -> BTreeMap<String, JobState> {
+        self.jobs.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Apply `action` to the named job; false if there is no such job.
+    pub fn act(&self, name: &str, action: JobAction) -> bool {
+        let Some(notify) = self
+            .wake
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name)
+            .cloned()
+        else {
+            return false;
+        };
+        match action {
+            JobAction::Pause | JobAction::Resume => {
+                if let Some(state) = self.write_jobs().get_mut(name) {
+                    state.paused = matches!(action, JobAction::Pause);
+                }
+            }
+            JobAction::RunNow => {
+                self.triggered
+                    .write()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .insert(name.to_string(), true);
+            }
+        }
+        notify.notify_one();
+        true
+    }
+
+    fn write_jobs(&self) -> std::sync::RwLockWriteGuard<'_, BTreeMap<String, JobState>> {
+        self.jobs.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn take_trigger(&self, name: &str) -> bool {
+        self.triggered
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(name)
+            .unwrap_or(false)
+    }
+}
+
+async fn run_job(
+    job: JobConfig,
+    every: Duration,
+    notify: Arc<Notify>,
+    prices: SharedPrices,
+    run: JobRunner,
+) {
+    let sched = scheduler();
+    let mut timer = tokio::time::interval_at(tokio::time::Instant::now() + every, every);
+    loop {
+        let due = tokio::select! {
+            _ = timer.tick() => true,
+            _ = notify.notified() => false,
+        };
+        let triggered = sched.take_trigger(&job.name);
+        let paused = sched.report().get(&job.name).is_some_and(|s| s.paused);
+        if !triggered && (!due || paused) {
+            continue;
+        }
+
+        let started = Instant::now();
+        let outcome = run(&job, &prices);
+        timer.reset();
+        let mut jobs = sched.write_jobs();
+        let Some(state) = jobs.get_mut(&job.name) else {
+            return;
+        };
+        state.last_run_ms = now_ms();
+        state.last_duration_ms = started.elapsed().as_millis() as u64;
+        state.next_run_ms = state.last_run_ms + state.every_ms;
+        state.runs_total += 1;
+        match outcome {
+            Ok(found) => {
+                state.last_results = found;
+                state.last_error = None;
+            }
+            Err(e) => {
+                warn!("job {} failed: {}", job.name, e);
+                state.failures_total += 1;
+                state.last_error = Some(e);
+            }
+        }
+    }
+}
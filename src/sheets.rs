@@ -0,0 +1,286 @@
+use crate::config::{config, SheetRows, SheetsConfig};
+use crate::models::TriangularResult;
+use crate::stats::canonical;
+use crate::tasks;
+use crate::utils::now_ms;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use once_cell::sync::{Lazy, OnceCell};
+use ring::rand::SystemRandom;
+use ring::signature::{RsaKeyPair, RSA_PKCS1_SHA256};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::sync::mpsc;
+use tokio::time::Duration;
+use tracing::{error, info, warn};
+
+const SCOPE: &str = "https://www.googleapis.com/auth/spreadsheets";
+/// Rows are sent in batches to stay within the Sheets write quota.
+const BATCH_EVERY: Duration = Duration::from_secs(5);
+const MAX_QUEUED_ROWS: usize = 10_000;
+/// Scans whose rows can wait for the exporter.
+const MAX_QUEUED_SCANS: usize = 1_000;
+/// Failed appends in a row after which the pending batch is dropped.
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Set when `sheets` is configured; each scan's rows are queued here for
+/// the exporter.
+static ROWS: OnceCell<mpsc::Sender<Vec<Vec<Value>>>> = OnceCell::new();
+
+/// "exchange:triangle" -> when it was last exported.
+static EXPORTED: Lazy<Mutex<HashMap<String, u64>>> = Lazy::new(Default::default);
+
+/// Start the exporter if `sheets` is configured.
+pub fn start() {
+    let Some(cfg) = config().sheets.clone() else {
+        return;
+    };
+    let key = match ServiceAccount::load(&cfg.credentials_path) {
+        Ok(key) => key,
+        Err(e) => {
+            error!("sheets export disabled: {}", e);
+            return;
+        }
+    };
+    let (tx, rx) = mpsc::channel(MAX_QUEUED_SCANS);
+    if ROWS.set(tx).is_ok() {
+        info!("exporting {:?} to sheet {}", cfg.rows, cfg.spreadsheet_id);
+        tasks::spawn("sheets", run(cfg, key, rx));
+    }
+}
+
+/// One row per passing result, in `opportunities` mode, skipping triangles
+/// exported within `cooldown_ms`. Called for live and scheduled scans only,
+/// never for file scans.
+pub fn export_results(exchange: &str, results: &[TriangularResult]) {
+    let Some(cfg) = config()
+        .sheets
+        .as_ref()
+        .filter(|c| c.rows == SheetRows::Opportunities)
+    else {
+        return;
+    };
+    let now = now_ms();
+    let mut exported = EXPORTED.lock().unwrap_or_else(|e| e.into_inner());
+    exported.retain(|_, at| now.saturating_sub(*at) < cfg.cooldown_ms);
+    let mut rows = Vec::new();
+    for r in results.iter().filter(|r| match cfg.min_profit {
+        Some(min) => r.profit_after >= min,
+        None => r.passes(),
+    }) {
+        let key = format!("{}:{}", exchange, canonical(r));
+        if exported.contains_key(&key) {
+            continue;
+        }
+        exported.insert(key, now);
+        rows.push(vec![
+            json!(timestamp(now)),
+            json!(exchange),
+            json!(r.triangle),
+            json!(r.profit_after),
+            json!(r.fees),
+            json!(r.score_liquidity),
+            json!(r.confidence),
+            json!(r.flags.join(" ")),
+        ]);
+    }
+    queue(rows);
+}
+
+/// One row per scheduled scan, in `summaries` mode.
+pub fn export_summary(job: &str, results: &[TriangularResult]) {
+    if config().sheets.as_ref().map(|c| c.rows) != Some(SheetRows::Summaries) {
+        return;
+    }
+    let best = results
+        .iter()
+        .max_by(|a, b| a.profit_after.total_cmp(&b.profit_after));
+    queue(vec![vec![
+        json!(timestamp(now_ms())),
+        json!(job),
+        json!(results.len()),
+        json!(best.map(|r| r.triangle.as_str())),
+        json!(best.map(|r| r.profit_after)),
+    ]]);
+}
+
+/// Hand one scan's rows to the exporter, which appends them with the rest
+/// of the batch.
+fn queue(rows: Vec<Vec<Value>>) {
+    if rows.is_empty() {
+        return;
+    }
+    if let Some(tx) = ROWS.get() {
+        if tx.try_send(rows).is_err() {
+            warn!("sheets export queue full, dropping a scan's rows");
+        }
+    }
+}
+
+/// Sheets-friendly "YYYY-MM-DD HH:MM:SS" (UTC).
+fn timestamp(ms: u64) -> String {
+    chrono::DateTime::from_timestamp_millis(ms as i64)
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+
+async fn run(cfg: SheetsConfig, key: ServiceAccount, mut rx: mpsc::Receiver<Vec<Vec<Value>>>) {
+    let client = reqwest::Client::new();
+    let mut token: Option<(String, u64)> = None;
+    let mut timer = tokio::time::interval(BATCH_EVERY);
+    let mut rows = Vec::new();
+    let mut failures = 0;
+    let mut url = match reqwest::Url::parse("https://sheets.googleapis.com/v4/spreadsheets") {
+        Ok(url) => url,
+        Err(e) => return error!("sheets export disabled: {}", e),
+    };
+    url.path_segments_mut()
+        .expect("https URL has path segments")
+        .push(&cfg.spreadsheet_id)
+        .push("values")
+        .push(&format!("{}:append", cfg.range));
+    url.set_query(Some("valueInputOption=USER_ENTERED"));
+    loop {
+        tokio::select! {
+            scan = rx.recv() => match scan {
+                Some(scan) => {
+                    rows.extend(scan);
+                    // While appends keep failing, keep the newest rows only.
+                    if rows.len() > MAX_QUEUED_ROWS {
+                        rows.drain(..rows.len() - MAX_QUEUED_ROWS);
+                    }
+                }
+                None => return,
+            },
+            _ = timer.tick() => {
+                if rows.is_empty() {
+                    continue;
+                }
+                let valid = token.as_ref().filter(|(_, exp)| *exp > now_ms() + 60_000);
+                let access = match valid {
+                    Some((t, _)) => t.clone(),
+                    None => match key.access_token(&client).await {
+                        Ok((t, exp)) => {
+                            token = Some((t.clone(), exp));
+                            t
+                        }
+                        Err(e) => {
+                            warn!("sheets auth failed: {}", e);
+                            continue;
+                        }
+                    },
+                };
+                let sent = client
+                    .post(url.clone())
+                    .bearer_auth(access)
+                    .json(&json!({ "values": rows }))
+                    .send()
+                    .await
+                    .and_then(|r| r.error_for_status());
+                match sent {
+                    Ok(_) => {
+                        rows.clear();
+                        failures = 0;
+                    }
+                    Err(e) => {
+                        failures += 1;
+                        if failures >= MAX_ATTEMPTS {
+                            warn!(
+                                "sheets append failed {} times, dropping {} rows: {}",
+                                failures,
+                                rows.len(),
+                                e
+                            );
+                            rows.clear();
+                            failures = 0;
+                        } else {
+                            // Keep the rows for the next batch.
+                            warn!("sheets append of {} rows failed: {}", rows.len(), e);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The fields of a service-account JSON key the exporter needs.
+#[derive(Deserialize)]
+struct ServiceAccountFile {
+    client_email: String,
+    private_key: String,
+    token_uri: String,
+}
+
+struct ServiceAccount {
+    email: String,
+    token_uri: String,
+    key: RsaKeyPair,
+}
+
+impl ServiceAccount {
+    fn load(path: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        let file: ServiceAccountFile =
+            serde_json::from_str(&text).map_err(|e| format!("{}: {}", path, e))?;
+        let der: String = file
+            .private_key
+            .lines()
+            .filter(|l| !l.starts_with("-----"))
+            .collect();
+        let der = STANDARD
+            .decode(der)
+            .map_err(|e| format!("private_key: {}", e))?;
+        let key = RsaKeyPair::from_pkcs8(&der).map_err(|e| format!("private_key: {}", e))?;
+        Ok(Self {
+            email: file.client_email,
+            token_uri: file.token_uri,
+            key,
+        })
+    }
+
+    /// OAuth access token and its expiry (unix ms), via a signed JWT grant.
+    async fn access_token(&self, client: &reqwest::Client) -> Result<(String, u64), String> {
+        let now = now_ms() / 1000;
+        let header = URL_SAFE_NO_PAD.encode(json!({ "alg": "RS256", "typ": "JWT" }).to_string());
+        let claims = json!({
+            "iss": self.email,
+            "scope": SCOPE,
+            "aud": self.token_uri,
+            "iat": now,
+            "exp": now + 3600,
+        });
+        let signing_input = format!("{}.{}", header, URL_SAFE_NO_PAD.encode(claims.to_string()));
+        let mut signature = vec![0; self.key.public().modulus_len()];
+        self.key
+            .sign(
+                &RSA_PKCS1_SHA256,
+                &SystemRandom::new(),
+                signing_input.as_bytes(),
+                &mut signature,
+            )
+            .map_err(|e| e.to_string())?;
+        let jwt = format!("{}.{}", signing_input, URL_SAFE_NO_PAD.encode(signature));
+
+        let body: Value = client
+            .post(&self.token_uri)
+            .form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                ("assertion", jwt.as_str()),
+            ])
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| e.to_string())?
+            .json()
+            .await
+            .map_err(|e| e.to_string())?;
+        let token = body["access_token"]
+            .as_str()
+            .ok_or("no access_token in response")?;
+        let expires_in = body["expires_in"].as_u64().unwrap_or(3600);
+        Ok((token.to_string(), now_ms() + expires_in * 1000))
+    }
+}
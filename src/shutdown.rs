@@ -0,0 +1,113 @@
+//! Graceful shutdown: wait for Ctrl-C or SIGTERM, then save state and emit a
+//! summary of the run for comparing daily restarts.
+
+use crate::alerts::alerts;
+use crate::checkpoint;
+use crate::config::config;
+use crate::health::health;
+use crate::lifetime::lifetime;
+use crate::store::SharedPrices;
+use crate::utils::now_ms;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use tracing::{info, warn};
+
+#[derive(Debug, Serialize)]
+pub struct ShutdownSummary {
+    pub started_ms: u64,
+    pub stopped_ms: u64,
+    pub uptime_ms: u64,
+    pub exchanges: BTreeMap<String, ExchangeSummary>,
+    pub scans: u64,
+    /// Results with a positive profit after fees.
+    pub opportunities: u64,
+    pub alerts_sent: u64,
+    pub alerts_failed: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExchangeSummary {
+    pub messages: u64,
+    pub reconnects: u64,
+    pub errors: u64,
+    pub symbols: usize,
+}
+
+/// Resolves on Ctrl-C or, on Unix, SIGTERM.
+pub async fn signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+    #[cfg(unix)]
+    let term = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut s) => {
+                s.recv().await;
+            }
+            Err(_) => std::future::pending().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let term = std::future::pending::<()>();
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = term => {}
+    }
+    info!("shutdown requested");
+}
+
+pub fn summary(prices: &SharedPrices) -> ShutdownSummary {
+    let now = now_ms();
+    let started_ms = lifetime().started_ms();
+    let run = lifetime().run();
+    let (alerts_sent, alerts_failed) = alerts().totals();
+    let exchanges = health()
+        .report()
+        .exchanges
+        .into_iter()
+        .map(|(name, h)| {
+            let summary = ExchangeSummary {
+                messages: h.messages_total,
+                reconnects: h.reconnects_total,
+                errors: h.errors_total,
+                symbols: prices.symbols(&name).len(),
+            };
+            (name, summary)
+        })
+        .collect();
+    ShutdownSummary {
+        started_ms,
+        stopped_ms: now,
+        uptime_ms: now.saturating_sub(started_ms),
+        exchanges,
+        scans: run.scans,
+        opportunities: run.opportunities,
+        alerts_sent,
+        alerts_failed,
+    }
+}
+
+/// Save the lifetime counters and a final checkpoint, then log the run's
+/// summary and append it to `shutdown.summary_path` when set.
+pub fn finish(prices: &SharedPrices) {
+    lifetime().save();
+    if let Some(path) = config().checkpoint.path.as_deref() {
+        if let Err(e) = checkpoint::save(path, prices) {
+            warn!("writing checkpoint: {}", e);
+        }
+    }
+    let line = serde_json::to_string(&summary(prices)).unwrap_or_default();
+    info!("shutdown summary: {}", line);
+    if let Some(path) = config().shutdown.summary_path.as_deref() {
+        let written = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut f| writeln!(f, "{}", line));
+        if let Err(e) = written {
+            warn!("appending shutdown summary to {}: {}", path, e);
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use crate::models::{TriangleContext, TriangularResult};
+use crate::utils::now_ms;
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, VecDeque};
+use std::sync::RwLock;
+
+/// How far back triangle appearances are remembered.
+const WINDOW_MS: u64 = 60 * 60 * 1000;
+/// Appearances closer together than this count once: clients polling
+/// `/scan` every second see the same opportunity, not a new one.
+const SAME_APPEARANCE_MS: u64 = 10_000;
+
+/// Process-wide record of which triangles recent scans returned.
+static STATS: Lazy<TriangleStats> = Lazy::new(TriangleStats::default);
+
+pub fn stats() -> &'static TriangleStats {
+    &STATS
+}
+
+#[derive(Debug, Default)]
+pub struct TriangleStats {
+    /// "exchange:A>B>C" -> (seen at ms, profit_after), oldest first.
+    inner: RwLock<HashMap<String, VecDeque<(u64, f64)>>>,
+}
+
+impl TriangleStats {
+    /// Attach each triangle's last-hour history to `results`.
+    pub fn annotate(&self, exchange: &str, results: &mut [TriangularResult]) {
+        let cutoff = now_ms().saturating_sub(WINDOW_MS);
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        for r in results.iter_mut() {
+            let Some(seen) = guard.get(&format!("{}:{}", exchange, canonical(r))) else {
+                continue;
+            };
+            let recent: Vec<f64> = seen
+                .iter()
+                .filter(|(at, _)| *at >= cutoff)
+                .map(|(_, p)| *p)
+                .collect();
+            if !recent.is_empty() {
+                r.context = Some(TriangleContext {
+                    avg_profit_1h: recent.iter().sum::<f64>() / recent.len() as f64,
+                    seen_1h: recent.len() as u64,
+                });
+            }
+        }
+    }
+
+    /// Count the passing results of a live scan. A triangle seen again
+    /// within [`SAME_APPEARANCE_MS`] updates its last appearance instead of
+    /// adding one.
+    pub fn record(&self, exchange: &str, results: &[TriangularResult]) {
+        let now = now_ms();
+        let cutoff = now.saturating_sub(WINDOW_MS);
+        let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        guard.retain(|_, seen| {
+            while seen.front().is_some_and(|(at, _)| *at < cutoff) {
+                seen.pop_front();
+            }
+            !seen.is_empty()
+        });
+
+        for r in results.iter().filter(|r| r.passes()) {
+            let seen = guard
+                .entry(format!("{}:{}", exchange, canonical(r)))
+                .or_default();
+            match seen.back_mut() {
+                Some((at, profit)) if now.saturating_sub(*at) < SAME_APPEARANCE_MS => {
+                    *profit = r.profit_after;
+                }
+                _ => seen.push_back((now, r.profit_after)),
+            }
+        }
+    }
+
+    /// Every remembered appearance, for checkpointing.
+    pub fn export(&self) -> HashMap<String, VecDeque<(u64, f64)>> {
+        self.inner.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Merge appearances from a checkpoint; expired ones drop out on the
+    /// next scan.
+    pub fn import(&self, seen: HashMap<String, VecDeque<(u64, f64)>>) {
+        let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        for (key, appearances) in seen {
+            guard.entry(key).or_insert(appearances);
+        }
+    }
+}
+
+/// The same cycle regardless of which asset it starts from.
+pub fn canonical(r: &TriangularResult) -> String {
+    let mut assets: Vec<&str> = r.pairs.iter().filter_map(|p| p.split('/').next()).collect();
+    if let Some(start) = (0..assets.len()).min_by_key(|&i| assets[i]) {
+        assets.rotate_left(start);
+    }
+    assets.join(">")
+}
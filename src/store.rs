@@ -0,0 +1,279 @@
+use crate::bus;
+use crate::candles::candles;
+use crate::exchanges::Delta;
+use crate::metrics::metrics;
+use crate::models::PairPrice;
+use crate::ticks::ticks;
+use crate::utils::now_ms;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// Latest prices per exchange, written by the connector workers.
+pub type SharedPrices = Arc<PriceStore>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    price: PairPrice,
+    /// Local time (ms) this symbol last changed in the store.
+    updated_ms: u64,
+    /// Moving average of the time between changes, once it changed twice.
+    interval_ms: Option<f64>,
+    /// Loaded from a checkpoint and not updated live since.
+    #[serde(skip)]
+    restored: bool,
+}
+
+/// How current one symbol's data is, for scoring results built on it.
+#[derive(Debug, Clone, Copy)]
+pub struct Freshness {
+    pub updated_ms: u64,
+    pub interval_ms: Option<f64>,
+}
+
+/// Weight of the newest interval in the moving average.
+const INTERVAL_ALPHA: f64 = 0.2;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Book {
+    /// "BASE/QUOTE" -> latest price.
+    entries: HashMap<String, Entry>,
+    /// When each symbol was first written, kept across removals.
+    first_seen: HashMap<String, u64>,
+    /// First write for this exchange; symbols seen shortly after it were
+    /// already listed when we connected.
+    started_ms: u64,
+}
+
+/// exchange -> book of latest prices.
+#[derive(Debug, Default)]
+pub struct PriceStore {
+    inner: RwLock<HashMap<String, Book>>,
+}
+
+impl PriceStore {
+    pub fn shared() -> SharedPrices {
+        Arc::new(Self::default())
+    }
+
+    /// Write only the symbols that changed since the previous flush. Returns
+    /// the time spent waiting for and holding the write lock.
+    pub fn apply_delta(&self, exchange: &str, delta: Delta) -> Duration {
+        let now = now_ms();
+        let changed = delta.changed.len();
+        let mut intervals = Vec::with_capacity(changed);
+        let latencies: Vec<u64> = delta
+            .changed
+            .iter()
+            .filter(|p| p.event_time > 0)
+            .map(|p| now.saturating_sub(p.event_time))
+            .collect();
+        bus::publish(exchange, &delta);
+        ticks().record(exchange, &delta.changed, now);
+        candles().record(exchange, &delta.changed, now);
+
+        let started = Instant::now();
+        if changed > 0 || !delta.removed.is_empty() {
+            let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+            let book = guard.entry(exchange.to_string()).or_default();
+            if book.started_ms == 0 {
+                book.started_ms = now;
+            }
+            for key in delta.removed {
+                book.entries.remove(&key);
+            }
+            for p in delta.changed {
+                let key = pair_key(&p.base, &p.quote);
+                book.first_seen.entry(key.clone()).or_insert(now);
+                let mut entry = Entry {
+                    price: p,
+                    updated_ms: now,
+                    interval_ms: None,
+                    restored: false,
+                };
+                // A restored entry was last written before the downtime, so
+                // its interval starts over rather than averaging the gap in.
+                if let Some(prev) = book.entries.get(&key).filter(|prev| !prev.restored) {
+                    let interval = now.saturating_sub(prev.updated_ms);
+                    intervals.push(interval);
+                    let avg = prev.interval_ms.map_or(interval as f64, |avg| {
+                        avg + INTERVAL_ALPHA * (interval as f64 - avg)
+                    });
+                    entry.interval_ms = Some(avg);
+                }
+                book.entries.insert(key, entry);
+            }
+        }
+        let write = started.elapsed();
+
+        metrics().record_flush(exchange, changed, &intervals, write);
+        metrics().record_ingest_latency(exchange, &latencies);
+        write
+    }
+
+    /// Exchanges that currently have prices in the store.
+    pub fn exchanges(&self) -> Vec<String> {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        let mut names: Vec<String> = guard
+            .iter()
+            .filter(|(_, book)| !book.entries.is_empty())
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Copy of the latest prices for one exchange.
+    pub fn snapshot(&self, exchange: &str) -> Vec<PairPrice> {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        guard
+            .get(exchange)
+            .map(|book| book.entries.values().map(|e| e.price.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    /// "BASE/QUOTE" keys currently held for one exchange.
+    pub fn symbols(&self, exchange: &str) -> HashSet<String> {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        guard
+            .get(exchange)
+            .map(|book| book.entries.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Last write and average update interval of every symbol of one
+    /// exchange, keyed "BASE/QUOTE".
+    pub fn freshness(&self, exchange: &str) -> HashMap<String, Freshness> {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        guard
+            .get(exchange)
+            .map(|book| {
+                book.entries
+                    .iter()
+                    .map(|(key, e)| {
+                        let freshness = Freshness {
+                            updated_ms: e.updated_ms,
+                            interval_ms: e.interval_ms,
+                        };
+                        (key.clone(), freshness)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// "BASE/QUOTE" keys of one exchange still holding checkpointed data.
+    pub fn restored_symbols(&self, exchange: &str) -> HashSet<String> {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        guard
+            .get(exchange)
+            .map(|book| {
+                book.entries
+                    .iter()
+                    .filter(|(_, e)| e.restored)
+                    .map(|(key, _)| key.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Copy of every book, for writing a checkpoint.
+    pub fn checkpoint(&self) -> StoreCheckpoint {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        StoreCheckpoint {
+            saved_ms: now_ms(),
+            books: guard.clone(),
+        }
+    }
+
+    /// Load a checkpoint into an empty store, skipping symbols last updated
+    /// more than `max_age_ms` ago. Loaded symbols are marked restored until
+    /// live data replaces them. Returns how many were loaded.
+    pub fn restore(&self, checkpoint: StoreCheckpoint, max_age_ms: u64) -> usize {
+        let cutoff = now_ms().saturating_sub(max_age_ms);
+        let mut loaded = 0;
+        let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        for (exchange, mut book) in checkpoint.books {
+            book.entries.retain(|_, e| e.updated_ms >= cutoff);
+            if book.entries.is_empty() {
+                continue;
+            }
+            for e in book.entries.values_mut() {
+                e.restored = true;
+            }
+            loaded += book.entries.len();
+            guard.entry(exchange).or_insert(book);
+        }
+        loaded
+    }
+
+    /// Symbols that appeared within the last `within_ms`, ignoring those
+    /// already present during the first `grace_ms` after the exchange's
+    /// first write (they were listed before we connected).
+    pub fn recent_listings(
+        &self,
+        exchange: &str,
+        within_ms: u64,
+        grace_ms: u64,
+    ) -> HashSet<String> {
+        let cutoff = now_ms().saturating_sub(within_ms);
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        guard
+            .get(exchange)
+            .map(|book| {
+                book.first_seen
+                    .iter()
+                    .filter(|(_, at)| **at >= cutoff && **at > book.started_ms + grace_ms)
+                    .map(|(key, _)| key.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+pub fn pair_key(base: &str, quote: &str) -> String {
+    format!("{}/{}", base, quote)
+}
+
+/// The price store as written to disk by `checkpoint`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StoreCheckpoint {
+    pub saved_ms: u64,
+    books: HashMap<String, Book>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delta(price: f64) -> Delta {
+        Delta {
+            changed: vec![PairPrice {
+                base: "BTC".into(),
+                quote: "USDT".into(),
+                price,
+                is_spot: true,
+                volume: 0.0,
+                event_time: 0,
+                bid: None,
+                ask: None,
+            }],
+            removed: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn restored_symbols_restart_their_interval() {
+        let before = PriceStore::default();
+        before.apply_delta("test", delta(1.0));
+        before.apply_delta("test", delta(2.0));
+        assert!(before.freshness("test")["BTC/USDT"].interval_ms.is_some());
+
+        let after = PriceStore::default();
+        after.restore(before.checkpoint(), u64::MAX);
+        after.apply_delta("test", delta(3.0));
+        assert!(after.freshness("test")["BTC/USDT"].interval_ms.is_none());
+        assert!(after.restored_symbols("test").is_empty());
+    }
+}
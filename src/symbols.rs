@@ -0,0 +1,313 @@
+//! Instrument metadata (base, quote, status, tick/step size, min notional)
+//! of every venue with a REST client, cached per exchange behind one API.
+//! Connectors split stream symbols through it; routes and the logic layer
+//! read trading rules from it, and both leave halted pairs out.
+
+use crate::config::{config, SymbolsConfig};
+use crate::coverage::coverage;
+use crate::rest::{self, Instrument};
+use crate::store::pair_key;
+use crate::tasks;
+use crate::utils::now_ms;
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, HashSet};
+use std::sync::RwLock;
+use tokio::time::Duration;
+use tracing::warn;
+
+static SYMBOLS: Lazy<SymbolRegistry> = Lazy::new(SymbolRegistry::default);
+
+pub fn symbols() -> &'static SymbolRegistry {
+    &SYMBOLS
+}
+
+#[derive(Debug, Default)]
+struct Venue {
+    /// Venue symbol -> instrument.
+    by_symbol: HashMap<String, Instrument>,
+    /// "BASE/QUOTE" -> venue symbol.
+    by_pair: HashMap<String, String>,
+    /// Every base and quote asset listed.
+    assets: HashSet<String>,
+    fetched_ms: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct SymbolRegistry {
+    inner: RwLock<HashMap<String, Venue>>,
+}
+
+impl SymbolRegistry {
+    /// Re-fetch a venue's instrument list and update the `/coverage` listed
+    /// count. On failure the cached list stays in use.
+    pub async fn refresh(&self, exchange: &str) -> Result<Vec<Instrument>, String> {
+        let list = rest::instruments(exchange).await?;
+        coverage().set_listed(exchange, list.iter().filter(|i| i.trading).count());
+        self.replace(exchange, &list);
+        Ok(list)
+    }
+
+    /// Replace the cached list of a venue.
+    pub(crate) fn replace(&self, exchange: &str, list: &[Instrument]) {
+        let venue = Venue {
+            by_pair: list
+                .iter()
+                .map(|i| (pair_key(&i.base, &i.quote), i.symbol.clone()))
+                .collect(),
+            by_symbol: list.iter().map(|i| (i.symbol.clone(), i.clone())).collect(),
+            assets: list
+                .iter()
+                .flat_map(|i| [i.base.clone(), i.quote.clone()])
+                .collect(),
+            fetched_ms: now_ms(),
+        };
+        let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        guard.insert(exchange.to_string(), venue);
+    }
+
+    /// Whether a list has been fetched for the venue.
+    pub fn is_loaded(&self, exchange: &str) -> bool {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        guard.contains_key(exchange)
+    }
+
+    /// Tradable instruments in the cached list.
+    pub fn count(&self, exchange: &str) -> usize {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        guard
+            .get(exchange)
+            .map_or(0, |v| v.by_symbol.values().filter(|i| i.trading).count())
+    }
+
+    /// Tradable venue symbols, sorted.
+    pub fn tradable(&self, exchange: &str) -> Vec<String> {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        let mut list: Vec<String> = guard
+            .get(exchange)
+            .map(|v| {
+                v.by_symbol
+                    .values()
+                    .filter(|i| i.trading)
+                    .map(|i| i.symbol.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+        list.sort_unstable();
+        list
+    }
+
+    /// Base and quote of a tradable venue symbol, strictly from the listing.
+    pub fn pair(&self, exchange: &str, symbol: &str) -> Option<(String, String)> {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        let inst = guard.get(exchange)?.by_symbol.get(symbol)?;
+        inst.trading
+            .then(|| (inst.base.clone(), inst.quote.clone()))
+    }
+
+    /// Base and quote of any venue symbol: from the listing when it has the
+    /// symbol, otherwise guessed (see [`guess_split`]).
+    pub fn split(&self, exchange: &str, symbol: &str) -> Option<(String, String)> {
+        {
+            let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+            if let Some(inst) = guard.get(exchange).and_then(|v| v.by_symbol.get(symbol)) {
+                return Some((inst.base.clone(), inst.quote.clone()));
+            }
+        }
+        guess_split(symbol)
+    }
+
+    /// Whether the venue lists the pair but doesn't trade it (halted,
+    /// delisted, auction/`BREAK`). Pairs missing from the listing or venues
+    /// without one are not treated as halted.
+    pub fn halted(&self, exchange: &str, base: &str, quote: &str) -> bool {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        guard
+            .get(exchange)
+            .and_then(|v| v.by_symbol.get(v.by_pair.get(&pair_key(base, quote))?))
+            .is_some_and(|i| !i.trading)
+    }
+
+    /// Whether `base` is one of the venue's leveraged tokens: it ends in a
+    /// configured suffix and, unless the suffix is a multiplier like "3L",
+    /// the rest is itself a listed asset (BTCUP, but not JUP or SYRUP).
+    /// Without a listing the rest must be at least three letters.
+    pub fn leveraged(&self, exchange: &str, base: &str) -> bool {
+        let cfg = &config().leveraged_tokens;
+        let Some(suffixes) = cfg.suffixes.get(exchange).filter(|_| cfg.enabled) else {
+            return false;
+        };
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        let assets = guard.get(exchange).map(|v| &v.assets);
+        suffixes.iter().any(|suffix| {
+            let Some(stem) = base.strip_suffix(suffix.as_str()).filter(|s| !s.is_empty()) else {
+                return false;
+            };
+            if suffix.starts_with(|c: char| c.is_ascii_digit()) {
+                return true;
+            }
+            match assets {
+                Some(assets) => assets.contains(stem),
+                None => stem.len() >= 3,
+            }
+        })
+    }
+
+    /// Metadata of one pair.
+    pub fn get(&self, exchange: &str, base: &str, quote: &str) -> Option<Instrument> {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        let venue = guard.get(exchange)?;
+        let symbol = venue.by_pair.get(&pair_key(base, quote))?;
+        venue.by_symbol.get(symbol).cloned()
+    }
+
+    /// The venue's list, fetched first if nothing is cached yet.
+    pub async fn load(&self, exchange: &str) -> Result<(u64, Vec<Instrument>), String> {
+        if !self.is_loaded(exchange) {
+            self.refresh(exchange).await?;
+        }
+        self.list(exchange)
+            .ok_or_else(|| format!("no instrument list for {}", exchange))
+    }
+
+    /// The cached list, sorted by symbol, and when it was fetched.
+    pub fn list(&self, exchange: &str) -> Option<(u64, Vec<Instrument>)> {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        let venue = guard.get(exchange)?;
+        let mut list: Vec<Instrument> = venue.by_symbol.values().cloned().collect();
+        list.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        Some((venue.fetched_ms, list))
+    }
+}
+
+/// Re-fetch every loaded venue's list each `refresh_ms`, so status changes
+/// reach connectors and scans without waiting for a reconnect.
+pub fn start(cfg: &SymbolsConfig) {
+    if cfg.refresh_ms == 0 {
+        return;
+    }
+    let every = Duration::from_millis(cfg.refresh_ms.max(10_000));
+    tasks::spawn("symbols", async move {
+        loop {
+            tokio::time::sleep(every).await;
+            let loaded: Vec<String> = {
+                let guard = SYMBOLS.inner.read().unwrap_or_else(|e| e.into_inner());
+                guard.keys().cloned().collect()
+            };
+            for exchange in loaded {
+                if let Err(e) = SYMBOLS.refresh(&exchange).await {
+                    warn!("{}: instrument refresh failed: {}", exchange, e);
+                }
+            }
+        }
+    });
+}
+
+/// Split a symbol no listing covers: at a `/`, `-` or `_` separator, else
+/// by a known quote suffix ("BTCUSDT"), else by a 3-4 letter suffix.
+pub fn guess_split(sym: &str) -> Option<(String, String)> {
+    let s = sym.to_uppercase();
+    if let Some((base, quote)) = s.split_once(['/', '-', '_']) {
+        return (!base.is_empty() && !quote.is_empty())
+            .then(|| (base.to_string(), quote.to_string()));
+    }
+    const QUOTES: [&str; 24] = [
+        "USDT", "BUSD", "USDC", "FDUSD", "TUSD", "BTC", "ETH", "BNB", "TRY", "EUR", "GBP", "AUD",
+        "BRL", "CAD", "ARS", "RUB", "ZAR", "NGN", "UAH", "IDR", "JPY", "KRW", "VND", "MXN",
+    ];
+
+    for q in &QUOTES {
+        if s.ends_with(q) && s.len() > q.len() {
+            let base = s[..s.len() - q.len()].to_string();
+            return Some((base, q.to_string()));
+        }
+    }
+
+    if s.len() > 6 {
+        let try3 = s.split_at(s.len() - 3);
+        if try3.1.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Some((try3.0.to_string(), try3.1.to_string()));
+        }
+    }
+    if s.len() > 7 {
+        let try4 = s.split_at(s.len() - 4);
+        if try4.1.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Some((try4.0.to_string(), try4.1.to_string()));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instrument(symbol: &str, base: &str, quote: &str, trading: bool) -> Instrument {
+        Instrument {
+            symbol: symbol.into(),
+            base: base.into(),
+            quote: quote.into(),
+            trading,
+            ..Default::default()
+        }
+    }
+
+    fn registry(list: &[Instrument]) -> SymbolRegistry {
+        let registry = SymbolRegistry::default();
+        registry.replace("test", list);
+        registry
+    }
+
+    #[test]
+    fn pair_needs_a_trading_listing_and_split_falls_back_to_a_guess() {
+        let reg = registry(&[
+            instrument("ABCDEF", "ABC", "DEF", true),
+            instrument("OLDUSDT", "OLD", "USDT", false),
+        ]);
+        let pair = |base: &str, quote: &str| Some((base.to_string(), quote.to_string()));
+        assert_eq!(reg.pair("test", "ABCDEF"), pair("ABC", "DEF"));
+        assert_eq!(reg.pair("test", "OLDUSDT"), None);
+        assert_eq!(reg.pair("test", "ETHUSDT"), None);
+        assert_eq!(reg.split("test", "OLDUSDT"), pair("OLD", "USDT"));
+        assert_eq!(reg.split("test", "ETHUSDT"), pair("ETH", "USDT"));
+        assert_eq!(reg.split("test", "eth-btc"), pair("ETH", "BTC"));
+        assert_eq!(reg.tradable("test"), ["ABCDEF"]);
+        assert_eq!(
+            reg.get("test", "OLD", "USDT").map(|i| i.symbol),
+            Some("OLDUSDT".into())
+        );
+    }
+
+    #[test]
+    fn only_listed_pairs_that_dont_trade_are_halted() {
+        let reg = registry(&[
+            instrument("BTCUSDT", "BTC", "USDT", true),
+            instrument("OLDUSDT", "OLD", "USDT", false),
+        ]);
+        assert!(reg.halted("test", "OLD", "USDT"));
+        assert!(!reg.halted("test", "BTC", "USDT"));
+        // Unlisted pairs and venues without a listing are left alone.
+        assert!(!reg.halted("test", "NEW", "USDT"));
+        assert!(!reg.halted("other", "OLD", "USDT"));
+    }
+
+    #[test]
+    fn leveraged_suffixes_need_a_listed_stem() {
+        let reg = SymbolRegistry::default();
+        reg.replace(
+            "binance",
+            &[
+                instrument("BTCUPUSDT", "BTCUP", "USDT", true),
+                instrument("JUPUSDT", "JUP", "USDT", true),
+                instrument("BTCUSDT", "BTC", "USDT", true),
+            ],
+        );
+        assert!(reg.leveraged("binance", "BTCUP"));
+        assert!(!reg.leveraged("binance", "JUP"));
+        assert!(!reg.leveraged("binance", "BTC"));
+        // Multiplier suffixes need no stem; unlisted venues go by stem length.
+        assert!(reg.leveraged("gateio", "ETH3L"));
+        assert!(reg.leveraged("kucoin", "SOLUP"));
+        assert!(!reg.leveraged("kucoin", "JUP"));
+        assert!(!reg.leveraged("okx", "BTCUP"));
+    }
+}
@@ -0,0 +1,195 @@
+//! Named background tasks. With the `tokio-console` feature every task
+//! spawned through [`spawn`] runs inside a `task{name=..}` span and has its
+//! polls timed, so `/admin/tasks` shows which connector or scan is hogging
+//! a runtime worker. The feature also installs the `console-subscriber`
+//! layer (see `utils::init_tracing`); building with
+//! `RUSTFLAGS="--cfg tokio_unstable"` registers the names with tokio so
+//! `tokio-console` shows them.
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use tokio::task::JoinHandle;
+use utoipa::ToSchema;
+
+static TASKS: Lazy<TaskRegistry> = Lazy::new(TaskRegistry::default);
+
+pub fn tasks() -> &'static TaskRegistry {
+    &TASKS
+}
+
+/// A poll longer than this blocks a worker thread and is counted as slow.
+#[cfg_attr(not(feature = "tokio-console"), allow(dead_code))]
+const SLOW_POLL_US: u64 = 10_000;
+
+/// Spawn `fut` on the runtime under `name` (e.g. "binance", "job:majors").
+pub fn spawn<F>(name: &str, fut: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    #[cfg(feature = "tokio-console")]
+    {
+        use tracing::Instrument;
+        let fut = timed::Timed::new(fut, tasks().counters(name))
+            .instrument(tracing::info_span!("task", name));
+        #[cfg(tokio_unstable)]
+        return tokio::task::Builder::new()
+            .name(name)
+            .spawn(fut)
+            .expect("spawn task");
+        #[cfg(not(tokio_unstable))]
+        return tokio::spawn(fut);
+    }
+    #[cfg(not(feature = "tokio-console"))]
+    {
+        let _ = name;
+        tokio::spawn(fut)
+    }
+}
+
+#[derive(Default)]
+pub struct TaskRegistry {
+    tasks: RwLock<BTreeMap<String, Arc<Counters>>>,
+}
+
+/// Totals over every task spawned under one name.
+#[derive(Default)]
+struct Counters {
+    spawned: AtomicU64,
+    finished: AtomicU64,
+    polls: AtomicU64,
+    busy_us: AtomicU64,
+    max_poll_us: AtomicU64,
+    slow_polls: AtomicU64,
+    /// Unix ms when the poll in progress started; 0 when none is.
+    polling_since_ms: AtomicU64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TasksReport {
+    /// False when built without the `tokio-console` feature; nothing is
+    /// recorded then.
+    pub enabled: bool,
+    pub tasks: BTreeMap<String, TaskReport>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TaskReport {
+    pub spawned: u64,
+    pub running: u64,
+    pub polls: u64,
+    pub busy_ms: f64,
+    pub max_poll_ms: f64,
+    /// Polls over 10ms.
+    pub slow_polls: u64,
+    /// How long the current poll has been running; a large value means the
+    /// task is blocking its worker right now.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_poll_ms: Option<u64>,
+}
+
+impl TaskRegistry {
+    #[cfg_attr(not(feature = "tokio-console"), allow(dead_code))]
+    fn counters(&self, name: &str) -> Arc<Counters> {
+        if let Some(c) = self
+            .tasks
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name)
+        {
+            return c.clone();
+        }
+        self.tasks
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(name.to_string())
+            .or_default()
+            .clone()
+    }
+
+    pub fn report(&self) -> TasksReport {
+        let now = crate::utils::now_ms();
+        let tasks = self
+            .tasks
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(name, c)| {
+                let spawned = c.spawned.load(Ordering::Relaxed);
+                let since = c.polling_since_ms.load(Ordering::Relaxed);
+                let report = TaskReport {
+                    spawned,
+                    running: spawned.saturating_sub(c.finished.load(Ordering::Relaxed)),
+                    polls: c.polls.load(Ordering::Relaxed),
+                    busy_ms: c.busy_us.load(Ordering::Relaxed) as f64 / 1000.0,
+                    max_poll_ms: c.max_poll_us.load(Ordering::Relaxed) as f64 / 1000.0,
+                    slow_polls: c.slow_polls.load(Ordering::Relaxed),
+                    in_poll_ms: (since > 0).then(|| now.saturating_sub(since)),
+                };
+                (name.clone(), report)
+            })
+            .collect();
+        TasksReport {
+            enabled: cfg!(feature = "tokio-console"),
+            tasks,
+        }
+    }
+}
+
+#[cfg(feature = "tokio-console")]
+mod timed {
+    use super::{Counters, SLOW_POLL_US};
+    use crate::utils::now_ms;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    use std::task::{Context, Poll};
+    use std::time::Instant;
+
+    /// Times every poll of the wrapped future into its name's counters.
+    pub struct Timed<F> {
+        inner: Pin<Box<F>>,
+        counters: Arc<Counters>,
+    }
+
+    impl<F> Timed<F> {
+        pub fn new(inner: F, counters: Arc<Counters>) -> Self {
+            counters.spawned.fetch_add(1, Ordering::Relaxed);
+            Self {
+                inner: Box::pin(inner),
+                counters,
+            }
+        }
+    }
+
+    impl<F: Future> Future for Timed<F> {
+        type Output = F::Output;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+            let c = self.counters.clone();
+            c.polling_since_ms.store(now_ms(), Ordering::Relaxed);
+            let started = Instant::now();
+            let out = self.inner.as_mut().poll(cx);
+            let us = started.elapsed().as_micros() as u64;
+            c.polling_since_ms.store(0, Ordering::Relaxed);
+            c.polls.fetch_add(1, Ordering::Relaxed);
+            c.busy_us.fetch_add(us, Ordering::Relaxed);
+            c.max_poll_us.fetch_max(us, Ordering::Relaxed);
+            if us > SLOW_POLL_US {
+                c.slow_polls.fetch_add(1, Ordering::Relaxed);
+            }
+            out
+        }
+    }
+
+    impl<F> Drop for Timed<F> {
+        fn drop(&mut self) {
+            self.counters.finished.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
@@ -0,0 +1,26 @@
+//! Alert bodies as Jinja templates (minijinja) over a JSON context, e.g.
+//! `{{ exchange | upper }} {{ triangle }} +{{ profit_after | round(2) }}%`
+//! or `{{ pairs[0] | tojson }}` inside a webhook body.
+
+use minijinja::{Environment, UndefinedBehavior};
+use serde_json::Value;
+
+/// Unknown fields are errors rather than empty text, so a typo in a
+/// template shows up in the log instead of as a blank message.
+fn env() -> Environment<'static> {
+    let mut env = Environment::new();
+    env.set_undefined_behavior(UndefinedBehavior::Strict);
+    env
+}
+
+/// Parse `template` without rendering it, for validation at config load.
+pub fn check(template: &str) -> Result<(), String> {
+    env()
+        .template_from_str(template)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+pub fn render(template: &str, ctx: &Value) -> Result<String, String> {
+    env().render_str(template, ctx).map_err(|e| e.to_string())
+}
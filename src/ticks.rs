@@ -0,0 +1,66 @@
+use crate::config::config;
+use crate::models::PairPrice;
+use crate::store::pair_key;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::RwLock;
+use utoipa::ToSchema;
+
+/// Recent price samples per symbol, fed by every store flush, so a leg's
+/// movement around an opportunity can be checked after the fact.
+static TICKS: Lazy<TickHistory> = Lazy::new(TickHistory::default);
+
+pub fn ticks() -> &'static TickHistory {
+    &TICKS
+}
+
+#[derive(Debug, Clone, Copy, Serialize, ToSchema)]
+pub struct Sample {
+    pub ts: u64,
+    pub price: f64,
+}
+
+#[derive(Debug, Default)]
+pub struct TickHistory {
+    /// (exchange, "BASE/QUOTE") -> samples, oldest first.
+    inner: RwLock<HashMap<(String, String), VecDeque<Sample>>>,
+}
+
+impl TickHistory {
+    /// Append one flush worth of changed prices. Samples use the exchange
+    /// timestamp when there is one, else `now`.
+    pub fn record(&self, exchange: &str, changed: &[PairPrice], now: u64) {
+        if changed.is_empty() {
+            return;
+        }
+        let cfg = &config().ticks;
+        let cutoff = now.saturating_sub(cfg.retention_ms);
+        let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        for p in changed {
+            let key = (exchange.to_string(), pair_key(&p.base, &p.quote));
+            let samples = guard.entry(key).or_default();
+            let ts = if p.event_time > 0 { p.event_time } else { now };
+            samples.push_back(Sample { ts, price: p.price });
+            while samples.len() > cfg.max_samples || samples.front().is_some_and(|s| s.ts < cutoff)
+            {
+                samples.pop_front();
+            }
+        }
+    }
+
+    /// Samples of one symbol at or after `since_ms`, oldest first.
+    pub fn since(&self, exchange: &str, symbol: &str, since_ms: u64) -> Vec<Sample> {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        guard
+            .get(&(exchange.to_string(), symbol.to_string()))
+            .map(|samples| {
+                samples
+                    .iter()
+                    .filter(|s| s.ts >= since_ms)
+                    .copied()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
@@ -12,9 +12,35 @@ pub fn init_tracing() {
     // allow overriding via RUST_LOG / default to "info"
     let filter_layer = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
 
-    // Registry + layers; `.with` is available thanks to prelude::*
-    Registry::default()
-        .with(filter_layer)
-        .with(fmt_layer)
-        .init();
+    // Registry + layers; `.with` is available thanks to prelude::*. The
+    // filter applies to the log output only, so the console layer still
+    // sees tokio's task spans.
+    let registry = Registry::default().with(fmt_layer.with_filter(filter_layer));
+    #[cfg(feature = "tokio-console")]
+    let registry = registry.with(console_subscriber::spawn());
+    registry.init();
+}
+
+/// Current wall-clock time in unix milliseconds.
+pub fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis().max(0) as u64
+}
+
+/// Parse a short duration like "500ms", "60s", "5m" or "1h" into
+/// milliseconds; a bare number is taken as seconds.
+pub fn parse_duration_ms(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (value, unit) = text.split_at(split);
+    let value: u64 = value.parse().ok()?;
+    let scale = match unit {
+        "ms" => 1,
+        "" | "s" => 1_000,
+        "m" => 60_000,
+        "h" => 3_600_000,
+        _ => return None,
+    };
+    value.checked_mul(scale)
 }
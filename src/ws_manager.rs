@@ -0,0 +1,826 @@
+use crate::blacklist::blacklist;
+use crate::chaos::{self, Fault};
+use crate::config::config;
+use crate::coverage::coverage;
+use crate::exchanges::ascendex::run_ascendex_ws;
+use crate::exchanges::binance::{run_binance_us_ws, run_binance_ws};
+use crate::exchanges::bingx::run_bingx_ws;
+use crate::exchanges::bitget::run_bitget_ws;
+use crate::exchanges::bithumb::run_bithumb_ws;
+use crate::exchanges::bitstamp::run_bitstamp_ws;
+use crate::exchanges::bybit::run_bybit_ws;
+use crate::exchanges::coinbase::run_coinbase_ws;
+use crate::exchanges::coinex::run_coinex_ws;
+use crate::exchanges::curve::run_curve;
+use crate::exchanges::declarative::run_declarative_ws;
+use crate::exchanges::dydx::run_dydx_ws;
+use crate::exchanges::gateio::run_gateio_ws;
+use crate::exchanges::gemini::run_gemini_ws;
+use crate::exchanges::hyperliquid::run_hyperliquid_ws;
+use crate::exchanges::jupiter::run_jupiter;
+use crate::exchanges::kraken::run_kraken_ws;
+use crate::exchanges::kucoin::run_kucoin_ws;
+use crate::exchanges::lbank::run_lbank_ws;
+use crate::exchanges::mexc::run_mexc_ws;
+use crate::exchanges::mock::run_mock;
+use crate::exchanges::okx::run_okx_ws;
+use crate::exchanges::osmosis::run_osmosis;
+use crate::exchanges::pancakeswap::run_pancakeswap;
+use crate::exchanges::phemex::run_phemex_ws;
+use crate::exchanges::poloniex::run_poloniex_ws;
+use crate::exchanges::rest_fallback::run_rest_fallback;
+use crate::exchanges::solana_dex::run_solana_dex;
+use crate::exchanges::synthetic::run_synthetic;
+use crate::exchanges::uniswap::run_uniswap;
+use crate::exchanges::whitebit::run_whitebit_ws;
+use crate::exchanges::{Parsed, TickerBuffer};
+use crate::health::health;
+use crate::inflate;
+use crate::load_shed::{LoadShedder, RateCap};
+use crate::models::PairPrice;
+use crate::orderbook;
+use crate::rest;
+use crate::store::{pair_key, SharedPrices};
+use crate::symbols::{self, symbols};
+use crate::tasks;
+use crate::utils::now_ms;
+use futures::future::BoxFuture;
+use futures_util::{SinkExt, StreamExt};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::future::Future;
+use tokio::net::TcpStream;
+use tokio::time::{Duration, Instant};
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+
+/// A built-in connector `start_all_workers` can launch.
+pub struct ConnectorEntry {
+    /// Key in the price store and in `connectors` settings.
+    pub name: &'static str,
+    /// Runs unless disabled in `connectors` (the original four venues);
+    /// every other connector must be listed there to run.
+    pub default_on: bool,
+    start: fn(SharedPrices) -> BoxFuture<'static, ()>,
+}
+
+/// One row of `GET /connectors`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConnectorStatus {
+    pub name: &'static str,
+    pub default_on: bool,
+    pub enabled: bool,
+}
+
+/// Every built-in connector and whether the current config runs it.
+pub fn connector_statuses() -> Vec<ConnectorStatus> {
+    CONNECTORS
+        .iter()
+        .map(|e| ConnectorStatus {
+            name: e.name,
+            default_on: e.default_on,
+            enabled: e.enabled(),
+        })
+        .collect()
+}
+
+impl ConnectorEntry {
+    pub fn enabled(&self) -> bool {
+        let cfg = config();
+        if self.default_on {
+            cfg.connector(self.name).enabled
+        } else {
+            cfg.opted_in(self.name)
+        }
+    }
+}
+
+/// Every built-in connector, in startup order. Mock, synthetic and
+/// declarative venues are configured by their own sections instead.
+pub static CONNECTORS: &[ConnectorEntry] = &[
+    ConnectorEntry {
+        name: "binance",
+        default_on: true,
+        start: |p| Box::pin(run_binance_ws(p)),
+    },
+    ConnectorEntry {
+        name: "bybit",
+        default_on: true,
+        start: |p| Box::pin(run_bybit_ws(p)),
+    },
+    ConnectorEntry {
+        name: "kucoin",
+        default_on: true,
+        start: |p| Box::pin(run_kucoin_ws(p)),
+    },
+    ConnectorEntry {
+        name: "gateio",
+        default_on: true,
+        start: |p| Box::pin(run_gateio_ws(p)),
+    },
+    ConnectorEntry {
+        name: "okx",
+        default_on: false,
+        start: |p| Box::pin(run_okx_ws(p)),
+    },
+    ConnectorEntry {
+        name: "kraken",
+        default_on: false,
+        start: |p| Box::pin(run_kraken_ws(p)),
+    },
+    ConnectorEntry {
+        name: "coinbase",
+        default_on: false,
+        start: |p| Box::pin(run_coinbase_ws(p)),
+    },
+    ConnectorEntry {
+        name: "mexc",
+        default_on: false,
+        start: |p| Box::pin(run_mexc_ws(p)),
+    },
+    ConnectorEntry {
+        name: "bitget",
+        default_on: false,
+        start: |p| Box::pin(run_bitget_ws(p)),
+    },
+    ConnectorEntry {
+        name: "gemini",
+        default_on: false,
+        start: |p| Box::pin(run_gemini_ws(p)),
+    },
+    ConnectorEntry {
+        name: "bithumb",
+        default_on: false,
+        start: |p| Box::pin(run_bithumb_ws(p)),
+    },
+    ConnectorEntry {
+        name: "bitstamp",
+        default_on: false,
+        start: |p| Box::pin(run_bitstamp_ws(p)),
+    },
+    ConnectorEntry {
+        name: "poloniex",
+        default_on: false,
+        start: |p| Box::pin(run_poloniex_ws(p)),
+    },
+    ConnectorEntry {
+        name: "bingx",
+        default_on: false,
+        start: |p| Box::pin(run_bingx_ws(p)),
+    },
+    ConnectorEntry {
+        name: "whitebit",
+        default_on: false,
+        start: |p| Box::pin(run_whitebit_ws(p)),
+    },
+    ConnectorEntry {
+        name: "lbank",
+        default_on: false,
+        start: |p| Box::pin(run_lbank_ws(p)),
+    },
+    ConnectorEntry {
+        name: "phemex",
+        default_on: false,
+        start: |p| Box::pin(run_phemex_ws(p)),
+    },
+    ConnectorEntry {
+        name: "ascendex",
+        default_on: false,
+        start: |p| Box::pin(run_ascendex_ws(p)),
+    },
+    ConnectorEntry {
+        name: "coinex",
+        default_on: false,
+        start: |p| Box::pin(run_coinex_ws(p)),
+    },
+    ConnectorEntry {
+        name: "binanceus",
+        default_on: false,
+        start: |p| Box::pin(run_binance_us_ws(p)),
+    },
+    ConnectorEntry {
+        name: "hyperliquid",
+        default_on: false,
+        start: |p| Box::pin(run_hyperliquid_ws(p)),
+    },
+    ConnectorEntry {
+        name: "dydx",
+        default_on: false,
+        start: |p| Box::pin(run_dydx_ws(p)),
+    },
+    ConnectorEntry {
+        name: "uniswap",
+        default_on: false,
+        start: |p| Box::pin(run_uniswap(config().uniswap.clone(), p)),
+    },
+    ConnectorEntry {
+        name: "pancakeswap",
+        default_on: false,
+        start: |p| Box::pin(run_pancakeswap(config().pancakeswap.clone(), p)),
+    },
+    ConnectorEntry {
+        name: "solana-dex",
+        default_on: false,
+        start: |p| Box::pin(run_solana_dex(p)),
+    },
+    ConnectorEntry {
+        name: "osmosis",
+        default_on: false,
+        start: |p| Box::pin(run_osmosis(p)),
+    },
+    ConnectorEntry {
+        name: "jupiter",
+        default_on: false,
+        start: |p| Box::pin(run_jupiter(p)),
+    },
+    ConnectorEntry {
+        name: "curve",
+        default_on: false,
+        start: |p| Box::pin(run_curve(config().curve.clone(), p)),
+    },
+];
+
+/// Connectors whose `testnet` flag switches their endpoints.
+const TESTNET_VENUES: &[&str] = &["binance", "bybit", "kucoin"];
+
+/// Connectors whose feeds carry no 24h volume (every pair has `volume: 0`),
+/// so a `min_volume` filter would drop all of them.
+pub const NO_VOLUME_VENUES: &[&str] = &[
+    "kucoin",
+    "mexc",
+    "gemini",
+    "bitstamp",
+    "ascendex",
+    "hyperliquid",
+    "uniswap",
+    "pancakeswap",
+    "solana-dex",
+    "osmosis",
+    "jupiter",
+    "curve",
+];
+
+/// Spawn a background worker for every enabled connector.
+pub fn start_all_workers(prices: SharedPrices) {
+    let cfg = config();
+    for entry in CONNECTORS.iter().filter(|e| e.enabled()) {
+        match (
+            cfg.connector(entry.name).testnet,
+            TESTNET_VENUES.contains(&entry.name),
+        ) {
+            (true, true) => info!("starting {} worker on testnet", entry.name),
+            (true, false) => warn!(
+                "{} has no testnet endpoints; starting against the live venue",
+                entry.name
+            ),
+            _ => info!("starting {} worker", entry.name),
+        }
+        tasks::spawn(entry.name, (entry.start)(prices.clone()));
+        if cfg.fallback.enabled && rest::can_poll(entry.name) {
+            let name = format!("{}-fallback", entry.name);
+            tasks::spawn(&name, run_rest_fallback(entry.name, prices.clone()));
+        }
+    }
+    if cfg.mock.enabled {
+        info!("starting mock exchange");
+        tasks::spawn("mock", run_mock(cfg.mock.clone(), prices.clone()));
+    }
+    if cfg.synthetic.enabled {
+        info!("starting synthetic exchange");
+        tasks::spawn(
+            "synthetic",
+            run_synthetic(cfg.synthetic.clone(), prices.clone()),
+        );
+    }
+    for spec in &cfg.declarative {
+        if cfg.connector(&spec.name).enabled {
+            info!("starting declarative worker for {}", spec.name);
+            tasks::spawn(&spec.name, run_declarative_ws(spec.clone(), prices.clone()));
+        }
+    }
+    orderbook::start(&cfg.orderbook);
+    symbols::start(&cfg.symbols);
+}
+
+/// A WS venue reduced to its session setup and frame parser;
+/// [`run_connector`] supplies the connect/flush/reconnect loop around it.
+pub trait ExchangeConnector: Send + 'static {
+    /// Key in the price store and in `connectors` settings.
+    fn name(&self) -> &str;
+
+    /// Prepare the next session (refresh instrument lists, fetch tokens)
+    /// and return the URL to connect to. Called before every (re)connect;
+    /// errors are retried with backoff.
+    fn connect(&mut self) -> impl Future<Output = Result<String, String>> + Send;
+
+    /// Frames sent right after connecting.
+    fn subscribe(&self) -> Vec<String>;
+
+    fn keepalive(&self) -> Heartbeat;
+
+    fn parse_message(&mut self, txt: &str) -> Parsed;
+}
+
+/// Long-running worker for an [`ExchangeConnector`].
+pub async fn run_connector(mut connector: impl ExchangeConnector, prices: SharedPrices) {
+    let exchange = connector.name().to_string();
+    let exchange = exchange.as_str();
+    let flush_every = config().connector(exchange).flush_interval();
+    let mut buffer = TickerBuffer::new(exchange);
+    let mut backoff = Backoff::default();
+
+    loop {
+        let url = match connector.connect().await {
+            Ok(url) => url,
+            Err(e) => {
+                error!("{} session setup failed: {}", exchange, e);
+                backoff.wait().await;
+                continue;
+            }
+        };
+        let endpoint = WsEndpoint {
+            url,
+            subscribe: connector.subscribe(),
+            heartbeat: connector.keepalive(),
+        };
+        let end = run_session(
+            exchange,
+            &endpoint,
+            flush_every,
+            &mut buffer,
+            &prices,
+            |txt| connector.parse_message(txt),
+        )
+        .await;
+        backoff.after(end).await;
+    }
+}
+
+/// Where and how to connect for one WS session. Built fresh on every
+/// (re)connect because some venues hand out per-connection URLs/tokens.
+struct WsEndpoint {
+    url: String,
+    /// Text frames sent right after connecting.
+    subscribe: Vec<String>,
+    heartbeat: Heartbeat,
+}
+
+/// Topics a connector is subscribed to. Owned by the worker so the set
+/// survives reconnects and is replayed, chunked to the venue's per-request
+/// limit, every time a new session starts.
+pub struct Subscriptions {
+    topics: BTreeSet<String>,
+    chunk_size: usize,
+    build: fn(&[String]) -> String,
+}
+
+impl Subscriptions {
+    /// `build` turns one chunk of topics into the venue's subscribe frame.
+    pub fn new(chunk_size: usize, build: fn(&[String]) -> String) -> Self {
+        Self {
+            topics: BTreeSet::new(),
+            chunk_size: chunk_size.max(1),
+            build,
+        }
+    }
+
+    pub fn with_topics(mut self, topics: impl IntoIterator<Item = String>) -> Self {
+        self.replace(topics);
+        self
+    }
+
+    /// Swap in a new topic set (e.g. after refreshing the instrument list).
+    pub fn replace(&mut self, topics: impl IntoIterator<Item = String>) {
+        self.topics = topics.into_iter().collect();
+    }
+
+    pub fn len(&self) -> usize {
+        self.topics.len()
+    }
+
+    /// Subscribe frames covering every active topic.
+    pub fn messages(&self) -> Vec<String> {
+        let topics: Vec<String> = self.topics.iter().cloned().collect();
+        topics.chunks(self.chunk_size).map(self.build).collect()
+    }
+}
+
+/// Keepalive protocol a venue documents for its public stream.
+pub enum Heartbeat {
+    /// The server sends WS pings and tungstenite answers them (Binance).
+    ServerPing,
+    /// The venue expects an application-level text ping every `interval`;
+    /// raw WS pings are ignored.
+    Text {
+        interval: Duration,
+        message: fn() -> String,
+    },
+    /// Like `Text` for venues that answer every ping (KuCoin's `pong`): a
+    /// session that receives nothing for `interval + timeout` is treated as
+    /// dead and reconnected.
+    Answered {
+        interval: Duration,
+        timeout: Duration,
+        message: fn() -> String,
+    },
+    /// Like `Text`, with the payload taken from config; `{ts}` / `{ts_s}`
+    /// expand to the current unix time in ms / seconds.
+    Template {
+        interval: Duration,
+        template: String,
+    },
+}
+
+impl Heartbeat {
+    fn interval(&self) -> Option<Duration> {
+        match self {
+            Heartbeat::ServerPing => None,
+            Heartbeat::Text { interval, .. }
+            | Heartbeat::Answered { interval, .. }
+            | Heartbeat::Template { interval, .. } => Some(*interval),
+        }
+    }
+
+    /// How long a session may stay silent before it counts as dead.
+    fn silence_limit(&self) -> Option<Duration> {
+        match self {
+            Heartbeat::Answered {
+                interval, timeout, ..
+            } => Some(*interval + *timeout),
+            _ => None,
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            Heartbeat::ServerPing => String::new(),
+            Heartbeat::Text { message, .. } | Heartbeat::Answered { message, .. } => message(),
+            Heartbeat::Template { template, .. } => {
+                let now = now_ms();
+                template
+                    .replace("{ts}", &now.to_string())
+                    .replace("{ts_s}", &(now / 1000).to_string())
+            }
+        }
+    }
+}
+
+/// Gap between consecutive subscribe frames, to stay under venues'
+/// inbound message rate limits (Binance allows 5/s).
+const SUBSCRIBE_PACING: Duration = Duration::from_millis(250);
+
+/// How many times a session re-sends its subscriptions after the venue
+/// rejects one before giving up and reconnecting.
+const MAX_RESUBSCRIBES: u32 = 3;
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Why a session ended; decides how long to wait before reconnecting.
+pub enum SessionEnd {
+    ConnectFailed,
+    Closed,
+    RateLimited,
+}
+
+/// Reconnect delay that doubles on every failure, capped at 30s.
+pub struct Backoff(Duration);
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self(Duration::from_secs(1))
+    }
+}
+
+impl Backoff {
+    const MAX: Duration = Duration::from_secs(30);
+
+    pub async fn wait(&mut self) {
+        tokio::time::sleep(self.next()).await;
+    }
+
+    /// Wait before reconnecting: a session that was up resets the delay,
+    /// a rate-limited one jumps straight to the maximum.
+    pub async fn after(&mut self, end: SessionEnd) {
+        tokio::time::sleep(self.next_after(end)).await;
+    }
+
+    fn next(&mut self) -> Duration {
+        let delay = self.0;
+        self.0 = (self.0 * 2).min(Self::MAX);
+        delay
+    }
+
+    fn next_after(&mut self, end: SessionEnd) -> Duration {
+        match end {
+            SessionEnd::ConnectFailed => {}
+            SessionEnd::Closed => *self = Self::default(),
+            SessionEnd::RateLimited => self.0 = Self::MAX,
+        }
+        self.next()
+    }
+}
+
+/// Run one connected session until the stream drops, flushing changed symbols
+/// into `prices` every `flush_every` and sending heartbeats as specified.
+/// Venue error responses are logged, recorded in the health registry and
+/// answered with a re-subscription (or a disconnect when rate limited).
+async fn run_session(
+    exchange: &str,
+    endpoint: &WsEndpoint,
+    flush_every: Duration,
+    buffer: &mut TickerBuffer,
+    prices: &SharedPrices,
+    mut parse: impl FnMut(&str) -> Parsed,
+) -> SessionEnd {
+    let mut ws_stream = match connect_async(endpoint.url.as_str()).await {
+        Ok((ws, _)) => ws,
+        Err(e) => {
+            error!("{} connect error: {:?}", exchange, e);
+            health().record_error(exchange, &format!("connect: {}", e), false);
+            return SessionEnd::ConnectFailed;
+        }
+    };
+    info!(
+        "{} worker connected (flush every {:?})",
+        exchange, flush_every
+    );
+    health().set_connected(exchange, true);
+
+    for (i, sub) in endpoint.subscribe.iter().enumerate() {
+        if i > 0 {
+            tokio::time::sleep(SUBSCRIBE_PACING).await;
+        }
+        if let Err(e) = ws_stream.send(Message::Text(sub.clone())).await {
+            error!("{} subscribe error: {:?}", exchange, e);
+            health().set_connected(exchange, false);
+            return SessionEnd::Closed;
+        }
+    }
+    if !endpoint.subscribe.is_empty() {
+        info!(
+            "{}: sent {} subscribe frame(s)",
+            exchange,
+            endpoint.subscribe.len()
+        );
+    }
+
+    let mut shedder = LoadShedder::new(flush_every);
+    let mut cap = RateCap::new(config().connector(exchange).max_messages_per_sec);
+    buffer.shed_lowest(shedder.drop_fraction());
+    let mut flush = tokio::time::interval(flush_every);
+    let mut ping = endpoint.heartbeat.interval().map(tokio::time::interval);
+
+    let mut resubscribes = 0;
+    let mut last_frame = Instant::now();
+    let end = loop {
+        tokio::select! {
+            msg = ws_stream.next() => match msg {
+                Some(Ok(m)) if m.is_text() || m.is_binary() => {
+                    last_frame = Instant::now();
+                    health().record_message(exchange);
+                    let Some(mut txt) = frame_text(m) else {
+                        continue;
+                    };
+                    match chaos::roll(exchange, &txt) {
+                        Some(Fault::Disconnect) => {
+                            warn!("{} chaos: injected disconnect", exchange);
+                            health().record_error(exchange, "chaos: injected disconnect", false);
+                            break SessionEnd::Closed;
+                        }
+                        Some(Fault::Delay(d)) => {
+                            warn!("{} chaos: delaying frame by {:?}", exchange, d);
+                            tokio::time::sleep(d).await;
+                        }
+                        Some(Fault::Corrupt(bad)) => {
+                            warn!("{} chaos: corrupting frame", exchange);
+                            txt = bad;
+                        }
+                        None => {}
+                    }
+                    let Some(parsed) = cap.admit(exchange, parse(&txt)) else {
+                        continue;
+                    };
+                    if let Some(end) =
+                        handle(exchange, parsed, &mut ws_stream, endpoint, buffer, &mut resubscribes).await
+                    {
+                        break end;
+                    }
+                }
+                Some(Ok(_)) => last_frame = Instant::now(),
+                Some(Err(e)) => {
+                    error!("{} ws read error: {:?}", exchange, e);
+                    break SessionEnd::Closed;
+                }
+                None => {
+                    warn!("{} ws closed by server", exchange);
+                    break SessionEnd::Closed;
+                }
+            },
+            _ = flush.tick() => {
+                let held = cap.take_held();
+                if !held.is_empty() {
+                    ingest(exchange, held, buffer);
+                }
+                let backlog = buffer.pending();
+                let write = prices.apply_delta(exchange, buffer.drain());
+                if shedder.observe(exchange, write, backlog) {
+                    flush = tokio::time::interval(shedder.flush_interval());
+                    flush.reset();
+                    buffer.shed_lowest(shedder.drop_fraction());
+                }
+            }
+            _ = async {
+                match ping.as_mut() {
+                    Some(timer) => timer.tick().await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                if let Some(limit) = endpoint.heartbeat.silence_limit() {
+                    if last_frame.elapsed() > limit {
+                        warn!("{} silent for {:?} despite pings, reconnecting", exchange, limit);
+                        health().record_error(exchange, "ping timeout", false);
+                        break SessionEnd::Closed;
+                    }
+                }
+                let message = endpoint.heartbeat.message();
+                if let Err(e) = ws_stream.send(Message::Text(message)).await {
+                    error!("{} heartbeat send error: {:?}", exchange, e);
+                    break SessionEnd::Closed;
+                }
+            }
+        }
+    };
+
+    prices.apply_delta(exchange, buffer.drain());
+    health().set_connected(exchange, false);
+    end
+}
+
+/// Act on one parsed frame; `Some` ends the session.
+async fn handle(
+    exchange: &str,
+    parsed: Parsed,
+    ws_stream: &mut WsStream,
+    endpoint: &WsEndpoint,
+    buffer: &mut TickerBuffer,
+    resubscribes: &mut u32,
+) -> Option<SessionEnd> {
+    match parsed {
+        Parsed::Prices(ps) | Parsed::Book(ps) => ingest(exchange, ps, buffer),
+        Parsed::Reply(reply) => {
+            if let Err(e) = ws_stream.send(Message::Text(reply)).await {
+                error!("{} reply send error: {:?}", exchange, e);
+                return Some(SessionEnd::Closed);
+            }
+        }
+        Parsed::Notice(text) => {
+            warn!("{} system notice: {}", exchange, text);
+            health().record_notice(exchange, &text);
+        }
+        Parsed::Error(err) => {
+            warn!(
+                "{} error response (rate_limited={}): {}",
+                exchange, err.rate_limited, err.message
+            );
+            health().record_error(exchange, &err.message, err.rate_limited);
+            if err.rate_limited {
+                return Some(SessionEnd::RateLimited);
+            }
+            if *resubscribes >= MAX_RESUBSCRIBES {
+                return Some(SessionEnd::Closed);
+            }
+            *resubscribes += 1;
+            health().record_resubscribe(exchange);
+            tokio::time::sleep(Duration::from_secs(2)).await;
+            for sub in &endpoint.subscribe {
+                if ws_stream.send(Message::Text(sub.clone())).await.is_err() {
+                    break;
+                }
+                tokio::time::sleep(SUBSCRIBE_PACING).await;
+            }
+        }
+    }
+    None
+}
+
+/// Text of a data frame. Bithumb sends its JSON in binary frames; BingX and
+/// CoinEx gzip it.
+fn frame_text(m: Message) -> Option<String> {
+    match m {
+        Message::Binary(data) if inflate::is_gzip(&data) => {
+            String::from_utf8(inflate::gunzip(&data)?).ok()
+        }
+        m => m.into_text().ok(),
+    }
+}
+
+/// Store the prices of one poll right away, for sources read on a timer
+/// instead of streamed (on-chain pools). Goes through the same ingestion
+/// filters as WS frames.
+pub fn publish(
+    exchange: &str,
+    ps: Vec<PairPrice>,
+    buffer: &mut TickerBuffer,
+    prices: &SharedPrices,
+) {
+    health().record_message(exchange);
+    store_polled(exchange, ps, buffer, prices);
+}
+
+/// [`publish`] without counting as stream traffic, for the REST fallback
+/// that runs while a venue's stream is down.
+pub fn store_polled(
+    exchange: &str,
+    ps: Vec<PairPrice>,
+    buffer: &mut TickerBuffer,
+    prices: &SharedPrices,
+) {
+    ingest(exchange, ps, buffer);
+    prices.apply_delta(exchange, buffer.drain());
+}
+
+/// Buffer one frame's prices, recording event lag and coverage and
+/// rejecting blacklisted, halted and leveraged-token pairs.
+pub(crate) fn ingest(exchange: &str, ps: Vec<PairPrice>, buffer: &mut TickerBuffer) {
+    let newest = ps.iter().map(|p| p.event_time).max().unwrap_or(0);
+    if newest > 0 {
+        let lag = now_ms() as i64 - newest as i64;
+        health().record_event_lag(exchange, lag);
+    }
+    let keys: Vec<String> = ps.iter().map(|p| pair_key(&p.base, &p.quote)).collect();
+    coverage().record_seen(exchange, keys.iter().map(String::as_str));
+    for (p, key) in ps.into_iter().zip(&keys) {
+        if blacklist().blocks(exchange, &p) {
+            buffer.reject(key, "blacklist");
+        } else if symbols().halted(exchange, &p.base, &p.quote) {
+            buffer.reject(key, "halted");
+        } else if symbols().leveraged(exchange, &p.base) {
+            buffer.reject(key, "leveraged");
+        } else {
+            buffer.record(p);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let mut backoff = Backoff::default();
+        let delays: Vec<u64> = (0..7)
+            .map(|_| backoff.next_after(SessionEnd::ConnectFailed).as_secs())
+            .collect();
+        assert_eq!(delays, [1, 2, 4, 8, 16, 30, 30]);
+    }
+
+    #[test]
+    fn backoff_resets_after_a_session_and_maxes_when_rate_limited() {
+        let mut backoff = Backoff::default();
+        for _ in 0..3 {
+            backoff.next();
+        }
+        assert_eq!(
+            backoff.next_after(SessionEnd::Closed),
+            Duration::from_secs(1)
+        );
+        assert_eq!(backoff.next_after(SessionEnd::RateLimited), Backoff::MAX);
+        assert_eq!(
+            backoff.next_after(SessionEnd::Closed),
+            Duration::from_secs(1)
+        );
+    }
+
+    fn joined(topics: &[String]) -> String {
+        topics.join(",")
+    }
+
+    #[test]
+    fn subscriptions_replay_in_chunks() {
+        let mut subs = Subscriptions::new(2, joined).with_topics(["c", "a", "b"].map(String::from));
+        assert_eq!(subs.messages(), ["a,b", "c"]);
+        // A refreshed list replaces the set rather than adding to it.
+        subs.replace(["d".to_string()]);
+        assert_eq!(subs.messages(), ["d"]);
+    }
+
+    #[test]
+    fn ingest_leaves_out_leveraged_tokens() {
+        let price = |base: &str| PairPrice {
+            base: base.into(),
+            quote: "USDT".into(),
+            price: 1.0,
+            is_spot: true,
+            volume: 1.0,
+            event_time: 0,
+            bid: None,
+            ask: None,
+        };
+        let mut buffer = TickerBuffer::default();
+        ingest("kucoin", vec![price("BTC"), price("BTC3L")], &mut buffer);
+        let bases: Vec<String> = buffer.drain().changed.into_iter().map(|p| p.base).collect();
+        assert_eq!(bases, ["BTC"]);
+    }
+}